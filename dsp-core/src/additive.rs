@@ -0,0 +1,125 @@
+use crate::oscillator::generate_sine;
+use crate::params::AdditivePreset;
+use core::f32::consts::PI;
+
+/// Most partials the additive oscillator sums.
+pub const MAX_PARTIALS: usize = 64;
+
+/// Output gain of the partial sum. It lets a square's odd-harmonic `1/k`
+/// series, the loudest of the presets, reach full scale with every
+/// amplitude within `[-1, 1]`.
+const ADDITIVE_GAIN: f32 = 4.0 / PI;
+
+/// Partial amplitudes reproducing `preset`'s waveform, with the same peak
+/// level as the other oscillator types.
+///
+/// Negative amplitudes invert a partial. The saw and square come out in
+/// phase with the PolyBLEP ones; the triangle, made of sines here rather
+/// than cosines, starts a quarter cycle later.
+pub fn preset_spectrum(preset: AdditivePreset) -> [f32; MAX_PARTIALS] {
+    let mut amplitudes = [0.0; MAX_PARTIALS];
+    for (i, amplitude) in amplitudes.iter_mut().enumerate() {
+        let k = (i + 1) as f32;
+        let odd = i % 2 == 0;
+        *amplitude = match preset {
+            AdditivePreset::Sine if i == 0 => 1.0 / ADDITIVE_GAIN,
+            AdditivePreset::Sine => 0.0,
+            AdditivePreset::Triangle if odd => {
+                let sign = if i % 4 == 0 { 1.0 } else { -1.0 };
+                sign * 2.0 / (PI * k * k)
+            }
+            AdditivePreset::Square if odd => 1.0 / k,
+            AdditivePreset::Triangle | AdditivePreset::Square => 0.0,
+            AdditivePreset::Saw => -0.5 / k,
+        };
+    }
+    amplitudes
+}
+
+/// Number of partials that fit below Nyquist at a phase increment of `dt`
+/// cycles per sample, up to `MAX_PARTIALS`.
+pub fn partial_limit(dt: f32) -> usize {
+    let dt = dt.abs();
+    if dt * (MAX_PARTIALS as f32) < 0.5 {
+        return MAX_PARTIALS;
+    }
+    // Largest k with k * dt strictly below half a cycle per sample
+    ((0.5 / dt).ceil() as usize).saturating_sub(1)
+}
+
+/// Sum of `amplitudes[k - 1] * sin(2π k phase)` over the given partials.
+///
+/// Only the fundamental's sine and cosine are evaluated; the higher
+/// partials follow from the Chebyshev recurrence
+/// `sin((k + 1)x) = 2 cos(x) sin(kx) - sin((k - 1)x)`, which is all
+/// multiply-adds, so the sum costs about the same per partial as a
+/// wavetable lookup and stays bit-exact across platforms.
+pub(crate) fn render(amplitudes: &[f32], phase: f32) -> f32 {
+    let sine = generate_sine(phase);
+    let two_cos = 2.0 * generate_sine(phase + 0.25);
+    let mut previous = 0.0;
+    let mut current = sine;
+    let mut sum = 0.0;
+    for &amplitude in amplitudes {
+        sum += amplitude * current;
+        let next = two_cos * current - previous;
+        previous = current;
+        current = next;
+    }
+    sum * ADDITIVE_GAIN
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_fit_the_amplitude_range() {
+        for &preset in AdditivePreset::VARIANTS {
+            let spectrum = preset_spectrum(preset);
+            assert!(spectrum.iter().all(|a| a.abs() <= 1.0), "{:?}", preset);
+            assert!(spectrum[0] != 0.0, "{:?}", preset);
+        }
+    }
+
+    #[test]
+    fn test_square_and_triangle_have_only_odd_partials() {
+        for preset in [AdditivePreset::Square, AdditivePreset::Triangle] {
+            let spectrum = preset_spectrum(preset);
+            for (i, &a) in spectrum.iter().enumerate() {
+                assert_eq!(a == 0.0, i % 2 == 1, "{:?} partial {}", preset, i + 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_partial_limit_stays_below_nyquist() {
+        assert_eq!(partial_limit(0.0), MAX_PARTIALS);
+        assert_eq!(partial_limit(100.0 / 44100.0), MAX_PARTIALS);
+        // 5 kHz at 44.1 kHz: 4 x 5 kHz fits, 5 x 5 kHz doesn't
+        assert_eq!(partial_limit(5000.0 / 44100.0), 4);
+        // Exactly a quarter of the sample rate: the 2nd partial would sit on Nyquist
+        assert_eq!(partial_limit(0.25), 1);
+        assert_eq!(partial_limit(-0.25), 1);
+        assert_eq!(partial_limit(0.6), 0);
+        for hz in [50.0, 440.0, 1000.0, 3000.0, 10000.0] {
+            for sample_rate in [44100.0, 48000.0] {
+                let limit = partial_limit(hz / sample_rate);
+                assert!(limit as f32 * hz < sample_rate / 2.0);
+                assert!(limit == MAX_PARTIALS || (limit + 1) as f32 * hz >= sample_rate / 2.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_recurrence_matches_direct_sines() {
+        let mut amplitudes = [0.0; MAX_PARTIALS];
+        amplitudes[MAX_PARTIALS - 1] = 1.0 / ADDITIVE_GAIN;
+        for i in 0..100 {
+            let phase = i as f32 / 100.0;
+            let expected = (2.0 * PI * MAX_PARTIALS as f32 * phase).sin();
+            let actual = render(&amplitudes, phase);
+            assert!((actual - expected).abs() < 1e-3, "{} vs {}", actual, expected);
+        }
+    }
+}
@@ -1,91 +1,268 @@
-/// ADSR envelope generator.
+use crate::params::{EnvelopeMode, RetriggerMode};
+
+/// Curve amount that gives the sharpest exponential or logarithmic stage.
+pub const MAX_ENVELOPE_CURVE: f32 = 1.0;
+
+/// How close a fully curved stage's pole sits to its end point, as a
+/// fraction of the stage's span. About seven time constants fit in the
+/// stage, so a fully exponential decay covers its last 0.1% as a tail.
+const MIN_CURVE_RATIO: f64 = 0.001;
+
+/// Length of the fade to zero before a `RetriggerMode::Reset` attack. Long
+/// enough not to click, short enough to sound like a hard restart.
+const RESET_FADE_SECONDS: f32 = 0.002;
+
+/// How close a stage has to get to its end point to count as there, so
+/// rounding can't carry it a sample past its configured time.
+const END_TOLERANCE: f64 = 1e-9;
+
+/// DAHDSR envelope generator.
 ///
 /// Produces a gain multiplier in [0, 1] that shapes the amplitude of a note
 /// over time. The envelope transitions through stages:
-/// Idle → Attack → Decay → Sustain → Release → Idle
+/// Idle → Delay → Attack → Hold → Decay → Sustain → Release → Idle
+///
+/// Delay and hold are skipped when their time is zero, which leaves a
+/// plain ADSR. The mode decides what happens after the decay: `Normal`
+/// sustains until note-off, `Loop` goes back to the attack for as long as
+/// the key is held, and `OneShot` ignores note-off altogether and carries
+/// straight on into the release.
+///
+/// The retrigger mode decides what a note-on does to an envelope that is
+/// still running: `Resume` attacks from the current level, `Reset` fades
+/// to zero over a couple of milliseconds and attacks from there, and
+/// `Legato` leaves a held envelope alone.
+///
+/// Each moving stage has its own curve, from `-MAX_ENVELOPE_CURVE` to
+/// `MAX_ENVELOPE_CURVE`. At 0 the stage is a straight line; above 0 it is
+/// an analog-style RC segment that moves quickly at first and eases into
+/// its end point (exponential); below 0 it starts slowly and speeds up
+/// (logarithmic). Whatever the curve, a stage reaches its end point in its
+/// configured time.
 #[derive(Debug)]
 pub struct Envelope {
     stage: Stage,
-    level: f32,
+    /// Kept in `f64`: a long, gently curved stage moves by far less than
+    /// an `f32` step near full level each sample.
+    level: f64,
     sample_rate: f32,
 
     // Times in seconds
+    delay: f32,
     attack: f32,
+    hold: f32,
     decay: f32,
     sustain: f32, // level, not time
     release: f32,
 
-    // Per-sample increments (computed from times + sample rate)
-    attack_rate: f32,
-    decay_rate: f32,
-    release_rate: f32,
+    mode: EnvelopeMode,
+    retrigger: RetriggerMode,
+    /// Samples left in the delay or hold stage.
+    countdown: u32,
+
+    // Curve amounts in [-1, 1]
+    attack_curve: f32,
+    decay_curve: f32,
+    release_curve: f32,
+
+    /// Level the attack stage started from.
+    attack_start: f64,
+    /// Level the release stage started from.
+    release_start: f64,
+
+    // Per-sample motion (computed from times, curves + sample rate)
+    fade_segment: Segment,
+    attack_segment: Segment,
+    decay_segment: Segment,
+    release_segment: Segment,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Stage {
     Idle,
+    /// Fading out before a reset retrigger.
+    Fade,
+    Delay,
     Attack,
+    Hold,
     Decay,
     Sustain,
     Release,
 }
 
+/// Per-sample motion of one stage, as a one-pole filter heading towards
+/// (or, for logarithmic curves, away from) a pole, plus a fixed step for
+/// linear stages.
+///
+/// Each sample the level becomes `level + step + (pole - level) * gain`.
+/// The pole sits beyond the stage's end point for exponential curves, and
+/// behind its start point for logarithmic ones, placed so the level lands
+/// on the end point after the stage's length. The start point is fixed
+/// when the stage begins, so setting a time or curve mid-stage, as a host
+/// does every block, rebuilds the same segment.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Segment {
+    step: f64,
+    pole: f64,
+    gain: f64,
+}
+
+impl Segment {
+    /// Motion from `start` to `end` over `samples` samples with curve
+    /// amount `curve`.
+    fn new(start: f64, end: f64, samples: f32, curve: f32) -> Self {
+        let samples = samples as f64;
+        let span = end - start;
+        let amount = curve.clamp(-MAX_ENVELOPE_CURVE, MAX_ENVELOPE_CURVE).abs() as f64;
+        if amount == 0.0 || span == 0.0 {
+            return Self {
+                step: span / samples,
+                pole: 0.0,
+                gain: 0.0,
+            };
+        }
+        // Distance from the pole to the near end of the stage, relative to
+        // the span. Infinite at zero curve (a straight line), shrinking to
+        // MIN_CURVE_RATIO at full curve.
+        let ratio = (1.0 - amount) / amount + MIN_CURVE_RATIO;
+        // The distance to the pole changes by a factor of (1 + 1/ratio)
+        // over the stage
+        let log_per_sample = (1.0 / ratio).ln_1p() / samples;
+        if curve > 0.0 {
+            Self {
+                step: 0.0,
+                pole: end + ratio * span,
+                gain: -(-log_per_sample).exp_m1(),
+            }
+        } else {
+            Self {
+                step: 0.0,
+                pole: start - ratio * span,
+                gain: -log_per_sample.exp_m1(),
+            }
+        }
+    }
+
+    fn advance(&self, level: f64) -> f64 {
+        level + self.step + (self.pole - level) * self.gain
+    }
+}
+
 impl Envelope {
     pub fn new() -> Self {
+        let linear = Segment::new(0.0, 0.0, 1.0, 0.0);
         let mut env = Self {
             stage: Stage::Idle,
             level: 0.0,
             sample_rate: 44100.0,
+            delay: 0.0,
             attack: 0.01,
+            hold: 0.0,
             decay: 0.1,
             sustain: 0.7,
             release: 0.3,
-            attack_rate: 0.0,
-            decay_rate: 0.0,
-            release_rate: 0.0,
+            mode: EnvelopeMode::Normal,
+            retrigger: RetriggerMode::Resume,
+            countdown: 0,
+            attack_curve: 0.0,
+            decay_curve: 0.0,
+            release_curve: 0.0,
+            attack_start: 0.0,
+            release_start: 0.7,
+            fade_segment: linear,
+            attack_segment: linear,
+            decay_segment: linear,
+            release_segment: linear,
         };
-        env.recalculate_rates();
+        env.recalculate_segments();
         env
     }
 
     pub fn set_sample_rate(&mut self, sample_rate: f32) {
         self.sample_rate = sample_rate;
-        self.recalculate_rates();
+        self.recalculate_segments();
+    }
+
+    /// Time between note-on and the start of the attack. 0 skips the
+    /// delay stage.
+    pub fn set_delay(&mut self, seconds: f32) {
+        self.delay = seconds.max(0.0);
     }
 
     pub fn set_attack(&mut self, seconds: f32) {
         self.attack = seconds.max(0.001);
-        self.attack_rate = 1.0 / (self.attack * self.sample_rate);
+        self.update_attack();
+    }
+
+    /// Time the envelope stays at full level between attack and decay. 0
+    /// skips the hold stage.
+    pub fn set_hold(&mut self, seconds: f32) {
+        self.hold = seconds.max(0.0);
     }
 
     pub fn set_decay(&mut self, seconds: f32) {
         self.decay = seconds.max(0.001);
-        self.decay_rate = (1.0 - self.sustain) / (self.decay * self.sample_rate);
+        self.update_decay();
     }
 
     pub fn set_sustain(&mut self, level: f32) {
         self.sustain = level.clamp(0.0, 1.0);
-        // Decay rate depends on sustain level
-        self.decay_rate = (1.0 - self.sustain) / (self.decay * self.sample_rate);
+        // Decay segment depends on sustain level
+        self.update_decay();
     }
 
     pub fn set_release(&mut self, seconds: f32) {
         self.release = seconds.max(0.001);
-        self.release_rate = self.sustain / (self.release * self.sample_rate);
+        self.update_release();
+    }
+
+    /// Attack curve: 0 is linear, positive exponential, negative
+    /// logarithmic. Clamped to `±MAX_ENVELOPE_CURVE`. The decay and release
+    /// curves take the same values.
+    pub fn set_attack_curve(&mut self, curve: f32) {
+        self.attack_curve = curve.clamp(-MAX_ENVELOPE_CURVE, MAX_ENVELOPE_CURVE);
+        self.update_attack();
+    }
+
+    /// Decay curve, as for `set_attack_curve`.
+    pub fn set_decay_curve(&mut self, curve: f32) {
+        self.decay_curve = curve.clamp(-MAX_ENVELOPE_CURVE, MAX_ENVELOPE_CURVE);
+        self.update_decay();
+    }
+
+    /// Release curve, as for `set_attack_curve`.
+    pub fn set_release_curve(&mut self, curve: f32) {
+        self.release_curve = curve.clamp(-MAX_ENVELOPE_CURVE, MAX_ENVELOPE_CURVE);
+        self.update_release();
+    }
+
+    /// What happens after the decay, and whether note-off is heard.
+    pub fn set_mode(&mut self, mode: EnvelopeMode) {
+        self.mode = mode;
+    }
+
+    /// What a note-on does to an envelope that is still running.
+    pub fn set_retrigger(&mut self, retrigger: RetriggerMode) {
+        self.retrigger = retrigger;
     }
 
     pub fn note_on(&mut self) {
-        self.stage = Stage::Attack;
-        // Don't reset level to 0 — allows retriggering without clicks
+        match self.retrigger {
+            _ if self.ignores_note_on() => {}
+            RetriggerMode::Reset if self.level > 0.0 => {
+                // Never jump to zero — that clicks
+                let samples = RESET_FADE_SECONDS * self.sample_rate;
+                self.fade_segment = Segment::new(self.level, 0.0, samples, 0.0);
+                self.stage = Stage::Fade;
+            }
+            _ => self.trigger(),
+        }
     }
 
+    /// Start the release, unless the envelope is in one-shot mode.
     pub fn note_off(&mut self) {
-        if self.stage != Stage::Idle {
-            self.stage = Stage::Release;
-            // Recalculate release rate from current level so it reaches 0
-            // in the configured release time
-            let release_samples = self.release * self.sample_rate;
-            self.release_rate = self.level / release_samples;
+        if self.stage != Stage::Idle && self.mode != EnvelopeMode::OneShot {
+            self.start_release();
         }
     }
 
@@ -93,42 +270,152 @@ impl Envelope {
         self.stage != Stage::Idle
     }
 
+    /// Whether a reset retrigger is still fading the previous note out.
+    pub fn is_fading(&self) -> bool {
+        self.stage == Stage::Fade
+    }
+
+    /// Whether `note_on` would leave the envelope alone: legato retrigger
+    /// while the note is still held.
+    pub fn ignores_note_on(&self) -> bool {
+        self.retrigger == RetriggerMode::Legato && self.is_held()
+    }
+
+    /// Whether the envelope is running and not yet released.
+    fn is_held(&self) -> bool {
+        !matches!(self.stage, Stage::Idle | Stage::Release)
+    }
+
+    /// Current output level without advancing the envelope.
+    pub fn level(&self) -> f32 {
+        self.level as f32
+    }
+
     /// Produce the next envelope value and advance state.
     pub fn tick(&mut self) -> f32 {
         match self.stage {
             Stage::Idle => 0.0,
+            Stage::Fade => {
+                self.level = self.fade_segment.advance(self.level);
+                if self.level <= END_TOLERANCE {
+                    self.level = 0.0;
+                    self.trigger();
+                }
+                self.level()
+            }
+            Stage::Delay => {
+                self.countdown -= 1;
+                if self.countdown == 0 {
+                    self.start_attack();
+                }
+                self.level()
+            }
             Stage::Attack => {
-                self.level += self.attack_rate;
-                if self.level >= 1.0 {
+                self.level = self.attack_segment.advance(self.level);
+                if self.level >= 1.0 - END_TOLERANCE {
                     self.level = 1.0;
+                    self.countdown = self.samples(self.hold);
+                    self.stage = if self.countdown > 0 {
+                        Stage::Hold
+                    } else {
+                        Stage::Decay
+                    };
+                }
+                self.level()
+            }
+            Stage::Hold => {
+                self.countdown -= 1;
+                if self.countdown == 0 {
                     self.stage = Stage::Decay;
                 }
-                self.level
+                self.level()
             }
             Stage::Decay => {
-                self.level -= self.decay_rate;
-                if self.level <= self.sustain {
-                    self.level = self.sustain;
-                    self.stage = Stage::Sustain;
+                self.level = self.decay_segment.advance(self.level);
+                if self.level <= self.sustain as f64 + END_TOLERANCE {
+                    self.level = self.sustain as f64;
+                    self.finish_decay();
+                }
+                self.level()
+            }
+            Stage::Sustain => {
+                // The mode may have changed since the decay finished
+                if self.mode != EnvelopeMode::Normal {
+                    self.finish_decay();
                 }
-                self.level
+                self.level()
             }
-            Stage::Sustain => self.level,
             Stage::Release => {
-                self.level -= self.release_rate;
-                if self.level <= 0.0 {
+                self.level = self.release_segment.advance(self.level);
+                if self.level <= END_TOLERANCE {
                     self.level = 0.0;
                     self.stage = Stage::Idle;
                 }
-                self.level
+                self.level()
             }
         }
     }
 
-    fn recalculate_rates(&mut self) {
-        self.attack_rate = 1.0 / (self.attack * self.sample_rate);
-        self.decay_rate = (1.0 - self.sustain) / (self.decay * self.sample_rate);
-        self.release_rate = self.sustain / (self.release * self.sample_rate);
+    /// Move on from the end of the decay as the mode says.
+    fn finish_decay(&mut self) {
+        match self.mode {
+            EnvelopeMode::Normal => self.stage = Stage::Sustain,
+            EnvelopeMode::Loop => self.start_attack(),
+            EnvelopeMode::OneShot => self.start_release(),
+        }
+    }
+
+    /// Start the delay, or the attack if there is no delay. The level
+    /// carries on from wherever it is.
+    fn trigger(&mut self) {
+        self.countdown = self.samples(self.delay);
+        if self.countdown > 0 {
+            self.stage = Stage::Delay;
+        } else {
+            self.start_attack();
+        }
+    }
+
+    fn start_attack(&mut self) {
+        self.stage = Stage::Attack;
+        // Start the attack from the current level so it reaches 1 in the
+        // configured attack time
+        self.attack_start = self.level;
+        self.update_attack();
+    }
+
+    fn start_release(&mut self) {
+        self.stage = Stage::Release;
+        // Start the release from the current level so it reaches 0 in
+        // the configured release time
+        self.release_start = self.level;
+        self.update_release();
+    }
+
+    /// Whole samples in `seconds` at the current sample rate.
+    fn samples(&self, seconds: f32) -> u32 {
+        (seconds * self.sample_rate).round() as u32
+    }
+
+    fn update_attack(&mut self) {
+        let samples = self.attack * self.sample_rate;
+        self.attack_segment = Segment::new(self.attack_start, 1.0, samples, self.attack_curve);
+    }
+
+    fn update_decay(&mut self) {
+        let samples = self.decay * self.sample_rate;
+        self.decay_segment = Segment::new(1.0, self.sustain as f64, samples, self.decay_curve);
+    }
+
+    fn update_release(&mut self) {
+        let samples = self.release * self.sample_rate;
+        self.release_segment = Segment::new(self.release_start, 0.0, samples, self.release_curve);
+    }
+
+    fn recalculate_segments(&mut self) {
+        self.update_attack();
+        self.update_decay();
+        self.update_release();
     }
 }
 
@@ -345,6 +632,147 @@ mod tests {
         );
     }
 
+    const CURVES: [f32; 7] = [-1.0, -0.5, -0.1, 0.0, 0.1, 0.5, 1.0];
+
+    /// Ticks until the envelope leaves its current stage.
+    fn stage_length(env: &mut Envelope) -> usize {
+        let stage = env.stage;
+        let mut count = 0;
+        while env.stage == stage && count < 1_000_000 {
+            env.tick();
+            count += 1;
+        }
+        count
+    }
+
+    #[test]
+    fn test_curved_stages_finish_in_configured_time() {
+        for &curve in CURVES.iter() {
+            for &seconds in [0.001, 0.05, 2.0].iter() {
+                let mut env = Envelope::new();
+                env.set_attack(seconds);
+                env.set_decay(seconds);
+                env.set_release(seconds);
+                env.set_attack_curve(curve);
+                env.set_decay_curve(curve);
+                env.set_release_curve(curve);
+                let samples = (seconds * 44100.0f32).ceil() as usize;
+                env.note_on();
+                let attack = stage_length(&mut env);
+                let decay = stage_length(&mut env);
+                env.note_off();
+                let release = stage_length(&mut env);
+                for (name, length) in [("attack", attack), ("decay", decay), ("release", release)] {
+                    assert!(
+                        length + 1 >= samples && length <= samples,
+                        "{} with curve {} over {} s took {} samples, expected {}",
+                        name, curve, seconds, length, samples
+                    );
+                }
+                assert!(!env.is_active());
+            }
+        }
+    }
+
+    #[test]
+    fn test_curved_stages_are_monotonic() {
+        for &curve in CURVES.iter() {
+            let mut env = Envelope::new();
+            env.set_attack_curve(curve);
+            env.set_decay_curve(curve);
+            env.set_release_curve(curve);
+            env.note_on();
+            let rising = collect_ticks(&mut env, 441);
+            assert!(rising.windows(2).all(|w| w[1] >= w[0]), "curve {}", curve);
+            let falling = collect_ticks(&mut env, 4410);
+            assert!(falling.windows(2).all(|w| w[1] <= w[0]), "curve {}", curve);
+            env.note_off();
+            let released = collect_ticks(&mut env, 13230);
+            assert!(released.windows(2).all(|w| w[1] <= w[0]), "curve {}", curve);
+        }
+    }
+
+    #[test]
+    fn test_curve_bends_the_stage() {
+        // Halfway through a decay from 1.0 to 0.0, linear sits at 0.5,
+        // exponential (RC) has dropped further and logarithmic less far
+        let halfway = |curve: f32| {
+            let mut env = Envelope::new();
+            env.set_sustain(0.0);
+            env.set_decay_curve(curve);
+            env.note_on();
+            tick_n(&mut env, 441);
+            tick_n(&mut env, 2205)
+        };
+        assert!((halfway(0.0) - 0.5).abs() < 1e-3);
+        assert!(halfway(0.5) < 0.45, "exponential: {}", halfway(0.5));
+        assert!(halfway(1.0) < halfway(0.5));
+        assert!(halfway(-0.5) > 0.55, "logarithmic: {}", halfway(-0.5));
+        assert!(halfway(-1.0) > halfway(-0.5));
+        // A small amount bends it only slightly
+        assert!((halfway(0.01) - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_curve_is_clamped() {
+        let mut a = Envelope::new();
+        let mut b = Envelope::new();
+        a.set_release_curve(5.0);
+        b.set_release_curve(MAX_ENVELOPE_CURVE);
+        a.note_on();
+        b.note_on();
+        tick_n(&mut a, 1000);
+        tick_n(&mut b, 1000);
+        a.note_off();
+        b.note_off();
+        assert_eq!(collect_ticks(&mut a, 1000), collect_ticks(&mut b, 1000));
+    }
+
+    #[test]
+    fn test_retrigger_during_curved_release_does_not_click() {
+        for &curve in CURVES.iter() {
+            let mut env = Envelope::new();
+            env.set_attack_curve(curve);
+            env.set_release_curve(curve);
+            env.note_on();
+            tick_n(&mut env, 441 + 4410);
+            env.note_off();
+            let before = tick_n(&mut env, 3000);
+            env.note_on();
+            let after = env.tick();
+            // One attack sample moves at most 1/441 linearly; a fully
+            // exponential attack starts around seven times steeper
+            assert!(
+                after >= before && after - before < 0.02,
+                "curve {}: {} -> {}",
+                curve, before, after
+            );
+        }
+    }
+
+    #[test]
+    fn test_release_setter_keeps_release_start_level() {
+        // Setting the release time mid-release, as a host does every block,
+        // keeps measuring the release from where it started
+        let mut env = Envelope::new();
+        env.note_on();
+        tick_n(&mut env, 200);
+        env.note_off();
+        let mut count = 0;
+        while env.is_active() && count < 20000 {
+            if count % 512 == 0 {
+                env.set_release(0.3);
+            }
+            env.tick();
+            count += 1;
+        }
+        assert!(
+            (count as i32 - 13230).abs() < 10,
+            "release should take ~13230 samples, took {}",
+            count
+        );
+    }
+
     #[test]
     fn test_full_sustain_envelope() {
         let mut env = Envelope::new();
@@ -361,4 +789,269 @@ mod tests {
             );
         }
     }
+
+    // --- Delay and hold ---
+
+    #[test]
+    fn test_delay_holds_off_the_attack() {
+        let mut env = Envelope::new();
+        env.set_delay(0.01); // 441 samples
+        env.note_on();
+        assert!(env.is_active(), "should be active during the delay");
+        let delayed = collect_ticks(&mut env, 441);
+        assert!(delayed.iter().all(|&s| s == 0.0));
+        // Then a full-length attack
+        assert!(env.tick() > 0.0);
+        let val = tick_n(&mut env, 440);
+        assert!((val - 1.0).abs() < 1e-3, "attack should reach 1.0, got {}", val);
+    }
+
+    #[test]
+    fn test_hold_stays_at_full_level() {
+        let mut env = Envelope::new();
+        env.set_hold(0.01); // 441 samples
+        env.note_on();
+        tick_n(&mut env, 441); // attack
+        let held = collect_ticks(&mut env, 441);
+        assert!(held.iter().all(|&s| s == 1.0));
+        assert!(env.tick() < 1.0, "decay should start after the hold");
+    }
+
+    #[test]
+    fn test_note_off_during_delay() {
+        let mut env = Envelope::new();
+        env.set_delay(0.01);
+        env.note_on();
+        tick_n(&mut env, 200);
+        env.note_off();
+        // Nothing has sounded yet, so there is nothing to release
+        assert_eq!(env.tick(), 0.0);
+        assert!(!env.is_active(), "should be idle after releasing from zero");
+    }
+
+    #[test]
+    fn test_note_off_during_hold() {
+        let mut env = Envelope::new();
+        env.set_hold(0.1);
+        env.note_on();
+        tick_n(&mut env, 441 + 200);
+        assert_eq!(env.level(), 1.0, "should be mid-hold");
+        env.note_off();
+        // Release from full level takes the full 0.3s = 13230 samples
+        let mut count = 0;
+        while env.is_active() && count < 20000 {
+            env.tick();
+            count += 1;
+        }
+        assert!(
+            (count as i32 - 13230).abs() < 10,
+            "release should take ~13230 samples, took {}",
+            count
+        );
+    }
+
+    #[test]
+    fn test_retrigger_during_delay_keeps_level() {
+        let mut env = Envelope::new();
+        env.note_on();
+        let before = tick_n(&mut env, 200);
+        env.set_delay(0.01);
+        env.note_on();
+        // The level holds where it was through the new delay
+        let delayed = collect_ticks(&mut env, 441);
+        assert!(delayed.iter().all(|&s| s == before));
+    }
+
+    // --- Loop mode ---
+
+    #[test]
+    fn test_loop_cycles_attack_and_decay_while_held() {
+        let mut env = Envelope::new();
+        env.set_mode(EnvelopeMode::Loop);
+        env.set_sustain(0.0);
+        env.set_attack(0.01); // 441 samples
+        env.set_decay(0.01); // 441 samples
+        env.note_on();
+        let samples = collect_ticks(&mut env, 441 * 2 * 4);
+        let peaks = samples.iter().filter(|&&s| s == 1.0).count();
+        let troughs = samples.iter().filter(|&&s| s == 0.0).count();
+        assert_eq!(peaks, 4, "one peak per cycle");
+        assert_eq!(troughs, 4, "one trough per cycle");
+        assert!(env.is_active());
+    }
+
+    #[test]
+    fn test_loop_includes_hold() {
+        let mut env = Envelope::new();
+        env.set_mode(EnvelopeMode::Loop);
+        env.set_hold(0.01);
+        env.note_on();
+        // Attack, hold, decay to sustain, then the next attack rises
+        tick_n(&mut env, 441 + 441 + 4410);
+        assert!((env.level() - 0.7).abs() < 1e-3);
+        let rising = collect_ticks(&mut env, 50);
+        assert!(rising.windows(2).all(|w| w[1] > w[0]));
+    }
+
+    #[test]
+    fn test_note_off_during_loop() {
+        let mut env = Envelope::new();
+        env.set_mode(EnvelopeMode::Loop);
+        env.set_sustain(0.0);
+        env.note_on();
+        tick_n(&mut env, 441 + 2000); // mid-decay of the first cycle
+        let level = env.level();
+        assert!(level > 0.0 && level < 1.0, "should be mid-decay: {}", level);
+        env.note_off();
+        let released = collect_ticks(&mut env, 13230 + 100);
+        assert!(released.windows(2).all(|w| w[1] <= w[0]), "no more cycles");
+        assert!(!env.is_active(), "should reach idle");
+    }
+
+    // --- One-shot mode ---
+
+    #[test]
+    fn test_one_shot_ignores_note_off() {
+        let mut env = Envelope::new();
+        env.set_mode(EnvelopeMode::OneShot);
+        env.note_on();
+        tick_n(&mut env, 200);
+        env.note_off();
+        // The attack carries on to full level regardless
+        let val = tick_n(&mut env, 241);
+        assert!((val - 1.0).abs() < 1e-3, "attack should reach 1.0, got {}", val);
+    }
+
+    #[test]
+    fn test_one_shot_runs_through_release() {
+        let mut env = Envelope::new();
+        env.set_mode(EnvelopeMode::OneShot);
+        env.note_on();
+        env.note_off();
+        // Attack, decay to sustain, then straight into the release without
+        // waiting at the sustain level
+        let val = tick_n(&mut env, 441 + 4410);
+        assert!((val - 0.7).abs() < 1e-3, "decay should reach sustain 0.7, got {}", val);
+        assert!(env.tick() < val, "release should follow the decay");
+        tick_n(&mut env, 13230 + 100);
+        assert!(!env.is_active(), "should be idle after the release");
+    }
+
+    #[test]
+    fn test_one_shot_without_note_off_still_ends() {
+        let mut env = Envelope::new();
+        env.set_mode(EnvelopeMode::OneShot);
+        env.set_sustain(0.0);
+        env.note_on();
+        tick_n(&mut env, 441 + 4410 + 1);
+        assert!(!env.is_active(), "a decay to zero should end the note");
+    }
+
+    #[test]
+    fn test_changing_mode_during_sustain() {
+        let mut env = Envelope::new();
+        env.note_on();
+        tick_n(&mut env, 441 + 4410 + 100); // sustaining
+        env.set_mode(EnvelopeMode::OneShot);
+        // Would otherwise sustain forever, since note-off is now ignored
+        tick_n(&mut env, 13230 + 100);
+        assert!(!env.is_active());
+
+        let mut env = Envelope::new();
+        env.note_on();
+        tick_n(&mut env, 441 + 4410 + 100);
+        env.set_mode(EnvelopeMode::Loop);
+        let rising = collect_ticks(&mut env, 50);
+        assert!(rising.windows(2).all(|w| w[1] > w[0]), "should start a new cycle");
+    }
+
+    // --- Retrigger modes ---
+
+    #[test]
+    fn test_attack_from_nonzero_level_takes_attack_time() {
+        for &curve in CURVES.iter() {
+            let mut env = Envelope::new();
+            env.set_attack_curve(curve);
+            env.note_on();
+            tick_n(&mut env, 441 + 4410); // sustaining at 0.7
+            env.note_off();
+            tick_n(&mut env, 3000);
+            let level = env.level();
+            assert!(level > 0.0 && level < 0.7, "should be mid-release: {}", level);
+            env.note_on();
+            // The full 441 samples, not just the remaining part of a 0→1 attack
+            let attack = stage_length(&mut env);
+            assert!((440..=441).contains(&attack), "curve {}: took {}", curve, attack);
+        }
+    }
+
+    #[test]
+    fn test_reset_fades_to_zero_before_attack() {
+        let mut env = Envelope::new();
+        env.set_retrigger(RetriggerMode::Reset);
+        env.note_on();
+        tick_n(&mut env, 441 + 4410); // sustaining at 0.7
+        env.note_on();
+        let fade = collect_ticks(&mut env, 89); // 2 ms
+        assert!(fade.windows(2).all(|w| w[1] <= w[0]), "fade should fall");
+        assert!(fade[0] < 0.7 && 0.7 - fade[0] < 0.01, "no jump: {}", fade[0]);
+        assert!(fade.windows(2).all(|w| w[0] - w[1] < 0.01), "no click");
+        assert_eq!(fade[88], 0.0, "should reach zero within the fade");
+        // Then a full attack from silence
+        let attack = stage_length(&mut env);
+        assert!((440..=442).contains(&attack), "attack took {}", attack);
+    }
+
+    #[test]
+    fn test_reset_from_silence_attacks_at_once() {
+        let mut env = Envelope::new();
+        env.set_retrigger(RetriggerMode::Reset);
+        env.note_on();
+        assert!(env.tick() > 0.0, "no fade needed from zero");
+    }
+
+    #[test]
+    fn test_note_off_during_reset_fade() {
+        let mut env = Envelope::new();
+        env.set_retrigger(RetriggerMode::Reset);
+        env.note_on();
+        tick_n(&mut env, 441 + 4410);
+        env.note_on();
+        tick_n(&mut env, 20);
+        env.note_off();
+        let released = collect_ticks(&mut env, 13230 + 100);
+        assert!(released.windows(2).all(|w| w[1] <= w[0]));
+        assert!(!env.is_active(), "should reach idle");
+    }
+
+    #[test]
+    fn test_legato_does_not_retrigger_held_envelope() {
+        let mut env = Envelope::new();
+        env.set_retrigger(RetriggerMode::Legato);
+        env.note_on();
+        tick_n(&mut env, 441 + 4410);
+        env.note_on();
+        let samples = collect_ticks(&mut env, 1000);
+        assert!(samples.iter().all(|&s| (s - 0.7).abs() < 1e-6), "should keep sustaining");
+
+        // Mid-decay too: the decay carries on down
+        let mut env = Envelope::new();
+        env.set_retrigger(RetriggerMode::Legato);
+        env.note_on();
+        let before = tick_n(&mut env, 441 + 1000);
+        env.note_on();
+        assert!(env.tick() < before);
+    }
+
+    #[test]
+    fn test_legato_retriggers_released_envelope() {
+        let mut env = Envelope::new();
+        env.set_retrigger(RetriggerMode::Legato);
+        env.note_on();
+        tick_n(&mut env, 441 + 4410);
+        env.note_off();
+        let before = tick_n(&mut env, 1000);
+        env.note_on();
+        assert!(env.tick() > before, "a released envelope attacks again");
+    }
 }
@@ -0,0 +1,239 @@
+use crate::params::FilterMode;
+use core::f32::consts::{PI, SQRT_2};
+
+/// Lowest cutoff the filter accepts, in Hz.
+pub const MIN_CUTOFF_HZ: f32 = 20.0;
+
+/// Highest cutoff as a fraction of the sample rate. The bilinear prewarp
+/// `tan(PI * fc / fs)` runs off to infinity at Nyquist, so the cutoff is
+/// held a little below it whatever the sample rate.
+const MAX_CUTOFF_RATIO: f32 = 0.49;
+
+/// Damping at full resonance. Kept above zero so the filter rings for a
+/// long time at the cutoff but never becomes a lossless oscillator.
+const MIN_DAMPING: f32 = 0.005;
+
+/// Zero-delay-feedback state-variable filter (Simper/Cytomic topology).
+///
+/// The two integrators are trapezoidal and the feedback loop is solved
+/// exactly each sample, so the response matches the analog prototype up to
+/// the prewarped cutoff and the filter is unconditionally stable for any
+/// cutoff below Nyquist and any resonance. Cutoff can be changed every
+/// sample without zipper artifacts or blow-ups.
+pub struct Filter {
+    mode: FilterMode,
+    sample_rate: f32,
+    cutoff: f32,
+    resonance: f32,
+
+    // Coefficients
+    k: f32,
+    a1: f32,
+    a2: f32,
+    a3: f32,
+
+    // Integrator state
+    ic1eq: f32,
+    ic2eq: f32,
+}
+
+impl Filter {
+    pub fn new() -> Self {
+        let mut filter = Self {
+            mode: FilterMode::Lowpass,
+            sample_rate: 44100.0,
+            cutoff: 20000.0,
+            resonance: 0.0,
+            k: SQRT_2,
+            a1: 0.0,
+            a2: 0.0,
+            a3: 0.0,
+            ic1eq: 0.0,
+            ic2eq: 0.0,
+        };
+        filter.recalculate();
+        filter
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.recalculate();
+    }
+
+    pub fn set_mode(&mut self, mode: FilterMode) {
+        self.mode = mode;
+    }
+
+    /// Cutoff frequency in Hz. Clamped to `MIN_CUTOFF_HZ` and to just below
+    /// Nyquist at the current sample rate.
+    pub fn set_cutoff(&mut self, hz: f32) {
+        self.cutoff = hz;
+        self.recalculate();
+    }
+
+    /// Resonance in `[0, 1]`: 0 is a flat (Butterworth) response, 1 rings
+    /// close to self-oscillation.
+    pub fn set_resonance(&mut self, resonance: f32) {
+        self.resonance = resonance.clamp(0.0, 1.0);
+        self.recalculate();
+    }
+
+    /// Clear the integrator state.
+    pub fn reset(&mut self) {
+        self.ic1eq = 0.0;
+        self.ic2eq = 0.0;
+    }
+
+    /// Filter one sample.
+    pub fn process(&mut self, input: f32) -> f32 {
+        let v3 = input - self.ic2eq;
+        let v1 = self.a1 * self.ic1eq + self.a2 * v3;
+        let v2 = self.ic2eq + self.a2 * self.ic1eq + self.a3 * v3;
+        self.ic1eq = 2.0 * v1 - self.ic1eq;
+        self.ic2eq = 2.0 * v2 - self.ic2eq;
+
+        match self.mode {
+            FilterMode::Lowpass => v2,
+            FilterMode::Highpass => input - self.k * v1 - v2,
+            FilterMode::Bandpass => v1,
+            FilterMode::Notch => input - self.k * v1,
+        }
+    }
+
+    fn recalculate(&mut self) {
+        let max_cutoff = self.sample_rate * MAX_CUTOFF_RATIO;
+        let cutoff = self.cutoff.clamp(MIN_CUTOFF_HZ.min(max_cutoff), max_cutoff);
+        let g = (PI * cutoff / self.sample_rate).tan();
+        self.k = (SQRT_2 * (1.0 - self.resonance)).max(MIN_DAMPING);
+        self.a1 = 1.0 / (1.0 + g * (g + self.k));
+        self.a2 = g * self.a1;
+        self.a3 = g * self.a2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(mode: FilterMode, cutoff: f32, resonance: f32, sample_rate: f32) -> Filter {
+        let mut filter = Filter::new();
+        filter.set_sample_rate(sample_rate);
+        filter.set_mode(mode);
+        filter.set_cutoff(cutoff);
+        filter.set_resonance(resonance);
+        filter
+    }
+
+    /// Steady-state gain of `filter` for a sine at `freq`, in dB.
+    fn gain_db(filter: &mut Filter, freq: f32, sample_rate: f32) -> f32 {
+        let n = sample_rate as usize;
+        let mut peak = 0.0f32;
+        for i in 0..n {
+            let x = (2.0 * PI * freq * i as f32 / sample_rate).sin();
+            let y = filter.process(x);
+            // Skip the first half while the filter settles
+            if i > n / 2 {
+                peak = peak.max(y.abs());
+            }
+        }
+        20.0 * peak.log10()
+    }
+
+    fn response(mode: FilterMode, resonance: f32, freq: f32) -> f32 {
+        let mut f = filter(mode, 1000.0, resonance, 44100.0);
+        gain_db(&mut f, freq, 44100.0)
+    }
+
+    #[test]
+    fn test_lowpass_passes_lows_and_cuts_highs() {
+        assert!(response(FilterMode::Lowpass, 0.0, 100.0).abs() < 0.5);
+        assert!((response(FilterMode::Lowpass, 0.0, 1000.0) + 3.0).abs() < 0.5);
+        // 12 dB/octave: over three octaves above cutoff is well below -30 dB
+        assert!(response(FilterMode::Lowpass, 0.0, 10000.0) < -30.0);
+    }
+
+    #[test]
+    fn test_highpass_passes_highs_and_cuts_lows() {
+        assert!(response(FilterMode::Highpass, 0.0, 10000.0).abs() < 0.5);
+        assert!(response(FilterMode::Highpass, 0.0, 100.0) < -30.0);
+    }
+
+    #[test]
+    fn test_bandpass_peaks_at_cutoff() {
+        let centre = response(FilterMode::Bandpass, 0.5, 1000.0);
+        assert!(centre > response(FilterMode::Bandpass, 0.5, 200.0) + 10.0);
+        assert!(centre > response(FilterMode::Bandpass, 0.5, 5000.0) + 10.0);
+    }
+
+    #[test]
+    fn test_notch_rejects_cutoff() {
+        assert!(response(FilterMode::Notch, 0.0, 1000.0) < -30.0);
+        assert!(response(FilterMode::Notch, 0.0, 100.0).abs() < 0.5);
+        assert!(response(FilterMode::Notch, 0.0, 10000.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_resonance_boosts_cutoff() {
+        let flat = response(FilterMode::Lowpass, 0.0, 1000.0);
+        let resonant = response(FilterMode::Lowpass, 0.9, 1000.0);
+        assert!(resonant > flat + 10.0, "flat {} resonant {}", flat, resonant);
+    }
+
+    #[test]
+    fn test_stable_at_all_sample_rates() {
+        for &sample_rate in &[8000.0, 22050.0, 44100.0, 48000.0, 96000.0, 192000.0] {
+            for &mode in FilterMode::VARIANTS {
+                // Cutoffs up to and beyond Nyquist, at full resonance
+                for &cutoff in &[20.0, 1000.0, 20000.0, sample_rate, 1e9] {
+                    let mut f = filter(mode, cutoff, 1.0, sample_rate);
+                    let mut max = 0.0f32;
+                    for i in 0..20000 {
+                        // Impulse followed by full-scale white-ish noise
+                        let x = if i == 0 {
+                            1.0
+                        } else {
+                            ((i as f32 * 12.9898).sin() * 43758.545).fract()
+                        };
+                        let y = f.process(x);
+                        assert!(
+                            y.is_finite(),
+                            "{:?} blew up at {} Hz / {} Hz",
+                            mode,
+                            cutoff,
+                            sample_rate
+                        );
+                        max = max.max(y.abs());
+                    }
+                    assert!(
+                        max < 1000.0,
+                        "{:?} at {} Hz / {} Hz peaked at {}",
+                        mode,
+                        cutoff,
+                        sample_rate,
+                        max
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cutoff_tracks_sample_rate() {
+        // The same cutoff in Hz gives the same response at a different rate
+        let mut a = filter(FilterMode::Lowpass, 1000.0, 0.0, 44100.0);
+        let mut b = filter(FilterMode::Lowpass, 1000.0, 0.0, 96000.0);
+        let ga = gain_db(&mut a, 4000.0, 44100.0);
+        let gb = gain_db(&mut b, 4000.0, 96000.0);
+        assert!((ga - gb).abs() < 1.0, "{} vs {}", ga, gb);
+    }
+
+    #[test]
+    fn test_reset_clears_state() {
+        let mut f = filter(FilterMode::Lowpass, 500.0, 0.8, 44100.0);
+        for _ in 0..100 {
+            f.process(1.0);
+        }
+        f.reset();
+        assert_eq!(f.process(0.0), 0.0);
+    }
+}
@@ -0,0 +1,196 @@
+use crate::params::{GlideMode, GlideTiming};
+
+/// Semitones in one octave; constant-rate glide time is measured per octave.
+const SEMITONES_PER_OCTAVE: f32 = 12.0;
+
+/// Synth-wide portamento settings shared by every voice.
+#[derive(Debug, Clone, Copy)]
+pub struct GlideSettings {
+    time: f32,
+    mode: GlideMode,
+    timing: GlideTiming,
+}
+
+impl GlideSettings {
+    pub fn new() -> Self {
+        Self {
+            time: 0.0,
+            mode: GlideMode::Always,
+            timing: GlideTiming::ConstantTime,
+        }
+    }
+
+    /// Glide time in seconds (0 = off). Per octave in constant-rate timing.
+    pub fn set_time(&mut self, seconds: f32) {
+        self.time = seconds.max(0.0);
+    }
+
+    pub fn set_mode(&mut self, mode: GlideMode) {
+        self.mode = mode;
+    }
+
+    pub fn set_timing(&mut self, timing: GlideTiming) {
+        self.timing = timing;
+    }
+
+    /// Whether a note starting now should glide. `legato` is true when
+    /// another key was still held as this note arrived.
+    pub fn applies(&self, legato: bool) -> bool {
+        self.time > 0.0 && (self.mode == GlideMode::Always || legato)
+    }
+
+    /// Pitch change per sample, in semitones, for a glide across `distance`
+    /// semitones.
+    fn step(&self, distance: f32, sample_rate: f32) -> f32 {
+        let seconds = match self.timing {
+            GlideTiming::ConstantTime => self.time,
+            GlideTiming::ConstantRate => self.time * distance / SEMITONES_PER_OCTAVE,
+        };
+        let samples = seconds * sample_rate;
+        if samples < 1.0 {
+            distance
+        } else {
+            distance / samples
+        }
+    }
+}
+
+/// Per-voice pitch that slides linearly (in semitones) towards its target.
+///
+/// Pitch is a fractional MIDI note number, so a glide moves at an even
+/// musical speed rather than an even speed in Hz.
+pub struct Glide {
+    current: f32,
+    target: f32,
+    /// Semitones moved per sample; always positive.
+    step: f32,
+}
+
+impl Glide {
+    pub fn new() -> Self {
+        Self {
+            current: 0.0,
+            target: 0.0,
+            step: 0.0,
+        }
+    }
+
+    /// Move straight to `pitch`, cancelling any glide in progress.
+    pub fn jump(&mut self, pitch: f32) {
+        self.current = pitch;
+        self.target = pitch;
+        self.step = 0.0;
+    }
+
+    /// Slide from `from` to `target` using `settings`.
+    pub fn start(&mut self, from: f32, target: f32, settings: &GlideSettings, sample_rate: f32) {
+        self.current = from;
+        self.target = target;
+        self.step = settings.step((target - from).abs(), sample_rate);
+    }
+
+    pub fn pitch(&self) -> f32 {
+        self.current
+    }
+
+    pub fn is_gliding(&self) -> bool {
+        self.current != self.target
+    }
+
+    /// Advance one sample and return the new pitch.
+    pub fn tick(&mut self) -> f32 {
+        let remaining = self.target - self.current;
+        if remaining.abs() <= self.step {
+            self.current = self.target;
+        } else {
+            self.current += self.step.copysign(remaining);
+        }
+        self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(time: f32, timing: GlideTiming) -> GlideSettings {
+        let mut settings = GlideSettings::new();
+        settings.set_time(time);
+        settings.set_timing(timing);
+        settings
+    }
+
+    fn samples_to_arrive(glide: &mut Glide) -> usize {
+        let mut n = 0;
+        while glide.is_gliding() {
+            glide.tick();
+            n += 1;
+            assert!(n < 1_000_000, "glide never arrived");
+        }
+        n
+    }
+
+    #[test]
+    fn test_zero_time_never_applies() {
+        let settings = GlideSettings::new();
+        assert!(!settings.applies(true));
+        assert!(!settings.applies(false));
+    }
+
+    #[test]
+    fn test_legato_mode_needs_held_key() {
+        let mut settings = settings(0.1, GlideTiming::ConstantTime);
+        settings.set_mode(GlideMode::Legato);
+        assert!(settings.applies(true));
+        assert!(!settings.applies(false));
+        settings.set_mode(GlideMode::Always);
+        assert!(settings.applies(false));
+    }
+
+    #[test]
+    fn test_constant_time_ignores_interval() {
+        let settings = settings(0.1, GlideTiming::ConstantTime);
+        let mut small = Glide::new();
+        small.start(60.0, 62.0, &settings, 1000.0);
+        let mut large = Glide::new();
+        large.start(60.0, 84.0, &settings, 1000.0);
+        assert!(samples_to_arrive(&mut small).abs_diff(100) <= 1);
+        assert!(samples_to_arrive(&mut large).abs_diff(100) <= 1);
+    }
+
+    #[test]
+    fn test_constant_rate_scales_with_interval() {
+        let settings = settings(0.1, GlideTiming::ConstantRate);
+        let mut octave = Glide::new();
+        octave.start(60.0, 72.0, &settings, 1000.0);
+        let mut two_octaves = Glide::new();
+        two_octaves.start(72.0, 48.0, &settings, 1000.0);
+        assert!(samples_to_arrive(&mut octave).abs_diff(100) <= 1);
+        assert!(samples_to_arrive(&mut two_octaves).abs_diff(200) <= 1);
+    }
+
+    #[test]
+    fn test_glide_moves_monotonically_and_lands_exactly() {
+        let settings = settings(0.05, GlideTiming::ConstantTime);
+        let mut glide = Glide::new();
+        glide.start(72.0, 60.0, &settings, 44100.0);
+        let mut prev = glide.pitch();
+        while glide.is_gliding() {
+            let pitch = glide.tick();
+            assert!(pitch <= prev, "downward glide went up: {} -> {}", prev, pitch);
+            prev = pitch;
+        }
+        assert_eq!(glide.pitch(), 60.0);
+    }
+
+    #[test]
+    fn test_jump_cancels_glide() {
+        let settings = settings(1.0, GlideTiming::ConstantTime);
+        let mut glide = Glide::new();
+        glide.start(60.0, 72.0, &settings, 44100.0);
+        glide.tick();
+        glide.jump(64.0);
+        assert!(!glide.is_gliding());
+        assert_eq!(glide.tick(), 64.0);
+    }
+}
@@ -0,0 +1,254 @@
+use crate::filter::MIN_CUTOFF_HZ;
+use core::f32::consts::PI;
+
+/// Highest cutoff as a fraction of the sample rate (see `filter.rs`).
+const MAX_CUTOFF_RATIO: f32 = 0.49;
+
+/// Feedback gain at full resonance. The linear ladder starts to
+/// self-oscillate at 4; going slightly past it makes the oscillation start
+/// reliably, and the saturator keeps its amplitude bounded.
+const MAX_FEEDBACK: f32 = 4.2;
+
+/// Input gain at full drive (+20 dB).
+const MAX_DRIVE_GAIN: f32 = 10.0;
+
+/// Four-pole (24 dB/octave) lowpass ladder in the style of the Moog
+/// transistor ladder.
+///
+/// Four zero-delay-feedback one-pole stages sit inside a global resonance
+/// loop, which is solved each sample so the cutoff and resonance behave
+/// the same at every sample rate. A `tanh` saturator at the loop input
+/// models the ladder's nonlinearity: it adds harmonics as drive rises, and
+/// it is what holds the filter at a steady amplitude when it
+/// self-oscillates at full resonance.
+pub struct LadderFilter {
+    sample_rate: f32,
+    cutoff: f32,
+    resonance: f32,
+
+    // Coefficients
+    /// One-pole gain `g / (1 + g)`.
+    g: f32,
+    /// Resonance feedback amount, `0..MAX_FEEDBACK`.
+    k: f32,
+    drive_gain: f32,
+
+    /// One state per one-pole stage.
+    state: [f32; 4],
+}
+
+impl LadderFilter {
+    pub fn new() -> Self {
+        let mut filter = Self {
+            sample_rate: 44100.0,
+            cutoff: 20000.0,
+            resonance: 0.0,
+            g: 0.0,
+            k: 0.0,
+            drive_gain: 1.0,
+            state: [0.0; 4],
+        };
+        filter.recalculate();
+        filter
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.recalculate();
+    }
+
+    /// Cutoff frequency in Hz, clamped the same way as the state-variable
+    /// filter.
+    pub fn set_cutoff(&mut self, hz: f32) {
+        self.cutoff = hz;
+        self.recalculate();
+    }
+
+    /// Resonance in `[0, 1]`. Full resonance self-oscillates.
+    pub fn set_resonance(&mut self, resonance: f32) {
+        self.resonance = resonance.clamp(0.0, 1.0);
+        self.k = MAX_FEEDBACK * self.resonance;
+    }
+
+    /// Input drive in `[0, 1]`, from clean to heavily saturated.
+    pub fn set_drive(&mut self, drive: f32) {
+        self.drive_gain = 1.0 + (MAX_DRIVE_GAIN - 1.0) * drive.clamp(0.0, 1.0);
+    }
+
+    pub fn reset(&mut self) {
+        self.state = [0.0; 4];
+    }
+
+    /// Filter one sample.
+    pub fn process(&mut self, input: f32) -> f32 {
+        let g = self.g;
+        // Each stage is y = g * x + (1 - g) * s, so the cascade output is
+        // g^4 * x plus a term that depends only on the stored states
+        let mut sigma = 0.0;
+        for &s in &self.state {
+            sigma = g * sigma + (1.0 - g) * s;
+        }
+        let g4 = g * g * g * g;
+        // Solve the linear feedback loop for this sample's output, then
+        // saturate the loop input
+        let y_estimate = (g4 * self.drive_gain * input + sigma) / (1.0 + self.k * g4);
+        let mut x = (self.drive_gain * input - self.k * y_estimate).tanh();
+
+        for s in self.state.iter_mut() {
+            let v = (x - *s) * g;
+            let y = v + *s;
+            *s = y + v;
+            x = y;
+        }
+
+        // Make up the passband level the resonance loop takes away, and
+        // back off part of the drive gain so drive mostly changes tone
+        x * (1.0 + self.k) / self.drive_gain.sqrt()
+    }
+
+    fn recalculate(&mut self) {
+        let max_cutoff = self.sample_rate * MAX_CUTOFF_RATIO;
+        let cutoff = self.cutoff.clamp(MIN_CUTOFF_HZ.min(max_cutoff), max_cutoff);
+        let g = (PI * cutoff / self.sample_rate).tan();
+        self.g = g / (1.0 + g);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::spectrum;
+
+    const SAMPLE_RATE: f32 = 48000.0;
+    const FFT_SIZE: usize = 8192;
+
+    fn ladder(cutoff: f32, resonance: f32, drive: f32) -> LadderFilter {
+        let mut filter = LadderFilter::new();
+        filter.set_sample_rate(SAMPLE_RATE);
+        filter.set_cutoff(cutoff);
+        filter.set_resonance(resonance);
+        filter.set_drive(drive);
+        filter
+    }
+
+    fn bin(freq: f32) -> usize {
+        (freq * FFT_SIZE as f32 / SAMPLE_RATE).round() as usize
+    }
+
+    fn db(x: f32) -> f32 {
+        20.0 * x.max(1e-12).log10()
+    }
+
+    /// Frequency response from a small impulse (small enough that the
+    /// saturator stays linear), normalised to the DC gain.
+    fn impulse_response_db(filter: &mut LadderFilter) -> Vec<f32> {
+        let amplitude = 1e-3;
+        let signal: Vec<f32> = (0..FFT_SIZE)
+            .map(|i| filter.process(if i == 0 { amplitude } else { 0.0 }) / amplitude)
+            .collect();
+        let mags = spectrum(&signal);
+        let dc = mags[0];
+        mags.iter().map(|&m| db(m / dc)).collect()
+    }
+
+    #[test]
+    fn test_lowpass_slope_is_24_db_per_octave() {
+        let response = impulse_response_db(&mut ladder(500.0, 0.0, 0.0));
+        assert!(response[bin(50.0)].abs() < 0.5, "passband {}", response[bin(50.0)]);
+        // Four one-pole stages at their cutoff: about -12 dB
+        assert!((response[bin(500.0)] + 12.0).abs() < 1.0, "cutoff {}", response[bin(500.0)]);
+        // Well above cutoff (and well below Nyquist, where the bilinear
+        // transform steepens it) each octave drops ~24 dB
+        let slope = response[bin(2000.0)] - response[bin(4000.0)];
+        assert!((slope - 24.0).abs() < 2.0, "slope {} dB/octave", slope);
+    }
+
+    #[test]
+    fn test_resonance_peaks_near_cutoff() {
+        let response = impulse_response_db(&mut ladder(1000.0, 0.9, 0.0));
+        let (peak_bin, peak_db) = response
+            .iter()
+            .enumerate()
+            .skip(1)
+            .fold((0, f32::MIN), |best, (i, &d)| if d > best.1 { (i, d) } else { best });
+        let peak_freq = peak_bin as f32 * SAMPLE_RATE / FFT_SIZE as f32;
+        assert!(peak_db > 10.0, "resonant peak only {} dB", peak_db);
+        assert!(
+            (peak_freq - 1000.0).abs() < 100.0,
+            "peak at {} Hz, expected near 1000 Hz",
+            peak_freq
+        );
+    }
+
+    #[test]
+    fn test_self_oscillates_at_max_resonance() {
+        let mut filter = ladder(1000.0, 1.0, 0.0);
+        filter.process(0.1);
+        // Let the oscillation build up with no further input
+        for _ in 0..SAMPLE_RATE as usize {
+            filter.process(0.0);
+        }
+        let signal: Vec<f32> = (0..FFT_SIZE).map(|_| filter.process(0.0)).collect();
+
+        let peak = signal.iter().fold(0.0f32, |a, &s| a.max(s.abs()));
+        assert!(peak > 0.1, "should sustain oscillation, peak {}", peak);
+        assert!(peak < 10.0, "oscillation should stay bounded, peak {}", peak);
+
+        let mags = spectrum(&signal);
+        let loudest = (1..mags.len()).max_by(|&a, &b| mags[a].total_cmp(&mags[b])).unwrap();
+        let freq = loudest as f32 * SAMPLE_RATE / FFT_SIZE as f32;
+        assert!((freq - 1000.0).abs() < 100.0, "oscillating at {} Hz", freq);
+    }
+
+    #[test]
+    fn test_below_max_resonance_decays() {
+        let mut filter = ladder(1000.0, 0.8, 0.0);
+        filter.process(0.1);
+        for _ in 0..SAMPLE_RATE as usize {
+            filter.process(0.0);
+        }
+        assert!(filter.process(0.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_drive_adds_harmonics() {
+        let third_harmonic_db = |drive: f32| {
+            let mut filter = ladder(15000.0, 0.0, drive);
+            let signal: Vec<f32> = (0..FFT_SIZE)
+                .map(|i| {
+                    let x = (2.0 * PI * 375.0 * i as f32 / SAMPLE_RATE).sin() * 0.5;
+                    filter.process(x)
+                })
+                .collect();
+            let mags = spectrum(&signal);
+            db(mags[bin(1125.0)] / mags[bin(375.0)])
+        };
+        let clean = third_harmonic_db(0.0);
+        let driven = third_harmonic_db(1.0);
+        assert!(driven > clean + 20.0, "clean {} dB, driven {} dB", clean, driven);
+    }
+
+    #[test]
+    fn test_stable_at_all_sample_rates() {
+        for &sample_rate in &[8000.0, 22050.0, 44100.0, 96000.0, 192000.0] {
+            for &cutoff in &[20.0, 1000.0, 20000.0, 1e9] {
+                let mut filter = LadderFilter::new();
+                filter.set_sample_rate(sample_rate);
+                filter.set_cutoff(cutoff);
+                filter.set_resonance(1.0);
+                filter.set_drive(1.0);
+                for i in 0..20000 {
+                    let x = if i % 100 < 50 { 1.0 } else { -1.0 };
+                    let y = filter.process(x);
+                    assert!(
+                        y.is_finite() && y.abs() < 100.0,
+                        "unstable at {} Hz / {} Hz: {}",
+                        cutoff,
+                        sample_rate,
+                        y
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use crate::params::{LfoShape, NoteDivision};
+use crate::rng::Rng;
+use core::f32::consts::PI;
+
+/// Tempo assumed until the host reports one, in beats per minute.
+pub const DEFAULT_TEMPO: f32 = 120.0;
+
+/// LFO rate in Hz for one cycle per `division` at `tempo` BPM.
+pub fn tempo_rate_hz(tempo: f32, division: NoteDivision) -> f32 {
+    tempo / 60.0 / division.beats()
+}
+
+/// Low-frequency oscillator producing a bipolar control signal in
+/// `[-1, 1]`.
+///
+/// The waveforms are naive (no anti-aliasing): at LFO rates the steps of the
+/// square, saw and sample-and-hold shapes are meant to be heard as steps.
+pub struct Lfo {
+    phase: f32,
+    phase_delta: f32,
+    sample_rate: f32,
+    rate: f32,
+    shape: LfoShape,
+    /// Level held by the sample-and-hold shape for the current cycle.
+    held: f32,
+    rng: Rng,
+}
+
+impl Lfo {
+    pub fn new() -> Self {
+        let mut rng = Rng::new(1);
+        let held = rng.next_bipolar();
+        let mut lfo = Self {
+            phase: 0.0,
+            phase_delta: 0.0,
+            sample_rate: 44100.0,
+            rate: 5.0,
+            shape: LfoShape::Sine,
+            held,
+            rng,
+        };
+        lfo.update_phase_delta();
+        lfo
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.update_phase_delta();
+    }
+
+    /// Rate in Hz.
+    pub fn set_rate(&mut self, hz: f32) {
+        self.rate = hz.max(0.0);
+        self.update_phase_delta();
+    }
+
+    pub fn set_shape(&mut self, shape: LfoShape) {
+        self.shape = shape;
+    }
+
+    /// Restart the cycle from zero phase, picking a new sample-and-hold level.
+    pub fn reset(&mut self) {
+        self.phase = 0.0;
+        self.held = self.rng.next_bipolar();
+    }
+
+    /// Produce the next value and advance the phase.
+    pub fn tick(&mut self) -> f32 {
+        let phase = self.phase;
+        let value = match self.shape {
+            LfoShape::Sine => (2.0 * PI * phase).sin(),
+            // Starts at zero and rises, like the sine
+            LfoShape::Triangle => 1.0 - (4.0 * ((phase + 0.25) % 1.0) - 2.0).abs(),
+            LfoShape::Saw => 2.0 * phase - 1.0,
+            LfoShape::Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            LfoShape::SampleAndHold => self.held,
+        };
+
+        self.phase += self.phase_delta;
+        if self.phase >= 1.0 {
+            self.phase %= 1.0;
+            self.held = self.rng.next_bipolar();
+        }
+        value
+    }
+
+    fn update_phase_delta(&mut self) {
+        self.phase_delta = self.rate / self.sample_rate;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lfo(shape: LfoShape, rate: f32, sample_rate: f32) -> Lfo {
+        let mut lfo = Lfo::new();
+        lfo.set_sample_rate(sample_rate);
+        lfo.set_rate(rate);
+        lfo.set_shape(shape);
+        lfo
+    }
+
+    #[test]
+    fn test_every_shape_stays_in_range() {
+        for &shape in LfoShape::VARIANTS {
+            let mut lfo = lfo(shape, 7.0, 1000.0);
+            for _ in 0..5000 {
+                let v = lfo.tick();
+                assert!((-1.0..=1.0).contains(&v), "{:?} produced {}", shape, v);
+            }
+        }
+    }
+
+    #[test]
+    fn test_shapes_at_quarter_cycle() {
+        // 1 Hz at 4 Hz sample rate: one sample per quarter cycle
+        let quarter = |shape: LfoShape| {
+            let mut lfo = lfo(shape, 1.0, 4.0);
+            (0..4).map(|_| lfo.tick()).collect::<Vec<f32>>()
+        };
+        let sine = quarter(LfoShape::Sine);
+        assert!(sine[0].abs() < 1e-6 && (sine[1] - 1.0).abs() < 1e-6);
+        assert!(sine[2].abs() < 1e-6 && (sine[3] + 1.0).abs() < 1e-6);
+        assert_eq!(quarter(LfoShape::Triangle), vec![0.0, 1.0, 0.0, -1.0]);
+        assert_eq!(quarter(LfoShape::Saw), vec![-1.0, -0.5, 0.0, 0.5]);
+        assert_eq!(quarter(LfoShape::Square), vec![1.0, 1.0, -1.0, -1.0]);
+    }
+
+    #[test]
+    fn test_rate_sets_cycle_length() {
+        let mut lfo = lfo(LfoShape::Saw, 2.0, 1000.0);
+        // A saw wraps once per cycle: count the downward jumps, ending a
+        // little past the second wrap
+        let mut prev = lfo.tick();
+        let mut wraps = 0;
+        for _ in 0..1200 {
+            let v = lfo.tick();
+            if v < prev {
+                wraps += 1;
+            }
+            prev = v;
+        }
+        assert_eq!(wraps, 2);
+    }
+
+    #[test]
+    fn test_sample_and_hold_holds_for_a_cycle() {
+        // 128 samples per cycle, so the phase steps are exact
+        let mut lfo = lfo(LfoShape::SampleAndHold, 8.0, 1024.0);
+        let values: Vec<f32> = (0..1024).map(|_| lfo.tick()).collect();
+        for cycle in values.chunks(128) {
+            assert!(cycle.iter().all(|&v| v == cycle[0]));
+        }
+        // ...and moves to a new level each cycle
+        let levels: Vec<f32> = values.chunks(128).map(|c| c[0]).collect();
+        assert!(levels.windows(2).all(|w| w[0] != w[1]));
+    }
+
+    #[test]
+    fn test_reset_restarts_phase() {
+        let mut lfo = lfo(LfoShape::Saw, 3.0, 1000.0);
+        for _ in 0..123 {
+            lfo.tick();
+        }
+        lfo.reset();
+        assert_eq!(lfo.tick(), -1.0);
+    }
+
+    #[test]
+    fn test_tempo_rate() {
+        assert_eq!(tempo_rate_hz(120.0, NoteDivision::Quarter), 2.0);
+        assert_eq!(tempo_rate_hz(120.0, NoteDivision::Whole), 0.5);
+        assert!((tempo_rate_hz(90.0, NoteDivision::EighthTriplet) - 4.5).abs() < 1e-5);
+    }
+}
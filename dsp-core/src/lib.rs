@@ -1,279 +1,2859 @@
+pub mod additive;
 pub mod envelope;
+pub mod filter;
+pub mod fm;
+pub mod glide;
+pub mod ladder;
+pub mod lfo;
+pub mod mod_matrix;
+pub mod noise;
+pub mod note_stack;
 pub mod oscillator;
 pub mod params;
+pub mod pluck;
+pub mod rng;
+pub mod smoothing;
+#[cfg(test)]
+mod test_util;
+pub mod unison;
+pub mod velocity;
+pub mod voice;
+pub mod wavetable;
+
+use additive::MAX_PARTIALS;
+use filter::MIN_CUTOFF_HZ;
+use fm::{MAX_FM_FIXED_HZ, MAX_FM_INDEX, MAX_FM_RATIO, MIN_FM_FIXED_HZ, MIN_FM_RATIO};
+use glide::GlideSettings;
+use lfo::{tempo_rate_hz, Lfo, DEFAULT_TEMPO};
+use mod_matrix::{ModMatrix, ModSlot, ModSources, MOD_SLOTS};
+use note_stack::NoteStack;
+use oscillator::{MAX_PULSE_WIDTH, MIN_PULSE_WIDTH};
+use params::{
+    AdditivePreset, Engine, EnvelopeMode, FilterMode, FilterType, GlideMode, GlideTiming, LfoShape,
+    LfoTiming, LfoTrigger, ModDestination, ModSource, NoiseType, NoteDivision, NotePriority,
+    OscillatorType, PluckExcitation, RetriggerMode, StealPolicy, SubOctave, SubShape, VelocityCurve,
+    VoiceMode, WavetableBank,
+};
+use smoothing::Smoothed;
+use std::sync::Arc;
+use unison::{MAX_LINEAR_FM, MAX_UNISON_DETUNE_CENTS};
+use velocity::VelocityResponse;
+use voice::{Voice, MAX_VOICES};
+use wavetable::Wavetable;
+
+/// Largest filter envelope depth either way, in octaves.
+pub const MAX_FILTER_ENV_OCTAVES: f32 = 8.0;
+
+/// Deepest LFO vibrato, in semitones either way.
+pub const MAX_LFO_PITCH_SEMITONES: f32 = 12.0;
 
-use envelope::Envelope;
-use oscillator::Oscillator;
-use params::OscillatorType;
+/// Widest osc 2 coarse tuning, in semitones either way.
+pub const MAX_OSC2_SEMITONES: i32 = 24;
+
+/// Widest osc 2 fine tuning, in cents either way.
+pub const MAX_OSC2_FINE_CENTS: f32 = 100.0;
 
 /// Convert a MIDI note number to frequency in Hz.
 pub fn midi_note_to_freq(note: u8) -> f32 {
-    440.0 * 2.0f32.powf((note as f32 - 69.0) / 12.0)
+    midi_pitch_to_freq(note as f32)
+}
+
+/// Convert a fractional MIDI note number (e.g. mid-glide) to frequency in Hz.
+pub fn midi_pitch_to_freq(pitch: f32) -> f32 {
+    440.0 * 2.0f32.powf((pitch - 69.0) / 12.0)
+}
+
+/// Left and right gains for a stereo position `pan` in `[-1, 1]`.
+///
+/// Balance law: at the centre both channels pass at full level and the far
+/// side fades out as the position moves away, so a centred signal comes
+/// out of both channels exactly as it went in.
+pub fn pan_gains(pan: f32) -> (f32, f32) {
+    let pan = pan.clamp(-1.0, 1.0);
+    ((1.0 - pan).min(1.0), (1.0 + pan).min(1.0))
 }
 
-/// A monophonic synthesizer engine.
+/// A polyphonic synthesizer engine.
 ///
 /// This is the shared DSP core that runs identically on native and WASM.
-/// It owns one oscillator and one ADSR envelope, producing mono audio output.
+/// It owns a fixed pool of `MAX_VOICES` voices (each a unison stack of
+/// oscillator pairs, a stereo resonant filter, amplitude and filter envelopes and
+/// a key-synced LFO), of which `voice_count` are used for new notes, and
+/// mixes them down to stereo audio output.
+///
+/// In `VoiceMode::Mono` only the first voice is used, and a stack of held
+/// keys decides which note it plays.
 pub struct Synth {
-    oscillator: Oscillator,
-    envelope: Envelope,
+    voices: [Voice; MAX_VOICES],
+    voice_count: usize,
+    steal_policy: StealPolicy,
+    voice_mode: VoiceMode,
+    note_priority: NotePriority,
+    legato: bool,
+    held_notes: NoteStack,
+    velocity: VelocityResponse,
+    glide: GlideSettings,
+    /// Most recently played note, which the next note glides from.
+    last_note: Option<u8>,
+    /// Free-running LFO shared by every voice; voices run their own in
+    /// key-sync mode.
+    lfo: Lfo,
+    lfo_timing: LfoTiming,
+    lfo_rate: f32,
+    lfo_division: NoteDivision,
+    /// Host tempo in BPM, for tempo-synced LFO rates.
+    tempo: f32,
+    mod_matrix: ModMatrix,
+    wavetable_bank: WavetableBank,
+    /// Table loaded for `WavetableBank::Custom`, if any.
+    custom_wavetable: Option<Arc<Wavetable>>,
+    /// Osc 2 coarse tuning relative to osc 1, in semitones; the fine
+    /// tuning is smoothed.
+    osc2_coarse: i32,
+    /// Current and target values of every `Continuous` parameter.
+    smoothed: [Smoothed; Continuous::ALL.len()],
+    /// Additive partial amplitudes, smoothed like the other continuous
+    /// parameters.
+    partials: [Smoothed; MAX_PARTIALS],
+    /// Mod matrix slot depths, smoothed likewise.
+    mod_depths: [Smoothed; MOD_SLOTS],
+    /// Whether any smoothed value is still ramping.
+    smoothing: bool,
     sample_rate: f32,
-    gain: f32,
-    current_note: Option<u8>,
+    /// Incremented on every note-on; used to find the oldest voice.
+    note_counter: u64,
 }
 
-impl Synth {
-    pub fn new() -> Self {
-        Self {
-            oscillator: Oscillator::new(),
-            envelope: Envelope::new(),
-            sample_rate: 44100.0,
-            gain: 0.8,
-            current_note: None,
+impl Synth {
+    pub fn new() -> Self {
+        Self {
+            voices: core::array::from_fn(|i| {
+                let mut voice = Voice::new();
+                voice.seed_noise(i as u32 + 1);
+                voice
+            }),
+            voice_count: 1,
+            steal_policy: StealPolicy::Oldest,
+            voice_mode: VoiceMode::Poly,
+            note_priority: NotePriority::Last,
+            legato: false,
+            held_notes: NoteStack::new(),
+            velocity: VelocityResponse::new(),
+            glide: GlideSettings::new(),
+            last_note: None,
+            lfo: Lfo::new(),
+            lfo_timing: LfoTiming::Hz,
+            lfo_rate: 5.0,
+            lfo_division: NoteDivision::Quarter,
+            tempo: DEFAULT_TEMPO,
+            mod_matrix: ModMatrix::new(),
+            wavetable_bank: WavetableBank::Basic,
+            custom_wavetable: None,
+            osc2_coarse: 0,
+            smoothed: Continuous::ALL.map(|param| Smoothed::new(param.default_value())),
+            partials: additive::preset_spectrum(AdditivePreset::Sine).map(Smoothed::new),
+            mod_depths: [Smoothed::new(0.0); MOD_SLOTS],
+            smoothing: false,
+            sample_rate: 44100.0,
+            note_counter: 0,
+        }
+    }
+
+    /// Call once when the host provides sample rate and buffer size info.
+    pub fn prepare(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.lfo.set_sample_rate(sample_rate);
+        for voice in self.voices.iter_mut() {
+            voice.set_sample_rate(sample_rate);
+        }
+        let smoothed = self.smoothed.iter_mut();
+        for smoothed in smoothed.chain(&mut self.partials).chain(&mut self.mod_depths) {
+            smoothed.set_sample_rate(sample_rate);
+        }
+    }
+
+    /// Set how many voices new notes may use (1 = monophonic).
+    ///
+    /// Clamped to `1..=MAX_VOICES`. Voices above the new count that are still
+    /// sounding are left to finish naturally; they just won't receive new notes.
+    pub fn set_voice_count(&mut self, count: usize) {
+        self.voice_count = count.clamp(1, MAX_VOICES);
+    }
+
+    pub fn voice_count(&self) -> usize {
+        self.voice_count
+    }
+
+    /// Choose which voice is taken over when a note arrives and none is idle.
+    pub fn set_steal_policy(&mut self, policy: StealPolicy) {
+        self.steal_policy = policy;
+    }
+
+    /// Switch between polyphonic and monophonic note handling.
+    ///
+    /// Changing mode releases every held voice so no note gets stuck
+    /// without a matching note-off path.
+    pub fn set_voice_mode(&mut self, mode: VoiceMode) {
+        if mode == self.voice_mode {
+            return;
+        }
+        self.voice_mode = mode;
+        self.held_notes.clear();
+        for voice in self.voices.iter_mut() {
+            if voice.is_held() {
+                voice.note_off();
+            }
+        }
+    }
+
+    /// Choose which held key sounds in mono mode.
+    pub fn set_note_priority(&mut self, priority: NotePriority) {
+        self.note_priority = priority;
+    }
+
+    /// In mono mode, let overlapping notes change pitch without retriggering
+    /// the envelope.
+    pub fn set_legato(&mut self, legato: bool) {
+        self.legato = legato;
+    }
+
+    pub fn set_velocity_curve(&mut self, curve: VelocityCurve) {
+        self.velocity.set_curve(curve);
+    }
+
+    /// How strongly velocity scales note amplitude (0 = ignore velocity).
+    pub fn set_velocity_amount(&mut self, amount: f32) {
+        self.velocity.set_amount(amount);
+    }
+
+    /// How strongly harder hits shorten the envelope attack.
+    pub fn set_velocity_to_attack(&mut self, amount: f32) {
+        self.velocity.set_to_attack(amount);
+    }
+
+    /// How strongly softer hits darken the tone.
+    pub fn set_velocity_to_brightness(&mut self, amount: f32) {
+        self.velocity.set_to_brightness(amount);
+    }
+
+    /// Portamento time in seconds (0 = off). In constant-rate timing this is
+    /// the time to glide one octave.
+    pub fn set_glide_time(&mut self, seconds: f32) {
+        self.glide.set_time(seconds);
+    }
+
+    /// Glide on every note, or only on notes played while a key is held.
+    pub fn set_glide_mode(&mut self, mode: GlideMode) {
+        self.glide.set_mode(mode);
+    }
+
+    pub fn set_glide_timing(&mut self, timing: GlideTiming) {
+        self.glide.set_timing(timing);
+    }
+
+    /// Number of voices currently producing sound (including release tails).
+    pub fn active_voice_count(&self) -> usize {
+        self.voices.iter().filter(|v| v.is_active()).count()
+    }
+
+    pub fn set_lfo_shape(&mut self, shape: LfoShape) {
+        self.lfo.set_shape(shape);
+        for voice in self.voices.iter_mut() {
+            voice.set_lfo_shape(shape);
+        }
+    }
+
+    pub fn set_lfo_trigger(&mut self, trigger: LfoTrigger) {
+        for voice in self.voices.iter_mut() {
+            voice.set_lfo_trigger(trigger);
+        }
+    }
+
+    pub fn set_lfo_timing(&mut self, timing: LfoTiming) {
+        self.lfo_timing = timing;
+        self.update_lfo_rate();
+    }
+
+    /// LFO rate in Hz, used when the timing is `LfoTiming::Hz`.
+    pub fn set_lfo_rate(&mut self, hz: f32) {
+        self.lfo_rate = hz;
+        self.update_lfo_rate();
+    }
+
+    /// LFO cycle length, used when the timing is `LfoTiming::TempoSync`.
+    pub fn set_lfo_division(&mut self, division: NoteDivision) {
+        self.lfo_division = division;
+        self.update_lfo_rate();
+    }
+
+    /// Host tempo in beats per minute. Non-positive tempos are ignored.
+    pub fn set_tempo(&mut self, bpm: f32) {
+        if bpm > 0.0 {
+            self.tempo = bpm;
+            self.update_lfo_rate();
+        }
+    }
+
+    /// Vibrato depth in semitones, clamped to `0..=MAX_LFO_PITCH_SEMITONES`.
+    pub fn set_lfo_to_pitch(&mut self, semitones: f32) {
+        let semitones = semitones.clamp(0.0, MAX_LFO_PITCH_SEMITONES);
+        self.smooth(Continuous::LfoToPitch, semitones);
+    }
+
+    /// Tremolo depth in `[0, 1]`.
+    pub fn set_lfo_to_amp(&mut self, depth: f32) {
+        self.smooth(Continuous::LfoToAmp, depth.clamp(0.0, 1.0));
+    }
+
+    /// Pulse width modulation depth in `[0, 1]`; only the square wave has a
+    /// pulse width.
+    pub fn set_lfo_to_pulse_width(&mut self, depth: f32) {
+        self.smooth(Continuous::LfoToPulseWidth, depth.clamp(0.0, 1.0));
+    }
+
+    /// Route `source` to `destination` in mod matrix slot `index` with a
+    /// bipolar `depth` in `[-1, 1]`. Indices past `MOD_SLOTS` are ignored.
+    ///
+    /// Depth changes on an unchanged routing are smoothed; a new routing
+    /// takes its depth at once.
+    pub fn set_mod_slot(
+        &mut self,
+        index: usize,
+        source: ModSource,
+        destination: ModDestination,
+        depth: f32,
+    ) {
+        let depth = depth.clamp(-1.0, 1.0);
+        let sounding = self.is_sounding();
+        let Some(smoothed) = self.mod_depths.get_mut(index) else {
+            return;
+        };
+        let slot = self.mod_matrix.slot(index);
+        if slot.source == source && slot.destination == destination && sounding {
+            smoothed.set_target(depth);
+            self.smoothing |= smoothed.is_smoothing();
+            return;
+        }
+        smoothed.reset(depth);
+        self.mod_matrix.set_slot(
+            index,
+            ModSlot {
+                source,
+                destination,
+                depth,
+            },
+        );
+    }
+
+    /// MIDI mod wheel position in `[0, 1]`.
+    pub fn set_mod_wheel(&mut self, value: f32) {
+        self.smooth(Continuous::ModWheel, value.clamp(0.0, 1.0));
+    }
+
+    /// MIDI channel pressure in `[0, 1]`.
+    pub fn set_aftertouch(&mut self, value: f32) {
+        self.smooth(Continuous::Aftertouch, value.clamp(0.0, 1.0));
+    }
+
+    fn update_lfo_rate(&mut self) {
+        let hz = match self.lfo_timing {
+            LfoTiming::Hz => self.lfo_rate,
+            LfoTiming::TempoSync => tempo_rate_hz(self.tempo, self.lfo_division),
+        };
+        self.lfo.set_rate(hz);
+        for voice in self.voices.iter_mut() {
+            voice.set_lfo_rate(hz);
+        }
+    }
+
+    pub fn set_oscillator_type(&mut self, osc_type: OscillatorType) {
+        for voice in self.voices.iter_mut() {
+            voice.set_oscillator_type(osc_type);
+        }
+    }
+
+    /// Sound source of every voice: the oscillators or a plucked string.
+    pub fn set_engine(&mut self, engine: Engine) {
+        for voice in self.voices.iter_mut() {
+            voice.set_engine(engine);
+        }
+    }
+
+    /// What plucks the string: a noise burst or a cycle of osc 1.
+    pub fn set_pluck_excitation(&mut self, excitation: PluckExcitation) {
+        for voice in self.voices.iter_mut() {
+            voice.set_pluck_excitation(excitation);
+        }
+    }
+
+    /// How much faster the string's high harmonics die than its
+    /// fundamental, in `[0, 1]`.
+    pub fn set_pluck_damping(&mut self, damping: f32) {
+        self.smooth(Continuous::PluckDamping, damping.clamp(0.0, 1.0));
+    }
+
+    /// Brightness of the pluck in `[0, 1]`.
+    pub fn set_pluck_brightness(&mut self, brightness: f32) {
+        self.smooth(Continuous::PluckBrightness, brightness.clamp(0.0, 1.0));
+    }
+
+    /// Seconds for the string's fundamental to fall 60 dB, clamped to
+    /// `[MIN_PLUCK_DECAY, MAX_PLUCK_DECAY]`.
+    pub fn set_pluck_decay(&mut self, seconds: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.set_pluck_decay(seconds);
+        }
+    }
+
+    /// Waveform of the second oscillator.
+    pub fn set_osc2_type(&mut self, osc_type: OscillatorType) {
+        for voice in self.voices.iter_mut() {
+            voice.set_osc2_type(osc_type);
+        }
+    }
+
+    /// Osc 2 coarse tuning in semitones, clamped to `±MAX_OSC2_SEMITONES`.
+    pub fn set_osc2_coarse(&mut self, semitones: i32) {
+        self.osc2_coarse = semitones.clamp(-MAX_OSC2_SEMITONES, MAX_OSC2_SEMITONES);
+        self.update_osc2_pitch();
+    }
+
+    /// Osc 2 fine tuning in cents, clamped to `±MAX_OSC2_FINE_CENTS`.
+    pub fn set_osc2_fine(&mut self, cents: f32) {
+        let cents = cents.clamp(-MAX_OSC2_FINE_CENTS, MAX_OSC2_FINE_CENTS);
+        self.smooth(Continuous::Osc2Fine, cents);
+    }
+
+    /// Osc 2 level in `[0, 1]`, mixed with osc 1 at full level. At 0 osc 2
+    /// is off.
+    pub fn set_osc2_level(&mut self, level: f32) {
+        self.smooth(Continuous::Osc2Level, level.clamp(0.0, 1.0));
+    }
+
+    /// Hard sync: restart osc 2's cycle every time osc 1 starts one, so osc 2
+    /// follows osc 1's pitch and its own tuning sweeps the timbre instead.
+    pub fn set_osc2_sync(&mut self, sync: bool) {
+        for voice in self.voices.iter_mut() {
+            voice.set_osc2_sync(sync);
+        }
+    }
+
+    /// Blend from the dry oscillators (0) to osc 1 × osc 2 ring modulation
+    /// (1).
+    pub fn set_ring_mix(&mut self, mix: f32) {
+        self.smooth(Continuous::RingMix, mix.clamp(0.0, 1.0));
+    }
+
+    /// Depth of osc 2's linear frequency modulation of osc 1, clamped to
+    /// `[0, MAX_LINEAR_FM]`. Past 1, osc 1 runs through zero frequency and
+    /// backwards at osc 2's troughs.
+    pub fn set_linear_fm(&mut self, depth: f32) {
+        self.smooth(Continuous::LinearFm, depth.clamp(0.0, MAX_LINEAR_FM));
+    }
+
+    fn update_osc2_pitch(&mut self) {
+        let fine = self.smoothed[Continuous::Osc2Fine as usize].value();
+        let semitones = self.osc2_coarse as f32 + fine / 100.0;
+        for voice in self.voices.iter_mut() {
+            voice.set_osc2_pitch(semitones);
+        }
+    }
+
+    /// Sub-oscillator level in `[0, 1]`; it sits centred under the
+    /// oscillators, before the filter and amp envelope.
+    pub fn set_sub_level(&mut self, level: f32) {
+        self.smooth(Continuous::SubLevel, level.clamp(0.0, 1.0));
+    }
+
+    pub fn set_sub_octave(&mut self, octave: SubOctave) {
+        for voice in self.voices.iter_mut() {
+            voice.set_sub_octave(octave);
+        }
+    }
+
+    pub fn set_sub_shape(&mut self, shape: SubShape) {
+        for voice in self.voices.iter_mut() {
+            voice.set_sub_shape(shape);
+        }
+    }
+
+    /// Noise level in `[0, 1]`, mixed in like the sub-oscillator.
+    pub fn set_noise_level(&mut self, level: f32) {
+        self.smooth(Continuous::NoiseLevel, level.clamp(0.0, 1.0));
+    }
+
+    pub fn set_noise_type(&mut self, noise_type: NoiseType) {
+        for voice in self.voices.iter_mut() {
+            voice.set_noise_type(noise_type);
+        }
+    }
+
+    /// Switch osc 1 to FM: its phase is modulated by a sine modulator
+    /// whose depth follows the FM envelope.
+    pub fn set_fm_enabled(&mut self, enabled: bool) {
+        for voice in self.voices.iter_mut() {
+            voice.set_fm(enabled);
+        }
+    }
+
+    /// Modulator frequency as a multiple of the note's, clamped to
+    /// `[MIN_FM_RATIO, MAX_FM_RATIO]`.
+    pub fn set_fm_ratio(&mut self, ratio: f32) {
+        self.smooth(Continuous::FmRatio, ratio.clamp(MIN_FM_RATIO, MAX_FM_RATIO));
+    }
+
+    /// Hold the modulator at `set_fm_fixed_frequency` instead of tracking
+    /// the note.
+    pub fn set_fm_fixed(&mut self, fixed: bool) {
+        for voice in self.voices.iter_mut() {
+            voice.set_fm_fixed(fixed);
+        }
+    }
+
+    pub fn set_fm_fixed_frequency(&mut self, hz: f32) {
+        let hz = hz.clamp(MIN_FM_FIXED_HZ, MAX_FM_FIXED_HZ);
+        self.smooth(Continuous::FmFixedFrequency, hz);
+    }
+
+    /// Peak modulation index in radians, reached at full FM envelope
+    /// level; clamped to `[0, MAX_FM_INDEX]`.
+    pub fn set_fm_index(&mut self, index: f32) {
+        self.smooth(Continuous::FmIndex, index.clamp(0.0, MAX_FM_INDEX));
+    }
+
+    /// How much the modulator modulates itself, in `[0, 1]`.
+    pub fn set_fm_feedback(&mut self, amount: f32) {
+        self.smooth(Continuous::FmFeedback, amount.clamp(0.0, 1.0));
+    }
+
+    pub fn set_fm_attack(&mut self, seconds: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.fm_envelope_mut().set_attack(seconds);
+        }
+    }
+
+    pub fn set_fm_decay(&mut self, seconds: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.fm_envelope_mut().set_decay(seconds);
+        }
+    }
+
+    pub fn set_fm_sustain(&mut self, level: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.fm_envelope_mut().set_sustain(level);
+        }
+    }
+
+    pub fn set_fm_release(&mut self, seconds: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.fm_envelope_mut().set_release(seconds);
+        }
+    }
+
+    /// Time the FM envelope waits before its attack; 0 skips the delay.
+    pub fn set_fm_delay(&mut self, seconds: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.fm_envelope_mut().set_delay(seconds);
+        }
+    }
+
+    /// Time the FM envelope holds full level before its decay; 0 skips
+    /// the hold.
+    pub fn set_fm_hold(&mut self, seconds: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.fm_envelope_mut().set_hold(seconds);
+        }
+    }
+
+    /// Whether the FM envelope sustains, loops while held or runs as a
+    /// one-shot.
+    pub fn set_fm_envelope_mode(&mut self, mode: EnvelopeMode) {
+        for voice in self.voices.iter_mut() {
+            voice.fm_envelope_mut().set_mode(mode);
+        }
+    }
+
+    /// What a new note does to the FM envelope while it is still running.
+    pub fn set_fm_retrigger_mode(&mut self, mode: RetriggerMode) {
+        for voice in self.voices.iter_mut() {
+            voice.fm_envelope_mut().set_retrigger(mode);
+        }
+    }
+
+    /// FM envelope attack curve; see [`envelope::Envelope::set_attack_curve`].
+    pub fn set_fm_attack_curve(&mut self, curve: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.fm_envelope_mut().set_attack_curve(curve);
+        }
+    }
+
+    /// FM envelope decay curve; see [`envelope::Envelope::set_decay_curve`].
+    pub fn set_fm_decay_curve(&mut self, curve: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.fm_envelope_mut().set_decay_curve(curve);
+        }
+    }
+
+    /// FM envelope release curve; see [`envelope::Envelope::set_release_curve`].
+    pub fn set_fm_release_curve(&mut self, curve: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.fm_envelope_mut().set_release_curve(curve);
+        }
+    }
+
+    /// Square wave duty cycle, clamped to
+    /// `MIN_PULSE_WIDTH..=MAX_PULSE_WIDTH`. LFO and mod matrix pulse width
+    /// modulation move it from here.
+    pub fn set_pulse_width(&mut self, width: f32) {
+        let width = width.clamp(MIN_PULSE_WIDTH, MAX_PULSE_WIDTH);
+        self.smooth(Continuous::PulseWidth, width);
+    }
+
+    /// Table played by the wavetable oscillator.
+    pub fn set_wavetable(&mut self, bank: WavetableBank) {
+        if bank != self.wavetable_bank {
+            self.wavetable_bank = bank;
+            self.apply_wavetable();
+        }
+    }
+
+    /// Install `table` as the `WavetableBank::Custom` table, replacing any
+    /// loaded before. Build it with `Wavetable::from_wav` off the audio
+    /// thread; installing it doesn't allocate.
+    pub fn load_wavetable(&mut self, table: Arc<Wavetable>) {
+        self.custom_wavetable = Some(table);
+        if self.wavetable_bank == WavetableBank::Custom {
+            self.apply_wavetable();
+        }
+    }
+
+    /// Position through the wavetable's frames, 0 (first) to 1 (last).
+    pub fn set_wavetable_position(&mut self, position: f32) {
+        self.smooth(Continuous::WavetablePosition, position.clamp(0.0, 1.0));
+    }
+
+    /// Amplitude of additive partial `index` (0 is the fundamental), in
+    /// `[-1, 1]`. Indices from `MAX_PARTIALS` on are ignored.
+    pub fn set_partial(&mut self, index: usize, amplitude: f32) {
+        let amplitude = amplitude.clamp(-1.0, 1.0);
+        let sounding = self.is_sounding();
+        let Some(partial) = self.partials.get_mut(index) else {
+            return;
+        };
+        if partial.target() == amplitude {
+            return;
+        }
+        if sounding {
+            partial.set_target(amplitude);
+            self.smoothing = true;
+        } else {
+            partial.reset(amplitude);
+            for voice in self.voices.iter_mut() {
+                voice.set_partial(index, amplitude);
+            }
+        }
+    }
+
+    /// Load `preset`'s spectrum into the additive partials.
+    pub fn set_additive_preset(&mut self, preset: AdditivePreset) {
+        for (index, &amplitude) in additive::preset_spectrum(preset).iter().enumerate() {
+            self.set_partial(index, amplitude);
+        }
+    }
+
+    fn apply_wavetable(&mut self) {
+        let table = match (&self.custom_wavetable, self.wavetable_bank) {
+            (Some(custom), WavetableBank::Custom) => custom.clone(),
+            (_, bank) => wavetable::builtin(bank),
+        };
+        for voice in self.voices.iter_mut() {
+            voice.set_wavetable(&table);
+        }
+    }
+
+    /// Number of oscillators stacked on each note, clamped to
+    /// `1..=MAX_UNISON`.
+    pub fn set_unison_voices(&mut self, voices: usize) {
+        for voice in self.voices.iter_mut() {
+            voice.set_unison_voices(voices);
+        }
+    }
+
+    /// Detune of the outermost unison oscillators in cents, clamped to
+    /// `0..=MAX_UNISON_DETUNE_CENTS`.
+    pub fn set_unison_detune(&mut self, cents: f32) {
+        let cents = cents.clamp(0.0, MAX_UNISON_DETUNE_CENTS);
+        self.smooth(Continuous::UnisonDetune, cents);
+    }
+
+    /// Stereo width of the unison stack, 0 (centred) to 1 (outermost
+    /// oscillators hard left and right).
+    pub fn set_unison_spread(&mut self, spread: f32) {
+        self.smooth(Continuous::UnisonSpread, spread.clamp(0.0, 1.0));
+    }
+
+    /// Start unison oscillators at random phases on each note instead of
+    /// all at zero.
+    pub fn set_unison_random_phase(&mut self, random_phase: bool) {
+        for voice in self.voices.iter_mut() {
+            voice.set_unison_random_phase(random_phase);
+        }
+    }
+
+    pub fn set_gain(&mut self, gain: f32) {
+        self.smooth(Continuous::Gain, gain.clamp(0.0, 1.0));
+    }
+
+    /// Stereo position of the output, `-1` (hard left) to `1` (hard right).
+    /// Voices are panned by the mod matrix before this is applied.
+    pub fn set_pan(&mut self, pan: f32) {
+        self.smooth(Continuous::Pan, pan.clamp(-1.0, 1.0));
+    }
+
+    pub fn set_filter_type(&mut self, filter_type: FilterType) {
+        for voice in self.voices.iter_mut() {
+            voice.set_filter_type(filter_type);
+        }
+    }
+
+    /// State-variable filter response. The ladder is always lowpass.
+    pub fn set_filter_mode(&mut self, mode: FilterMode) {
+        for voice in self.voices.iter_mut() {
+            voice.set_filter_mode(mode);
+        }
+    }
+
+    /// Filter cutoff in Hz, before key tracking.
+    pub fn set_filter_cutoff(&mut self, hz: f32) {
+        self.smooth(Continuous::FilterCutoff, hz.max(MIN_CUTOFF_HZ));
+    }
+
+    /// Filter resonance in `[0, 1]`.
+    pub fn set_filter_resonance(&mut self, resonance: f32) {
+        self.smooth(Continuous::FilterResonance, resonance.clamp(0.0, 1.0));
+    }
+
+    /// Ladder filter input drive in `[0, 1]`.
+    pub fn set_filter_drive(&mut self, drive: f32) {
+        self.smooth(Continuous::FilterDrive, drive.clamp(0.0, 1.0));
+    }
+
+    /// How far the cutoff follows the played note, in `[0, 1]` (1 = the
+    /// cutoff moves an octave for every octave played, centred on middle C).
+    pub fn set_filter_keytrack(&mut self, amount: f32) {
+        self.smooth(Continuous::FilterKeytrack, amount.clamp(0.0, 1.0));
+    }
+
+    /// Filter envelope depth in octaves, clamped to
+    /// `±MAX_FILTER_ENV_OCTAVES`. Negative amounts sweep the cutoff down.
+    pub fn set_filter_env_amount(&mut self, octaves: f32) {
+        let octaves = octaves.clamp(-MAX_FILTER_ENV_OCTAVES, MAX_FILTER_ENV_OCTAVES);
+        self.smooth(Continuous::FilterEnvAmount, octaves);
+    }
+
+    pub fn set_filter_attack(&mut self, seconds: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.filter_envelope_mut().set_attack(seconds);
+        }
+    }
+
+    pub fn set_filter_decay(&mut self, seconds: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.filter_envelope_mut().set_decay(seconds);
+        }
+    }
+
+    pub fn set_filter_sustain(&mut self, level: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.filter_envelope_mut().set_sustain(level);
+        }
+    }
+
+    pub fn set_filter_release(&mut self, seconds: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.filter_envelope_mut().set_release(seconds);
+        }
+    }
+
+    /// Time the filter envelope waits before its attack; 0 skips the delay.
+    pub fn set_filter_delay(&mut self, seconds: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.filter_envelope_mut().set_delay(seconds);
+        }
+    }
+
+    /// Time the filter envelope holds full level before its decay; 0 skips
+    /// the hold.
+    pub fn set_filter_hold(&mut self, seconds: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.filter_envelope_mut().set_hold(seconds);
+        }
+    }
+
+    /// Whether the filter envelope sustains, loops while held or runs as a
+    /// one-shot.
+    pub fn set_filter_envelope_mode(&mut self, mode: EnvelopeMode) {
+        for voice in self.voices.iter_mut() {
+            voice.filter_envelope_mut().set_mode(mode);
+        }
+    }
+
+    /// What a new note does to the filter envelope while it is still running.
+    pub fn set_filter_retrigger_mode(&mut self, mode: RetriggerMode) {
+        for voice in self.voices.iter_mut() {
+            voice.filter_envelope_mut().set_retrigger(mode);
+        }
+    }
+
+    /// Filter envelope attack curve; see [`envelope::Envelope::set_attack_curve`].
+    pub fn set_filter_attack_curve(&mut self, curve: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.filter_envelope_mut().set_attack_curve(curve);
+        }
+    }
+
+    /// Filter envelope decay curve; see [`envelope::Envelope::set_decay_curve`].
+    pub fn set_filter_decay_curve(&mut self, curve: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.filter_envelope_mut().set_decay_curve(curve);
+        }
+    }
+
+    /// Filter envelope release curve; see [`envelope::Envelope::set_release_curve`].
+    pub fn set_filter_release_curve(&mut self, curve: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.filter_envelope_mut().set_release_curve(curve);
+        }
+    }
+
+    pub fn set_attack(&mut self, seconds: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.set_attack(seconds);
+        }
+    }
+
+    pub fn set_decay(&mut self, seconds: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.set_decay(seconds);
+        }
+    }
+
+    pub fn set_sustain(&mut self, level: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.envelope_mut().set_sustain(level);
+        }
+    }
+
+    pub fn set_release(&mut self, seconds: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.set_release(seconds);
+        }
+    }
+
+    /// Time the amp envelope waits before its attack; 0 skips the delay.
+    pub fn set_delay(&mut self, seconds: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.envelope_mut().set_delay(seconds);
+        }
+    }
+
+    /// Time the amp envelope holds full level before its decay; 0 skips
+    /// the hold.
+    pub fn set_hold(&mut self, seconds: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.envelope_mut().set_hold(seconds);
+        }
+    }
+
+    /// Whether the amp envelope sustains, loops while held or runs as a
+    /// one-shot.
+    pub fn set_envelope_mode(&mut self, mode: EnvelopeMode) {
+        for voice in self.voices.iter_mut() {
+            voice.envelope_mut().set_mode(mode);
+        }
+    }
+
+    /// What a new note does to the amp envelope while it is still running.
+    pub fn set_retrigger_mode(&mut self, mode: RetriggerMode) {
+        for voice in self.voices.iter_mut() {
+            voice.envelope_mut().set_retrigger(mode);
+        }
+    }
+
+    /// Amp envelope attack curve; see [`envelope::Envelope::set_attack_curve`].
+    pub fn set_attack_curve(&mut self, curve: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.envelope_mut().set_attack_curve(curve);
+        }
+    }
+
+    /// Amp envelope decay curve; see [`envelope::Envelope::set_decay_curve`].
+    pub fn set_decay_curve(&mut self, curve: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.envelope_mut().set_decay_curve(curve);
+        }
+    }
+
+    /// Amp envelope release curve; see [`envelope::Envelope::set_release_curve`].
+    pub fn set_release_curve(&mut self, curve: f32) {
+        for voice in self.voices.iter_mut() {
+            voice.envelope_mut().set_release_curve(curve);
+        }
+    }
+
+    pub fn note_on(&mut self, note: u8, velocity: f32) {
+        if self.voice_mode == VoiceMode::Mono {
+            self.held_notes.push(note);
+            self.update_mono_voice(Some(velocity));
+            return;
+        }
+
+        let from = self.glide_source();
+        let legato = self.voices.iter().any(|v| v.is_held());
+        let Some(index) = self.allocate_voice(note) else {
+            // Every voice is held and the steal policy refuses new notes
+            return;
+        };
+        self.note_counter += 1;
+        self.voices[index].note_on(note, velocity, self.note_counter, &self.velocity);
+        self.glide_voice(index, from, legato);
+    }
+
+    pub fn note_off(&mut self, note: u8) {
+        if self.voice_mode == VoiceMode::Mono {
+            self.held_notes.remove(note);
+            self.update_mono_voice(None);
+            return;
+        }
+
+        // Only release voices that are still held on this note
+        for voice in self.voices.iter_mut() {
+            if voice.is_held() && voice.note() == note {
+                voice.note_off();
+            }
+        }
+    }
+
+    /// Make the mono voice follow the held-key stack.
+    ///
+    /// With keys still held, the voice plays whichever one the note priority
+    /// selects, gliding over without retrigger when legato is on. With no keys
+    /// held, the voice is released. `velocity` is the new key's velocity, or
+    /// `None` when falling back after a release (the previous velocity is kept).
+    fn update_mono_voice(&mut self, velocity: Option<f32>) {
+        let from = self.glide_source();
+        let voice = &mut self.voices[0];
+        let velocity = velocity.unwrap_or(voice.velocity());
+        let held = voice.is_held();
+        match self.held_notes.select(self.note_priority) {
+            Some(target) if held && voice.note() == target => return,
+            Some(target) if held && self.legato => voice.change_note(target),
+            Some(target) => {
+                self.note_counter += 1;
+                voice.note_on(target, velocity, self.note_counter, &self.velocity);
+            }
+            None => {
+                if held {
+                    voice.note_off();
+                }
+                return;
+            }
+        }
+        self.glide_voice(0, from, held);
+    }
+
+    /// Pitch a new note glides from: wherever the most recent note is
+    /// sounding now (part-way through its own glide, if it is still sliding),
+    /// or the last note played once that voice has finished.
+    fn glide_source(&self) -> Option<f32> {
+        self.voices
+            .iter()
+            .find(|v| v.is_active() && v.started_at() == self.note_counter)
+            .map(|v| v.pitch())
+            .or(self.last_note.map(f32::from))
+    }
+
+    /// Start the portamento on a voice that was just given a new note, if the
+    /// glide settings call for one. `legato` is true when a key was already
+    /// held as the note arrived.
+    fn glide_voice(&mut self, index: usize, from: Option<f32>, legato: bool) {
+        let voice = &mut self.voices[index];
+        if let Some(from) = from {
+            if self.glide.applies(legato) {
+                voice.glide_from(from, &self.glide);
+            }
+        }
+        self.last_note = Some(voice.note());
+    }
+
+    /// Pick the voice a new note should play on.
+    ///
+    /// Preference order: a voice already holding this note (retrigger), then
+    /// an idle voice, then a voice chosen by the steal policy. Returns `None`
+    /// if the policy refuses to steal.
+    fn allocate_voice(&self, note: u8) -> Option<usize> {
+        let pool = &self.voices[..self.voice_count];
+
+        if let Some(i) = pool.iter().position(|v| v.is_held() && v.note() == note) {
+            return Some(i);
+        }
+        if let Some(i) = pool.iter().position(|v| !v.is_active()) {
+            return Some(i);
+        }
+        self.steal_voice()
+    }
+
+    /// Choose a busy voice to take over according to `steal_policy`.
+    ///
+    /// Voices in their release stage are reclaimed before held voices, so
+    /// the policy only has to pick among held notes when all voices are held.
+    fn steal_voice(&self) -> Option<usize> {
+        let pool = &self.voices[..self.voice_count];
+        let any_releasing = pool.iter().any(|v| !v.is_held());
+        let candidates = pool
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| v.is_held() != any_releasing);
+
+        match self.steal_policy {
+            StealPolicy::Oldest => candidates.min_by_key(|(_, v)| v.started_at()),
+            StealPolicy::Quietest => {
+                candidates.min_by(|(_, a), (_, b)| a.level().total_cmp(&b.level()))
+            }
+            StealPolicy::Lowest => candidates.min_by_key(|(_, v)| v.note()),
+            StealPolicy::Highest => candidates.max_by_key(|(_, v)| v.note()),
+            StealPolicy::Refuse if any_releasing => {
+                candidates.min_by_key(|(_, v)| v.started_at())
+            }
+            StealPolicy::Refuse => None,
+        }
+        .map(|(i, _)| i)
+    }
+
+    /// Fill `output` with mono audio samples: the stereo mix folded down to
+    /// one channel. No allocations.
+    pub fn process(&mut self, output: &mut [f32]) {
+        for sample in output.iter_mut() {
+            let (left, right) = self.tick();
+            *sample = (left + right) * 0.5;
+        }
+    }
+
+    /// Render stereo audio into `left` and `right`, which must be the same
+    /// length. No allocations.
+    pub fn process_stereo(&mut self, left: &mut [f32], right: &mut [f32]) {
+        debug_assert_eq!(left.len(), right.len());
+        for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+            (*l, *r) = self.tick();
+        }
+    }
+
+    /// Produce the next left and right samples.
+    fn tick(&mut self) -> (f32, f32) {
+        if self.smoothing {
+            self.advance_smoothing();
+        }
+        let mut left = 0.0;
+        let mut right = 0.0;
+        let shared = ModSources {
+            lfo: self.lfo.tick(),
+            mod_wheel: self.value(Continuous::ModWheel),
+            aftertouch: self.value(Continuous::Aftertouch),
+            ..ModSources::default()
+        };
+        for voice in self.voices.iter_mut() {
+            if voice.is_active() {
+                let (l, r) = voice.tick(&shared, &self.mod_matrix);
+                left += l;
+                right += r;
+            }
+        }
+        let gain = self.value(Continuous::Gain);
+        let (pan_left, pan_right) = pan_gains(self.value(Continuous::Pan));
+        (left * gain * pan_left, right * gain * pan_right)
+    }
+
+    /// Whether any voice is making sound, so parameter changes need
+    /// smoothing. While silent they apply at once.
+    fn is_sounding(&self) -> bool {
+        self.voices.iter().any(Voice::is_active)
+    }
+
+    /// Current value of `param`, in the units its setter takes.
+    fn value(&self, param: Continuous) -> f32 {
+        let value = self.smoothed[param as usize].value();
+        if param.is_logarithmic() {
+            value.exp2()
+        } else {
+            value
+        }
+    }
+
+    /// Move `param` to `value`: over `SMOOTHING_SECONDS` while a voice is
+    /// sounding, immediately otherwise.
+    fn smooth(&mut self, param: Continuous, value: f32) {
+        let value = if param.is_logarithmic() {
+            value.log2()
+        } else {
+            value
+        };
+        if self.smoothed[param as usize].target() == value {
+            return;
+        }
+        if self.is_sounding() {
+            self.smoothed[param as usize].set_target(value);
+            self.smoothing = true;
+        } else {
+            self.smoothed[param as usize].reset(value);
+            self.apply(param);
+        }
+    }
+
+    /// Step every ramping value on by one sample and pass it on.
+    fn advance_smoothing(&mut self) {
+        let mut smoothing = false;
+        for param in Continuous::ALL {
+            let smoothed = &mut self.smoothed[param as usize];
+            if smoothed.is_smoothing() {
+                smoothed.tick();
+                smoothing |= smoothed.is_smoothing();
+                self.apply(param);
+            }
+        }
+        for (index, partial) in self.partials.iter_mut().enumerate() {
+            if partial.is_smoothing() {
+                let amplitude = partial.tick();
+                smoothing |= partial.is_smoothing();
+                for voice in self.voices.iter_mut() {
+                    voice.set_partial(index, amplitude);
+                }
+            }
+        }
+        for (index, depth) in self.mod_depths.iter_mut().enumerate() {
+            if depth.is_smoothing() {
+                let slot = ModSlot {
+                    depth: depth.tick(),
+                    ..self.mod_matrix.slot(index)
+                };
+                smoothing |= depth.is_smoothing();
+                self.mod_matrix.set_slot(index, slot);
+            }
+        }
+        self.smoothing = smoothing;
+    }
+
+    /// Send `param`'s current value to wherever it takes effect.
+    fn apply(&mut self, param: Continuous) {
+        let value = self.value(param);
+        for voice in self.voices.iter_mut() {
+            match param {
+                // Read by `tick` every sample
+                Continuous::Gain
+                | Continuous::Pan
+                | Continuous::ModWheel
+                | Continuous::Aftertouch => return,
+                Continuous::LfoToPitch => voice.set_lfo_to_pitch(value),
+                Continuous::LfoToAmp => voice.set_lfo_to_amp(value),
+                Continuous::LfoToPulseWidth => voice.set_lfo_to_pulse_width(value),
+                Continuous::PluckDamping => voice.set_pluck_damping(value),
+                Continuous::PluckBrightness => voice.set_pluck_brightness(value),
+                Continuous::Osc2Fine => {
+                    voice.set_osc2_pitch(self.osc2_coarse as f32 + value / 100.0)
+                }
+                Continuous::Osc2Level => voice.set_osc2_level(value),
+                Continuous::RingMix => voice.set_ring_mix(value),
+                Continuous::LinearFm => voice.set_linear_fm(value),
+                Continuous::SubLevel => voice.set_sub_level(value),
+                Continuous::NoiseLevel => voice.set_noise_level(value),
+                Continuous::FmRatio => voice.set_fm_ratio(value),
+                Continuous::FmFixedFrequency => voice.set_fm_fixed_frequency(value),
+                Continuous::FmIndex => voice.set_fm_index(value),
+                Continuous::FmFeedback => voice.set_fm_feedback(value),
+                Continuous::PulseWidth => voice.set_pulse_width(value),
+                Continuous::WavetablePosition => voice.set_wavetable_position(value),
+                Continuous::UnisonDetune => voice.set_unison_detune(value),
+                Continuous::UnisonSpread => voice.set_unison_spread(value),
+                Continuous::FilterCutoff => voice.set_filter_cutoff(value),
+                Continuous::FilterResonance => voice.set_filter_resonance(value),
+                Continuous::FilterDrive => voice.set_filter_drive(value),
+                Continuous::FilterKeytrack => voice.set_filter_keytrack(value),
+                Continuous::FilterEnvAmount => voice.set_filter_env_amount(value),
+            }
+        }
+    }
+}
+
+/// Parameters `Synth` smooths per sample rather than applying as they
+/// arrive, so block-rate changes don't step the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Continuous {
+    Gain,
+    Pan,
+    ModWheel,
+    Aftertouch,
+    LfoToPitch,
+    LfoToAmp,
+    LfoToPulseWidth,
+    PluckDamping,
+    PluckBrightness,
+    Osc2Fine,
+    Osc2Level,
+    RingMix,
+    LinearFm,
+    SubLevel,
+    NoiseLevel,
+    FmRatio,
+    FmFixedFrequency,
+    FmIndex,
+    FmFeedback,
+    PulseWidth,
+    WavetablePosition,
+    UnisonDetune,
+    UnisonSpread,
+    FilterCutoff,
+    FilterResonance,
+    FilterDrive,
+    FilterKeytrack,
+    FilterEnvAmount,
+}
+
+impl Continuous {
+    const ALL: [Continuous; 28] = [
+        Continuous::Gain,
+        Continuous::Pan,
+        Continuous::ModWheel,
+        Continuous::Aftertouch,
+        Continuous::LfoToPitch,
+        Continuous::LfoToAmp,
+        Continuous::LfoToPulseWidth,
+        Continuous::PluckDamping,
+        Continuous::PluckBrightness,
+        Continuous::Osc2Fine,
+        Continuous::Osc2Level,
+        Continuous::RingMix,
+        Continuous::LinearFm,
+        Continuous::SubLevel,
+        Continuous::NoiseLevel,
+        Continuous::FmRatio,
+        Continuous::FmFixedFrequency,
+        Continuous::FmIndex,
+        Continuous::FmFeedback,
+        Continuous::PulseWidth,
+        Continuous::WavetablePosition,
+        Continuous::UnisonDetune,
+        Continuous::UnisonSpread,
+        Continuous::FilterCutoff,
+        Continuous::FilterResonance,
+        Continuous::FilterDrive,
+        Continuous::FilterKeytrack,
+        Continuous::FilterEnvAmount,
+    ];
+
+    /// The filter cutoff is smoothed in octaves, so a sweep moves evenly in
+    /// pitch instead of rushing through the low end. The FM ratio and fixed
+    /// frequency ramp linearly, keeping the FM path clear of the maths
+    /// library.
+    fn is_logarithmic(self) -> bool {
+        self == Continuous::FilterCutoff
+    }
+
+    /// Value before any setter is called, matching what the voices start
+    /// with. Logarithmic parameters are given in octaves.
+    fn default_value(self) -> f32 {
+        match self {
+            Continuous::Gain => 0.8,
+            Continuous::PluckDamping | Continuous::PluckBrightness | Continuous::PulseWidth => 0.5,
+            Continuous::FmRatio => 1.0,
+            Continuous::FmFixedFrequency => 100.0,
+            Continuous::FilterCutoff => 20000.0f32.log2(),
+            _ => 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_midi_note_to_freq_a4() {
+        assert!((midi_note_to_freq(69) - 440.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_midi_note_to_freq_c4() {
+        assert!((midi_note_to_freq(60) - 261.63).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_midi_note_to_freq_a3() {
+        assert!((midi_note_to_freq(57) - 220.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_pan_gains() {
+        assert_eq!(pan_gains(0.0), (1.0, 1.0));
+        assert_eq!(pan_gains(-1.0), (1.0, 0.0));
+        assert_eq!(pan_gains(0.5), (0.5, 1.0));
+        assert_eq!(pan_gains(7.0), (0.0, 1.0));
+    }
+
+    #[test]
+    fn test_midi_note_to_freq_octave_relationship() {
+        let a4 = midi_note_to_freq(69);
+        let a5 = midi_note_to_freq(81);
+        let a3 = midi_note_to_freq(57);
+        assert!((a5 - 2.0 * a4).abs() < 0.01, "A5 should be 2x A4");
+        assert!((a3 - a4 / 2.0).abs() < 0.01, "A3 should be A4/2");
+    }
+
+    #[test]
+    fn test_midi_note_to_freq_extremes() {
+        let low = midi_note_to_freq(0);
+        let high = midi_note_to_freq(127);
+        assert!(low > 0.0 && low.is_finite(), "note 0 freq: {}", low);
+        assert!(high > 0.0 && high.is_finite(), "note 127 freq: {}", high);
+    }
+
+    #[test]
+    fn test_note_on_produces_nonsilent_output() {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.note_on(69, 0.8);
+        let mut buf = [0.0f32; 512];
+        synth.process(&mut buf);
+        assert!(
+            buf.iter().any(|s| s.abs() > 0.001),
+            "note_on should produce sound"
+        );
+    }
+
+    #[test]
+    fn test_note_off_wrong_note_keeps_playing() {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.note_on(69, 0.8);
+        let mut buf = [0.0f32; 256];
+        synth.process(&mut buf);
+        synth.note_off(60);
+        let mut buf2 = [0.0f32; 256];
+        synth.process(&mut buf2);
+        assert!(
+            buf2.iter().any(|s| s.abs() > 0.001),
+            "wrong note_off should not stop playback"
+        );
+    }
+
+    #[test]
+    fn test_note_off_correct_note_eventually_silent() {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.set_release(0.01);
+        synth.note_on(69, 0.8);
+        let mut buf = [0.0f32; 4410];
+        synth.process(&mut buf);
+        synth.note_off(69);
+        let mut buf2 = [0.0f32; 1000];
+        synth.process(&mut buf2);
+        assert!(
+            buf2[900..].iter().all(|s| s.abs() < 1e-6),
+            "should be silent after release"
+        );
+    }
+
+    #[test]
+    fn test_process_empty_buffer_is_safe() {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.process(&mut []);
+    }
+
+    #[test]
+    fn test_set_gain_zero_produces_silence() {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.set_gain(0.0);
+        synth.note_on(69, 0.8);
+        let mut buf = [0.0f32; 512];
+        synth.process(&mut buf);
+        assert!(
+            buf.iter().all(|s| *s == 0.0),
+            "gain=0 should produce silence"
+        );
+    }
+
+    #[test]
+    fn test_set_gain_one_produces_full_volume() {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.set_gain(1.0);
+        synth.note_on(69, 0.8);
+        let mut buf = [0.0f32; 512];
+        synth.process(&mut buf);
+        assert!(
+            buf.iter().any(|s| s.abs() > 0.5),
+            "gain=1 should produce audible output"
+        );
+    }
+
+    #[test]
+    fn test_set_gain_scales_output() {
+        let mut synth_full = Synth::new();
+        synth_full.prepare(44100.0);
+        synth_full.set_gain(1.0);
+        synth_full.note_on(69, 0.8);
+        let mut buf_full = [0.0f32; 512];
+        synth_full.process(&mut buf_full);
+
+        let mut synth_half = Synth::new();
+        synth_half.prepare(44100.0);
+        synth_half.set_gain(0.5);
+        synth_half.note_on(69, 0.8);
+        let mut buf_half = [0.0f32; 512];
+        synth_half.process(&mut buf_half);
+
+        for i in 0..512 {
+            let expected = buf_full[i] * 0.5;
+            assert!(
+                (buf_half[i] - expected).abs() < 1e-6,
+                "sample {}: half={} expected={}",
+                i,
+                buf_half[i],
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_oscillator_type_switching() {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.note_on(69, 0.8);
+        let mut buf_sine = [0.0f32; 512];
+        synth.process(&mut buf_sine);
+
+        synth.set_oscillator_type(OscillatorType::Saw);
+        let mut buf_saw = [0.0f32; 512];
+        synth.process(&mut buf_saw);
+
+        assert!(
+            buf_saw.iter().any(|s| s.abs() > 0.001),
+            "saw should produce sound"
+        );
+        assert_ne!(buf_sine, buf_saw, "different waveforms should differ");
+    }
+
+    #[test]
+    fn test_prepare_sets_sample_rate() {
+        let mut synth = Synth::new();
+        synth.prepare(48000.0);
+        synth.note_on(69, 0.8);
+        let mut buf = [0.0f32; 48000];
+        synth.process(&mut buf);
+        let crossings = buf
+            .windows(2)
+            .filter(|w| (w[0] >= 0.0) != (w[1] >= 0.0))
+            .count();
+        // 440 Hz sine = 880 crossings/sec
+        assert!(
+            (crossings as i32 - 880).abs() <= 4,
+            "expected ~880 crossings at 48kHz, got {}",
+            crossings
+        );
+    }
+
+    #[test]
+    fn test_default_voice_count_is_one() {
+        let synth = Synth::new();
+        assert_eq!(synth.voice_count(), 1);
+    }
+
+    #[test]
+    fn test_set_voice_count_clamps() {
+        let mut synth = Synth::new();
+        synth.set_voice_count(0);
+        assert_eq!(synth.voice_count(), 1);
+        synth.set_voice_count(MAX_VOICES + 10);
+        assert_eq!(synth.voice_count(), MAX_VOICES);
+        synth.set_voice_count(4);
+        assert_eq!(synth.voice_count(), 4);
+    }
+
+    #[test]
+    fn test_single_voice_collapses_chord() {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.set_voice_count(1);
+        synth.note_on(60, 0.8);
+        synth.note_on(64, 0.8);
+        synth.note_on(67, 0.8);
+        assert_eq!(synth.active_voice_count(), 1);
+    }
+
+    #[test]
+    fn test_chord_uses_one_voice_per_note() {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.set_voice_count(8);
+        synth.note_on(60, 0.8);
+        synth.note_on(64, 0.8);
+        synth.note_on(67, 0.8);
+        assert_eq!(synth.active_voice_count(), 3);
+    }
+
+    #[test]
+    fn test_chord_is_sum_of_individual_notes() {
+        let render = |notes: &[u8]| {
+            let mut synth = Synth::new();
+            synth.prepare(44100.0);
+            synth.set_voice_count(8);
+            for &note in notes {
+                synth.note_on(note, 0.8);
+            }
+            let mut buf = [0.0f32; 512];
+            synth.process(&mut buf);
+            buf
+        };
+        let chord = render(&[60, 64]);
+        let c = render(&[60]);
+        let e = render(&[64]);
+        for i in 0..512 {
+            assert!(
+                (chord[i] - (c[i] + e[i])).abs() < 1e-5,
+                "sample {}: chord={} sum={}",
+                i,
+                chord[i],
+                c[i] + e[i]
+            );
+        }
+    }
+
+    #[test]
+    fn test_voice_count_limits_active_voices() {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.set_voice_count(2);
+        for note in 60..66 {
+            synth.note_on(note, 0.8);
+        }
+        assert_eq!(synth.active_voice_count(), 2);
+    }
+
+    #[test]
+    fn test_same_note_retriggers_existing_voice() {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.set_voice_count(8);
+        synth.note_on(60, 0.8);
+        synth.note_on(60, 0.8);
+        assert_eq!(synth.active_voice_count(), 1);
+    }
+
+    #[test]
+    fn test_note_off_releases_only_matching_voice() {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.set_voice_count(8);
+        synth.set_release(0.01);
+        synth.note_on(60, 0.8);
+        synth.note_on(64, 0.8);
+        synth.note_off(60);
+        let mut buf = [0.0f32; 4410];
+        synth.process(&mut buf);
+        assert_eq!(synth.active_voice_count(), 1);
+        assert!(
+            buf[4000..].iter().any(|s| s.abs() > 0.001),
+            "held note should keep sounding"
+        );
+    }
+
+    #[test]
+    fn test_reducing_voice_count_keeps_sounding_voices() {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.set_voice_count(4);
+        synth.note_on(60, 0.8);
+        synth.note_on(64, 0.8);
+        synth.note_on(67, 0.8);
+        synth.set_voice_count(1);
+        assert_eq!(synth.active_voice_count(), 3);
+        // Releasing still works for voices above the new count
+        synth.set_release(0.001);
+        synth.note_off(67);
+        let mut buf = [0.0f32; 512];
+        synth.process(&mut buf);
+        assert_eq!(synth.active_voice_count(), 2);
+    }
+
+    // --- Voice stealing ---
+
+    fn held_notes(synth: &Synth) -> Vec<u8> {
+        let mut notes: Vec<u8> = synth
+            .voices
+            .iter()
+            .filter(|v| v.is_held())
+            .map(|v| v.note())
+            .collect();
+        notes.sort();
+        notes
+    }
+
+    /// Three voices, all held on a C major triad played in the given order.
+    fn full_synth(policy: StealPolicy, notes: [u8; 3]) -> Synth {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.set_voice_count(3);
+        synth.set_steal_policy(policy);
+        let mut buf = [0.0f32; 64];
+        for note in notes {
+            synth.note_on(note, 0.8);
+            synth.process(&mut buf);
+        }
+        synth
+    }
+
+    #[test]
+    fn test_steal_oldest() {
+        let mut synth = full_synth(StealPolicy::Oldest, [64, 60, 67]);
+        synth.note_on(72, 0.8);
+        assert_eq!(held_notes(&synth), vec![60, 67, 72]);
+    }
+
+    #[test]
+    fn test_steal_quietest() {
+        // Mid-attack, the most recently started note has the lowest level
+        let mut synth = full_synth(StealPolicy::Quietest, [60, 67, 64]);
+        synth.note_on(72, 0.8);
+        assert_eq!(held_notes(&synth), vec![60, 67, 72]);
+    }
+
+    #[test]
+    fn test_steal_lowest() {
+        let mut synth = full_synth(StealPolicy::Lowest, [64, 60, 67]);
+        synth.note_on(72, 0.8);
+        assert_eq!(held_notes(&synth), vec![64, 67, 72]);
+    }
+
+    #[test]
+    fn test_steal_highest() {
+        let mut synth = full_synth(StealPolicy::Highest, [64, 67, 60]);
+        synth.note_on(72, 0.8);
+        assert_eq!(held_notes(&synth), vec![60, 64, 72]);
+    }
+
+    #[test]
+    fn test_steal_refuse_drops_new_note() {
+        let mut synth = full_synth(StealPolicy::Refuse, [60, 64, 67]);
+        synth.note_on(72, 0.8);
+        assert_eq!(held_notes(&synth), vec![60, 64, 67]);
+        assert_eq!(synth.active_voice_count(), 3);
+    }
+
+    #[test]
+    fn test_steal_prefers_releasing_voice() {
+        let mut synth = full_synth(StealPolicy::Highest, [60, 64, 67]);
+        synth.note_off(64);
+        synth.note_on(72, 0.8);
+        assert_eq!(held_notes(&synth), vec![60, 67, 72]);
+    }
+
+    #[test]
+    fn test_steal_refuse_still_reclaims_releasing_voice() {
+        let mut synth = full_synth(StealPolicy::Refuse, [60, 64, 67]);
+        synth.note_off(60);
+        synth.note_on(72, 0.8);
+        assert_eq!(held_notes(&synth), vec![64, 67, 72]);
+    }
+
+    #[test]
+    fn test_idle_voice_used_before_stealing() {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.set_voice_count(3);
+        synth.set_steal_policy(StealPolicy::Refuse);
+        synth.note_on(60, 0.8);
+        synth.note_on(64, 0.8);
+        synth.note_on(67, 0.8);
+        assert_eq!(held_notes(&synth), vec![60, 64, 67]);
+    }
+
+    // --- Mono mode ---
+
+    fn mono_synth(priority: NotePriority, legato: bool) -> Synth {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.set_voice_mode(VoiceMode::Mono);
+        synth.set_note_priority(priority);
+        synth.set_legato(legato);
+        synth
+    }
+
+    fn mono_note(synth: &Synth) -> Option<u8> {
+        let voice = &synth.voices[0];
+        voice.is_held().then(|| voice.note())
+    }
+
+    #[test]
+    fn test_mono_trill_falls_back_to_held_note() {
+        let mut synth = mono_synth(NotePriority::Last, false);
+        synth.note_on(60, 0.8); // hold C
+        synth.note_on(62, 0.8); // tap D
+        assert_eq!(mono_note(&synth), Some(62));
+        synth.note_off(62); // release D
+        assert_eq!(mono_note(&synth), Some(60), "should fall back to C");
+        let mut buf = [0.0f32; 512];
+        synth.process(&mut buf);
+        assert!(buf.iter().any(|s| s.abs() > 0.001), "C should still sound");
+    }
+
+    #[test]
+    fn test_mono_releasing_last_key_releases_voice() {
+        let mut synth = mono_synth(NotePriority::Last, false);
+        synth.note_on(60, 0.8);
+        synth.note_on(62, 0.8);
+        synth.note_off(62);
+        synth.note_off(60);
+        assert_eq!(mono_note(&synth), None);
+    }
+
+    #[test]
+    fn test_mono_uses_single_voice() {
+        let mut synth = mono_synth(NotePriority::Last, false);
+        synth.set_voice_count(8);
+        synth.note_on(60, 0.8);
+        synth.note_on(64, 0.8);
+        synth.note_on(67, 0.8);
+        assert_eq!(synth.active_voice_count(), 1);
+    }
+
+    #[test]
+    fn test_mono_low_priority() {
+        let mut synth = mono_synth(NotePriority::Low, false);
+        synth.note_on(64, 0.8);
+        synth.note_on(60, 0.8);
+        assert_eq!(mono_note(&synth), Some(60));
+        synth.note_on(67, 0.8);
+        assert_eq!(mono_note(&synth), Some(60), "higher note should not steal");
+        synth.note_off(60);
+        assert_eq!(mono_note(&synth), Some(64));
+    }
+
+    #[test]
+    fn test_mono_high_priority() {
+        let mut synth = mono_synth(NotePriority::High, false);
+        synth.note_on(64, 0.8);
+        synth.note_on(60, 0.8);
+        assert_eq!(mono_note(&synth), Some(64), "lower note should not steal");
+        synth.note_on(67, 0.8);
+        assert_eq!(mono_note(&synth), Some(67));
+        synth.note_off(67);
+        assert_eq!(mono_note(&synth), Some(64));
+    }
+
+    #[test]
+    fn test_mono_non_sounding_note_off_is_ignored() {
+        let mut synth = mono_synth(NotePriority::Low, false);
+        synth.note_on(60, 0.8);
+        synth.note_on(67, 0.8);
+        synth.note_off(67);
+        assert_eq!(mono_note(&synth), Some(60));
+    }
+
+    #[test]
+    fn test_mono_legato_does_not_retrigger_envelope() {
+        let mut synth = mono_synth(NotePriority::Last, true);
+        synth.note_on(60, 0.8);
+        let mut buf = [0.0f32; 441 + 4410 + 100];
+        synth.process(&mut buf); // into sustain
+        let level_before = synth.voices[0].level();
+        synth.note_on(62, 0.8);
+        synth.process(&mut buf[..10]);
+        assert_eq!(mono_note(&synth), Some(62));
+        assert!(
+            (synth.voices[0].level() - level_before).abs() < 1e-6,
+            "legato should hold the sustain level: {} -> {}",
+            level_before,
+            synth.voices[0].level()
+        );
+    }
+
+    #[test]
+    fn test_mono_without_legato_retriggers_envelope() {
+        let mut synth = mono_synth(NotePriority::Last, false);
+        synth.note_on(60, 0.8);
+        let mut buf = [0.0f32; 441 + 4410 + 100];
+        synth.process(&mut buf); // into sustain
+        let level_before = synth.voices[0].level();
+        synth.note_on(62, 0.8);
+        synth.process(&mut buf[..10]);
+        assert!(
+            synth.voices[0].level() > level_before,
+            "new note should restart the attack"
+        );
+    }
+
+    #[test]
+    fn test_mono_legato_fallback_does_not_retrigger() {
+        let mut synth = mono_synth(NotePriority::Last, true);
+        synth.note_on(60, 0.8);
+        let mut buf = [0.0f32; 441 + 4410 + 100];
+        synth.process(&mut buf);
+        synth.note_on(62, 0.8);
+        synth.note_off(62);
+        synth.process(&mut buf[..10]);
+        assert_eq!(mono_note(&synth), Some(60));
+        assert!((synth.voices[0].level() - 0.7).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_switching_voice_mode_releases_held_voices() {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.set_voice_count(4);
+        synth.note_on(60, 0.8);
+        synth.note_on(64, 0.8);
+        synth.set_voice_mode(VoiceMode::Mono);
+        assert!(held_notes(&synth).is_empty());
+        synth.note_on(67, 0.8);
+        synth.set_voice_mode(VoiceMode::Poly);
+        assert!(held_notes(&synth).is_empty());
+    }
+
+    // --- Velocity ---
+
+    fn render_note(synth: &mut Synth, velocity: f32) -> f32 {
+        synth.prepare(44100.0);
+        synth.note_on(69, velocity);
+        let mut buf = [0.0f32; 2048];
+        synth.process(&mut buf);
+        buf.iter().fold(0.0f32, |a, &b| a.max(b.abs()))
+    }
+
+    #[test]
+    fn test_velocity_scales_output() {
+        let loud = render_note(&mut Synth::new(), 1.0);
+        let soft = render_note(&mut Synth::new(), 0.5);
+        assert!((soft / loud - 0.5).abs() < 0.01, "ratio {}", soft / loud);
+    }
+
+    #[test]
+    fn test_fixed_velocity_curve_ignores_velocity() {
+        let mut a = Synth::new();
+        a.set_velocity_curve(VelocityCurve::Fixed);
+        let mut b = Synth::new();
+        b.set_velocity_curve(VelocityCurve::Fixed);
+        assert_eq!(render_note(&mut a, 1.0), render_note(&mut b, 0.1));
+    }
+
+    #[test]
+    fn test_velocity_amount_zero_ignores_velocity() {
+        let mut a = Synth::new();
+        a.set_velocity_amount(0.0);
+        let mut b = Synth::new();
+        b.set_velocity_amount(0.0);
+        assert_eq!(render_note(&mut a, 1.0), render_note(&mut b, 0.1));
+    }
+
+    #[test]
+    fn test_mono_fallback_keeps_previous_velocity() {
+        let mut synth = mono_synth(NotePriority::Last, false);
+        synth.note_on(60, 0.3);
+        synth.note_on(62, 0.9);
+        synth.note_off(62);
+        assert_eq!(synth.voices[0].velocity(), 0.9);
+    }
+
+    // --- Glide ---
+
+    fn glide_synth(time: f32, mode: GlideMode) -> Synth {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.set_voice_count(4);
+        synth.set_glide_time(time);
+        synth.set_glide_mode(mode);
+        synth
+    }
+
+    /// Pitch of the voice holding `note`.
+    fn pitch_of(synth: &Synth, note: u8) -> f32 {
+        synth
+            .voices
+            .iter()
+            .find(|v| v.is_held() && v.note() == note)
+            .map(|v| v.pitch())
+            .expect("note should be held")
+    }
+
+    fn run(synth: &mut Synth, samples: usize) {
+        let mut buf = [0.0f32; 441];
+        for _ in 0..samples / buf.len() {
+            synth.process(&mut buf);
+        }
+    }
+
+    #[test]
+    fn test_zero_glide_time_jumps_to_pitch() {
+        let mut synth = glide_synth(0.0, GlideMode::Always);
+        synth.note_on(60, 0.8);
+        synth.note_on(72, 0.8);
+        assert_eq!(pitch_of(&synth, 72), 72.0);
+    }
+
+    #[test]
+    fn test_glide_always_slides_from_previous_note() {
+        let mut synth = glide_synth(0.1, GlideMode::Always);
+        synth.note_on(60, 0.8);
+        synth.note_off(60);
+        synth.note_on(72, 0.8);
+        assert_eq!(pitch_of(&synth, 72), 60.0);
+        // Half of the 0.1 s glide time
+        run(&mut synth, 2205);
+        assert!((pitch_of(&synth, 72) - 66.0).abs() < 0.1, "{}", pitch_of(&synth, 72));
+        run(&mut synth, 2646);
+        assert_eq!(pitch_of(&synth, 72), 72.0);
+    }
+
+    #[test]
+    fn test_first_note_does_not_glide() {
+        let mut synth = glide_synth(0.1, GlideMode::Always);
+        synth.note_on(72, 0.8);
+        assert_eq!(pitch_of(&synth, 72), 72.0);
+    }
+
+    #[test]
+    fn test_glide_legato_mode_only_glides_overlapping_notes() {
+        let mut synth = glide_synth(0.1, GlideMode::Legato);
+        synth.note_on(60, 0.8);
+        synth.note_off(60);
+        synth.note_on(67, 0.8);
+        assert_eq!(pitch_of(&synth, 67), 67.0, "detached note should not glide");
+        synth.note_on(72, 0.8);
+        assert_eq!(pitch_of(&synth, 72), 67.0, "overlapping note should glide");
+    }
+
+    #[test]
+    fn test_glide_constant_rate_takes_longer_for_wider_intervals() {
+        let mut synth = glide_synth(0.1, GlideMode::Always);
+        synth.set_glide_timing(GlideTiming::ConstantRate);
+        synth.note_on(48, 0.8);
+        synth.note_off(48);
+        synth.note_on(72, 0.8);
+        // 0.1 s per octave: after 0.1 s a two-octave glide is only half done
+        run(&mut synth, 4410);
+        assert!((pitch_of(&synth, 72) - 60.0).abs() < 0.1, "{}", pitch_of(&synth, 72));
+    }
+
+    #[test]
+    fn test_mono_legato_glides_without_retrigger() {
+        let mut synth = mono_synth(NotePriority::Last, true);
+        synth.set_glide_time(0.05);
+        synth.set_glide_mode(GlideMode::Legato);
+        synth.note_on(60, 0.8);
+        run(&mut synth, 4410);
+        synth.note_on(64, 0.8);
+        assert_eq!(synth.voices[0].pitch(), 60.0);
+        run(&mut synth, 441);
+        let pitch = synth.voices[0].pitch();
+        assert!(pitch > 60.0 && pitch < 64.0, "{}", pitch);
+        // Releasing the top key glides back down to the held one
+        run(&mut synth, 4410);
+        synth.note_off(64);
+        assert_eq!(synth.voices[0].pitch(), 64.0);
+        run(&mut synth, 441);
+        assert!(synth.voices[0].pitch() < 64.0);
+    }
+
+    #[test]
+    fn test_mono_retrigger_mid_glide_starts_from_current_pitch() {
+        let mut synth = mono_synth(NotePriority::Last, false);
+        synth.set_glide_time(0.1);
+        synth.note_on(60, 0.8);
+        synth.note_on(72, 0.8);
+        run(&mut synth, 2205);
+        let mid = synth.voices[0].pitch();
+        synth.note_on(48, 0.8);
+        assert_eq!(synth.voices[0].pitch(), mid);
+    }
+
+    // --- Filter ---
+
+    #[test]
+    fn test_filter_is_stable_across_sample_rates() {
+        for &sample_rate in &[8000.0, 44100.0, 96000.0, 192000.0] {
+            let mut synth = Synth::new();
+            synth.prepare(sample_rate);
+            synth.set_oscillator_type(OscillatorType::Saw);
+            synth.set_filter_cutoff(20000.0);
+            synth.set_filter_resonance(1.0);
+            synth.set_filter_keytrack(1.0);
+            // Key tracking pushes the cutoff far past Nyquist
+            synth.note_on(96, 1.0);
+            let mut buf = [0.0f32; 4096];
+            synth.process(&mut buf);
+            assert!(
+                buf.iter().all(|s| s.is_finite() && s.abs() < 100.0),
+                "unstable at {} Hz",
+                sample_rate
+            );
+        }
+    }
+
+    #[test]
+    fn test_ladder_is_stable_across_sample_rates() {
+        for &sample_rate in &[8000.0, 44100.0, 96000.0, 192000.0] {
+            let mut synth = Synth::new();
+            synth.prepare(sample_rate);
+            synth.set_oscillator_type(OscillatorType::Saw);
+            synth.set_filter_type(FilterType::Ladder);
+            synth.set_filter_cutoff(20000.0);
+            synth.set_filter_resonance(1.0);
+            synth.set_filter_drive(1.0);
+            synth.note_on(96, 1.0);
+            let mut buf = [0.0f32; 4096];
+            synth.process(&mut buf);
+            assert!(
+                buf.iter().all(|s| s.is_finite() && s.abs() < 100.0),
+                "unstable at {} Hz",
+                sample_rate
+            );
+        }
+    }
+
+    #[test]
+    fn test_filter_type_switch_keeps_cutoff() {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.set_oscillator_type(OscillatorType::Saw);
+        synth.set_filter_cutoff(300.0);
+        // Switching after the cutoff was set must still apply it to the ladder
+        synth.set_filter_type(FilterType::Ladder);
+        synth.note_on(57, 1.0);
+        let mut dark = [0.0f32; 2048];
+        synth.process(&mut dark);
+
+        let mut open = Synth::new();
+        open.prepare(44100.0);
+        open.set_oscillator_type(OscillatorType::Saw);
+        open.set_filter_type(FilterType::Ladder);
+        open.note_on(57, 1.0);
+        let mut bright = [0.0f32; 2048];
+        open.process(&mut bright);
+
+        let energy = |buf: &[f32]| buf.windows(2).map(|w| (w[1] - w[0]).powi(2)).sum::<f32>();
+        assert!(energy(&dark) < energy(&bright) * 0.1);
+    }
+
+    #[test]
+    fn test_filter_mode_changes_output() {
+        let render = |mode: FilterMode| {
+            let mut synth = Synth::new();
+            synth.prepare(44100.0);
+            synth.set_oscillator_type(OscillatorType::Saw);
+            synth.set_filter_mode(mode);
+            synth.set_filter_cutoff(1000.0);
+            synth.note_on(57, 1.0);
+            let mut buf = [0.0f32; 1024];
+            synth.process(&mut buf);
+            buf
+        };
+        let outputs: Vec<_> = FilterMode::VARIANTS.iter().map(|&m| render(m)).collect();
+        for i in 0..outputs.len() {
+            for j in (i + 1)..outputs.len() {
+                assert_ne!(outputs[i], outputs[j]);
+            }
         }
     }
 
-    /// Call once when the host provides sample rate and buffer size info.
-    pub fn prepare(&mut self, sample_rate: f32) {
-        self.sample_rate = sample_rate;
-        self.oscillator.set_sample_rate(sample_rate);
-        self.envelope.set_sample_rate(sample_rate);
+    #[test]
+    fn test_filter_envelope_is_stable_at_extreme_amounts() {
+        for &amount in &[-100.0, 100.0] {
+            let mut synth = Synth::new();
+            synth.prepare(44100.0);
+            synth.set_oscillator_type(OscillatorType::Saw);
+            synth.set_filter_resonance(1.0);
+            synth.set_filter_cutoff(1000.0);
+            synth.set_filter_env_amount(amount);
+            synth.set_filter_attack(0.001);
+            synth.set_filter_decay(0.01);
+            synth.note_on(60, 1.0);
+            let mut buf = [0.0f32; 4096];
+            synth.process(&mut buf);
+            assert!(buf.iter().all(|s| s.is_finite() && s.abs() < 100.0));
+        }
     }
 
-    pub fn set_oscillator_type(&mut self, osc_type: OscillatorType) {
-        self.oscillator.set_type(osc_type);
+    #[test]
+    fn test_filter_envelope_release_closes_filter() {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.set_oscillator_type(OscillatorType::Saw);
+        synth.set_filter_cutoff(300.0);
+        synth.set_filter_env_amount(5.0);
+        synth.set_filter_sustain(1.0);
+        synth.set_filter_release(0.01);
+        synth.set_release(1.0);
+        synth.note_on(57, 1.0);
+        let mut buf = [0.0f32; 4410];
+        synth.process(&mut buf);
+        let energy = |buf: &[f32]| buf.windows(2).map(|w| (w[1] - w[0]).powi(2)).sum::<f32>();
+        let held = energy(&buf);
+        synth.note_off(57);
+        // Let the short filter release finish while the amp still rings
+        synth.process(&mut buf);
+        synth.process(&mut buf);
+        assert!(energy(&buf) < held * 0.1);
     }
 
-    pub fn set_gain(&mut self, gain: f32) {
-        self.gain = gain.clamp(0.0, 1.0);
+    // --- LFO ---
+
+    #[test]
+    fn test_lfo_tempo_sync_follows_tempo() {
+        let mut synth = Synth::new();
+        synth.prepare(1000.0);
+        synth.set_oscillator_type(OscillatorType::Saw);
+        synth.set_attack(0.001);
+        synth.set_lfo_shape(LfoShape::Square);
+        synth.set_lfo_to_amp(1.0);
+        synth.set_lfo_timing(LfoTiming::TempoSync);
+        synth.set_lfo_division(NoteDivision::Quarter);
+        synth.set_tempo(120.0);
+        synth.note_on(60, 1.0);
+        // Two cycles per second: a quarter second up, a quarter second silent
+        let mut buf = [0.0f32; 500];
+        synth.process(&mut buf);
+        assert!(buf[10..240].iter().any(|&s| s != 0.0));
+        assert!(buf[260..490].iter().all(|&s| s == 0.0));
+
+        // Half the tempo doubles the cycle length
+        synth.set_tempo(60.0);
+        synth.process(&mut buf);
+        assert!(buf[10..490].iter().all(|&s| s != 0.0));
+        synth.process(&mut buf);
+        assert!(buf[10..490].iter().all(|&s| s == 0.0));
     }
 
-    pub fn set_attack(&mut self, seconds: f32) {
-        self.envelope.set_attack(seconds);
+    #[test]
+    fn test_free_lfo_keeps_running_between_notes() {
+        let onset_peak = |trigger: LfoTrigger| {
+            let mut synth = Synth::new();
+            synth.prepare(1000.0);
+            synth.set_voice_count(2);
+            synth.set_oscillator_type(OscillatorType::Saw);
+            synth.set_lfo_shape(LfoShape::Saw);
+            synth.set_lfo_to_amp(1.0);
+            synth.set_lfo_trigger(trigger);
+            synth.note_on(60, 1.0);
+            let mut buf = [0.0f32; 100];
+            synth.process(&mut buf);
+            synth.note_off(60);
+            synth.set_release(0.001);
+            synth.process(&mut buf[..30]);
+            synth.note_on(64, 1.0);
+            let mut onset = [0.0f32; 10];
+            synth.process(&mut onset);
+            onset.iter().fold(0.0f32, |a, s| a.max(s.abs()))
+        };
+        // Key sync starts the saw LFO at its bottom, nearly silencing the new
+        // note; the free LFO is mid-cycle
+        let synced = onset_peak(LfoTrigger::KeySync);
+        let free = onset_peak(LfoTrigger::Free);
+        assert!(free > synced * 5.0, "free {} key sync {}", free, synced);
     }
 
-    pub fn set_decay(&mut self, seconds: f32) {
-        self.envelope.set_decay(seconds);
+    // --- Mod matrix ---
+
+    fn render_mod(synth: &mut Synth, n: usize) -> Vec<f32> {
+        let mut buf = vec![0.0f32; n];
+        synth.process(&mut buf);
+        buf
     }
 
-    pub fn set_sustain(&mut self, level: f32) {
-        self.envelope.set_sustain(level);
+    #[test]
+    fn test_mod_wheel_to_gain() {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.set_mod_slot(0, ModSource::ModWheel, ModDestination::Gain, -1.0);
+        synth.note_on(69, 1.0);
+        let open = render_mod(&mut synth, 2000);
+        assert!(open.iter().any(|&s| s != 0.0));
+        // Full wheel with full negative depth pulls the level to silence
+        synth.set_mod_wheel(1.0);
+        // The wheel glides over one smoothing ramp before the level bottoms out
+        render_mod(&mut synth, 1000);
+        assert!(render_mod(&mut synth, 2000).iter().all(|&s| s == 0.0));
     }
 
-    pub fn set_release(&mut self, seconds: f32) {
-        self.envelope.set_release(seconds);
+    #[test]
+    fn test_aftertouch_to_pitch_raises_frequency() {
+        let crossings = |pressure: f32| {
+            let mut synth = Synth::new();
+            synth.prepare(44100.0);
+            synth.set_mod_slot(5, ModSource::Aftertouch, ModDestination::Pitch, 1.0);
+            synth.set_aftertouch(pressure);
+            synth.note_on(57, 1.0);
+            let buf = render_mod(&mut synth, 44100);
+            buf.windows(2)
+                .filter(|w| (w[0] >= 0.0) != (w[1] >= 0.0))
+                .count()
+        };
+        // A full octave up doubles the zero crossings of the 220 Hz sine
+        let ratio = crossings(1.0) as f32 / crossings(0.0) as f32;
+        assert!((ratio - 2.0).abs() < 0.02, "ratio {}", ratio);
     }
 
-    pub fn note_on(&mut self, note: u8, _velocity: f32) {
-        self.current_note = Some(note);
-        self.oscillator.set_frequency(midi_note_to_freq(note));
-        self.oscillator.reset();
-        self.envelope.note_on();
+    #[test]
+    fn test_velocity_to_attack_time() {
+        let level_after = |velocity: f32| {
+            let mut synth = Synth::new();
+            synth.prepare(1000.0);
+            synth.set_attack(0.1);
+            // Harder notes get up to 16 times longer attacks
+            synth.set_mod_slot(0, ModSource::Velocity, ModDestination::Attack, 1.0);
+            synth.note_on(60, velocity);
+            render_mod(&mut synth, 100);
+            synth.voices[0].level()
+        };
+        assert!(level_after(0.0) > 0.95);
+        assert!(level_after(1.0) < 0.1);
     }
 
-    pub fn note_off(&mut self, note: u8) {
-        // Only release if this is the note currently playing
-        if self.current_note == Some(note) {
-            self.envelope.note_off();
-            self.current_note = None;
-        }
+    #[test]
+    fn test_release_time_modulation() {
+        let mut synth = Synth::new();
+        synth.prepare(1000.0);
+        synth.set_release(0.1);
+        synth.set_mod_wheel(1.0);
+        synth.set_mod_slot(7, ModSource::ModWheel, ModDestination::Release, 0.25);
+        synth.note_on(60, 1.0);
+        render_mod(&mut synth, 200);
+        synth.note_off(60);
+        // Double the release: still sounding after the unmodulated 100 ms
+        render_mod(&mut synth, 150);
+        assert!(synth.voices[0].is_active());
+        render_mod(&mut synth, 100);
+        assert!(!synth.voices[0].is_active());
     }
 
-    /// Fill `output` with mono audio samples. No allocations.
-    pub fn process(&mut self, output: &mut [f32]) {
-        for sample in output.iter_mut() {
-            if self.envelope.is_active() {
-                let osc = self.oscillator.tick();
-                let env = self.envelope.tick();
-                *sample = osc * env * self.gain;
-            } else {
-                *sample = 0.0;
+    #[test]
+    fn test_envelope_time_modulation_survives_block_updates() {
+        // Render `blocks` blocks of 10 ms, nudging the envelope times at the
+        // start of each as host automation would
+        fn play(synth: &mut Synth, block: &mut usize, blocks: usize) {
+            for _ in 0..blocks {
+                let nudge = *block as f32 * 1e-5;
+                *block += 1;
+                synth.set_attack(0.01 + nudge);
+                synth.set_decay(0.1 + nudge);
+                synth.set_release(0.1 + nudge);
+                render_mod(synth, 10);
             }
         }
+        let mut synth = Synth::new();
+        synth.prepare(1000.0);
+        // Full velocity stretches the attack 16 times; the wheel doubles
+        // the decay and release
+        synth.set_mod_slot(0, ModSource::Velocity, ModDestination::Attack, 1.0);
+        synth.set_mod_wheel(1.0);
+        synth.set_mod_slot(6, ModSource::ModWheel, ModDestination::Decay, 0.25);
+        synth.set_mod_slot(7, ModSource::ModWheel, ModDestination::Release, 0.25);
+        synth.note_on(60, 1.0);
+        let mut block = 0;
+        play(&mut synth, &mut block, 10);
+        let level = synth.voices[0].level();
+        assert!(level < 0.7, "100 ms into a 160 ms attack: {}", level);
+        play(&mut synth, &mut block, 16);
+        let level = synth.voices[0].level();
+        assert!(level > 0.75, "halfway down a 200 ms decay: {}", level);
+        synth.note_off(60);
+        play(&mut synth, &mut block, 15);
+        assert!(synth.voices[0].is_active(), "a 200 ms release outlasts 150 ms");
+        play(&mut synth, &mut block, 10);
+        assert!(!synth.voices[0].is_active());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_pan_follows_key_track() {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.set_voice_count(2);
+        synth.set_mod_slot(1, ModSource::KeyTrack, ModDestination::Pan, 1.0);
+        synth.note_on(0, 1.0);
+        synth.note_on(127, 1.0);
+        render_mod(&mut synth, 10);
+        assert_eq!(synth.voices[0].pan(), -1.0);
+        assert!(synth.voices[1].pan() > 0.95);
+    }
 
     #[test]
-    fn test_midi_note_to_freq_a4() {
-        assert!((midi_note_to_freq(69) - 440.0).abs() < 0.01);
+    fn test_empty_matrix_leaves_sound_unchanged() {
+        let mut plain = Synth::new();
+        plain.prepare(44100.0);
+        plain.note_on(60, 0.7);
+        let mut routed = Synth::new();
+        routed.prepare(44100.0);
+        routed.set_mod_slot(0, ModSource::Lfo, ModDestination::Pitch, 0.0);
+        routed.set_mod_slot(1, ModSource::Off, ModDestination::Gain, 1.0);
+        routed.note_on(60, 0.7);
+        assert_eq!(render_mod(&mut plain, 4096), render_mod(&mut routed, 4096));
+    }
+
+    // --- Unison and stereo output ---
+
+    fn render_stereo(synth: &mut Synth, n: usize) -> (Vec<f32>, Vec<f32>) {
+        let mut left = vec![0.0f32; n];
+        let mut right = vec![0.0f32; n];
+        synth.process_stereo(&mut left, &mut right);
+        (left, right)
     }
 
     #[test]
-    fn test_midi_note_to_freq_c4() {
-        assert!((midi_note_to_freq(60) - 261.63).abs() < 0.01);
+    fn test_stereo_output_matches_mono_without_spread() {
+        let mut mono = Synth::new();
+        mono.prepare(44100.0);
+        mono.set_oscillator_type(OscillatorType::Saw);
+        mono.note_on(60, 0.9);
+        let mut stereo = Synth::new();
+        stereo.prepare(44100.0);
+        stereo.set_oscillator_type(OscillatorType::Saw);
+        stereo.note_on(60, 0.9);
+        let (left, right) = render_stereo(&mut stereo, 2048);
+        assert_eq!(left, right);
+        assert_eq!(render_mod(&mut mono, 2048), left);
     }
 
     #[test]
-    fn test_midi_note_to_freq_a3() {
-        assert!((midi_note_to_freq(57) - 220.0).abs() < 0.01);
+    fn test_unison_spread_makes_channels_differ() {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.set_oscillator_type(OscillatorType::Saw);
+        synth.set_unison_voices(7);
+        synth.set_unison_detune(25.0);
+        synth.set_unison_spread(1.0);
+        synth.note_on(60, 0.9);
+        let (left, right) = render_stereo(&mut synth, 4096);
+        assert_ne!(left, right);
+        assert!(left.iter().chain(&right).all(|s| s.is_finite() && s.abs() < 4.0));
     }
 
     #[test]
-    fn test_midi_note_to_freq_octave_relationship() {
-        let a4 = midi_note_to_freq(69);
-        let a5 = midi_note_to_freq(81);
-        let a3 = midi_note_to_freq(57);
-        assert!((a5 - 2.0 * a4).abs() < 0.01, "A5 should be 2x A4");
-        assert!((a3 - a4 / 2.0).abs() < 0.01, "A3 should be A4/2");
+    fn test_unison_random_phase_is_deterministic() {
+        let render = || {
+            let mut synth = Synth::new();
+            synth.prepare(44100.0);
+            synth.set_oscillator_type(OscillatorType::Saw);
+            synth.set_unison_voices(4);
+            synth.set_unison_random_phase(true);
+            synth.note_on(60, 0.9);
+            render_mod(&mut synth, 1024)
+        };
+        assert_eq!(render(), render());
     }
 
     #[test]
-    fn test_midi_note_to_freq_extremes() {
-        let low = midi_note_to_freq(0);
-        let high = midi_note_to_freq(127);
-        assert!(low > 0.0 && low.is_finite(), "note 0 freq: {}", low);
-        assert!(high > 0.0 && high.is_finite(), "note 127 freq: {}", high);
+    fn test_pan_moves_mix_between_channels() {
+        let render = |pan: f32| {
+            let mut synth = Synth::new();
+            synth.prepare(44100.0);
+            synth.set_oscillator_type(OscillatorType::Saw);
+            synth.set_pan(pan);
+            synth.note_on(60, 0.9);
+            render_stereo(&mut synth, 1024)
+        };
+        let (centre_left, centre_right) = render(0.0);
+        let (left, right) = render(-1.0);
+        assert_eq!(left, centre_left);
+        assert!(right.iter().all(|&s| s == 0.0));
+        let (left, right) = render(0.5);
+        assert_eq!(right, centre_right);
+        assert!(left.iter().zip(&centre_left).all(|(&l, &c)| l == c * 0.5));
     }
 
     #[test]
-    fn test_note_on_produces_nonsilent_output() {
+    fn test_voice_pan_from_mod_matrix_is_audible() {
         let mut synth = Synth::new();
         synth.prepare(44100.0);
-        synth.note_on(69, 0.8);
-        let mut buf = [0.0f32; 512];
-        synth.process(&mut buf);
-        assert!(
-            buf.iter().any(|s| s.abs() > 0.001),
-            "note_on should produce sound"
-        );
+        synth.set_voice_count(2);
+        synth.set_mod_slot(0, ModSource::KeyTrack, ModDestination::Pan, 1.0);
+        // Note 0 sits at key track -1: hard left
+        synth.note_on(0, 1.0);
+        let (left, right) = render_stereo(&mut synth, 2048);
+        assert!(left.iter().any(|&s| s != 0.0));
+        assert!(right.iter().all(|&s| s == 0.0));
     }
 
-    #[test]
-    fn test_note_off_wrong_note_keeps_playing() {
+    fn wavetable_synth(bank: WavetableBank, position: f32) -> Synth {
         let mut synth = Synth::new();
         synth.prepare(44100.0);
-        synth.note_on(69, 0.8);
-        let mut buf = [0.0f32; 256];
-        synth.process(&mut buf);
-        synth.note_off(60);
-        let mut buf2 = [0.0f32; 256];
-        synth.process(&mut buf2);
-        assert!(
-            buf2.iter().any(|s| s.abs() > 0.001),
-            "wrong note_off should not stop playback"
-        );
+        synth.set_oscillator_type(OscillatorType::Wavetable);
+        synth.set_wavetable(bank);
+        synth.set_wavetable_position(position);
+        synth.note_on(57, 1.0);
+        synth
     }
 
     #[test]
-    fn test_note_off_correct_note_eventually_silent() {
+    fn test_wavetable_bank_and_position_change_the_sound() {
+        let basic = render_mod(&mut wavetable_synth(WavetableBank::Basic, 0.0), 2048);
+        let pulse = render_mod(&mut wavetable_synth(WavetableBank::Pulse, 0.0), 2048);
+        let scanned = render_mod(&mut wavetable_synth(WavetableBank::Basic, 1.0), 2048);
+        assert_ne!(basic, pulse);
+        assert_ne!(basic, scanned);
+        // With nothing loaded the custom bank plays the basic table
+        let custom = render_mod(&mut wavetable_synth(WavetableBank::Custom, 0.0), 2048);
+        assert_eq!(basic, custom);
+    }
+
+    #[test]
+    fn test_loaded_wavetable_plays_in_custom_bank() {
+        let frame = [0.0; wavetable::WAVETABLE_FRAME_SIZE];
+        let silent = Arc::new(Wavetable::from_frames(&frame).unwrap());
+        let mut synth = wavetable_synth(WavetableBank::Custom, 0.0);
+        synth.load_wavetable(silent.clone());
+        assert!(render_mod(&mut synth, 2048).iter().all(|&s| s == 0.0));
+        // Other banks ignore it
+        let mut synth = wavetable_synth(WavetableBank::Basic, 0.0);
+        synth.load_wavetable(silent);
+        assert!(render_mod(&mut synth, 2048).iter().any(|&s| s != 0.0));
+    }
+
+    #[test]
+    fn test_mod_matrix_scans_wavetable() {
+        let still = render_mod(&mut wavetable_synth(WavetableBank::Basic, 0.0), 2048);
+        let mut synth = wavetable_synth(WavetableBank::Basic, 0.0);
+        synth.set_mod_slot(0, ModSource::Velocity, ModDestination::WavetablePosition, 1.0);
+        let modulated = render_mod(&mut synth, 2048);
+        assert_ne!(still, modulated);
+        // Full velocity at full depth reaches the last frame
+        let last = render_mod(&mut wavetable_synth(WavetableBank::Basic, 1.0), 2048);
+        assert_eq!(modulated, last);
+    }
+
+    // --- Second oscillator ---
+
+    fn osc2_synth(coarse: i32, level: f32, sync: bool) -> Synth {
         let mut synth = Synth::new();
         synth.prepare(44100.0);
-        synth.set_release(0.01);
-        synth.note_on(69, 0.8);
-        let mut buf = [0.0f32; 4410];
-        synth.process(&mut buf);
-        synth.note_off(69);
-        let mut buf2 = [0.0f32; 1000];
-        synth.process(&mut buf2);
-        assert!(
-            buf2[900..].iter().all(|s| s.abs() < 1e-6),
-            "should be silent after release"
-        );
+        synth.set_osc2_coarse(coarse);
+        synth.set_osc2_level(level);
+        synth.set_osc2_sync(sync);
+        synth.note_on(57, 1.0);
+        synth
     }
 
     #[test]
-    fn test_process_empty_buffer_is_safe() {
+    fn test_osc2_level_and_sync_change_the_sound() {
+        let off = render_mod(&mut osc2_synth(7, 0.0, false), 2048);
+        let mixed = render_mod(&mut osc2_synth(7, 1.0, false), 2048);
+        let synced = render_mod(&mut osc2_synth(7, 1.0, true), 2048);
+        assert_ne!(off, mixed);
+        assert_ne!(mixed, synced);
+        // At zero level the second oscillator's settings don't matter
+        assert_eq!(off, render_mod(&mut osc2_synth(0, 0.0, true), 2048));
+    }
+
+    #[test]
+    fn test_osc2_tuning_is_clamped() {
+        let mut synth = Synth::new();
+        synth.set_osc2_coarse(100);
+        synth.set_osc2_fine(-1000.0);
+        assert_eq!(synth.osc2_coarse, MAX_OSC2_SEMITONES);
+        assert_eq!(synth.value(Continuous::Osc2Fine), -MAX_OSC2_FINE_CENTS);
+    }
+
+    // --- FM ---
+
+    fn fm_synth() -> Synth {
         let mut synth = Synth::new();
         synth.prepare(44100.0);
-        synth.process(&mut []);
+        synth.set_oscillator_type(OscillatorType::Sine);
+        synth.set_fm_enabled(true);
+        synth.set_fm_ratio(3.5);
+        synth.set_fm_index(4.0);
+        synth.set_fm_feedback(0.3);
+        synth.set_fm_decay(0.2);
+        synth.set_fm_sustain(0.2);
+        synth
     }
 
     #[test]
-    fn test_set_gain_zero_produces_silence() {
+    fn test_fm_changes_the_sound() {
+        let mut plain = Synth::new();
+        plain.prepare(44100.0);
+        plain.set_oscillator_type(OscillatorType::Sine);
+        plain.note_on(57, 1.0);
+        let mut fm = fm_synth();
+        fm.note_on(57, 1.0);
+        assert_ne!(render_mod(&mut plain, 2048), render_mod(&mut fm, 2048));
+    }
+
+    #[test]
+    fn test_fm_render_is_repeatable() {
+        // The same notes and settings give the same bits, run after run
+        let render = || {
+            let mut synth = fm_synth();
+            synth.note_on(45, 0.8);
+            synth.note_on(52, 0.6);
+            let mut out = render_mod(&mut synth, 4096);
+            synth.note_off(45);
+            out.extend(render_mod(&mut synth, 4096));
+            out.iter().map(|s| s.to_bits()).collect::<Vec<_>>()
+        };
+        assert_eq!(render(), render());
+    }
+
+    // --- Sub-oscillator and noise ---
+
+    fn noise_synth() -> Synth {
         let mut synth = Synth::new();
         synth.prepare(44100.0);
-        synth.set_gain(0.0);
-        synth.note_on(69, 0.8);
-        let mut buf = [0.0f32; 512];
-        synth.process(&mut buf);
-        assert!(
-            buf.iter().all(|s| *s == 0.0),
-            "gain=0 should produce silence"
-        );
+        synth.set_voice_count(2);
+        synth.set_noise_type(NoiseType::Pink);
+        synth.set_noise_level(1.0);
+        synth
     }
 
     #[test]
-    fn test_set_gain_one_produces_full_volume() {
+    fn test_noise_renders_reproducibly() {
+        let render = || {
+            let mut synth = noise_synth();
+            synth.note_on(60, 1.0);
+            render_mod(&mut synth, 4096)
+        };
+        assert_eq!(render(), render());
+    }
+
+    #[test]
+    fn test_sub_level_changes_the_sound() {
+        let render = |level: f32, shape: SubShape| {
+            let mut synth = Synth::new();
+            synth.prepare(44100.0);
+            synth.set_sub_level(level);
+            synth.set_sub_shape(shape);
+            synth.set_sub_octave(SubOctave::TwoDown);
+            synth.note_on(57, 1.0);
+            render_mod(&mut synth, 2048)
+        };
+        let off = render(0.0, SubShape::Square);
+        assert_ne!(off, render(1.0, SubShape::Square));
+        assert_ne!(render(1.0, SubShape::Square), render(1.0, SubShape::Sine));
+    }
+
+    // --- Ring modulation and linear FM ---
+
+    #[test]
+    fn test_ring_and_linear_fm_change_the_sound() {
+        let render = |ring: f32, linear_fm: f32| {
+            let mut synth = Synth::new();
+            synth.prepare(44100.0);
+            synth.set_osc2_coarse(7);
+            synth.set_ring_mix(ring);
+            synth.set_linear_fm(linear_fm);
+            synth.note_on(57, 1.0);
+            render_mod(&mut synth, 2048)
+        };
+        let dry = render(0.0, 0.0);
+        let ring = render(1.0, 0.0);
+        let fm = render(0.0, 2.0);
+        assert_ne!(dry, ring);
+        assert_ne!(dry, fm);
+        assert!(fm.iter().all(|s| s.is_finite()));
+    }
+
+    // --- Plucked string ---
+
+    #[test]
+    fn test_plucked_string_engine_replaces_the_oscillators() {
+        let render = |engine: Engine, excitation: PluckExcitation| {
+            let mut synth = Synth::new();
+            synth.prepare(48000.0);
+            synth.set_engine(engine);
+            synth.set_pluck_excitation(excitation);
+            synth.note_on(64, 1.0);
+            render_mod(&mut synth, 2048)
+        };
+        let oscillators = render(Engine::Oscillators, PluckExcitation::Noise);
+        let noise = render(Engine::PluckedString, PluckExcitation::Noise);
+        let osc = render(Engine::PluckedString, PluckExcitation::Oscillator);
+        assert_ne!(oscillators, noise);
+        assert_ne!(noise, osc);
+        assert_eq!(noise, render(Engine::PluckedString, PluckExcitation::Noise));
+        assert!(noise.iter().any(|&s| s.abs() > 0.01));
+    }
+
+    // --- Additive oscillator ---
+
+    #[test]
+    fn test_additive_partials_shape_the_sound() {
+        let render = |setup: &dyn Fn(&mut Synth)| {
+            let mut synth = Synth::new();
+            synth.prepare(44100.0);
+            synth.set_oscillator_type(OscillatorType::Additive);
+            setup(&mut synth);
+            synth.note_on(57, 1.0);
+            render_mod(&mut synth, 2048)
+        };
+        let sine = render(&|_| {});
+        let saw = render(&|s| s.set_additive_preset(AdditivePreset::Saw));
+        assert_ne!(sine, saw);
+        // The default spectrum is the sine preset
+        assert_eq!(sine, render(&|s| s.set_additive_preset(AdditivePreset::Sine)));
+        let edited = render(&|s| {
+            s.set_additive_preset(AdditivePreset::Saw);
+            s.set_partial(1, 0.0);
+        });
+        assert_ne!(saw, edited);
+        // Out-of-range partials are ignored
+        assert_eq!(saw, render(&|s| {
+            s.set_additive_preset(AdditivePreset::Saw);
+            s.set_partial(MAX_PARTIALS, 1.0);
+        }));
+    }
+
+    // --- Envelope curves ---
+
+    #[test]
+    fn test_envelope_curves_reach_every_voice() {
+        let halfway = |curve: f32| {
+            let mut synth = Synth::new();
+            synth.prepare(1000.0);
+            synth.set_voice_count(2);
+            synth.set_release(0.1);
+            synth.set_release_curve(curve);
+            synth.set_filter_release_curve(curve);
+            synth.set_fm_release_curve(curve);
+            synth.note_on(60, 1.0);
+            synth.note_on(64, 1.0);
+            render_mod(&mut synth, 200);
+            synth.note_off(60);
+            synth.note_off(64);
+            render_mod(&mut synth, 50);
+            let levels: Vec<[f32; 3]> = synth.voices[..2]
+                .iter_mut()
+                .map(|v| {
+                    [
+                        v.envelope_mut().level(),
+                        v.filter_envelope_mut().level(),
+                        v.fm_envelope_mut().level(),
+                    ]
+                })
+                .collect();
+            // Still finishes within the release time
+            render_mod(&mut synth, 50);
+            assert!(synth.voices.iter().all(|v| !v.is_active()), "curve {}", curve);
+            levels
+        };
+        let linear = halfway(0.0);
+        let exponential = halfway(1.0);
+        let logarithmic = halfway(-1.0);
+        for voice in 0..2 {
+            for env in 0..3 {
+                assert!(exponential[voice][env] < linear[voice][env]);
+                assert!(logarithmic[voice][env] > linear[voice][env]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_one_shot_envelope_outlasts_note_off() {
+        let active_after = |mode: EnvelopeMode, ms: usize| {
+            let mut synth = Synth::new();
+            synth.prepare(1000.0);
+            synth.set_envelope_mode(mode);
+            synth.note_on(60, 1.0);
+            render_mod(&mut synth, 5);
+            synth.note_off(60);
+            render_mod(&mut synth, ms);
+            synth.voices[0].is_active()
+        };
+        // Normal mode is done 300 ms (the release) after note-off; one-shot
+        // plays attack, decay and release in full, about 410 ms
+        assert!(!active_after(EnvelopeMode::Normal, 310));
+        assert!(active_after(EnvelopeMode::OneShot, 310));
+        assert!(!active_after(EnvelopeMode::OneShot, 420));
+    }
+
+    #[test]
+    fn test_envelope_delay_reaches_every_envelope() {
         let mut synth = Synth::new();
-        synth.prepare(44100.0);
-        synth.set_gain(1.0);
-        synth.note_on(69, 0.8);
-        let mut buf = [0.0f32; 512];
-        synth.process(&mut buf);
-        assert!(
-            buf.iter().any(|s| s.abs() > 0.5),
-            "gain=1 should produce audible output"
-        );
+        synth.prepare(1000.0);
+        synth.set_delay(0.05);
+        synth.set_filter_delay(0.05);
+        synth.set_fm_delay(0.05);
+        synth.set_filter_envelope_mode(EnvelopeMode::Loop);
+        synth.set_fm_hold(0.05);
+        synth.note_on(60, 1.0);
+        let silent = render_mod(&mut synth, 40);
+        assert!(silent.iter().all(|&s| s == 0.0));
+        let voice = &mut synth.voices[0];
+        assert!(voice.is_active());
+        assert_eq!(voice.filter_envelope_mut().level(), 0.0);
+        assert_eq!(voice.fm_envelope_mut().level(), 0.0);
+        let sounding = render_mod(&mut synth, 40);
+        assert!(sounding.iter().any(|&s| s != 0.0));
     }
 
     #[test]
-    fn test_set_gain_scales_output() {
-        let mut synth_full = Synth::new();
-        synth_full.prepare(44100.0);
-        synth_full.set_gain(1.0);
-        synth_full.note_on(69, 0.8);
-        let mut buf_full = [0.0f32; 512];
-        synth_full.process(&mut buf_full);
+    fn test_retrigger_modes_on_mono_retrigger() {
+        let levels = |mode: RetriggerMode| {
+            let mut synth = Synth::new();
+            synth.prepare(1000.0);
+            synth.set_voice_mode(VoiceMode::Mono);
+            synth.set_retrigger_mode(mode);
+            synth.set_filter_retrigger_mode(mode);
+            synth.set_fm_retrigger_mode(mode);
+            synth.note_on(60, 1.0);
+            render_mod(&mut synth, 200); // sustaining at 0.7
+            synth.note_on(64, 1.0);
+            render_mod(&mut synth, 1);
+            let voice = &mut synth.voices[0];
+            [
+                voice.envelope_mut().level(),
+                voice.filter_envelope_mut().level(),
+                voice.fm_envelope_mut().level(),
+            ]
+        };
+        for level in levels(RetriggerMode::Resume) {
+            assert!(level > 0.7, "resume attacks from the sustain level: {}", level);
+        }
+        for level in levels(RetriggerMode::Reset) {
+            assert!(level < 0.7, "reset fades towards zero first: {}", level);
+        }
+        for level in levels(RetriggerMode::Legato) {
+            assert!((level - 0.7).abs() < 1e-6, "legato keeps sustaining: {}", level);
+        }
+    }
 
-        let mut synth_half = Synth::new();
-        synth_half.prepare(44100.0);
-        synth_half.set_gain(0.5);
-        synth_half.note_on(69, 0.8);
-        let mut buf_half = [0.0f32; 512];
-        synth_half.process(&mut buf_half);
+    #[test]
+    fn test_reset_retrigger_does_not_click() {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.set_oscillator_type(OscillatorType::Sine);
+        synth.set_retrigger_mode(RetriggerMode::Reset);
+        synth.note_on(60, 1.0);
+        let mut output = render_mod(&mut synth, 4410);
+        let settled = max_step(&output[2205..]);
+        // Mid-cycle, well away from a zero crossing, and a new pitch
+        synth.note_on(64, 1.0);
+        output.extend(render_mod(&mut synth, 4410));
+        let step = max_step(&output[4400..4410 + 441]);
+        assert!(step < settled * 1.5, "step {step} against {settled} when settled");
+    }
 
-        for i in 0..512 {
-            let expected = buf_full[i] * 0.5;
+    // --- Parameter smoothing ---
+
+    /// Largest jump between neighbouring samples.
+    fn max_step(samples: &[f32]) -> f32 {
+        samples.windows(2).map(|w| (w[1] - w[0]).abs()).fold(0.0, f32::max)
+    }
+
+    /// Play a held sine, jump a parameter from `before` to `after` and
+    /// return the largest step around the jump alongside the largest step
+    /// of the sound settled on either side.
+    fn steps_across_change(before: &dyn Fn(&mut Synth), after: &dyn Fn(&mut Synth)) -> (f32, f32) {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.set_oscillator_type(OscillatorType::Sine);
+        before(&mut synth);
+        synth.note_on(45, 1.0);
+        render_mod(&mut synth, 4410);
+        // A quarter cycle past a whole number of cycles, so the jump lands
+        // on a peak of the sine
+        let settled_before = render_mod(&mut synth, 4510);
+        after(&mut synth);
+        let mut change = settled_before[4510 - 64..].to_vec();
+        change.extend(render_mod(&mut synth, 2205));
+        let settled_after = render_mod(&mut synth, 4410);
+        (max_step(&change), max_step(&settled_before).max(max_step(&settled_after)))
+    }
+
+    #[test]
+    fn test_parameter_jumps_have_no_discontinuities() {
+        type Change = fn(&mut Synth);
+        let cases: [(&str, Change, Change); 10] = [
+            ("gain", |s| s.set_gain(0.1), |s| s.set_gain(1.0)),
+            ("pan", |_| {}, |s| s.set_pan(1.0)),
+            ("osc 2 level", |s| s.set_osc2_level(0.2), |s| s.set_osc2_level(1.0)),
+            ("sub level", |s| s.set_sub_level(0.2), |s| s.set_sub_level(1.0)),
+            ("ring mix", |s| s.set_osc2_level(1.0), |s| s.set_ring_mix(1.0)),
+            ("FM index", |s| s.set_fm_enabled(true), |s| s.set_fm_index(5.0)),
+            (
+                "drive",
+                |s| s.set_filter_type(FilterType::Ladder),
+                |s| s.set_filter_drive(1.0),
+            ),
+            (
+                "mod depth",
+                |s| s.set_mod_slot(0, ModSource::ModWheel, ModDestination::Gain, -1.0),
+                |s| s.set_mod_wheel(1.0),
+            ),
+            (
+                "filter cutoff",
+                |s| s.set_filter_cutoff(100.0),
+                |s| s.set_filter_cutoff(20000.0),
+            ),
+            (
+                "partial",
+                |s| s.set_oscillator_type(OscillatorType::Additive),
+                |s| s.set_partial(0, -1.0),
+            ),
+        ];
+        for (name, before, after) in cases {
+            let (change, settled) = steps_across_change(&before, &after);
             assert!(
-                (buf_half[i] - expected).abs() < 1e-6,
-                "sample {}: half={} expected={}",
-                i,
-                buf_half[i],
-                expected
+                change < settled * 1.2 + 0.005,
+                "{name}: step {change} against {settled} when settled"
             );
         }
     }
 
     #[test]
-    fn test_oscillator_type_switching() {
+    fn test_block_rate_automation_ramps_smoothly() {
         let mut synth = Synth::new();
         synth.prepare(44100.0);
-        synth.note_on(69, 0.8);
-        let mut buf_sine = [0.0f32; 512];
-        synth.process(&mut buf_sine);
-
-        synth.set_oscillator_type(OscillatorType::Saw);
-        let mut buf_saw = [0.0f32; 512];
-        synth.process(&mut buf_saw);
+        synth.set_oscillator_type(OscillatorType::Sine);
+        synth.set_gain(0.0);
+        synth.note_on(45, 1.0);
+        render_mod(&mut synth, 4410);
+        // A fast fade-in written once per 64-sample block, as a host sends it
+        let mut output = Vec::new();
+        for block in 0..=32 {
+            synth.set_gain(block as f32 / 32.0);
+            output.extend(render_mod(&mut synth, 64));
+        }
+        output.extend(render_mod(&mut synth, 4410));
+        let settled = max_step(&output[output.len() - 4410..]);
+        let ramp = max_step(&output);
+        assert!(ramp <= settled * 1.05, "step {ramp} against {settled} when settled");
+    }
 
-        assert!(
-            buf_saw.iter().any(|s| s.abs() > 0.001),
-            "saw should produce sound"
-        );
-        assert_ne!(buf_sine, buf_saw, "different waveforms should differ");
+    #[test]
+    fn test_fm_ratio_ramps_linearly() {
+        let mut synth = Synth::new();
+        synth.prepare(44100.0);
+        synth.set_fm_ratio(1.0);
+        synth.note_on(60, 1.0);
+        synth.set_fm_ratio(3.0);
+        // Halfway through the ramp; an octave-space ramp would be at 1.73
+        render_mod(&mut synth, 220);
+        let ratio = synth.value(Continuous::FmRatio);
+        assert!((ratio - 2.0).abs() < 0.01, "{ratio}");
     }
 
     #[test]
-    fn test_prepare_sets_sample_rate() {
+    fn test_changes_while_silent_apply_at_once() {
         let mut synth = Synth::new();
-        synth.prepare(48000.0);
-        synth.note_on(69, 0.8);
-        let mut buf = [0.0f32; 48000];
-        synth.process(&mut buf);
-        let crossings = buf
-            .windows(2)
-            .filter(|w| (w[0] >= 0.0) != (w[1] >= 0.0))
-            .count();
-        // 440 Hz sine = 880 crossings/sec
-        assert!(
-            (crossings as i32 - 880).abs() <= 4,
-            "expected ~880 crossings at 48kHz, got {}",
-            crossings
-        );
+        synth.prepare(44100.0);
+        synth.set_gain(0.2);
+        synth.set_filter_cutoff(500.0);
+        assert_eq!(synth.value(Continuous::Gain), 0.2);
+        assert!((synth.value(Continuous::FilterCutoff) - 500.0).abs() < 1e-2);
+        assert!(!synth.smoothing);
+        synth.note_on(60, 1.0);
+        synth.set_gain(0.6);
+        assert!(synth.smoothing);
+        assert_eq!(synth.value(Continuous::Gain), 0.2);
+        render_mod(&mut synth, 441);
+        assert_eq!(synth.value(Continuous::Gain), 0.6);
+        assert!(!synth.smoothing);
     }
 }
@@ -0,0 +1,253 @@
+use crate::params::{ModDestination, ModSource};
+
+/// Number of slots in the mod matrix.
+pub const MOD_SLOTS: usize = 8;
+
+/// Pitch change at full depth and full source, in semitones.
+pub const MOD_PITCH_SEMITONES: f32 = 12.0;
+
+/// How far a full-depth modulation scales an envelope time, in octaves
+/// (doublings or halvings) either way.
+pub const MOD_TIME_OCTAVES: f32 = 4.0;
+
+/// One routing: `source` scaled by `depth` is added to `destination`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModSlot {
+    pub source: ModSource,
+    pub destination: ModDestination,
+    /// Bipolar depth in `[-1, 1]`.
+    pub depth: f32,
+}
+
+impl ModSlot {
+    pub const OFF: ModSlot = ModSlot {
+        source: ModSource::Off,
+        destination: ModDestination::Off,
+        depth: 0.0,
+    };
+
+    fn is_active(&self) -> bool {
+        self.source != ModSource::Off
+            && self.destination != ModDestination::Off
+            && self.depth != 0.0
+    }
+}
+
+/// Values of every modulation source for one voice at one sample.
+///
+/// Envelopes, velocity and the controllers are unipolar (`0..1`); the LFO
+/// and key track are bipolar (`-1..1`).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ModSources {
+    pub lfo: f32,
+    pub amp_envelope: f32,
+    pub filter_envelope: f32,
+    pub velocity: f32,
+    pub keytrack: f32,
+    pub mod_wheel: f32,
+    pub aftertouch: f32,
+}
+
+impl ModSources {
+    pub fn value(&self, source: ModSource) -> f32 {
+        match source {
+            ModSource::Off => 0.0,
+            ModSource::Lfo => self.lfo,
+            ModSource::AmpEnvelope => self.amp_envelope,
+            ModSource::FilterEnvelope => self.filter_envelope,
+            ModSource::Velocity => self.velocity,
+            ModSource::KeyTrack => self.keytrack,
+            ModSource::ModWheel => self.mod_wheel,
+            ModSource::Aftertouch => self.aftertouch,
+        }
+    }
+}
+
+/// Summed modulation per destination, each the sum of `depth * source`
+/// over the slots routed there. The voice scales them into real units.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ModOffsets {
+    pub pitch: f32,
+    pub gain: f32,
+    pub pan: f32,
+    pub pulse_width: f32,
+    pub attack: f32,
+    pub decay: f32,
+    pub release: f32,
+    pub wavetable_position: f32,
+}
+
+impl ModOffsets {
+    /// Pitch offset in semitones.
+    pub fn pitch_semitones(&self) -> f32 {
+        self.pitch * MOD_PITCH_SEMITONES
+    }
+
+    /// Level multiplier, from silent up to double level.
+    pub fn gain_scale(&self) -> f32 {
+        (1.0 + self.gain).clamp(0.0, 2.0)
+    }
+
+    /// Multiplier for an envelope time given that time's offset.
+    pub fn time_scale(offset: f32) -> f32 {
+        if offset == 0.0 {
+            1.0
+        } else {
+            2.0f32.powf(offset * MOD_TIME_OCTAVES)
+        }
+    }
+}
+
+/// A fixed set of `MOD_SLOTS` routings from modulation sources to
+/// destinations.
+///
+/// The slots live in a plain array and evaluation only reads them, so the
+/// matrix can be evaluated per sample on the audio thread without
+/// allocating.
+#[derive(Debug, Clone, Copy)]
+pub struct ModMatrix {
+    slots: [ModSlot; MOD_SLOTS],
+}
+
+impl ModMatrix {
+    pub fn new() -> Self {
+        Self {
+            slots: [ModSlot::OFF; MOD_SLOTS],
+        }
+    }
+
+    /// Replace slot `index`. Out-of-range indices are ignored; depth is
+    /// clamped to `[-1, 1]`.
+    pub fn set_slot(&mut self, index: usize, slot: ModSlot) {
+        if let Some(s) = self.slots.get_mut(index) {
+            *s = ModSlot {
+                depth: slot.depth.clamp(-1.0, 1.0),
+                ..slot
+            };
+        }
+    }
+
+    pub fn slot(&self, index: usize) -> ModSlot {
+        self.slots[index]
+    }
+
+    /// Sum every active slot's contribution.
+    pub fn evaluate(&self, sources: &ModSources) -> ModOffsets {
+        let mut offsets = ModOffsets::default();
+        for slot in self.slots.iter().filter(|s| s.is_active()) {
+            let amount = slot.depth * sources.value(slot.source);
+            let target = match slot.destination {
+                ModDestination::Off => continue,
+                ModDestination::Pitch => &mut offsets.pitch,
+                ModDestination::Gain => &mut offsets.gain,
+                ModDestination::Pan => &mut offsets.pan,
+                ModDestination::PulseWidth => &mut offsets.pulse_width,
+                ModDestination::Attack => &mut offsets.attack,
+                ModDestination::Decay => &mut offsets.decay,
+                ModDestination::Release => &mut offsets.release,
+                ModDestination::WavetablePosition => &mut offsets.wavetable_position,
+            };
+            *target += amount;
+        }
+        offsets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot(source: ModSource, destination: ModDestination, depth: f32) -> ModSlot {
+        ModSlot {
+            source,
+            destination,
+            depth,
+        }
+    }
+
+    #[test]
+    fn test_empty_matrix_has_no_effect() {
+        let matrix = ModMatrix::new();
+        let sources = ModSources {
+            lfo: 1.0,
+            velocity: 1.0,
+            mod_wheel: 1.0,
+            ..ModSources::default()
+        };
+        assert_eq!(matrix.evaluate(&sources), ModOffsets::default());
+    }
+
+    #[test]
+    fn test_slots_scale_and_sum_per_destination() {
+        let mut matrix = ModMatrix::new();
+        matrix.set_slot(0, slot(ModSource::Lfo, ModDestination::Pitch, 0.5));
+        matrix.set_slot(1, slot(ModSource::ModWheel, ModDestination::Pitch, -0.25));
+        matrix.set_slot(2, slot(ModSource::Velocity, ModDestination::Gain, 1.0));
+        let sources = ModSources {
+            lfo: -1.0,
+            mod_wheel: 1.0,
+            velocity: 0.5,
+            ..ModSources::default()
+        };
+        let offsets = matrix.evaluate(&sources);
+        assert_eq!(offsets.pitch, -0.75);
+        assert_eq!(offsets.gain, 0.5);
+        assert_eq!(offsets.pan, 0.0);
+    }
+
+    #[test]
+    fn test_each_source_reaches_its_destination() {
+        let sources = ModSources {
+            lfo: 0.1,
+            amp_envelope: 0.2,
+            filter_envelope: 0.3,
+            velocity: 0.4,
+            keytrack: 0.5,
+            mod_wheel: 0.6,
+            aftertouch: 0.7,
+        };
+        for (i, &source) in ModSource::VARIANTS.iter().enumerate().skip(1) {
+            let mut matrix = ModMatrix::new();
+            matrix.set_slot(3, slot(source, ModDestination::Pan, 1.0));
+            let expected = i as f32 / 10.0;
+            assert_eq!(matrix.evaluate(&sources).pan, expected, "{:?}", source);
+        }
+    }
+
+    #[test]
+    fn test_off_slots_and_zero_depth_are_ignored() {
+        let mut matrix = ModMatrix::new();
+        matrix.set_slot(0, slot(ModSource::Off, ModDestination::Pitch, 1.0));
+        matrix.set_slot(1, slot(ModSource::Lfo, ModDestination::Off, 1.0));
+        matrix.set_slot(2, slot(ModSource::Lfo, ModDestination::Pitch, 0.0));
+        let sources = ModSources {
+            lfo: 1.0,
+            ..ModSources::default()
+        };
+        assert_eq!(matrix.evaluate(&sources), ModOffsets::default());
+    }
+
+    #[test]
+    fn test_depth_is_clamped_and_bad_index_ignored() {
+        let mut matrix = ModMatrix::new();
+        matrix.set_slot(0, slot(ModSource::Lfo, ModDestination::Gain, 5.0));
+        matrix.set_slot(MOD_SLOTS, slot(ModSource::Lfo, ModDestination::Gain, 1.0));
+        assert_eq!(matrix.slot(0).depth, 1.0);
+    }
+
+    #[test]
+    fn test_time_scale_is_exponential() {
+        assert_eq!(ModOffsets::time_scale(0.0), 1.0);
+        assert_eq!(ModOffsets::time_scale(0.25), 2.0);
+        assert_eq!(ModOffsets::time_scale(-0.25), 0.5);
+    }
+
+    #[test]
+    fn test_gain_scale_stays_non_negative() {
+        let offsets = ModOffsets {
+            gain: -3.0,
+            ..ModOffsets::default()
+        };
+        assert_eq!(offsets.gain_scale(), 0.0);
+    }
+}
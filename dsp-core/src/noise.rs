@@ -0,0 +1,135 @@
+use crate::params::NoiseType;
+use crate::rng::Rng;
+
+/// Rough gain bringing the pink filter's output back to about the range
+/// of the white noise feeding it.
+const PINK_GAIN: f32 = 0.11;
+
+/// White or pink noise source.
+///
+/// Draws from the crate's seedable `Rng` rather than any system source, so
+/// it runs anywhere `dsp-core` does (WASM included) and a given seed always
+/// makes the same noise. Pink noise is white noise through Paul Kellett's
+/// parallel one-pole filter bank, which holds -3 dB/octave to within about
+/// 0.05 dB across the audio band.
+pub struct Noise {
+    noise_type: NoiseType,
+    rng: Rng,
+    /// Pink filter states.
+    pink: [f32; 7],
+}
+
+impl Noise {
+    pub fn new(seed: u32) -> Self {
+        Self {
+            noise_type: NoiseType::White,
+            rng: Rng::new(seed),
+            pink: [0.0; 7],
+        }
+    }
+
+    pub fn set_type(&mut self, noise_type: NoiseType) {
+        self.noise_type = noise_type;
+    }
+
+    /// Restart the sequence from `seed`.
+    pub fn seed(&mut self, seed: u32) {
+        self.rng = Rng::new(seed);
+        self.pink = [0.0; 7];
+    }
+
+    /// Generate the next sample, roughly in `[-1, 1]`.
+    pub fn tick(&mut self) -> f32 {
+        let white = self.rng.next_bipolar();
+        match self.noise_type {
+            NoiseType::White => white,
+            NoiseType::Pink => {
+                let b = &mut self.pink;
+                b[0] = 0.99886 * b[0] + white * 0.0555179;
+                b[1] = 0.99332 * b[1] + white * 0.0750759;
+                b[2] = 0.96900 * b[2] + white * 0.153852;
+                b[3] = 0.86650 * b[3] + white * 0.3104856;
+                b[4] = 0.55000 * b[4] + white * 0.5329522;
+                b[5] = -0.7616 * b[5] - white * 0.0168980;
+                let pink = b[0] + b[1] + b[2] + b[3] + b[4] + b[5] + b[6] + white * 0.5362;
+                b[6] = white * 0.115926;
+                pink * PINK_GAIN
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustfft::{num_complex::Complex, FftPlanner};
+
+    fn render(noise_type: NoiseType, seed: u32, n: usize) -> Vec<f32> {
+        let mut noise = Noise::new(seed);
+        noise.set_type(noise_type);
+        (0..n).map(|_| noise.tick()).collect()
+    }
+
+    /// Average power in `[lo, hi)` Hz at 44.1 kHz, over 4096-point frames.
+    fn band_power(samples: &[f32], lo: f32, hi: f32) -> f32 {
+        const N: usize = 4096;
+        let fft = FftPlanner::new().plan_fft_forward(N);
+        let bin_hz = 44100.0 / N as f32;
+        let mut power = 0.0;
+        let mut bins = 0;
+        for frame in samples.chunks_exact(N) {
+            let mut buf: Vec<Complex<f32>> = frame.iter().map(|&s| Complex::new(s, 0.0)).collect();
+            fft.process(&mut buf);
+            for (k, c) in buf.iter().enumerate().take(N / 2) {
+                let hz = k as f32 * bin_hz;
+                if hz >= lo && hz < hi {
+                    power += c.norm_sqr();
+                    bins += 1;
+                }
+            }
+        }
+        power / bins as f32
+    }
+
+    #[test]
+    fn test_same_seed_same_noise() {
+        for &noise_type in NoiseType::VARIANTS {
+            assert_eq!(render(noise_type, 7, 1000), render(noise_type, 7, 1000));
+            assert_ne!(render(noise_type, 7, 1000), render(noise_type, 8, 1000));
+        }
+    }
+
+    #[test]
+    fn test_reseeding_restarts_sequence() {
+        let mut noise = Noise::new(3);
+        noise.set_type(NoiseType::Pink);
+        let first: Vec<f32> = (0..100).map(|_| noise.tick()).collect();
+        noise.seed(3);
+        let again: Vec<f32> = (0..100).map(|_| noise.tick()).collect();
+        assert_eq!(first, again);
+    }
+
+    #[test]
+    fn test_noise_stays_in_range() {
+        for &noise_type in NoiseType::VARIANTS {
+            let samples = render(noise_type, 1, 44100);
+            assert!(samples.iter().all(|s| s.abs() <= 1.0), "{:?}", noise_type);
+            let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+            assert!(mean.abs() < 0.05, "{:?} mean {}", noise_type, mean);
+        }
+    }
+
+    #[test]
+    fn test_white_is_flat_and_pink_falls_3db_per_octave() {
+        let white = render(NoiseType::White, 1, 4096 * 32);
+        let pink = render(NoiseType::Pink, 1, 4096 * 32);
+        // Two octave-wide bands, four octaves apart
+        let db = |s: &[f32]| {
+            10.0 * (band_power(s, 250.0, 500.0) / band_power(s, 4000.0, 8000.0)).log10()
+        };
+        let white_tilt = db(&white);
+        let pink_tilt = db(&pink);
+        assert!(white_tilt.abs() < 1.0, "white tilt {} dB", white_tilt);
+        assert!((pink_tilt - 12.0).abs() < 1.5, "pink tilt {} dB", pink_tilt);
+    }
+}
@@ -0,0 +1,139 @@
+use crate::params::NotePriority;
+
+/// Every MIDI note can be held at most once, so 128 slots always suffice.
+const CAPACITY: usize = 128;
+
+/// Fixed-capacity stack of currently held keys, in the order they were pressed.
+///
+/// Mono mode uses this to fall back to a still-held key when the sounding one
+/// is released, like a hardware mono synth. Push and remove are O(n) over the
+/// held keys but never allocate.
+pub struct NoteStack {
+    notes: [u8; CAPACITY],
+    len: usize,
+}
+
+impl NoteStack {
+    pub fn new() -> Self {
+        Self {
+            notes: [0; CAPACITY],
+            len: 0,
+        }
+    }
+
+    /// Record a key press. A key that is already held moves to the top.
+    pub fn push(&mut self, note: u8) {
+        self.remove(note);
+        if self.len < CAPACITY {
+            self.notes[self.len] = note;
+            self.len += 1;
+        }
+    }
+
+    /// Record a key release. Releasing a key that isn't held is a no-op.
+    pub fn remove(&mut self, note: u8) {
+        if let Some(pos) = self.held().iter().position(|&n| n == note) {
+            self.notes.copy_within(pos + 1..self.len, pos);
+            self.len -= 1;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Held keys, oldest first.
+    pub fn held(&self) -> &[u8] {
+        &self.notes[..self.len]
+    }
+
+    /// The held key that should sound under the given priority rule.
+    pub fn select(&self, priority: NotePriority) -> Option<u8> {
+        let held = self.held();
+        match priority {
+            NotePriority::Last => held.last().copied(),
+            NotePriority::Low => held.iter().min().copied(),
+            NotePriority::High => held.iter().max().copied(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_stack_is_empty() {
+        let stack = NoteStack::new();
+        assert!(stack.is_empty());
+        assert_eq!(stack.select(NotePriority::Last), None);
+    }
+
+    #[test]
+    fn test_push_keeps_press_order() {
+        let mut stack = NoteStack::new();
+        stack.push(64);
+        stack.push(60);
+        stack.push(67);
+        assert_eq!(stack.held(), &[64, 60, 67]);
+    }
+
+    #[test]
+    fn test_repeated_push_moves_note_to_top() {
+        let mut stack = NoteStack::new();
+        stack.push(60);
+        stack.push(64);
+        stack.push(60);
+        assert_eq!(stack.held(), &[64, 60]);
+    }
+
+    #[test]
+    fn test_remove_from_middle() {
+        let mut stack = NoteStack::new();
+        stack.push(60);
+        stack.push(64);
+        stack.push(67);
+        stack.remove(64);
+        assert_eq!(stack.held(), &[60, 67]);
+    }
+
+    #[test]
+    fn test_remove_unknown_note_is_noop() {
+        let mut stack = NoteStack::new();
+        stack.push(60);
+        stack.remove(61);
+        assert_eq!(stack.held(), &[60]);
+    }
+
+    #[test]
+    fn test_select_by_priority() {
+        let mut stack = NoteStack::new();
+        stack.push(64);
+        stack.push(60);
+        stack.push(67);
+        stack.push(62);
+        assert_eq!(stack.select(NotePriority::Last), Some(62));
+        assert_eq!(stack.select(NotePriority::Low), Some(60));
+        assert_eq!(stack.select(NotePriority::High), Some(67));
+    }
+
+    #[test]
+    fn test_all_notes_fit() {
+        let mut stack = NoteStack::new();
+        for note in 0..=127u8 {
+            stack.push(note);
+        }
+        assert_eq!(stack.len(), 128);
+        assert_eq!(stack.select(NotePriority::Last), Some(127));
+        stack.clear();
+        assert!(stack.is_empty());
+    }
+}
@@ -1,19 +1,44 @@
-use crate::params::OscillatorType;
+use crate::additive::{self, MAX_PARTIALS};
+use crate::params::{AdditivePreset, OscillatorType, WavetableBank};
+use crate::wavetable::{self, Wavetable};
 use core::f32::consts::PI;
+use std::sync::Arc;
 
 /// A phase-accumulator oscillator with PolyBLEP anti-aliasing.
 ///
 /// PolyBLEP (Polynomial Band-Limited Step) applies a small correction near
 /// waveform discontinuities, dramatically reducing aliasing artifacts in
 /// square, saw, and triangle waves without the cost of oversampling.
+///
+/// In wavetable mode it instead reads a shared, mipmapped `Wavetable`,
+/// picking the mip level from the pitch so the table stays band-limited.
+/// In additive mode it sums sine partials, leaving out any that would land
+/// above Nyquist.
 pub struct Oscillator {
     phase: f32,
     phase_delta: f32,
     sample_rate: f32,
     frequency: f32,
     osc_type: OscillatorType,
+    /// Fraction of the square wave's cycle spent high.
+    pulse_width: f32,
     // Running sum for PolyBLEP-integrated triangle wave
     tri_integrator: f32,
+    wavetable: Arc<Wavetable>,
+    /// Scan position through the wavetable's frames, in `[0, 1]`.
+    wavetable_position: f32,
+    /// Mip level matching the current phase delta.
+    wavetable_level: usize,
+    /// Second half of the band-limiting for a hard sync reset, applied on
+    /// the sample after the reset.
+    sync_residual: f32,
+    /// Linear FM: the phase increment is scaled by `1 + linear_fm`.
+    linear_fm: f32,
+    /// Additive partial amplitudes, fundamental first. Boxed to keep the
+    /// many oscillators in a voice pool from bloating it.
+    partials: Box<[f32; MAX_PARTIALS]>,
+    /// Partials below Nyquist at the current phase delta.
+    partial_limit: usize,
 }
 
 impl Oscillator {
@@ -24,7 +49,15 @@ impl Oscillator {
             sample_rate: 44100.0,
             frequency: 440.0,
             osc_type: OscillatorType::Sine,
+            pulse_width: 0.5,
             tri_integrator: 0.0,
+            wavetable: wavetable::builtin(WavetableBank::Basic),
+            wavetable_position: 0.0,
+            wavetable_level: 0,
+            sync_residual: 0.0,
+            linear_fm: 0.0,
+            partials: Box::new(additive::preset_spectrum(AdditivePreset::Sine)),
+            partial_limit: MAX_PARTIALS,
         }
     }
 
@@ -42,47 +75,231 @@ impl Oscillator {
         self.osc_type = osc_type;
     }
 
+    /// Linear frequency modulation: scale the frequency by `1 + amount`.
+    /// Below -1 the phase runs backwards (through-zero FM). Takes effect
+    /// from the next sample, so it can be modulated per sample.
+    pub fn set_linear_fm(&mut self, amount: f32) {
+        self.linear_fm = amount;
+        self.update_phase_delta();
+    }
+
+    /// Square wave duty cycle, clamped to `[MIN_PULSE_WIDTH, MAX_PULSE_WIDTH]`.
+    /// Takes effect from the next sample, so it can be modulated per sample.
+    /// The triangle is always built from a 50% square.
+    pub fn set_pulse_width(&mut self, width: f32) {
+        self.pulse_width = width.clamp(MIN_PULSE_WIDTH, MAX_PULSE_WIDTH);
+    }
+
+    /// Table read in `OscillatorType::Wavetable` mode.
+    pub fn set_wavetable(&mut self, table: &Arc<Wavetable>) {
+        if !Arc::ptr_eq(&self.wavetable, table) {
+            self.wavetable = table.clone();
+        }
+    }
+
+    /// Scan position through the wavetable's frames, clamped to `[0, 1]`.
+    pub fn set_wavetable_position(&mut self, position: f32) {
+        self.wavetable_position = position.clamp(0.0, 1.0);
+    }
+
+    /// Amplitude of additive partial `index` (0 is the fundamental),
+    /// clamped to `[-1, 1]`. Out-of-range indices are ignored.
+    pub fn set_partial(&mut self, index: usize, amplitude: f32) {
+        if let Some(partial) = self.partials.get_mut(index) {
+            *partial = amplitude.clamp(-1.0, 1.0);
+        }
+    }
+
     pub fn reset(&mut self) {
-        self.phase = 0.0;
+        self.reset_to(0.0);
+    }
+
+    /// Restart the waveform at `phase` (a fraction of a cycle in `[0, 1)`).
+    pub fn reset_to(&mut self, phase: f32) {
+        self.phase = phase.rem_euclid(1.0);
         self.tri_integrator = 0.0;
+        self.sync_residual = 0.0;
     }
 
     /// Generate the next sample and advance the phase.
     pub fn tick(&mut self) -> f32 {
+        let sample = self.render(0.0);
+        self.advance();
+        sample
+    }
+
+    /// Generate the next sample with the waveform read `offset` cycles
+    /// away from the running phase (phase modulation), then advance. The
+    /// running phase itself is left alone, so the pitch doesn't drift.
+    pub fn tick_modulated(&mut self, offset: f32) -> f32 {
+        let phase = self.phase;
+        let shifted = phase + offset;
+        self.phase = shifted - shifted.floor();
+        let sample = self.render(0.0);
+        self.phase = phase;
+        self.advance();
+        sample
+    }
+
+    /// Where in the next sample this oscillator's cycle ends, as a fraction
+    /// of the sample, or `None` if it doesn't end during the next sample.
+    /// Call before `tick`; a hard-synced oscillator restarts at that point.
+    pub fn wrap_position(&self) -> Option<f32> {
+        // Same test as `advance`, so the two never disagree on rounding
+        if self.phase + self.phase_delta >= 1.0 {
+            Some((1.0 - self.phase) / self.phase_delta)
+        } else {
+            None
+        }
+    }
+
+    /// Generate the next sample of an oscillator hard-synced to another.
+    ///
+    /// `master_wrap` is the master's `wrap_position` for this sample. When
+    /// it is set, the cycle restarts at that point, and the jump in the
+    /// waveform is band-limited with a PolyBLEP spread over this sample and
+    /// the next, the same way the waveforms' own edges are.
+    pub fn tick_synced(&mut self, master_wrap: Option<f32>) -> f32 {
+        let residual = core::mem::take(&mut self.sync_residual);
+        let Some(wrap) = master_wrap else {
+            let sample = self.render(residual);
+            self.advance();
+            return sample;
+        };
         let dt = self.phase_delta;
-        let sample = match self.osc_type {
-            OscillatorType::Sine => generate_sine(self.phase),
-            OscillatorType::Saw => generate_saw_polyblep(self.phase, dt),
-            OscillatorType::Square => generate_square_polyblep(self.phase, dt),
+        let edge = self.wrap_step();
+        let mut correction = residual;
+        let mut residual = 0.0;
+        let mut at_reset = self.phase + wrap * dt;
+        // Samples until the cycle would have ended by itself
+        let own = (1.0 - self.phase) / dt;
+        if at_reset >= 1.0 {
+            // It did, just before the reset; the second half of that edge's
+            // PolyBLEP still lands at its own time
+            at_reset -= 1.0;
+            residual -= 0.5 * edge * own * own;
+        } else if own < 1.0 {
+            // The reset cuts the cycle short, so the edge the PolyBLEP is
+            // already correcting for never comes
+            correction -= 0.5 * edge * (1.0 - own) * (1.0 - own);
+        }
+        // Jump from where the cycle is cut off to its start. The PolyBLEP
+        // at phase 0 already covers the waveform's own edge there on the
+        // next sample, so only the difference is left over for it.
+        let step = self.naive(0.0) - self.naive(at_reset);
+        let sample = self.render(correction + 0.5 * step * (1.0 - wrap) * (1.0 - wrap));
+        self.sync_residual = residual - 0.5 * (step - edge) * wrap * wrap;
+        self.phase = (1.0 - wrap) * dt;
+        sample
+    }
+
+    /// Produce the sample at the current phase with `correction` added to
+    /// the waveform's discontinuous part.
+    fn render(&mut self, correction: f32) -> f32 {
+        // Edges are band-limited the same whichever way the phase runs
+        let dt = self.phase_delta.abs();
+        match self.osc_type {
+            OscillatorType::Sine => generate_sine(self.phase) + correction,
+            OscillatorType::Saw => generate_saw_polyblep(self.phase, dt) + correction,
+            OscillatorType::Square => {
+                generate_pulse_polyblep(self.phase, dt, self.pulse_width) + correction
+            }
             OscillatorType::Triangle => {
                 // PolyBLEP triangle: integrate a PolyBLEP square wave, then
                 // normalize. This produces a band-limited triangle with smooth
                 // peaks instead of the sharp corners of a naive triangle.
-                let square = generate_square_polyblep(self.phase, dt);
+                let square = generate_pulse_polyblep(self.phase, dt, 0.5) + correction;
                 // Leaky integrator: the 4.0 * dt factor normalizes amplitude;
-                // the leak term (1.0 - dt) prevents DC drift.
-                self.tri_integrator = dt * square + (1.0 - dt) * self.tri_integrator;
+                // the leak term (1.0 - dt) prevents DC drift. The signed
+                // increment integrates backwards when the phase runs backwards.
+                self.tri_integrator =
+                    self.phase_delta * square + (1.0 - dt) * self.tri_integrator;
                 // Scale to approximately [-1, 1] range
                 self.tri_integrator * 4.0
             }
-        };
+            OscillatorType::Wavetable => {
+                self.wavetable
+                    .sample(self.wavetable_level, self.wavetable_position, self.phase)
+                    + correction
+            }
+            OscillatorType::Additive => {
+                additive::render(&self.partials[..self.partial_limit], self.phase) + correction
+            }
+        }
+    }
 
-        self.phase += dt;
+    fn advance(&mut self) {
+        self.phase += self.phase_delta;
         if self.phase >= 1.0 {
             self.phase -= 1.0;
+        } else if self.phase < 0.0 {
+            self.phase += 1.0;
+        }
+    }
+
+    /// Waveform at `phase` without band-limiting. The triangle is built
+    /// from a square, so its sync jumps are measured on that square.
+    fn naive(&self, phase: f32) -> f32 {
+        match self.osc_type {
+            OscillatorType::Sine => generate_sine(phase),
+            OscillatorType::Saw => 2.0 * phase - 1.0,
+            OscillatorType::Square if phase < self.pulse_width => 1.0,
+            OscillatorType::Triangle if phase < 0.5 => 1.0,
+            OscillatorType::Square | OscillatorType::Triangle => -1.0,
+            OscillatorType::Wavetable => {
+                self.wavetable
+                    .sample(self.wavetable_level, self.wavetable_position, phase)
+            }
+            OscillatorType::Additive => {
+                additive::render(&self.partials[..self.partial_limit], phase)
+            }
+        }
+    }
+
+    /// Size of the edge at phase 0 that the waveform's own PolyBLEP
+    /// corrects.
+    fn wrap_step(&self) -> f32 {
+        match self.osc_type {
+            OscillatorType::Saw => -2.0,
+            OscillatorType::Square | OscillatorType::Triangle => 2.0,
+            OscillatorType::Sine | OscillatorType::Wavetable | OscillatorType::Additive => 0.0,
         }
-        sample
     }
 
     fn update_phase_delta(&mut self) {
-        self.phase_delta = self.frequency / self.sample_rate;
+        self.phase_delta = self.frequency * (1.0 + self.linear_fm) / self.sample_rate;
+        self.wavetable_level = wavetable::mip_level(self.phase_delta.abs());
+        self.partial_limit = additive::partial_limit(self.phase_delta);
     }
 }
 
+/// Narrowest pulse width. Much thinner and the pulse gets so short that it
+/// is mostly PolyBLEP correction and fades out.
+pub const MIN_PULSE_WIDTH: f32 = 0.05;
+pub const MAX_PULSE_WIDTH: f32 = 0.95;
+
 // --- Waveform generators ---
 
-fn generate_sine(phase: f32) -> f32 {
-    (phase * 2.0 * PI).sin()
+/// Sine of `phase` cycles from a polynomial rather than the platform's
+/// `sin`. It only adds, multiplies and rounds, which IEEE 754 pins down
+/// exactly, so native and WASM builds produce the same bits.
+pub(crate) fn generate_sine(phase: f32) -> f32 {
+    let phase = phase - phase.floor();
+    // Fold onto the quarter cycle either side of zero, as sin(π - x) = sin(x)
+    let quarter = if phase < 0.25 {
+        phase
+    } else if phase < 0.75 {
+        0.5 - phase
+    } else {
+        phase - 1.0
+    };
+    let x = 2.0 * PI * quarter;
+    let x2 = x * x;
+    // Taylor series to x^11: under 1e-7 from the true sine at the peaks
+    x * (1.0
+        + x2 * (-1.0 / 6.0
+            + x2 * (1.0 / 120.0
+                + x2 * (-1.0 / 5040.0 + x2 * (1.0 / 362880.0 + x2 * (-1.0 / 39916800.0))))))
 }
 
 /// Naive saw: rises from -1 to +1 over one period.
@@ -93,14 +310,16 @@ fn generate_saw_polyblep(phase: f32, dt: f32) -> f32 {
     naive - polyblep(phase, dt)
 }
 
-/// Naive square: +1 for first half, -1 for second half.
-/// PolyBLEP corrections at both transitions (phase ≈ 0 and phase ≈ 0.5).
-fn generate_square_polyblep(phase: f32, dt: f32) -> f32 {
-    let naive = if phase < 0.5 { 1.0 } else { -1.0 };
+/// Naive pulse: +1 for the first `width` of the cycle, -1 for the rest.
+/// PolyBLEP corrections at the rising edge (phase ≈ 0) and at the falling
+/// edge (phase ≈ width). The falling-edge correction is placed relative to
+/// `width`, so it follows the edge as the width is modulated.
+fn generate_pulse_polyblep(phase: f32, dt: f32, width: f32) -> f32 {
+    let naive = if phase < width { 1.0 } else { -1.0 };
     // Correction at the rising edge (phase ≈ 0)
     let mut sample = naive + polyblep(phase, dt);
-    // Correction at the falling edge (phase ≈ 0.5)
-    sample -= polyblep((phase + 0.5) % 1.0, dt);
+    // Correction at the falling edge (phase ≈ width)
+    sample -= polyblep((phase + 1.0 - width) % 1.0, dt);
     sample
 }
 
@@ -132,6 +351,7 @@ fn polyblep(t: f32, dt: f32) -> f32 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::spectrum;
 
     fn collect_samples(osc_type: OscillatorType, freq: f32, sample_rate: f32, n: usize) -> Vec<f32> {
         let mut osc = Oscillator::new();
@@ -142,6 +362,25 @@ mod tests {
         (0..n).map(|_| osc.tick()).collect()
     }
 
+    /// Loudest bin that isn't a multiple of `spacing`, relative to bin
+    /// `spacing`, in dB.
+    fn worst_alias_db(mags: &[f32], spacing: usize) -> f32 {
+        let worst = mags
+            .iter()
+            .enumerate()
+            .filter(|&(bin, _)| bin % spacing != 0)
+            .fold(0.0f32, |m, (_, &v)| m.max(v));
+        20.0 * (worst / mags[spacing]).log10()
+    }
+
+    fn pulse(width: f32, freq: f32, n: usize) -> Vec<f32> {
+        let mut osc = Oscillator::new();
+        osc.set_type(OscillatorType::Square);
+        osc.set_frequency(freq);
+        osc.set_pulse_width(width);
+        (0..n).map(|_| osc.tick()).collect()
+    }
+
     fn count_zero_crossings(samples: &[f32]) -> usize {
         samples
             .windows(2)
@@ -207,6 +446,33 @@ mod tests {
         assert!((samples[3] - -1.0).abs() < eps, "sin(3pi/2) = {}", samples[3]);
     }
 
+    #[test]
+    fn test_sine_matches_platform_sine() {
+        for i in -2000..=2000 {
+            let phase = i as f32 / 1000.0;
+            let expected = (2.0 * PI * phase).sin();
+            assert!((generate_sine(phase) - expected).abs() < 1e-6, "phase {}", phase);
+        }
+    }
+
+    #[test]
+    fn test_phase_modulation_offsets_waveform() {
+        let mut plain = Oscillator::new();
+        plain.set_frequency(441.0);
+        let mut modulated = Oscillator::new();
+        modulated.set_frequency(441.0);
+        for _ in 0..1000 {
+            // A quarter cycle ahead turns the sine into a cosine
+            let phase = plain.phase;
+            assert_eq!(modulated.tick_modulated(0.0), plain.tick());
+            let cosine = modulated.tick_modulated(0.25);
+            assert!((cosine - (2.0 * PI * (phase + plain.phase_delta)).cos()).abs() < 1e-5);
+            plain.tick();
+        }
+        // The offset never moves the running phase
+        assert_eq!(modulated.phase, plain.phase);
+    }
+
     #[test]
     fn test_sine_frequency_accuracy() {
         let samples = collect_samples(OscillatorType::Sine, 440.0, 44100.0, 44100);
@@ -297,6 +563,339 @@ mod tests {
         assert!(mean.abs() < 0.02, "square DC offset: {}", mean);
     }
 
+    #[test]
+    fn test_pulse_width_shifts_dc() {
+        let mut osc = Oscillator::new();
+        osc.set_frequency(441.0);
+        osc.set_type(OscillatorType::Square);
+        osc.set_pulse_width(0.25);
+        let mean = (0..44100).map(|_| osc.tick()).sum::<f32>() / 44100.0;
+        // High for a quarter of the cycle: 0.25 - 0.75
+        assert!((mean + 0.5).abs() < 0.02, "25% pulse DC offset: {}", mean);
+    }
+
+    #[test]
+    fn test_pulse_dc_tracks_duty_cycle() {
+        for &width in &[MIN_PULSE_WIDTH, 0.1, 0.3, 0.5, 0.7, 0.9, MAX_PULSE_WIDTH] {
+            // 441 Hz: exactly 100 samples per cycle
+            let samples = pulse(width, 441.0, 44100);
+            let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+            let expected = 2.0 * width - 1.0;
+            assert!((mean - expected).abs() < 0.01, "{} width: DC {}", width, mean);
+        }
+    }
+
+    #[test]
+    fn test_pulse_harmonics_track_duty_cycle() {
+        // Harmonic n of a pulse with duty cycle D has an amplitude
+        // proportional to |sin(pi n D)| / n: a square has no even
+        // harmonics, a 25% pulse has no multiples of the 4th
+        for &width in &[0.5, 0.25, 0.1] {
+            // 100 Hz fits exactly 10 cycles into 4410 samples, so harmonic
+            // n lands on bin 10n
+            let mags = spectrum(&pulse(width, 100.0, 4410));
+            let expected = |n: f32| (PI * n * width).sin().abs() / n;
+            for n in 2..=8 {
+                let relative = mags[10 * n] / mags[10];
+                let predicted = expected(n as f32) / expected(1.0);
+                assert!(
+                    (relative - predicted).abs() < 0.01,
+                    "{} width, harmonic {}: {} vs {}",
+                    width, n, relative, predicted
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_pulse_edge_correction_follows_width() {
+        // 1500 Hz: harmonics on every 150th bin of 4410, aliases between.
+        // PolyBLEP does most of its work well below Nyquist, so compare
+        // the aliasing under 10 kHz
+        for &width in &[0.1, 0.3, 0.5, 0.8] {
+            let corrected = worst_alias_db(&spectrum(&pulse(width, 1500.0, 4410))[..1000], 150);
+            let naive: Vec<f32> = (0..4410)
+                .map(|i| {
+                    let phase = (i as f32 * 1500.0 / 44100.0) % 1.0;
+                    if phase < width {
+                        1.0
+                    } else {
+                        -1.0
+                    }
+                })
+                .collect();
+            let naive = worst_alias_db(&spectrum(&naive)[..1000], 150);
+            assert!(
+                corrected < naive - 15.0,
+                "{} width: {} vs naive {} dB",
+                width, corrected, naive
+            );
+        }
+    }
+
+    #[test]
+    fn test_pulse_width_is_clamped() {
+        assert_eq!(pulse(0.0, 441.0, 4410), pulse(MIN_PULSE_WIDTH, 441.0, 4410));
+        assert_eq!(pulse(1.0, 441.0, 4410), pulse(MAX_PULSE_WIDTH, 441.0, 4410));
+    }
+
+    /// `slave` hard-synced to a saw `master`, both at 44.1 kHz.
+    fn synced(slave_type: OscillatorType, master_hz: f32, slave_hz: f32, n: usize) -> Vec<f32> {
+        let mut master = Oscillator::new();
+        master.set_type(OscillatorType::Saw);
+        master.set_frequency(master_hz);
+        let mut slave = Oscillator::new();
+        slave.set_type(slave_type);
+        slave.set_frequency(slave_hz);
+        (0..n)
+            .map(|_| {
+                let wrap = master.wrap_position();
+                master.tick();
+                slave.tick_synced(wrap)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_linear_fm_scales_frequency() {
+        let mut osc = Oscillator::new();
+        osc.set_type(OscillatorType::Sine);
+        osc.set_frequency(220.0);
+        osc.set_linear_fm(1.0);
+        let samples: Vec<f32> = (0..44100).map(|_| osc.tick()).collect();
+        let crossings = count_zero_crossings(&samples);
+        assert!((crossings as i32 - 880).abs() <= 2, "{}", crossings);
+    }
+
+    #[test]
+    fn test_through_zero_fm_runs_waveform_backwards() {
+        for &osc_type in OscillatorType::VARIANTS {
+            let mut forward = Oscillator::new();
+            forward.set_type(osc_type);
+            forward.set_frequency(441.0);
+            let mut backward = Oscillator::new();
+            backward.set_type(osc_type);
+            backward.set_frequency(441.0);
+            backward.set_linear_fm(-2.0);
+            let forward: Vec<f32> = (0..4000).map(|_| forward.tick()).collect();
+            let backward: Vec<f32> = (0..4000).map(|_| backward.tick()).collect();
+            // Past the triangle integrator's startup transient
+            let settled = &backward[400..];
+            assert!(settled.iter().all(|s| s.abs() <= 1.2), "{:?}", osc_type);
+            if osc_type == OscillatorType::Sine {
+                // sin(-x) = -sin(x)
+                for (f, b) in forward.iter().zip(&backward) {
+                    assert!((f + b).abs() < 1e-3);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sync_without_wraps_matches_free_running() {
+        let mut free = Oscillator::new();
+        free.set_type(OscillatorType::Saw);
+        free.set_frequency(660.0);
+        let mut synced = Oscillator::new();
+        synced.set_type(OscillatorType::Saw);
+        synced.set_frequency(660.0);
+        for _ in 0..1000 {
+            assert_eq!(synced.tick_synced(None), free.tick());
+        }
+    }
+
+    #[test]
+    fn test_sync_at_same_pitch_matches_free_running() {
+        // Both cycles end together, so the reset changes nothing
+        for &osc_type in &[OscillatorType::Saw, OscillatorType::Square] {
+            let mut free = Oscillator::new();
+            free.set_type(osc_type);
+            free.set_frequency(1234.5);
+            let samples = synced(osc_type, 1234.5, 1234.5, 4410);
+            for (i, &s) in samples.iter().enumerate() {
+                let expected = free.tick();
+                assert!((s - expected).abs() < 1e-3, "{:?} sample {}: {} vs {}", osc_type, i, s, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_wrap_position_marks_cycle_end() {
+        let mut osc = Oscillator::new();
+        // 100 samples per cycle
+        osc.set_frequency(441.0);
+        let wraps: Vec<usize> = (0..1050)
+            .filter(|_| {
+                let wrap = osc.wrap_position();
+                osc.tick();
+                wrap.is_some()
+            })
+            .collect();
+        assert_eq!(wraps.len(), 10);
+        assert!(wraps.windows(2).all(|w| w[1] - w[0] == 100));
+    }
+
+    #[test]
+    fn test_sync_locks_slave_to_master_period() {
+        // 441 Hz master: 100 samples per cycle. The slave runs at an
+        // unrelated pitch but repeats with the master
+        for &osc_type in OscillatorType::VARIANTS {
+            let samples = synced(osc_type, 441.0, 1234.5, 2000);
+            for i in 1000..1900 {
+                assert!(
+                    (samples[i] - samples[i + 100]).abs() < 1e-3,
+                    "{:?} sample {}: {} vs {}",
+                    osc_type, i, samples[i], samples[i + 100]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_sync_reset_is_band_limited() {
+        // 1500 Hz master: harmonics on every 150th bin of 4410, aliases
+        // between. Compare against resetting the slave's phase with no
+        // correction
+        for &slave_hz in &[2300.0, 3555.0, 5210.0] {
+            let corrected = worst_alias_db(
+                &spectrum(&synced(OscillatorType::Saw, 1500.0, slave_hz, 4410))[..1000],
+                150,
+            );
+            let mut master = 0.0f32;
+            let mut slave = 0.0f32;
+            let naive: Vec<f32> = (0..4410)
+                .map(|_| {
+                    let sample = 2.0 * slave - 1.0;
+                    master += 1500.0 / 44100.0;
+                    slave += slave_hz / 44100.0;
+                    if master >= 1.0 {
+                        master -= 1.0;
+                        slave = master * slave_hz / 1500.0;
+                    }
+                    slave %= 1.0;
+                    sample
+                })
+                .collect();
+            let naive = worst_alias_db(&spectrum(&naive)[..1000], 150);
+            assert!(
+                corrected < naive - 15.0,
+                "{} Hz slave: {} vs naive {} dB",
+                slave_hz, corrected, naive
+            );
+        }
+    }
+
+    #[test]
+    fn test_reset_to_starts_at_phase() {
+        let mut osc = Oscillator::new();
+        osc.set_type(OscillatorType::Saw);
+        osc.set_frequency(100.0);
+        osc.reset_to(0.75);
+        assert!((osc.tick() - 0.5).abs() < 1e-6);
+        // Phases outside a cycle wrap into it
+        osc.reset_to(-0.25);
+        assert!((osc.tick() - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_wavetable_position_scans_frames() {
+        // The basic table starts with a sine and ends with a square
+        let mut osc = Oscillator::new();
+        osc.set_type(OscillatorType::Wavetable);
+        osc.set_frequency(100.0);
+        let mut sine = Oscillator::new();
+        sine.set_frequency(100.0);
+        for _ in 0..1000 {
+            assert!((osc.tick() - sine.tick()).abs() < 1e-3);
+        }
+        osc.set_wavetable_position(1.0);
+        osc.reset_to(0.25);
+        assert!((osc.tick() - 1.0).abs() < 0.1);
+        osc.reset_to(0.75);
+        assert!((osc.tick() + 1.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_wavetable_high_notes_stay_band_limited() {
+        // 1500 Hz fits exactly 150 cycles into 4410 samples, so harmonics
+        // land on every 150th bin and anything in between is aliasing
+        let mut osc = Oscillator::new();
+        osc.set_type(OscillatorType::Wavetable);
+        osc.set_wavetable_position(2.0 / 3.0); // saw frame
+        osc.set_frequency(1500.0);
+        let samples: Vec<f32> = (0..4410).map(|_| osc.tick()).collect();
+        let db = worst_alias_db(&spectrum(&samples), 150);
+        assert!(db < -45.0, "aliasing at {} dB", db);
+    }
+
+    fn additive(preset: AdditivePreset, freq: f32, sample_rate: f32) -> Oscillator {
+        let mut osc = Oscillator::new();
+        osc.set_type(OscillatorType::Additive);
+        osc.set_sample_rate(sample_rate);
+        osc.set_frequency(freq);
+        for (i, &amplitude) in additive::preset_spectrum(preset).iter().enumerate() {
+            osc.set_partial(i, amplitude);
+        }
+        osc
+    }
+
+    #[test]
+    fn test_additive_presets_match_basic_waveforms() {
+        let cases = [
+            (AdditivePreset::Sine, OscillatorType::Sine),
+            (AdditivePreset::Triangle, OscillatorType::Triangle),
+            (AdditivePreset::Square, OscillatorType::Square),
+            (AdditivePreset::Saw, OscillatorType::Saw),
+        ];
+        for (preset, osc_type) in cases {
+            // 220 Hz puts the harmonics on every 22nd bin of 4410
+            let mut osc = additive(preset, 220.0, 44100.0);
+            let sum: Vec<f32> = (0..8820).map(|_| osc.tick()).collect();
+            let reference = collect_samples(osc_type, 220.0, 44100.0, 8820);
+            // Skip the triangle integrator's startup transient
+            let (sum, reference) = (&sum[4410..], &reference[4410..]);
+            let (sum_mags, reference_mags) = (spectrum(sum), spectrum(reference));
+            for harmonic in 1..8 {
+                let bin = 22 * harmonic;
+                if reference_mags[bin] < 0.01 * reference_mags[22] {
+                    assert!(sum_mags[bin] < 0.01 * sum_mags[22], "{:?} {}", preset, harmonic);
+                    continue;
+                }
+                let db = 20.0 * (sum_mags[bin] / reference_mags[bin]).log10();
+                assert!(db.abs() < 0.5, "{:?} harmonic {}: {} dB", preset, harmonic, db);
+            }
+            if preset != AdditivePreset::Triangle {
+                // In phase too, not just the same spectrum
+                let dot: f32 = sum.iter().zip(reference).map(|(a, b)| a * b).sum();
+                let power = |s: &[f32]| s.iter().map(|x| x * x).sum::<f32>();
+                let correlation = dot / (power(sum) * power(reference)).sqrt();
+                assert!(correlation > 0.99, "{:?} correlation {}", preset, correlation);
+            }
+        }
+    }
+
+    #[test]
+    fn test_additive_high_notes_stay_band_limited() {
+        // As for the wavetable: harmonics of 1500 Hz land on every 150th bin
+        let mut osc = additive(AdditivePreset::Saw, 1500.0, 44100.0);
+        let samples: Vec<f32> = (0..4410).map(|_| osc.tick()).collect();
+        let db = worst_alias_db(&spectrum(&samples), 150);
+        assert!(db < -80.0, "aliasing at {} dB", db);
+    }
+
+    #[test]
+    fn test_additive_drops_partials_above_nyquist() {
+        let peak_with_tenth_partial = |freq: f32| {
+            let mut osc = additive(AdditivePreset::Sine, freq, 48000.0);
+            osc.set_partial(0, 0.0);
+            osc.set_partial(9, 1.0);
+            (0..4800).map(|_| osc.tick().abs()).fold(0.0, f32::max)
+        };
+        // The 10th partial of 2 kHz is at 20 kHz; of 3 kHz it would be at 30
+        assert!(peak_with_tenth_partial(2000.0) > 0.5);
+        assert_eq!(peak_with_tenth_partial(3000.0), 0.0);
+    }
+
     #[test]
     fn test_polyblep_function_directly() {
         let dt = 0.01;
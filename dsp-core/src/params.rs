@@ -5,6 +5,11 @@ pub enum OscillatorType {
     Triangle,
     Square,
     Saw,
+    /// Plays frames from a wavetable; see `WavetableBank`.
+    Wavetable,
+    /// Sums up to `additive::MAX_PARTIALS` sine partials with editable
+    /// amplitudes.
+    Additive,
 }
 
 impl OscillatorType {
@@ -13,6 +18,8 @@ impl OscillatorType {
         OscillatorType::Triangle,
         OscillatorType::Square,
         OscillatorType::Saw,
+        OscillatorType::Wavetable,
+        OscillatorType::Additive,
     ];
 
     pub fn name(&self) -> &'static str {
@@ -21,6 +28,744 @@ impl OscillatorType {
             OscillatorType::Triangle => "Triangle",
             OscillatorType::Square => "Square",
             OscillatorType::Saw => "Saw",
+            OscillatorType::Wavetable => "Wavetable",
+            OscillatorType::Additive => "Additive",
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::VARIANTS[index.min(Self::VARIANTS.len() - 1)]
+    }
+}
+
+/// Starting spectra for the additive oscillator, each reproducing one of
+/// the basic waveforms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdditivePreset {
+    Sine,
+    Triangle,
+    Square,
+    Saw,
+}
+
+impl AdditivePreset {
+    pub const VARIANTS: &'static [AdditivePreset] = &[
+        AdditivePreset::Sine,
+        AdditivePreset::Triangle,
+        AdditivePreset::Square,
+        AdditivePreset::Saw,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AdditivePreset::Sine => "Sine",
+            AdditivePreset::Triangle => "Triangle",
+            AdditivePreset::Square => "Square",
+            AdditivePreset::Saw => "Saw",
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::VARIANTS[index.min(Self::VARIANTS.len() - 1)]
+    }
+}
+
+/// Which table the wavetable oscillator plays.
+///
+/// `Custom` is a table loaded from a WAV file; until one is loaded it plays
+/// the same table as `Basic`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WavetableBank {
+    /// Sine, triangle, saw and square.
+    Basic,
+    /// A pulse narrowing from square to 5%.
+    Pulse,
+    /// Saw harmonics added one at a time.
+    Harmonics,
+    Custom,
+}
+
+impl WavetableBank {
+    pub const VARIANTS: &'static [WavetableBank] = &[
+        WavetableBank::Basic,
+        WavetableBank::Pulse,
+        WavetableBank::Harmonics,
+        WavetableBank::Custom,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WavetableBank::Basic => "Basic",
+            WavetableBank::Pulse => "Pulse",
+            WavetableBank::Harmonics => "Harmonics",
+            WavetableBank::Custom => "Custom",
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::VARIANTS[index.min(Self::VARIANTS.len() - 1)]
+    }
+}
+
+/// Colour of the noise source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoiseType {
+    /// Equal energy at every frequency.
+    White,
+    /// Falls 3 dB per octave: equal energy in every octave.
+    Pink,
+}
+
+impl NoiseType {
+    pub const VARIANTS: &'static [NoiseType] = &[NoiseType::White, NoiseType::Pink];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NoiseType::White => "White",
+            NoiseType::Pink => "Pink",
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::VARIANTS[index.min(Self::VARIANTS.len() - 1)]
+    }
+}
+
+/// How far below the played note the sub-oscillator sounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubOctave {
+    OneDown,
+    TwoDown,
+}
+
+impl SubOctave {
+    pub const VARIANTS: &'static [SubOctave] = &[SubOctave::OneDown, SubOctave::TwoDown];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SubOctave::OneDown => "-1 Oct",
+            SubOctave::TwoDown => "-2 Oct",
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::VARIANTS[index.min(Self::VARIANTS.len() - 1)]
+    }
+
+    /// Sub-oscillator frequency as a fraction of the played note's.
+    pub fn ratio(&self) -> f32 {
+        match self {
+            SubOctave::OneDown => 0.5,
+            SubOctave::TwoDown => 0.25,
+        }
+    }
+}
+
+/// Waveform of the sub-oscillator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubShape {
+    Square,
+    Sine,
+}
+
+impl SubShape {
+    pub const VARIANTS: &'static [SubShape] = &[SubShape::Square, SubShape::Sine];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SubShape::Square => "Square",
+            SubShape::Sine => "Sine",
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::VARIANTS[index.min(Self::VARIANTS.len() - 1)]
+    }
+
+    pub fn oscillator_type(&self) -> OscillatorType {
+        match self {
+            SubShape::Square => OscillatorType::Square,
+            SubShape::Sine => OscillatorType::Sine,
+        }
+    }
+}
+
+/// What makes each voice's sound before the filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Engine {
+    /// The oscillator stack, sub-oscillator and noise.
+    Oscillators,
+    /// A Karplus-Strong plucked string; see `pluck::PluckedString`.
+    PluckedString,
+}
+
+impl Engine {
+    pub const VARIANTS: &'static [Engine] = &[Engine::Oscillators, Engine::PluckedString];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Engine::Oscillators => "Oscillators",
+            Engine::PluckedString => "Plucked String",
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::VARIANTS[index.min(Self::VARIANTS.len() - 1)]
+    }
+}
+
+/// What the plucked string is struck with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PluckExcitation {
+    /// A burst of white noise, the classic Karplus-Strong pluck.
+    Noise,
+    /// One cycle of osc 1's current waveform.
+    Oscillator,
+}
+
+impl PluckExcitation {
+    pub const VARIANTS: &'static [PluckExcitation] =
+        &[PluckExcitation::Noise, PluckExcitation::Oscillator];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PluckExcitation::Noise => "Noise",
+            PluckExcitation::Oscillator => "Oscillator",
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::VARIANTS[index.min(Self::VARIANTS.len() - 1)]
+    }
+}
+
+/// What the voice allocator does when a note arrives and no voice is idle.
+///
+/// Voices that are already releasing are always reclaimed before held ones;
+/// the policy picks among them. `Refuse` drops the new note only when every
+/// voice is still held.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StealPolicy {
+    Oldest,
+    Quietest,
+    Lowest,
+    Highest,
+    Refuse,
+}
+
+impl StealPolicy {
+    pub const VARIANTS: &'static [StealPolicy] = &[
+        StealPolicy::Oldest,
+        StealPolicy::Quietest,
+        StealPolicy::Lowest,
+        StealPolicy::Highest,
+        StealPolicy::Refuse,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StealPolicy::Oldest => "Oldest",
+            StealPolicy::Quietest => "Quietest",
+            StealPolicy::Lowest => "Lowest",
+            StealPolicy::Highest => "Highest",
+            StealPolicy::Refuse => "Refuse",
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::VARIANTS[index.min(Self::VARIANTS.len() - 1)]
+    }
+}
+
+/// Whether notes are spread across the voice pool or share a single voice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoiceMode {
+    Poly,
+    Mono,
+}
+
+impl VoiceMode {
+    pub const VARIANTS: &'static [VoiceMode] = &[VoiceMode::Poly, VoiceMode::Mono];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            VoiceMode::Poly => "Poly",
+            VoiceMode::Mono => "Mono",
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::VARIANTS[index.min(Self::VARIANTS.len() - 1)]
+    }
+}
+
+/// Which held key sounds in mono mode when several are down at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotePriority {
+    Last,
+    Low,
+    High,
+}
+
+impl NotePriority {
+    pub const VARIANTS: &'static [NotePriority] = &[
+        NotePriority::Last,
+        NotePriority::Low,
+        NotePriority::High,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NotePriority::Last => "Last",
+            NotePriority::Low => "Low",
+            NotePriority::High => "High",
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::VARIANTS[index.min(Self::VARIANTS.len() - 1)]
+    }
+}
+
+/// How incoming note velocity is shaped before it reaches its destinations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VelocityCurve {
+    Linear,
+    Exponential,
+    Fixed,
+}
+
+impl VelocityCurve {
+    pub const VARIANTS: &'static [VelocityCurve] = &[
+        VelocityCurve::Linear,
+        VelocityCurve::Exponential,
+        VelocityCurve::Fixed,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            VelocityCurve::Linear => "Linear",
+            VelocityCurve::Exponential => "Exponential",
+            VelocityCurve::Fixed => "Fixed",
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::VARIANTS[index.min(Self::VARIANTS.len() - 1)]
+    }
+}
+
+/// When a new note slides from the previous note's pitch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlideMode {
+    /// Every note glides from the previous one.
+    Always,
+    /// Only notes played while another key is held glide.
+    Legato,
+}
+
+impl GlideMode {
+    pub const VARIANTS: &'static [GlideMode] = &[GlideMode::Always, GlideMode::Legato];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GlideMode::Always => "Always",
+            GlideMode::Legato => "Legato",
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::VARIANTS[index.min(Self::VARIANTS.len() - 1)]
+    }
+}
+
+/// How the glide time is interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlideTiming {
+    /// Every glide takes the glide time, however far it travels.
+    ConstantTime,
+    /// The glide time is per octave, so wider intervals take longer.
+    ConstantRate,
+}
+
+impl GlideTiming {
+    pub const VARIANTS: &'static [GlideTiming] =
+        &[GlideTiming::ConstantTime, GlideTiming::ConstantRate];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GlideTiming::ConstantTime => "Constant Time",
+            GlideTiming::ConstantRate => "Constant Rate",
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::VARIANTS[index.min(Self::VARIANTS.len() - 1)]
+    }
+}
+
+/// Filter models available in the synth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterType {
+    StateVariable,
+    Ladder,
+}
+
+impl FilterType {
+    pub const VARIANTS: &'static [FilterType] = &[FilterType::StateVariable, FilterType::Ladder];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FilterType::StateVariable => "SVF",
+            FilterType::Ladder => "Ladder",
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::VARIANTS[index.min(Self::VARIANTS.len() - 1)]
+    }
+}
+
+/// Which response the state-variable filter outputs. The ladder is
+/// always lowpass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterMode {
+    Lowpass,
+    Highpass,
+    Bandpass,
+    Notch,
+}
+
+impl FilterMode {
+    pub const VARIANTS: &'static [FilterMode] = &[
+        FilterMode::Lowpass,
+        FilterMode::Highpass,
+        FilterMode::Bandpass,
+        FilterMode::Notch,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FilterMode::Lowpass => "Lowpass",
+            FilterMode::Highpass => "Highpass",
+            FilterMode::Bandpass => "Bandpass",
+            FilterMode::Notch => "Notch",
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::VARIANTS[index.min(Self::VARIANTS.len() - 1)]
+    }
+}
+
+/// How an envelope responds to the key being held and released.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvelopeMode {
+    /// Hold at the sustain level until note-off, then release.
+    Normal,
+    /// Cycle attack, hold and decay for as long as the key is held, then
+    /// release.
+    Loop,
+    /// Run straight through to the end of the release, ignoring note-off.
+    OneShot,
+}
+
+impl EnvelopeMode {
+    pub const VARIANTS: &'static [EnvelopeMode] =
+        &[EnvelopeMode::Normal, EnvelopeMode::Loop, EnvelopeMode::OneShot];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EnvelopeMode::Normal => "Normal",
+            EnvelopeMode::Loop => "Loop",
+            EnvelopeMode::OneShot => "One Shot",
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::VARIANTS[index.min(Self::VARIANTS.len() - 1)]
+    }
+}
+
+/// What an envelope does when a note starts while it is still running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetriggerMode {
+    /// Start the attack from the current level.
+    Resume,
+    /// Fade quickly to zero, then start the attack from silence.
+    Reset,
+    /// Carry on undisturbed while a note is held; only retrigger once it
+    /// is releasing or idle.
+    Legato,
+}
+
+impl RetriggerMode {
+    pub const VARIANTS: &'static [RetriggerMode] =
+        &[RetriggerMode::Resume, RetriggerMode::Reset, RetriggerMode::Legato];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RetriggerMode::Resume => "Resume",
+            RetriggerMode::Reset => "Reset",
+            RetriggerMode::Legato => "Legato",
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::VARIANTS[index.min(Self::VARIANTS.len() - 1)]
+    }
+}
+
+/// LFO waveform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LfoShape {
+    Sine,
+    Triangle,
+    Saw,
+    Square,
+    /// A new random level at the start of every cycle, held until the next.
+    SampleAndHold,
+}
+
+impl LfoShape {
+    pub const VARIANTS: &'static [LfoShape] = &[
+        LfoShape::Sine,
+        LfoShape::Triangle,
+        LfoShape::Saw,
+        LfoShape::Square,
+        LfoShape::SampleAndHold,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LfoShape::Sine => "Sine",
+            LfoShape::Triangle => "Triangle",
+            LfoShape::Saw => "Saw",
+            LfoShape::Square => "Square",
+            LfoShape::SampleAndHold => "S&H",
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::VARIANTS[index.min(Self::VARIANTS.len() - 1)]
+    }
+}
+
+/// Where the LFO's phase comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LfoTrigger {
+    /// One LFO runs continuously and every voice follows it.
+    Free,
+    /// Each voice has its own LFO, restarted from zero phase on note-on.
+    KeySync,
+}
+
+impl LfoTrigger {
+    pub const VARIANTS: &'static [LfoTrigger] = &[LfoTrigger::Free, LfoTrigger::KeySync];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LfoTrigger::Free => "Free",
+            LfoTrigger::KeySync => "Key Sync",
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::VARIANTS[index.min(Self::VARIANTS.len() - 1)]
+    }
+}
+
+/// How the LFO rate is set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LfoTiming {
+    /// A rate in Hz.
+    Hz,
+    /// A note division of the host tempo.
+    TempoSync,
+}
+
+impl LfoTiming {
+    pub const VARIANTS: &'static [LfoTiming] = &[LfoTiming::Hz, LfoTiming::TempoSync];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LfoTiming::Hz => "Hz",
+            LfoTiming::TempoSync => "Tempo Sync",
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::VARIANTS[index.min(Self::VARIANTS.len() - 1)]
+    }
+}
+
+/// Length of one tempo-synced LFO cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteDivision {
+    FourBars,
+    TwoBars,
+    Whole,
+    Half,
+    HalfTriplet,
+    Quarter,
+    QuarterTriplet,
+    Eighth,
+    EighthTriplet,
+    Sixteenth,
+    SixteenthTriplet,
+    ThirtySecond,
+}
+
+impl NoteDivision {
+    pub const VARIANTS: &'static [NoteDivision] = &[
+        NoteDivision::FourBars,
+        NoteDivision::TwoBars,
+        NoteDivision::Whole,
+        NoteDivision::Half,
+        NoteDivision::HalfTriplet,
+        NoteDivision::Quarter,
+        NoteDivision::QuarterTriplet,
+        NoteDivision::Eighth,
+        NoteDivision::EighthTriplet,
+        NoteDivision::Sixteenth,
+        NoteDivision::SixteenthTriplet,
+        NoteDivision::ThirtySecond,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NoteDivision::FourBars => "4 Bars",
+            NoteDivision::TwoBars => "2 Bars",
+            NoteDivision::Whole => "1/1",
+            NoteDivision::Half => "1/2",
+            NoteDivision::HalfTriplet => "1/2T",
+            NoteDivision::Quarter => "1/4",
+            NoteDivision::QuarterTriplet => "1/4T",
+            NoteDivision::Eighth => "1/8",
+            NoteDivision::EighthTriplet => "1/8T",
+            NoteDivision::Sixteenth => "1/16",
+            NoteDivision::SixteenthTriplet => "1/16T",
+            NoteDivision::ThirtySecond => "1/32",
+        }
+    }
+
+    /// Length of the division in quarter-note beats (4/4 time).
+    pub fn beats(&self) -> f32 {
+        match self {
+            NoteDivision::FourBars => 16.0,
+            NoteDivision::TwoBars => 8.0,
+            NoteDivision::Whole => 4.0,
+            NoteDivision::Half => 2.0,
+            NoteDivision::HalfTriplet => 4.0 / 3.0,
+            NoteDivision::Quarter => 1.0,
+            NoteDivision::QuarterTriplet => 2.0 / 3.0,
+            NoteDivision::Eighth => 0.5,
+            NoteDivision::EighthTriplet => 1.0 / 3.0,
+            NoteDivision::Sixteenth => 0.25,
+            NoteDivision::SixteenthTriplet => 1.0 / 6.0,
+            NoteDivision::ThirtySecond => 0.125,
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::VARIANTS[index.min(Self::VARIANTS.len() - 1)]
+    }
+}
+
+/// Signal that drives a mod matrix slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModSource {
+    /// The slot does nothing.
+    Off,
+    /// The LFO, bipolar.
+    Lfo,
+    /// The amplitude envelope's level.
+    AmpEnvelope,
+    /// The filter envelope's level.
+    FilterEnvelope,
+    /// The note's velocity.
+    Velocity,
+    /// The played pitch, bipolar around the middle of the keyboard.
+    KeyTrack,
+    /// MIDI mod wheel (CC 1).
+    ModWheel,
+    /// MIDI channel pressure.
+    Aftertouch,
+}
+
+impl ModSource {
+    pub const VARIANTS: &'static [ModSource] = &[
+        ModSource::Off,
+        ModSource::Lfo,
+        ModSource::AmpEnvelope,
+        ModSource::FilterEnvelope,
+        ModSource::Velocity,
+        ModSource::KeyTrack,
+        ModSource::ModWheel,
+        ModSource::Aftertouch,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ModSource::Off => "Off",
+            ModSource::Lfo => "LFO",
+            ModSource::AmpEnvelope => "Amp Env",
+            ModSource::FilterEnvelope => "Filter Env",
+            ModSource::Velocity => "Velocity",
+            ModSource::KeyTrack => "Key Track",
+            ModSource::ModWheel => "Mod Wheel",
+            ModSource::Aftertouch => "Aftertouch",
+        }
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::VARIANTS[index.min(Self::VARIANTS.len() - 1)]
+    }
+}
+
+/// What a mod matrix slot modulates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModDestination {
+    /// The slot does nothing.
+    Off,
+    Pitch,
+    Gain,
+    Pan,
+    PulseWidth,
+    /// Amplitude envelope attack time.
+    Attack,
+    /// Amplitude envelope decay time.
+    Decay,
+    /// Amplitude envelope release time.
+    Release,
+    /// Position in the wavetable.
+    WavetablePosition,
+}
+
+impl ModDestination {
+    pub const VARIANTS: &'static [ModDestination] = &[
+        ModDestination::Off,
+        ModDestination::Pitch,
+        ModDestination::Gain,
+        ModDestination::Pan,
+        ModDestination::PulseWidth,
+        ModDestination::Attack,
+        ModDestination::Decay,
+        ModDestination::Release,
+        ModDestination::WavetablePosition,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ModDestination::Off => "Off",
+            ModDestination::Pitch => "Pitch",
+            ModDestination::Gain => "Gain",
+            ModDestination::Pan => "Pan",
+            ModDestination::PulseWidth => "Pulse Width",
+            ModDestination::Attack => "Attack",
+            ModDestination::Decay => "Decay",
+            ModDestination::Release => "Release",
+            ModDestination::WavetablePosition => "WT Position",
         }
     }
 
@@ -35,7 +780,7 @@ mod tests {
 
     #[test]
     fn test_variants_count() {
-        assert_eq!(OscillatorType::VARIANTS.len(), 4);
+        assert_eq!(OscillatorType::VARIANTS.len(), 6);
     }
 
     #[test]
@@ -45,6 +790,8 @@ mod tests {
         assert!(v.contains(&OscillatorType::Triangle));
         assert!(v.contains(&OscillatorType::Square));
         assert!(v.contains(&OscillatorType::Saw));
+        assert!(v.contains(&OscillatorType::Wavetable));
+        assert!(v.contains(&OscillatorType::Additive));
     }
 
     #[test]
@@ -53,13 +800,15 @@ mod tests {
         assert_eq!(OscillatorType::from_index(1), OscillatorType::Triangle);
         assert_eq!(OscillatorType::from_index(2), OscillatorType::Square);
         assert_eq!(OscillatorType::from_index(3), OscillatorType::Saw);
+        assert_eq!(OscillatorType::from_index(4), OscillatorType::Wavetable);
+        assert_eq!(OscillatorType::from_index(5), OscillatorType::Additive);
     }
 
     #[test]
     fn test_from_index_out_of_range_clamps() {
-        assert_eq!(OscillatorType::from_index(4), OscillatorType::Saw);
-        assert_eq!(OscillatorType::from_index(100), OscillatorType::Saw);
-        assert_eq!(OscillatorType::from_index(usize::MAX), OscillatorType::Saw);
+        assert_eq!(OscillatorType::from_index(6), OscillatorType::Additive);
+        assert_eq!(OscillatorType::from_index(100), OscillatorType::Additive);
+        assert_eq!(OscillatorType::from_index(usize::MAX), OscillatorType::Additive);
     }
 
     #[test]
@@ -68,6 +817,8 @@ mod tests {
         assert_eq!(OscillatorType::Triangle.name(), "Triangle");
         assert_eq!(OscillatorType::Square.name(), "Square");
         assert_eq!(OscillatorType::Saw.name(), "Saw");
+        assert_eq!(OscillatorType::Wavetable.name(), "Wavetable");
+        assert_eq!(OscillatorType::Additive.name(), "Additive");
     }
 
     #[test]
@@ -76,4 +827,221 @@ mod tests {
             assert_eq!(variant.name(), format!("{:?}", variant));
         }
     }
+
+    #[test]
+    fn test_steal_policy_from_index() {
+        assert_eq!(StealPolicy::from_index(0), StealPolicy::Oldest);
+        assert_eq!(StealPolicy::from_index(1), StealPolicy::Quietest);
+        assert_eq!(StealPolicy::from_index(2), StealPolicy::Lowest);
+        assert_eq!(StealPolicy::from_index(3), StealPolicy::Highest);
+        assert_eq!(StealPolicy::from_index(4), StealPolicy::Refuse);
+        assert_eq!(StealPolicy::from_index(100), StealPolicy::Refuse);
+    }
+
+    #[test]
+    fn test_steal_policy_name_matches_variant_debug() {
+        for variant in StealPolicy::VARIANTS {
+            assert_eq!(variant.name(), format!("{:?}", variant));
+        }
+    }
+
+    #[test]
+    fn test_voice_mode_from_index() {
+        assert_eq!(VoiceMode::from_index(0), VoiceMode::Poly);
+        assert_eq!(VoiceMode::from_index(1), VoiceMode::Mono);
+        assert_eq!(VoiceMode::from_index(5), VoiceMode::Mono);
+    }
+
+    #[test]
+    fn test_note_priority_from_index() {
+        assert_eq!(NotePriority::from_index(0), NotePriority::Last);
+        assert_eq!(NotePriority::from_index(1), NotePriority::Low);
+        assert_eq!(NotePriority::from_index(2), NotePriority::High);
+        assert_eq!(NotePriority::from_index(9), NotePriority::High);
+    }
+
+    #[test]
+    fn test_mode_and_priority_names_match_variant_debug() {
+        for variant in VoiceMode::VARIANTS {
+            assert_eq!(variant.name(), format!("{:?}", variant));
+        }
+        for variant in NotePriority::VARIANTS {
+            assert_eq!(variant.name(), format!("{:?}", variant));
+        }
+    }
+
+    #[test]
+    fn test_velocity_curve_from_index() {
+        assert_eq!(VelocityCurve::from_index(0), VelocityCurve::Linear);
+        assert_eq!(VelocityCurve::from_index(1), VelocityCurve::Exponential);
+        assert_eq!(VelocityCurve::from_index(2), VelocityCurve::Fixed);
+        assert_eq!(VelocityCurve::from_index(7), VelocityCurve::Fixed);
+    }
+
+    #[test]
+    fn test_velocity_curve_name_matches_variant_debug() {
+        for variant in VelocityCurve::VARIANTS {
+            assert_eq!(variant.name(), format!("{:?}", variant));
+        }
+    }
+
+    #[test]
+    fn test_glide_mode_from_index() {
+        assert_eq!(GlideMode::from_index(0), GlideMode::Always);
+        assert_eq!(GlideMode::from_index(1), GlideMode::Legato);
+        assert_eq!(GlideMode::from_index(5), GlideMode::Legato);
+    }
+
+    #[test]
+    fn test_glide_timing_from_index() {
+        assert_eq!(GlideTiming::from_index(0), GlideTiming::ConstantTime);
+        assert_eq!(GlideTiming::from_index(1), GlideTiming::ConstantRate);
+        assert_eq!(GlideTiming::from_index(9), GlideTiming::ConstantRate);
+    }
+
+    #[test]
+    fn test_glide_timing_names() {
+        assert_eq!(GlideTiming::ConstantTime.name(), "Constant Time");
+        assert_eq!(GlideTiming::ConstantRate.name(), "Constant Rate");
+    }
+
+    #[test]
+    fn test_filter_mode_from_index() {
+        assert_eq!(FilterMode::from_index(0), FilterMode::Lowpass);
+        assert_eq!(FilterMode::from_index(1), FilterMode::Highpass);
+        assert_eq!(FilterMode::from_index(2), FilterMode::Bandpass);
+        assert_eq!(FilterMode::from_index(3), FilterMode::Notch);
+        assert_eq!(FilterMode::from_index(10), FilterMode::Notch);
+    }
+
+    #[test]
+    fn test_filter_mode_name_matches_variant_debug() {
+        for variant in FilterMode::VARIANTS {
+            assert_eq!(variant.name(), format!("{:?}", variant));
+        }
+    }
+
+    #[test]
+    fn test_filter_type_from_index() {
+        assert_eq!(FilterType::from_index(0), FilterType::StateVariable);
+        assert_eq!(FilterType::from_index(1), FilterType::Ladder);
+        assert_eq!(FilterType::from_index(8), FilterType::Ladder);
+    }
+
+    #[test]
+    fn test_filter_type_names() {
+        assert_eq!(FilterType::StateVariable.name(), "SVF");
+        assert_eq!(FilterType::Ladder.name(), "Ladder");
+    }
+
+    #[test]
+    fn test_lfo_shape_from_index() {
+        assert_eq!(LfoShape::from_index(0), LfoShape::Sine);
+        assert_eq!(LfoShape::from_index(4), LfoShape::SampleAndHold);
+        assert_eq!(LfoShape::from_index(50), LfoShape::SampleAndHold);
+    }
+
+    #[test]
+    fn test_lfo_trigger_and_timing_from_index() {
+        assert_eq!(LfoTrigger::from_index(0), LfoTrigger::Free);
+        assert_eq!(LfoTrigger::from_index(7), LfoTrigger::KeySync);
+        assert_eq!(LfoTiming::from_index(0), LfoTiming::Hz);
+        assert_eq!(LfoTiming::from_index(7), LfoTiming::TempoSync);
+    }
+
+    #[test]
+    fn test_note_division_from_index() {
+        assert_eq!(NoteDivision::from_index(5), NoteDivision::Quarter);
+        assert_eq!(NoteDivision::from_index(99), NoteDivision::ThirtySecond);
+    }
+
+    #[test]
+    fn test_note_division_beats_get_shorter() {
+        for pair in NoteDivision::VARIANTS.windows(2) {
+            assert!(pair[1].beats() < pair[0].beats(), "{:?}", pair);
+        }
+        assert_eq!(NoteDivision::Quarter.beats(), 1.0);
+    }
+
+    #[test]
+    fn test_mod_source_from_index() {
+        assert_eq!(ModSource::from_index(0), ModSource::Off);
+        assert_eq!(ModSource::from_index(1), ModSource::Lfo);
+        assert_eq!(ModSource::from_index(7), ModSource::Aftertouch);
+        assert_eq!(ModSource::from_index(70), ModSource::Aftertouch);
+    }
+
+    #[test]
+    fn test_mod_destination_from_index() {
+        assert_eq!(ModDestination::from_index(0), ModDestination::Off);
+        assert_eq!(ModDestination::from_index(1), ModDestination::Pitch);
+        assert_eq!(ModDestination::from_index(7), ModDestination::Release);
+        assert_eq!(ModDestination::from_index(8), ModDestination::WavetablePosition);
+        assert_eq!(ModDestination::from_index(70), ModDestination::WavetablePosition);
+    }
+
+    #[test]
+    fn test_wavetable_bank_from_index() {
+        assert_eq!(WavetableBank::from_index(0), WavetableBank::Basic);
+        assert_eq!(WavetableBank::from_index(3), WavetableBank::Custom);
+        assert_eq!(WavetableBank::from_index(30), WavetableBank::Custom);
+    }
+
+    #[test]
+    fn test_wavetable_bank_name_matches_variant_debug() {
+        for variant in WavetableBank::VARIANTS {
+            assert_eq!(variant.name(), format!("{:?}", variant));
+        }
+    }
+
+    #[test]
+    fn test_noise_and_sub_from_index() {
+        assert_eq!(NoiseType::from_index(1), NoiseType::Pink);
+        assert_eq!(NoiseType::from_index(9), NoiseType::Pink);
+        assert_eq!(SubOctave::from_index(0), SubOctave::OneDown);
+        assert_eq!(SubOctave::from_index(9), SubOctave::TwoDown);
+        assert_eq!(SubShape::from_index(9), SubShape::Sine);
+    }
+
+    #[test]
+    fn test_sub_octave_ratios() {
+        assert_eq!(SubOctave::OneDown.ratio(), 0.5);
+        assert_eq!(SubOctave::TwoDown.ratio(), 0.25);
+        assert_eq!(SubOctave::OneDown.name(), "-1 Oct");
+    }
+
+    #[test]
+    fn test_engine_and_excitation_from_index() {
+        assert_eq!(Engine::from_index(0), Engine::Oscillators);
+        assert_eq!(Engine::from_index(9), Engine::PluckedString);
+        assert_eq!(PluckExcitation::from_index(1), PluckExcitation::Oscillator);
+        assert_eq!(PluckExcitation::from_index(9), PluckExcitation::Oscillator);
+    }
+
+    #[test]
+    fn test_additive_preset_from_index() {
+        assert_eq!(AdditivePreset::from_index(0), AdditivePreset::Sine);
+        assert_eq!(AdditivePreset::from_index(3), AdditivePreset::Saw);
+        assert_eq!(AdditivePreset::from_index(30), AdditivePreset::Saw);
+        for variant in AdditivePreset::VARIANTS {
+            assert_eq!(variant.name(), format!("{:?}", variant));
+        }
+    }
+
+    #[test]
+    fn test_envelope_mode_from_index() {
+        assert_eq!(EnvelopeMode::from_index(0), EnvelopeMode::Normal);
+        assert_eq!(EnvelopeMode::from_index(1), EnvelopeMode::Loop);
+        assert_eq!(EnvelopeMode::from_index(9), EnvelopeMode::OneShot);
+        assert_eq!(EnvelopeMode::OneShot.name(), "One Shot");
+    }
+
+    #[test]
+    fn test_retrigger_mode_from_index() {
+        assert_eq!(RetriggerMode::from_index(0), RetriggerMode::Resume);
+        assert_eq!(RetriggerMode::from_index(9), RetriggerMode::Legato);
+        for variant in RetriggerMode::VARIANTS {
+            assert_eq!(variant.name(), format!("{:?}", variant));
+        }
+    }
 }
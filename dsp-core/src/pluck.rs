@@ -0,0 +1,417 @@
+use crate::oscillator::Oscillator;
+use crate::params::{OscillatorType, PluckExcitation};
+use crate::rng::Rng;
+use crate::wavetable::Wavetable;
+use core::f32::consts::{PI, TAU};
+use std::sync::Arc;
+
+/// Shortest and longest string decay, in seconds for the fundamental to
+/// fall 60 dB.
+pub const MIN_PLUCK_DECAY: f32 = 0.05;
+pub const MAX_PLUCK_DECAY: f32 = 20.0;
+
+/// Lowest string frequency. Together with `MAX_SAMPLE_RATE` this sizes the
+/// delay line; lower notes are played at this pitch.
+const MIN_STRING_HZ: f32 = 20.0;
+
+/// Highest sample rate the delay line is sized for.
+const MAX_SAMPLE_RATE: f32 = 192000.0;
+
+/// Delay line length: a power of two holding one period of the lowest
+/// string at the highest sample rate.
+const DELAY_LEN: usize = 16384;
+
+/// How far above the fundamental full brightness opens the excitation
+/// lowpass, in octaves. At zero brightness it sits on the fundamental.
+const BRIGHTNESS_OCTAVES: f32 = 10.0;
+
+/// Cutoff of the output DC blocker, in Hz.
+const DC_BLOCK_HZ: f32 = 20.0;
+
+/// A Karplus-Strong plucked string.
+///
+/// A delay line one period long, fed back through a damping lowpass, so a
+/// short burst loaded into it rings on at the delay's pitch and loses its
+/// high harmonics first, like a real string. The burst is white noise or
+/// one cycle of an `Oscillator`, softened by a brightness lowpass.
+///
+/// The loop's length is the integer delay plus the damping filter's phase
+/// delay plus a first-order allpass that supplies the fraction, all solved
+/// at the fundamental, so the string stays in tune up where a whole-sample
+/// delay would be tens of cents out. The delay line is allocated once, so
+/// plucking and playing never touch the heap.
+pub struct PluckedString {
+    delay: Box<[f32]>,
+    write: usize,
+    sample_rate: f32,
+    frequency: f32,
+    excitation: PluckExcitation,
+    exciter: Oscillator,
+    rng: Rng,
+    /// Damping amount in `[0, 1]`.
+    damping: f32,
+    /// Excitation brightness in `[0, 1]`.
+    brightness: f32,
+    /// Fundamental decay time, in seconds.
+    decay: f32,
+    /// Loop length in samples at the clamped frequency.
+    period: f32,
+    /// Whole-sample part of the loop delay.
+    delay_samples: usize,
+    /// Weight of the previous sample in the damping filter, in `[0, 0.5]`.
+    damping_coeff: f32,
+    /// Fractional-delay allpass coefficient.
+    allpass_coeff: f32,
+    /// Loop gain setting the fundamental's decay.
+    loop_gain: f32,
+    /// Excitation lowpass coefficient.
+    brightness_coeff: f32,
+    dc_block_coeff: f32,
+    /// Samples of excitation still to feed in.
+    burst_remaining: usize,
+    damping_state: f32,
+    allpass_in: f32,
+    allpass_out: f32,
+    brightness_state: f32,
+    dc_in: f32,
+    dc_out: f32,
+}
+
+impl PluckedString {
+    pub fn new() -> Self {
+        let mut exciter = Oscillator::new();
+        exciter.set_type(OscillatorType::Saw);
+        let mut string = Self {
+            delay: vec![0.0; DELAY_LEN].into_boxed_slice(),
+            write: 0,
+            sample_rate: 44100.0,
+            frequency: 440.0,
+            excitation: PluckExcitation::Noise,
+            exciter,
+            rng: Rng::new(1),
+            damping: 0.5,
+            brightness: 0.5,
+            decay: 2.0,
+            period: 100.0,
+            delay_samples: 1,
+            damping_coeff: 0.0,
+            allpass_coeff: 0.0,
+            loop_gain: 0.0,
+            brightness_coeff: 1.0,
+            dc_block_coeff: 0.0,
+            burst_remaining: 0,
+            damping_state: 0.0,
+            allpass_in: 0.0,
+            allpass_out: 0.0,
+            brightness_state: 0.0,
+            dc_in: 0.0,
+            dc_out: 0.0,
+        };
+        string.set_sample_rate(44100.0);
+        string
+    }
+
+    /// Sample rate in Hz, up to 192 kHz.
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate.min(MAX_SAMPLE_RATE);
+        self.exciter.set_sample_rate(self.sample_rate);
+        self.dc_block_coeff = 1.0 - TAU * DC_BLOCK_HZ / self.sample_rate;
+        self.update_tuning();
+    }
+
+    /// Tune the string to `hz`, clamped to the range the delay line can
+    /// play at this sample rate.
+    pub fn set_frequency(&mut self, hz: f32) {
+        self.frequency = hz;
+        self.update_tuning();
+    }
+
+    pub fn set_excitation(&mut self, excitation: PluckExcitation) {
+        self.excitation = excitation;
+    }
+
+    /// Waveform of the oscillator excitation.
+    pub fn set_exciter_type(&mut self, osc_type: OscillatorType) {
+        self.exciter.set_type(osc_type);
+    }
+
+    pub fn set_exciter_pulse_width(&mut self, width: f32) {
+        self.exciter.set_pulse_width(width);
+    }
+
+    pub fn set_wavetable(&mut self, table: &Arc<Wavetable>) {
+        self.exciter.set_wavetable(table);
+    }
+
+    pub fn set_partial(&mut self, index: usize, amplitude: f32) {
+        self.exciter.set_partial(index, amplitude);
+    }
+
+    pub fn set_wavetable_position(&mut self, position: f32) {
+        self.exciter.set_wavetable_position(position);
+    }
+
+    /// How quickly high harmonics die away relative to the fundamental,
+    /// in `[0, 1]`. At 0 every harmonic rings as long as the fundamental.
+    pub fn set_damping(&mut self, damping: f32) {
+        self.damping = damping.clamp(0.0, 1.0);
+        self.update_tuning();
+    }
+
+    /// Brightness of the pluck itself in `[0, 1]`, from a soft, thumb-like
+    /// pluck at 0 to the full excitation at 1.
+    pub fn set_brightness(&mut self, brightness: f32) {
+        self.brightness = brightness.clamp(0.0, 1.0);
+        self.update_tuning();
+    }
+
+    /// Time for the fundamental to fall 60 dB, clamped to
+    /// `[MIN_PLUCK_DECAY, MAX_PLUCK_DECAY]`. Heavy damping can shorten it.
+    pub fn set_decay(&mut self, seconds: f32) {
+        self.decay = seconds.clamp(MIN_PLUCK_DECAY, MAX_PLUCK_DECAY);
+        self.update_tuning();
+    }
+
+    /// Restart the noise burst sequence from `seed`.
+    pub fn seed(&mut self, seed: u32) {
+        self.rng = Rng::new(seed);
+    }
+
+    /// Silence the string.
+    pub fn reset(&mut self) {
+        self.delay.fill(0.0);
+        self.burst_remaining = 0;
+        self.damping_state = 0.0;
+        self.allpass_in = 0.0;
+        self.allpass_out = 0.0;
+        self.brightness_state = 0.0;
+        self.dc_in = 0.0;
+        self.dc_out = 0.0;
+    }
+
+    /// Pluck the string: feed one period of excitation into the loop. A
+    /// string that is still ringing keeps its vibration underneath.
+    pub fn pluck(&mut self) {
+        self.burst_remaining = (self.period + 0.5) as usize;
+        self.brightness_state = 0.0;
+        self.exciter.reset();
+    }
+
+    /// Generate the next sample.
+    pub fn tick(&mut self) -> f32 {
+        let mut input = 0.0;
+        if self.burst_remaining > 0 {
+            self.burst_remaining -= 1;
+            let raw = match self.excitation {
+                PluckExcitation::Noise => self.rng.next_bipolar(),
+                PluckExcitation::Oscillator => self.exciter.tick(),
+            };
+            self.brightness_state += self.brightness_coeff * (raw - self.brightness_state);
+            input = self.brightness_state;
+        }
+
+        let read = (self.write + DELAY_LEN - self.delay_samples) & (DELAY_LEN - 1);
+        let delayed = self.delay[read];
+        let a = self.damping_coeff;
+        let damped = (1.0 - a) * delayed + a * self.damping_state;
+        self.damping_state = delayed;
+        let c = self.allpass_coeff;
+        let tuned = c * damped + self.allpass_in - c * self.allpass_out;
+        self.allpass_in = damped;
+        self.allpass_out = tuned;
+
+        let out = input + self.loop_gain * tuned;
+        self.delay[self.write] = out;
+        self.write = (self.write + 1) & (DELAY_LEN - 1);
+
+        // The loop passes DC, so a lopsided burst would leave an offset
+        let blocked = out - self.dc_in + self.dc_block_coeff * self.dc_out;
+        self.dc_in = out;
+        self.dc_out = blocked;
+        blocked
+    }
+
+    fn update_tuning(&mut self) {
+        // The loop needs at least a sample of whole delay on top of the
+        // filters' phase delay
+        let max_hz = self.sample_rate / 4.0;
+        let hz = self.frequency.clamp(MIN_STRING_HZ, max_hz);
+        self.exciter.set_frequency(hz);
+        let w = TAU * hz / self.sample_rate;
+        let period = self.sample_rate / hz;
+        self.period = period;
+
+        // Two-tap lowpass (1 - a) + a z^-1; at a = 0.5 it is the original
+        // Karplus-Strong average
+        let a = 0.5 * self.damping;
+        self.damping_coeff = a;
+        let (sin_w, cos_w) = w.sin_cos();
+        let re = 1.0 - a + a * cos_w;
+        let im = a * sin_w;
+        let damping_delay = im.atan2(re) / w;
+        let damping_gain = (re * re + im * im).sqrt();
+
+        // Split what's left into whole samples and a fraction in
+        // [0.5, 1.5), where the allpass is accurate and stable
+        let remaining = period - damping_delay;
+        let whole = (remaining - 0.5).floor();
+        let fraction = remaining - whole;
+        self.delay_samples = (whole as usize).max(1);
+        // Allpass coefficient giving exactly `fraction` samples of phase
+        // delay at the fundamental
+        self.allpass_coeff =
+            (0.5 * w * (1.0 - fraction)).sin() / (0.5 * w * (1.0 + fraction)).sin();
+
+        // Per-period loss for a 60 dB fall over the decay time, less what
+        // the damping filter already takes. Never above one, or DC would grow
+        let per_period = 0.001f32.powf(1.0 / (self.decay * hz));
+        self.loop_gain = (per_period / damping_gain).min(1.0);
+
+        let cutoff = hz * 2.0f32.powf(BRIGHTNESS_OCTAVES * self.brightness);
+        self.brightness_coeff = 1.0 - (-2.0 * PI * cutoff / self.sample_rate).exp();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(sample_rate: f32, hz: f32) -> PluckedString {
+        let mut string = PluckedString::new();
+        string.set_sample_rate(sample_rate);
+        string.set_frequency(hz);
+        string
+    }
+
+    fn render(string: &mut PluckedString, n: usize) -> Vec<f32> {
+        (0..n).map(|_| string.tick()).collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    /// Frequency from the time between the first and last upward zero
+    /// crossings, interpolated to a fraction of a sample.
+    fn measure_hz(samples: &[f32], sample_rate: f32) -> f32 {
+        let mut crossings = Vec::new();
+        for i in 1..samples.len() {
+            let (a, b) = (samples[i - 1], samples[i]);
+            if a < 0.0 && b >= 0.0 {
+                crossings.push((i - 1) as f32 + a / (a - b));
+            }
+        }
+        let cycles = (crossings.len() - 1) as f32;
+        cycles * sample_rate / (crossings[crossings.len() - 1] - crossings[0])
+    }
+
+    #[test]
+    fn test_high_notes_stay_in_tune() {
+        for &sample_rate in &[44100.0, 48000.0] {
+            for note in (48..=108).step_by(5) {
+                let hz = 440.0 * 2.0f32.powf((note as f32 - 69.0) / 12.0);
+                let mut s = string(sample_rate, hz);
+                // A sine pluck rings as an almost pure tone to count
+                s.set_excitation(PluckExcitation::Oscillator);
+                s.set_exciter_type(OscillatorType::Sine);
+                s.set_brightness(1.0);
+                s.set_damping(0.5);
+                s.pluck();
+                // High notes die away quickly, so measure early on
+                let samples = render(&mut s, 6000);
+                let measured = measure_hz(&samples[1000..], sample_rate);
+                let cents = 1200.0 * (measured / hz).log2();
+                assert!(
+                    cents.abs() < 1.0,
+                    "note {} at {} Hz: {} cents out",
+                    note,
+                    sample_rate,
+                    cents
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_decay_sets_the_fundamental_fall() {
+        let mut s = string(44100.0, 220.0);
+        s.set_excitation(PluckExcitation::Oscillator);
+        s.set_exciter_type(OscillatorType::Sine);
+        s.set_brightness(1.0);
+        s.set_damping(0.0);
+        s.set_decay(1.0);
+        s.pluck();
+        let samples = render(&mut s, 44100);
+        // Half the decay time is 30 dB down
+        let early = rms(&samples[4410..8820]);
+        let late = rms(&samples[26460..30870]);
+        let db = 20.0 * (late / early).log10();
+        assert!((db + 30.0).abs() < 2.0, "{} dB", db);
+    }
+
+    #[test]
+    fn test_damping_dulls_the_tone_over_time() {
+        // Energy of the first difference against the signal's is a rough
+        // measure of how much high end is left
+        let brightness = |damping: f32| {
+            let mut s = string(44100.0, 110.0);
+            s.set_brightness(1.0);
+            s.set_damping(damping);
+            s.pluck();
+            let samples = render(&mut s, 22050);
+            let tail = &samples[11025..];
+            let diff: Vec<f32> = tail.windows(2).map(|w| w[1] - w[0]).collect();
+            rms(&diff) / rms(tail)
+        };
+        assert!(brightness(1.0) < 0.5 * brightness(0.0));
+    }
+
+    #[test]
+    fn test_brightness_softens_the_pluck() {
+        let attack = |brightness: f32| {
+            let mut s = string(44100.0, 110.0);
+            s.set_brightness(brightness);
+            s.pluck();
+            let samples = render(&mut s, 2000);
+            let diff: Vec<f32> = samples.windows(2).map(|w| w[1] - w[0]).collect();
+            rms(&diff) / rms(&samples)
+        };
+        assert!(attack(0.0) < 0.5 * attack(1.0));
+    }
+
+    #[test]
+    fn test_string_rings_then_dies_away_without_dc() {
+        let mut s = string(44100.0, 330.0);
+        s.set_decay(0.5);
+        s.pluck();
+        let samples = render(&mut s, 44100 * 2);
+        assert!(samples.iter().all(|x| x.is_finite() && x.abs() <= 2.0));
+        assert!(rms(&samples[..4410]) > 0.01);
+        assert!(rms(&samples[44100 * 2 - 4410..]) < 1e-4);
+    }
+
+    #[test]
+    fn test_extreme_settings_stay_bounded() {
+        for &hz in &[1.0, 20.0, 5000.0, 30000.0] {
+            let mut s = string(48000.0, hz);
+            s.set_damping(0.0);
+            s.set_brightness(1.0);
+            s.set_decay(MAX_PLUCK_DECAY);
+            s.pluck();
+            let samples = render(&mut s, 48000);
+            assert!(samples.iter().all(|x| x.is_finite() && x.abs() <= 4.0), "{} Hz", hz);
+        }
+    }
+
+    #[test]
+    fn test_same_seed_same_pluck() {
+        let pluck = |seed: u32| {
+            let mut s = string(44100.0, 220.0);
+            s.seed(seed);
+            s.pluck();
+            render(&mut s, 1000)
+        };
+        assert_eq!(pluck(5), pluck(5));
+        assert_ne!(pluck(5), pluck(6));
+    }
+}
@@ -0,0 +1,151 @@
+/// Time a smoothed parameter takes to reach a new value, in seconds.
+pub const SMOOTHING_SECONDS: f32 = 0.01;
+
+/// A parameter value that ramps linearly to each new target instead of
+/// jumping, so changes that arrive once per block don't step the output.
+///
+/// A new target restarts the ramp from wherever the value is now, so a
+/// stream of targets (block-rate automation) is followed without corners
+/// sharper than one ramp's slope.
+#[derive(Debug, Clone, Copy)]
+pub struct Smoothed {
+    value: f32,
+    target: f32,
+    step: f32,
+    remaining: u32,
+    ramp_samples: u32,
+}
+
+impl Smoothed {
+    pub fn new(value: f32) -> Self {
+        let mut smoothed = Self {
+            value,
+            target: value,
+            step: 0.0,
+            remaining: 0,
+            ramp_samples: 1,
+        };
+        smoothed.set_sample_rate(44100.0);
+        smoothed
+    }
+
+    /// Ramps already under way keep their step; the next target uses the
+    /// new length.
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.ramp_samples = ((SMOOTHING_SECONDS * sample_rate).round() as u32).max(1);
+    }
+
+    /// Start ramping towards `target`. Setting the current target again
+    /// changes nothing.
+    pub fn set_target(&mut self, target: f32) {
+        if target != self.target {
+            self.target = target;
+            self.remaining = self.ramp_samples;
+            self.step = (target - self.value) / self.ramp_samples as f32;
+        }
+    }
+
+    /// Jump straight to `value`, abandoning any ramp.
+    pub fn reset(&mut self, value: f32) {
+        self.value = value;
+        self.target = value;
+        self.remaining = 0;
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn target(&self) -> f32 {
+        self.target
+    }
+
+    pub fn is_smoothing(&self) -> bool {
+        self.remaining > 0
+    }
+
+    /// Advance one sample and return the new value. The last step of a
+    /// ramp lands exactly on the target.
+    pub fn tick(&mut self) -> f32 {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            self.value = if self.remaining == 0 {
+                self.target
+            } else {
+                self.value + self.step
+            };
+        }
+        self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ramp_samples() -> usize {
+        (SMOOTHING_SECONDS * 44100.0).round() as usize
+    }
+
+    #[test]
+    fn test_ramps_linearly_to_target() {
+        let mut smoothed = Smoothed::new(0.0);
+        smoothed.set_target(1.0);
+        let n = ramp_samples();
+        let values: Vec<f32> = (0..n).map(|_| smoothed.tick()).collect();
+        assert!((values[n / 2 - 1] - 0.5).abs() < 0.01, "{}", values[n / 2 - 1]);
+        assert_eq!(values[n - 1], 1.0);
+        assert!(!smoothed.is_smoothing());
+        assert_eq!(smoothed.tick(), 1.0);
+    }
+
+    #[test]
+    fn test_steps_never_exceed_ramp_slope() {
+        let mut smoothed = Smoothed::new(0.0);
+        smoothed.set_target(1.0);
+        let slope = 1.0 / ramp_samples() as f32;
+        let mut last = smoothed.value();
+        for i in 0..1000 {
+            // Retarget mid-ramp, as block-rate automation does
+            if i % 64 == 0 {
+                smoothed.set_target(if i % 128 == 0 { 0.0 } else { 1.0 });
+            }
+            let value = smoothed.tick();
+            assert!((value - last).abs() <= slope * 1.001, "step {} at {i}", value - last);
+            last = value;
+        }
+    }
+
+    #[test]
+    fn test_same_target_keeps_ramp() {
+        let mut smoothed = Smoothed::new(0.0);
+        smoothed.set_target(1.0);
+        for _ in 0..100 {
+            smoothed.tick();
+        }
+        let before = smoothed.value();
+        smoothed.set_target(1.0);
+        let step = smoothed.tick() - before;
+        assert!((step - 1.0 / ramp_samples() as f32).abs() < 1e-6, "{step}");
+    }
+
+    #[test]
+    fn test_reset_jumps() {
+        let mut smoothed = Smoothed::new(0.0);
+        smoothed.set_target(1.0);
+        smoothed.tick();
+        smoothed.reset(0.25);
+        assert!(!smoothed.is_smoothing());
+        assert_eq!(smoothed.tick(), 0.25);
+        assert_eq!(smoothed.target(), 0.25);
+    }
+
+    #[test]
+    fn test_ramp_length_follows_sample_rate() {
+        let mut smoothed = Smoothed::new(0.0);
+        smoothed.set_sample_rate(96000.0);
+        smoothed.set_target(1.0);
+        let n = (0..).take_while(|_| smoothed.tick() < 1.0).count() + 1;
+        assert_eq!(n, (SMOOTHING_SECONDS * 96000.0).round() as usize);
+    }
+}
@@ -0,0 +1,10 @@
+use rustfft::{num_complex::Complex, FftPlanner};
+
+/// Magnitude spectrum of `samples`, one value per bin up to Nyquist.
+pub fn spectrum(samples: &[f32]) -> Vec<f32> {
+    let mut buffer: Vec<Complex<f32>> = samples.iter().map(|&s| Complex::new(s, 0.0)).collect();
+    FftPlanner::new()
+        .plan_fft_forward(samples.len())
+        .process(&mut buffer);
+    buffer[..samples.len() / 2].iter().map(|c| c.norm()).collect()
+}
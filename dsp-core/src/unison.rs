@@ -0,0 +1,540 @@
+use crate::fm::{self, FmModulator};
+use crate::oscillator::Oscillator;
+use crate::pan_gains;
+use crate::params::OscillatorType;
+use crate::rng::Rng;
+use crate::wavetable::Wavetable;
+use std::sync::Arc;
+
+/// Most oscillators one voice can stack in unison.
+pub const MAX_UNISON: usize = 16;
+
+/// Widest unison detune: the outermost oscillators sit this many cents
+/// either side of the played pitch.
+pub const MAX_UNISON_DETUNE_CENTS: f32 = 100.0;
+
+/// Deepest linear FM of osc 1 by osc 2: at this depth osc 1's frequency
+/// swings from `-3` to `5` times its own.
+pub const MAX_LINEAR_FM: f32 = 4.0;
+
+/// A stack of detuned oscillators playing one note, spread across the
+/// stereo field.
+///
+/// Oscillators are spaced evenly from `-detune` to `+detune` cents and from
+/// `-spread` to `+spread` in pan, lowest pitch on the left. The mix is
+/// scaled by `1 / sqrt(voices)` so a thicker stack stays at roughly the same
+/// loudness. With one oscillator the output is that oscillator, unchanged,
+/// on both channels.
+///
+/// Every oscillator in the stack is paired with a second oscillator, tuned
+/// relative to it and optionally hard-synced to it, so the second
+/// oscillator follows the unison detune and spread too.
+///
+/// The pair can be ring-modulated (osc 1 × osc 2, blended with the dry
+/// mix) and osc 2 can modulate osc 1's frequency linearly, through zero.
+/// The linear FM uses osc 2's previous sample so it can sit alongside hard
+/// sync, which needs osc 1 first.
+///
+/// In FM mode each osc 1 also gets its own modulator, tuned from that
+/// oscillator's detuned pitch, which modulates its phase.
+pub struct Unison {
+    oscillators: [Oscillator; MAX_UNISON],
+    /// Osc 2 partner of each oscillator.
+    second: [Oscillator; MAX_UNISON],
+    /// Osc 2 frequency relative to osc 1.
+    second_ratio: f32,
+    /// Osc 2 level in the mix; osc 1 is always at full level.
+    second_level: f32,
+    /// Restart osc 2's cycle whenever its osc 1 partner starts a new one.
+    sync: bool,
+    /// Blend from the dry oscillators (0) to their ring modulation (1).
+    ring: f32,
+    /// Depth of osc 2's linear FM of osc 1.
+    linear_fm: f32,
+    /// Latest output of each osc 2, driving linear FM on the next sample.
+    last_second: [f32; MAX_UNISON],
+    /// FM modulator of each osc 1.
+    modulators: [FmModulator; MAX_UNISON],
+    /// Phase-modulate osc 1 by its modulator.
+    fm: bool,
+    /// Modulation index in radians.
+    fm_index: f32,
+    voices: usize,
+    detune: f32,
+    spread: f32,
+    random_phase: bool,
+    /// Played frequency in Hz, before detuning.
+    frequency: f32,
+    /// Frequency multiplier for each oscillator.
+    ratios: [f32; MAX_UNISON],
+    /// Left and right gain for each oscillator, including the level
+    /// normalisation.
+    gains: [(f32, f32); MAX_UNISON],
+    rng: Rng,
+}
+
+impl Unison {
+    pub fn new() -> Self {
+        let mut unison = Self {
+            oscillators: core::array::from_fn(|_| Oscillator::new()),
+            second: core::array::from_fn(|_| Oscillator::new()),
+            second_ratio: 1.0,
+            second_level: 0.0,
+            sync: false,
+            ring: 0.0,
+            linear_fm: 0.0,
+            last_second: [0.0; MAX_UNISON],
+            modulators: core::array::from_fn(|_| FmModulator::new()),
+            fm: false,
+            fm_index: 0.0,
+            voices: 1,
+            detune: 0.0,
+            spread: 0.0,
+            random_phase: false,
+            frequency: 440.0,
+            ratios: [1.0; MAX_UNISON],
+            gains: [(1.0, 1.0); MAX_UNISON],
+            rng: Rng::new(1),
+        };
+        unison.update_layout();
+        unison
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        for osc in self.oscillators.iter_mut().chain(self.second.iter_mut()) {
+            osc.set_sample_rate(sample_rate);
+        }
+        for modulator in self.modulators.iter_mut() {
+            modulator.set_sample_rate(sample_rate);
+        }
+    }
+
+    pub fn set_type(&mut self, osc_type: OscillatorType) {
+        for osc in self.oscillators.iter_mut() {
+            osc.set_type(osc_type);
+        }
+    }
+
+    pub fn set_second_type(&mut self, osc_type: OscillatorType) {
+        for osc in self.second.iter_mut() {
+            osc.set_type(osc_type);
+        }
+    }
+
+    /// Osc 2 pitch relative to osc 1, in semitones.
+    pub fn set_second_pitch(&mut self, semitones: f32) {
+        let ratio = 2.0f32.powf(semitones / 12.0);
+        if ratio != self.second_ratio {
+            self.second_ratio = ratio;
+            self.set_frequency(self.frequency);
+        }
+    }
+
+    /// Osc 2 level in `[0, 1]`.
+    pub fn set_second_level(&mut self, level: f32) {
+        self.second_level = level.clamp(0.0, 1.0);
+    }
+
+    /// Hard sync osc 2 to osc 1.
+    pub fn set_sync(&mut self, sync: bool) {
+        self.sync = sync;
+    }
+
+    /// Ring modulation blend in `[0, 1]`: 0 is the dry mix, 1 is osc 1 ×
+    /// osc 2 alone.
+    pub fn set_ring(&mut self, ring: f32) {
+        self.ring = ring.clamp(0.0, 1.0);
+    }
+
+    /// Depth of osc 2's linear FM of osc 1, clamped to `[0, MAX_LINEAR_FM]`.
+    /// Above 1 osc 1 runs backwards at osc 2's troughs (through-zero FM).
+    pub fn set_linear_fm(&mut self, depth: f32) {
+        self.linear_fm = depth.clamp(0.0, MAX_LINEAR_FM);
+        if self.linear_fm == 0.0 {
+            for osc in self.oscillators.iter_mut() {
+                osc.set_linear_fm(0.0);
+            }
+        }
+    }
+
+    /// Phase-modulate each osc 1 by its FM modulator.
+    pub fn set_fm(&mut self, fm: bool) {
+        self.fm = fm;
+    }
+
+    pub fn set_fm_ratio(&mut self, ratio: f32) {
+        for modulator in self.modulators.iter_mut() {
+            modulator.set_ratio(ratio);
+        }
+    }
+
+    pub fn set_fm_fixed(&mut self, fixed: bool) {
+        for modulator in self.modulators.iter_mut() {
+            modulator.set_fixed(fixed);
+        }
+    }
+
+    pub fn set_fm_fixed_frequency(&mut self, hz: f32) {
+        for modulator in self.modulators.iter_mut() {
+            modulator.set_fixed_frequency(hz);
+        }
+    }
+
+    pub fn set_fm_feedback(&mut self, amount: f32) {
+        for modulator in self.modulators.iter_mut() {
+            modulator.set_feedback(amount);
+        }
+    }
+
+    /// Modulation index in radians. Takes effect from the next sample, so
+    /// an envelope can drive it.
+    pub fn set_fm_index(&mut self, index: f32) {
+        self.fm_index = index;
+    }
+
+    /// Pulse width of both oscillators' square waves.
+    pub fn set_pulse_width(&mut self, width: f32) {
+        let voices = self.voices;
+        for osc in self.oscillators[..voices]
+            .iter_mut()
+            .chain(self.second[..voices].iter_mut())
+        {
+            osc.set_pulse_width(width);
+        }
+    }
+
+    pub fn set_wavetable(&mut self, table: &Arc<Wavetable>) {
+        for osc in self.oscillators.iter_mut().chain(self.second.iter_mut()) {
+            osc.set_wavetable(table);
+        }
+    }
+
+    /// Additive partial amplitude of both oscillators.
+    pub fn set_partial(&mut self, index: usize, amplitude: f32) {
+        for osc in self.oscillators.iter_mut().chain(self.second.iter_mut()) {
+            osc.set_partial(index, amplitude);
+        }
+    }
+
+    /// Wavetable position of both oscillators.
+    pub fn set_wavetable_position(&mut self, position: f32) {
+        let voices = self.voices;
+        for osc in self.oscillators[..voices]
+            .iter_mut()
+            .chain(self.second[..voices].iter_mut())
+        {
+            osc.set_wavetable_position(position);
+        }
+    }
+
+    /// Number of stacked oscillators, clamped to `[1, MAX_UNISON]`.
+    pub fn set_voices(&mut self, voices: usize) {
+        let voices = voices.clamp(1, MAX_UNISON);
+        if voices != self.voices {
+            self.voices = voices;
+            self.update_layout();
+        }
+    }
+
+    /// Detune of the outermost oscillators in cents, clamped to
+    /// `[0, MAX_UNISON_DETUNE_CENTS]`.
+    pub fn set_detune(&mut self, cents: f32) {
+        let cents = cents.clamp(0.0, MAX_UNISON_DETUNE_CENTS);
+        if cents != self.detune {
+            self.detune = cents;
+            self.update_layout();
+        }
+    }
+
+    /// Stereo width in `[0, 1]`: 0 keeps every oscillator centred, 1 pans
+    /// the outermost ones hard left and right.
+    pub fn set_spread(&mut self, spread: f32) {
+        let spread = spread.clamp(0.0, 1.0);
+        if spread != self.spread {
+            self.spread = spread;
+            self.update_layout();
+        }
+    }
+
+    /// Start each oscillator at a random phase on `reset` instead of all at
+    /// zero.
+    pub fn set_random_phase(&mut self, random_phase: bool) {
+        self.random_phase = random_phase;
+    }
+
+    /// Played frequency in Hz; each oscillator is detuned from it.
+    pub fn set_frequency(&mut self, hz: f32) {
+        self.frequency = hz;
+        let voices = self.voices;
+        for (((osc, second), modulator), &ratio) in self.oscillators[..voices]
+            .iter_mut()
+            .zip(self.second[..voices].iter_mut())
+            .zip(self.modulators[..voices].iter_mut())
+            .zip(self.ratios.iter())
+        {
+            osc.set_frequency(hz * ratio);
+            second.set_frequency(hz * ratio * self.second_ratio);
+            modulator.set_frequency(hz * ratio);
+        }
+    }
+
+    /// Restart every oscillator for a new note. Each osc 2 starts in phase
+    /// with its osc 1 partner.
+    pub fn reset(&mut self) {
+        for (osc, second) in self.oscillators.iter_mut().zip(self.second.iter_mut()) {
+            let phase = if self.random_phase {
+                self.rng.next_f32()
+            } else {
+                0.0
+            };
+            osc.reset_to(phase);
+            second.reset_to(phase);
+        }
+        for modulator in self.modulators.iter_mut() {
+            modulator.reset();
+        }
+        self.last_second = [0.0; MAX_UNISON];
+    }
+
+    /// Produce the next left and right samples.
+    pub fn tick(&mut self) -> (f32, f32) {
+        let mut left = 0.0;
+        let mut right = 0.0;
+        // An unused osc 2 is skipped; it restarts in phase on the next note
+        let second_on = self.second_level > 0.0 || self.ring > 0.0 || self.linear_fm > 0.0;
+        for ((((osc, second), last_second), modulator), &(l, r)) in self.oscillators[..self.voices]
+            .iter_mut()
+            .zip(self.second.iter_mut())
+            .zip(self.last_second.iter_mut())
+            .zip(self.modulators.iter_mut())
+            .zip(self.gains.iter())
+        {
+            if self.linear_fm > 0.0 {
+                osc.set_linear_fm(self.linear_fm * *last_second);
+            }
+            let wrap = osc.wrap_position();
+            let mut sample = if self.fm {
+                osc.tick_modulated(fm::phase_offset(self.fm_index, modulator.tick()))
+            } else {
+                osc.tick()
+            };
+            if second_on {
+                let second = if self.sync {
+                    second.tick_synced(wrap)
+                } else {
+                    second.tick()
+                };
+                *last_second = second;
+                let dry = sample + second * self.second_level;
+                sample = dry + self.ring * (sample * second - dry);
+            }
+            left += sample * l;
+            right += sample * r;
+        }
+        (left, right)
+    }
+
+    fn update_layout(&mut self) {
+        let level = 1.0 / (self.voices as f32).sqrt();
+        for i in 0..self.voices {
+            // Position in the stack from -1 (lowest, left) to 1
+            let position = if self.voices == 1 {
+                0.0
+            } else {
+                2.0 * i as f32 / (self.voices - 1) as f32 - 1.0
+            };
+            self.ratios[i] = 2.0f32.powf(self.detune * position / 1200.0);
+            let (l, r) = pan_gains(self.spread * position);
+            self.gains[i] = (l * level, r * level);
+        }
+        // Oscillators just brought in pick up the current pitch
+        self.set_frequency(self.frequency);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unison(voices: usize, detune: f32, spread: f32) -> Unison {
+        let mut unison = Unison::new();
+        unison.set_sample_rate(44100.0);
+        unison.set_type(OscillatorType::Saw);
+        unison.set_voices(voices);
+        unison.set_detune(detune);
+        unison.set_spread(spread);
+        unison.set_frequency(220.0);
+        unison.reset();
+        unison
+    }
+
+    #[test]
+    fn test_single_voice_matches_plain_oscillator() {
+        let mut unison = unison(1, 50.0, 1.0);
+        let mut osc = Oscillator::new();
+        osc.set_sample_rate(44100.0);
+        osc.set_type(OscillatorType::Saw);
+        osc.set_frequency(220.0);
+        for _ in 0..1000 {
+            let expected = osc.tick();
+            assert_eq!(unison.tick(), (expected, expected));
+        }
+    }
+
+    #[test]
+    fn test_detune_spreads_pitch_symmetrically() {
+        let unison = unison(3, 100.0, 0.0);
+        let semitone = 2.0f32.powf(1.0 / 12.0);
+        assert!((unison.ratios[0] - 1.0 / semitone).abs() < 1e-5);
+        assert_eq!(unison.ratios[1], 1.0);
+        assert!((unison.ratios[2] - semitone).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_no_spread_stays_centred() {
+        let mut unison = unison(7, 30.0, 0.0);
+        for _ in 0..1000 {
+            let (l, r) = unison.tick();
+            assert_eq!(l, r);
+        }
+    }
+
+    #[test]
+    fn test_full_spread_pans_outer_voices_hard() {
+        let unison = unison(4, 30.0, 1.0);
+        let level = 0.5; // 1 / sqrt(4)
+        assert_eq!(unison.gains[0], (level, 0.0));
+        assert_eq!(unison.gains[3], (0.0, level));
+        let mut unison = unison;
+        let differs = (0..1000).any(|_| {
+            let (l, r) = unison.tick();
+            l != r
+        });
+        assert!(differs);
+    }
+
+    #[test]
+    fn test_random_phase_varies_between_notes() {
+        let mut unison = unison(1, 0.0, 0.0);
+        unison.set_random_phase(true);
+        unison.reset();
+        let first = unison.tick();
+        unison.reset();
+        assert_ne!(unison.tick(), first);
+    }
+
+    #[test]
+    fn test_voice_count_is_clamped() {
+        let mut unison = Unison::new();
+        unison.set_voices(0);
+        assert_eq!(unison.voices, 1);
+        unison.set_voices(100);
+        assert_eq!(unison.voices, MAX_UNISON);
+    }
+
+    #[test]
+    fn test_second_oscillator_mixes_at_its_level() {
+        let mut unison = unison(1, 0.0, 0.0);
+        unison.set_second_type(OscillatorType::Sine);
+        unison.set_second_pitch(12.0);
+        unison.set_second_level(0.5);
+        unison.reset();
+        let mut first = Oscillator::new();
+        first.set_type(OscillatorType::Saw);
+        first.set_frequency(220.0);
+        let mut second = Oscillator::new();
+        second.set_type(OscillatorType::Sine);
+        second.set_frequency(440.0);
+        for _ in 0..1000 {
+            let expected = first.tick() + 0.5 * second.tick();
+            let (l, _) = unison.tick();
+            assert!((l - expected).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_silent_second_oscillator_leaves_stack_unchanged() {
+        let mut plain = unison(3, 20.0, 0.5);
+        let mut paired = unison(3, 20.0, 0.5);
+        paired.set_second_type(OscillatorType::Square);
+        paired.set_second_pitch(7.0);
+        paired.set_sync(true);
+        paired.reset();
+        for _ in 0..1000 {
+            assert_eq!(paired.tick(), plain.tick());
+        }
+    }
+
+    #[test]
+    fn test_fm_at_zero_index_leaves_carrier_unchanged() {
+        let mut plain = unison(3, 20.0, 0.5);
+        let mut fm = unison(3, 20.0, 0.5);
+        fm.set_fm(true);
+        fm.set_fm_ratio(2.0);
+        for _ in 0..1000 {
+            assert_eq!(fm.tick(), plain.tick());
+        }
+        fm.set_fm_index(2.0);
+        let differs = (0..1000).any(|_| fm.tick() != plain.tick());
+        assert!(differs);
+    }
+
+    #[test]
+    fn test_full_ring_is_product_of_the_pair() {
+        let mut ring = unison(1, 0.0, 0.0);
+        ring.set_second_type(OscillatorType::Sine);
+        ring.set_second_pitch(7.0);
+        ring.set_ring(1.0);
+        ring.reset();
+        let mut first = Oscillator::new();
+        first.set_type(OscillatorType::Saw);
+        first.set_frequency(220.0);
+        let mut second = Oscillator::new();
+        second.set_type(OscillatorType::Sine);
+        second.set_frequency(220.0 * 2.0f32.powf(7.0 / 12.0));
+        for _ in 0..1000 {
+            let expected = first.tick() * second.tick();
+            let (l, _) = ring.tick();
+            assert!((l - expected).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_ring_blends_with_dry_mix() {
+        let render = |ring: f32| {
+            let mut unison = unison(1, 0.0, 0.0);
+            unison.set_second_pitch(5.0);
+            unison.set_second_level(0.5);
+            unison.set_ring(ring);
+            unison.reset();
+            (0..1000).map(|_| unison.tick().0).collect::<Vec<_>>()
+        };
+        let (dry, half, wet) = (render(0.0), render(0.5), render(1.0));
+        for i in 0..1000 {
+            assert!((half[i] - 0.5 * (dry[i] + wet[i])).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_linear_fm_goes_through_zero_and_stops_cleanly() {
+        let mut plain = unison(2, 10.0, 0.0);
+        let mut fm = unison(2, 10.0, 0.0);
+        fm.set_second_type(OscillatorType::Sine);
+        fm.set_linear_fm(3.0);
+        fm.reset();
+        let mut differs = false;
+        for _ in 0..4410 {
+            let (a, b) = (plain.tick(), fm.tick());
+            assert!(b.0.is_finite() && b.0.abs() < 4.0);
+            differs |= a != b;
+        }
+        assert!(differs);
+        // Switching it off puts every osc 1 back on its own pitch
+        fm.set_linear_fm(0.0);
+        fm.reset();
+        plain.reset();
+        for _ in 0..1000 {
+            assert_eq!(fm.tick(), plain.tick());
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use crate::params::VelocityCurve;
+
+/// Dynamic range of the exponential curve. Velocity steps map to equal
+/// loudness steps across this many decibels.
+const EXPONENTIAL_RANGE_DB: f32 = 40.0;
+
+/// Shortest attack a hard hit can produce, as a fraction of the set attack.
+const MIN_ATTACK_SCALE: f32 = 0.1;
+
+/// How many octaves the tone control darkens the softest note at full amount.
+const MAX_DARKEN_OCTAVES: f32 = 6.0;
+
+/// Maps note velocity to amplitude, attack time and brightness.
+///
+/// Velocity is first shaped by the selected curve; each destination then
+/// applies its own amount in `[0, 1]`. An amount of zero makes that
+/// destination ignore velocity entirely.
+#[derive(Debug, Clone, Copy)]
+pub struct VelocityResponse {
+    curve: VelocityCurve,
+    amount: f32,
+    to_attack: f32,
+    to_brightness: f32,
+}
+
+impl VelocityResponse {
+    pub fn new() -> Self {
+        Self {
+            curve: VelocityCurve::Linear,
+            amount: 1.0,
+            to_attack: 0.0,
+            to_brightness: 0.0,
+        }
+    }
+
+    pub fn set_curve(&mut self, curve: VelocityCurve) {
+        self.curve = curve;
+    }
+
+    /// How strongly velocity scales amplitude.
+    pub fn set_amount(&mut self, amount: f32) {
+        self.amount = amount.clamp(0.0, 1.0);
+    }
+
+    /// How strongly harder hits shorten the attack.
+    pub fn set_to_attack(&mut self, amount: f32) {
+        self.to_attack = amount.clamp(0.0, 1.0);
+    }
+
+    /// How strongly softer hits darken the tone.
+    pub fn set_to_brightness(&mut self, amount: f32) {
+        self.to_brightness = amount.clamp(0.0, 1.0);
+    }
+
+    /// Velocity after the curve, in `[0, 1]`.
+    pub fn shape(&self, velocity: f32) -> f32 {
+        let v = velocity.clamp(0.0, 1.0);
+        match self.curve {
+            VelocityCurve::Linear => v,
+            VelocityCurve::Exponential => {
+                if v <= 0.0 {
+                    0.0
+                } else {
+                    10.0f32.powf((v - 1.0) * EXPONENTIAL_RANGE_DB / 20.0)
+                }
+            }
+            VelocityCurve::Fixed => 1.0,
+        }
+    }
+
+    /// Gain multiplier for a note of this velocity.
+    pub fn amplitude(&self, velocity: f32) -> f32 {
+        1.0 - self.amount + self.amount * self.shape(velocity)
+    }
+
+    /// Multiplier for the configured attack time.
+    pub fn attack_scale(&self, velocity: f32) -> f32 {
+        1.0 - (1.0 - MIN_ATTACK_SCALE) * self.to_attack * self.shape(velocity)
+    }
+
+    /// How many octaves below fully open the tone control sits.
+    pub fn darken_octaves(&self, velocity: f32) -> f32 {
+        MAX_DARKEN_OCTAVES * self.to_brightness * (1.0 - self.shape(velocity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_is_linear_full_amplitude_only() {
+        let vr = VelocityResponse::new();
+        assert!((vr.amplitude(0.5) - 0.5).abs() < 1e-6);
+        assert_eq!(vr.attack_scale(0.5), 1.0);
+        assert_eq!(vr.darken_octaves(0.5), 0.0);
+    }
+
+    #[test]
+    fn test_zero_amount_ignores_velocity() {
+        let mut vr = VelocityResponse::new();
+        vr.set_amount(0.0);
+        assert_eq!(vr.amplitude(0.1), 1.0);
+        assert_eq!(vr.amplitude(1.0), 1.0);
+    }
+
+    #[test]
+    fn test_half_amount_blends_toward_full() {
+        let mut vr = VelocityResponse::new();
+        vr.set_amount(0.5);
+        assert!((vr.amplitude(0.0) - 0.5).abs() < 1e-6);
+        assert!((vr.amplitude(1.0) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_fixed_curve_ignores_velocity() {
+        let mut vr = VelocityResponse::new();
+        vr.set_curve(VelocityCurve::Fixed);
+        vr.set_to_brightness(1.0);
+        assert_eq!(vr.amplitude(0.2), 1.0);
+        assert_eq!(vr.darken_octaves(0.2), 0.0);
+    }
+
+    #[test]
+    fn test_exponential_curve_is_40db_range() {
+        let mut vr = VelocityResponse::new();
+        vr.set_curve(VelocityCurve::Exponential);
+        assert!((vr.shape(1.0) - 1.0).abs() < 1e-6);
+        // Half velocity = -20 dB
+        assert!((vr.shape(0.5) - 0.1).abs() < 1e-6);
+        assert_eq!(vr.shape(0.0), 0.0);
+    }
+
+    #[test]
+    fn test_curves_are_monotonic() {
+        for &curve in VelocityCurve::VARIANTS {
+            let mut vr = VelocityResponse::new();
+            vr.set_curve(curve);
+            let mut prev = vr.shape(0.0);
+            for i in 1..=127 {
+                let next = vr.shape(i as f32 / 127.0);
+                assert!(next >= prev, "{:?} decreased at {}", curve, i);
+                prev = next;
+            }
+        }
+    }
+
+    #[test]
+    fn test_hard_hits_shorten_attack() {
+        let mut vr = VelocityResponse::new();
+        vr.set_to_attack(1.0);
+        assert!((vr.attack_scale(1.0) - MIN_ATTACK_SCALE).abs() < 1e-6);
+        assert!((vr.attack_scale(0.0) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_soft_hits_darken_tone() {
+        let mut vr = VelocityResponse::new();
+        vr.set_to_brightness(1.0);
+        assert_eq!(vr.darken_octaves(1.0), 0.0);
+        assert!((vr.darken_octaves(0.0) - MAX_DARKEN_OCTAVES).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_amounts_are_clamped() {
+        let mut vr = VelocityResponse::new();
+        vr.set_amount(5.0);
+        vr.set_to_attack(-1.0);
+        assert!((vr.amplitude(0.0) - 0.0).abs() < 1e-6);
+        assert_eq!(vr.attack_scale(1.0), 1.0);
+    }
+}
@@ -0,0 +1,1316 @@
+use crate::envelope::Envelope;
+use crate::filter::Filter;
+use crate::glide::{Glide, GlideSettings};
+use crate::ladder::LadderFilter;
+use crate::lfo::Lfo;
+use crate::{midi_pitch_to_freq, pan_gains};
+use crate::mod_matrix::{ModMatrix, ModOffsets, ModSources};
+use crate::noise::Noise;
+use crate::oscillator::Oscillator;
+use crate::params::{
+    Engine, FilterMode, FilterType, LfoShape, LfoTrigger, NoiseType, OscillatorType,
+    PluckExcitation, SubOctave, SubShape,
+};
+use crate::pluck::PluckedString;
+use crate::unison::Unison;
+use crate::velocity::VelocityResponse;
+use crate::wavetable::Wavetable;
+use core::f32::consts::PI;
+use std::sync::Arc;
+
+/// Maximum number of simultaneously sounding voices.
+///
+/// The voice pool is a fixed-size array of this length, allocated once when
+/// the `Synth` is created, so `process` never touches the heap.
+pub const MAX_VOICES: usize = 16;
+
+/// Cutoff of the tone control when velocity isn't darkening it.
+const TONE_OPEN_HZ: f32 = 20000.0;
+
+/// Note at which filter key tracking leaves the cutoff unchanged (middle C).
+const KEYTRACK_CENTER_NOTE: f32 = 60.0;
+
+/// Note at which the key track mod source is zero; it reaches -1 at note 0
+/// and just under 1 at note 127.
+const KEYTRACK_SOURCE_CENTER_NOTE: f32 = 64.0;
+
+/// How far full-depth modulation moves the pulse width either side of its
+/// set value; from a square it reaches the oscillator's pulse width limits.
+const LFO_PULSE_WIDTH_RANGE: f32 = 0.45;
+
+/// A single synth voice: a unison stack of oscillator pairs, plus a
+/// sub-oscillator and noise, or a plucked string, through a stereo pair
+/// of resonant filters, with one ADSR envelope shaping the amplitude, a
+/// second sweeping the cutoff and a third driving the FM index.
+///
+/// Voices are owned by the `Synth` voice pool and reused for every note,
+/// so a voice never allocates after construction.
+pub struct Voice {
+    engine: Engine,
+    unison: Unison,
+    /// Sub-oscillator, an octave or two below the stack.
+    sub: Oscillator,
+    sub_octave: SubOctave,
+    sub_level: f32,
+    noise: Noise,
+    noise_level: f32,
+    string: PluckedString,
+    /// Frequency the oscillators are tuned to, in Hz, after modulation.
+    frequency: f32,
+    filter_type: FilterType,
+    /// Left and right filters. Both channels always run so unison spread
+    /// can change mid-note without the right channel jumping in from
+    /// stale state.
+    filters: [Filter; 2],
+    ladders: [LadderFilter; 2],
+    envelope: Envelope,
+    filter_envelope: Envelope,
+    fm_envelope: Envelope,
+    tones: [ToneFilter; 2],
+    /// This voice's own LFO, used in key-sync mode.
+    lfo: Lfo,
+    lfo_trigger: LfoTrigger,
+    /// Vibrato depth in semitones at full LFO swing.
+    lfo_to_pitch: f32,
+    /// Tremolo depth in `[0, 1]`: how far the LFO pulls the level down.
+    lfo_to_amp: f32,
+    /// Pulse width modulation depth in `[0, 1]`.
+    lfo_to_pulse_width: f32,
+    /// Square wave duty cycle before modulation.
+    pulse_width: f32,
+    /// Wavetable scan position before modulation, in `[0, 1]`.
+    wavetable_position: f32,
+    /// Current pitch as a fractional MIDI note, sliding during portamento.
+    pitch: Glide,
+    sample_rate: f32,
+    /// Attack time before velocity scaling and modulation, in seconds.
+    attack: f32,
+    /// Decay and release times before modulation, in seconds.
+    decay: f32,
+    release: f32,
+    /// Attack time multiplier from velocity for the current note.
+    attack_scale: f32,
+    /// Mod matrix output from the latest sample.
+    modulation: ModOffsets,
+    /// Set by `note_on` so the first sample applies modulated attack and
+    /// decay times.
+    envelope_times_pending: bool,
+    /// Pitch modulation currently applied to the oscillators, in semitones.
+    pitch_offset: f32,
+    /// Filter cutoff before key tracking, in Hz.
+    cutoff: f32,
+    /// How far the cutoff follows the played pitch (1 = one octave per octave).
+    keytrack: f32,
+    /// How far the filter envelope moves the cutoff at full level, in
+    /// octaves. Negative amounts sweep it down.
+    filter_env_amount: f32,
+    /// FM index at full FM envelope level, in radians.
+    fm_index: f32,
+    /// Velocity the current note was played with.
+    velocity: f32,
+    /// Gain from velocity for the current note.
+    amplitude: f32,
+    note: u8,
+    /// True while the key that started this voice is still held.
+    gate: bool,
+    /// Value of the synth's note-on counter when this voice was started.
+    /// Lower values are older notes.
+    started_at: u64,
+    /// A note-on waiting for a reset retrigger's fade to silence the
+    /// previous note; `tick` starts it when the fade ends.
+    pending_start: Option<NoteStart>,
+}
+
+/// The parts of a note-on that change the sound, which a reset retrigger
+/// holds back until the amp envelope has faded the previous note out.
+#[derive(Debug, Clone, Copy)]
+struct NoteStart {
+    velocity: f32,
+    amplitude: f32,
+    tone_hz: f32,
+    /// Pitch to glide from and how, if the note glides in.
+    glide: Option<(f32, GlideSettings)>,
+    /// The amp envelope carried on legato, so the phases do too.
+    legato: bool,
+    /// The voice was silent, so filters and string can start afresh.
+    from_silence: bool,
+}
+
+impl Voice {
+    pub fn new() -> Self {
+        Self {
+            engine: Engine::Oscillators,
+            unison: Unison::new(),
+            sub: Oscillator::new(),
+            sub_octave: SubOctave::OneDown,
+            sub_level: 0.0,
+            noise: Noise::new(1),
+            noise_level: 0.0,
+            string: PluckedString::new(),
+            frequency: 440.0,
+            filter_type: FilterType::StateVariable,
+            filters: [Filter::new(), Filter::new()],
+            ladders: [LadderFilter::new(), LadderFilter::new()],
+            envelope: Envelope::new(),
+            filter_envelope: Envelope::new(),
+            fm_envelope: Envelope::new(),
+            tones: [ToneFilter::new(), ToneFilter::new()],
+            lfo: Lfo::new(),
+            lfo_trigger: LfoTrigger::Free,
+            lfo_to_pitch: 0.0,
+            lfo_to_amp: 0.0,
+            lfo_to_pulse_width: 0.0,
+            pulse_width: 0.5,
+            wavetable_position: 0.0,
+            pitch: Glide::new(),
+            sample_rate: 44100.0,
+            attack: 0.01,
+            decay: 0.1,
+            release: 0.3,
+            attack_scale: 1.0,
+            modulation: ModOffsets::default(),
+            envelope_times_pending: false,
+            pitch_offset: 0.0,
+            cutoff: 20000.0,
+            keytrack: 0.0,
+            filter_env_amount: 0.0,
+            fm_index: 0.0,
+            velocity: 1.0,
+            amplitude: 1.0,
+            note: 0,
+            gate: false,
+            started_at: 0,
+            pending_start: None,
+        }
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.unison.set_sample_rate(sample_rate);
+        self.sub.set_sample_rate(sample_rate);
+        self.string.set_sample_rate(sample_rate);
+        for filter in self.filters.iter_mut() {
+            filter.set_sample_rate(sample_rate);
+        }
+        for ladder in self.ladders.iter_mut() {
+            ladder.set_sample_rate(sample_rate);
+        }
+        self.envelope.set_sample_rate(sample_rate);
+        self.filter_envelope.set_sample_rate(sample_rate);
+        self.fm_envelope.set_sample_rate(sample_rate);
+        self.lfo.set_sample_rate(sample_rate);
+    }
+
+    pub fn set_oscillator_type(&mut self, osc_type: OscillatorType) {
+        self.unison.set_type(osc_type);
+        self.string.set_exciter_type(osc_type);
+    }
+
+    pub fn set_engine(&mut self, engine: Engine) {
+        if engine != self.engine {
+            self.engine = engine;
+            // The string is only retuned while it's playing
+            self.set_frequency(self.frequency);
+        }
+    }
+
+    pub fn set_pluck_excitation(&mut self, excitation: PluckExcitation) {
+        self.string.set_excitation(excitation);
+    }
+
+    /// String damping in `[0, 1]`.
+    pub fn set_pluck_damping(&mut self, damping: f32) {
+        self.string.set_damping(damping);
+    }
+
+    /// Pluck brightness in `[0, 1]`.
+    pub fn set_pluck_brightness(&mut self, brightness: f32) {
+        self.string.set_brightness(brightness);
+    }
+
+    /// String decay time in seconds.
+    pub fn set_pluck_decay(&mut self, seconds: f32) {
+        self.string.set_decay(seconds);
+    }
+
+    /// Number of oscillators stacked on each note.
+    pub fn set_unison_voices(&mut self, voices: usize) {
+        self.unison.set_voices(voices);
+    }
+
+    /// Detune of the outermost unison oscillators, in cents.
+    pub fn set_unison_detune(&mut self, cents: f32) {
+        self.unison.set_detune(cents);
+    }
+
+    /// Stereo width of the unison stack in `[0, 1]`.
+    pub fn set_unison_spread(&mut self, spread: f32) {
+        self.unison.set_spread(spread);
+    }
+
+    pub fn set_unison_random_phase(&mut self, random_phase: bool) {
+        self.unison.set_random_phase(random_phase);
+    }
+
+    pub fn set_osc2_type(&mut self, osc_type: OscillatorType) {
+        self.unison.set_second_type(osc_type);
+    }
+
+    /// Osc 2 pitch relative to osc 1, in semitones.
+    pub fn set_osc2_pitch(&mut self, semitones: f32) {
+        self.unison.set_second_pitch(semitones);
+    }
+
+    /// Osc 2 level in `[0, 1]`.
+    pub fn set_osc2_level(&mut self, level: f32) {
+        self.unison.set_second_level(level);
+    }
+
+    /// Hard sync osc 2 to osc 1.
+    pub fn set_osc2_sync(&mut self, sync: bool) {
+        self.unison.set_sync(sync);
+    }
+
+    pub fn set_ring_mix(&mut self, mix: f32) {
+        self.unison.set_ring(mix);
+    }
+
+    pub fn set_linear_fm(&mut self, depth: f32) {
+        self.unison.set_linear_fm(depth);
+    }
+
+    /// How far below the note the sub-oscillator plays.
+    pub fn set_sub_octave(&mut self, octave: SubOctave) {
+        self.sub_octave = octave;
+        self.sub.set_frequency(self.frequency * octave.ratio());
+    }
+
+    pub fn set_sub_shape(&mut self, shape: SubShape) {
+        self.sub.set_type(shape.oscillator_type());
+    }
+
+    /// Sub-oscillator level in `[0, 1]`.
+    pub fn set_sub_level(&mut self, level: f32) {
+        self.sub_level = level.clamp(0.0, 1.0);
+    }
+
+    pub fn set_noise_type(&mut self, noise_type: NoiseType) {
+        self.noise.set_type(noise_type);
+    }
+
+    /// Noise level in `[0, 1]`.
+    pub fn set_noise_level(&mut self, level: f32) {
+        self.noise_level = level.clamp(0.0, 1.0);
+    }
+
+    /// Restart this voice's noise from `seed`. Voices are seeded
+    /// differently so chords don't play the same noise several times over.
+    pub fn seed_noise(&mut self, seed: u32) {
+        self.noise.seed(seed);
+        self.string.seed(seed);
+    }
+
+    /// Phase-modulate osc 1 with the FM modulator.
+    pub fn set_fm(&mut self, fm: bool) {
+        self.unison.set_fm(fm);
+    }
+
+    pub fn set_fm_ratio(&mut self, ratio: f32) {
+        self.unison.set_fm_ratio(ratio);
+    }
+
+    pub fn set_fm_fixed(&mut self, fixed: bool) {
+        self.unison.set_fm_fixed(fixed);
+    }
+
+    pub fn set_fm_fixed_frequency(&mut self, hz: f32) {
+        self.unison.set_fm_fixed_frequency(hz);
+    }
+
+    pub fn set_fm_feedback(&mut self, amount: f32) {
+        self.unison.set_fm_feedback(amount);
+    }
+
+    /// FM index the FM envelope reaches at full level, in radians.
+    pub fn set_fm_index(&mut self, index: f32) {
+        self.fm_index = index;
+    }
+
+    /// Square wave duty cycle before modulation.
+    pub fn set_pulse_width(&mut self, width: f32) {
+        self.pulse_width = width;
+        self.string.set_exciter_pulse_width(width);
+    }
+
+    pub fn set_wavetable(&mut self, table: &Arc<Wavetable>) {
+        self.unison.set_wavetable(table);
+        self.string.set_wavetable(table);
+    }
+
+    /// Amplitude of additive partial `index` in `[-1, 1]`.
+    pub fn set_partial(&mut self, index: usize, amplitude: f32) {
+        self.unison.set_partial(index, amplitude);
+        self.string.set_partial(index, amplitude);
+    }
+
+    /// Wavetable scan position in `[0, 1]`, before modulation.
+    pub fn set_wavetable_position(&mut self, position: f32) {
+        self.wavetable_position = position;
+        self.string.set_wavetable_position(position);
+    }
+
+    pub fn envelope_mut(&mut self) -> &mut Envelope {
+        &mut self.envelope
+    }
+
+    pub fn filter_envelope_mut(&mut self) -> &mut Envelope {
+        &mut self.filter_envelope
+    }
+
+    pub fn fm_envelope_mut(&mut self) -> &mut Envelope {
+        &mut self.fm_envelope
+    }
+
+    pub fn set_filter_type(&mut self, filter_type: FilterType) {
+        if filter_type != self.filter_type {
+            self.filter_type = filter_type;
+            // Only the active filter's cutoff is kept up to date
+            self.update_filter_cutoff();
+        }
+    }
+
+    pub fn set_filter_mode(&mut self, mode: FilterMode) {
+        for filter in self.filters.iter_mut() {
+            filter.set_mode(mode);
+        }
+    }
+
+    /// Set the filter cutoff before key tracking is applied.
+    pub fn set_filter_cutoff(&mut self, hz: f32) {
+        self.cutoff = hz;
+        self.update_filter_cutoff();
+    }
+
+    pub fn set_filter_resonance(&mut self, resonance: f32) {
+        for filter in self.filters.iter_mut() {
+            filter.set_resonance(resonance);
+        }
+        for ladder in self.ladders.iter_mut() {
+            ladder.set_resonance(resonance);
+        }
+    }
+
+    /// Ladder filter input drive in `[0, 1]`.
+    pub fn set_filter_drive(&mut self, drive: f32) {
+        for ladder in self.ladders.iter_mut() {
+            ladder.set_drive(drive);
+        }
+    }
+
+    pub fn set_filter_keytrack(&mut self, amount: f32) {
+        self.keytrack = amount;
+        self.update_filter_cutoff();
+    }
+
+    /// Filter envelope depth in octaves; negative values sweep the cutoff
+    /// down.
+    pub fn set_filter_env_amount(&mut self, octaves: f32) {
+        self.filter_env_amount = octaves;
+        self.update_filter_cutoff();
+    }
+
+    pub fn set_lfo_shape(&mut self, shape: LfoShape) {
+        self.lfo.set_shape(shape);
+    }
+
+    /// Rate of this voice's key-synced LFO, in Hz.
+    pub fn set_lfo_rate(&mut self, hz: f32) {
+        self.lfo.set_rate(hz);
+    }
+
+    pub fn set_lfo_trigger(&mut self, trigger: LfoTrigger) {
+        self.lfo_trigger = trigger;
+    }
+
+    /// Vibrato depth in semitones.
+    pub fn set_lfo_to_pitch(&mut self, semitones: f32) {
+        self.lfo_to_pitch = semitones;
+    }
+
+    /// Tremolo depth in `[0, 1]`.
+    pub fn set_lfo_to_amp(&mut self, depth: f32) {
+        self.lfo_to_amp = depth;
+    }
+
+    /// Pulse width modulation depth in `[0, 1]`.
+    pub fn set_lfo_to_pulse_width(&mut self, depth: f32) {
+        self.lfo_to_pulse_width = depth;
+    }
+
+    /// Set the attack time before velocity scaling is applied. The
+    /// current note keeps its velocity scaling.
+    pub fn set_attack(&mut self, seconds: f32) {
+        if seconds != self.attack {
+            self.attack = seconds;
+            self.apply_envelope_times();
+        }
+    }
+
+    /// Set the decay time before modulation is applied.
+    pub fn set_decay(&mut self, seconds: f32) {
+        if seconds != self.decay {
+            self.decay = seconds;
+            self.apply_envelope_times();
+        }
+    }
+
+    /// Set the release time before modulation is applied.
+    pub fn set_release(&mut self, seconds: f32) {
+        if seconds != self.release {
+            self.release = seconds;
+            self.apply_envelope_times();
+        }
+    }
+
+    /// Start (or restart) this voice on `note`.
+    ///
+    /// `velocity` is applied through `response` to this note's amplitude,
+    /// attack time and tone. With a reset retrigger the previous note fades
+    /// out first and the new one starts from silence once it has.
+    pub fn note_on(
+        &mut self,
+        note: u8,
+        velocity: f32,
+        started_at: u64,
+        response: &VelocityResponse,
+    ) {
+        self.note = note;
+        self.gate = true;
+        self.started_at = started_at;
+        self.attack_scale = response.attack_scale(velocity);
+        self.envelope.set_attack(self.attack * self.attack_scale);
+        self.envelope_times_pending = true;
+        let start = NoteStart {
+            velocity,
+            amplitude: response.amplitude(velocity),
+            tone_hz: TONE_OPEN_HZ * 2.0f32.powf(-response.darken_octaves(velocity)),
+            glide: None,
+            legato: self.envelope.ignores_note_on(),
+            from_silence: !self.envelope.is_active(),
+        };
+        self.envelope.note_on();
+        self.filter_envelope.note_on();
+        self.fm_envelope.note_on();
+        if self.envelope.is_fading() {
+            self.pending_start = Some(start);
+        } else {
+            self.pending_start = None;
+            self.start_note(start);
+        }
+    }
+
+    /// Everything a note-on does besides starting the envelopes.
+    fn start_note(&mut self, start: NoteStart) {
+        self.velocity = start.velocity;
+        self.amplitude = start.amplitude;
+        for tone in self.tones.iter_mut() {
+            tone.set_cutoff(start.tone_hz, self.sample_rate);
+        }
+        self.set_pitch(self.note as f32);
+        if let Some((from, settings)) = start.glide {
+            self.start_glide(from, &settings);
+        }
+        if !start.legato {
+            // A legato note carries on the sounding waveform, as its
+            // envelope does; restarting the phases would click
+            self.unison.reset();
+            self.sub.reset();
+        }
+        if start.from_silence {
+            // Start from silence; a stolen voice keeps its state so it can't click
+            self.string.reset();
+            for filter in self.filters.iter_mut() {
+                filter.reset();
+            }
+            for ladder in self.ladders.iter_mut() {
+                ladder.reset();
+            }
+        }
+        if self.engine == Engine::PluckedString {
+            self.string.pluck();
+        }
+        if self.lfo_trigger == LfoTrigger::KeySync {
+            self.lfo.reset();
+        }
+    }
+
+    /// Move a sounding voice to `note` without retriggering the envelope or
+    /// resetting the oscillator phases (legato).
+    pub fn change_note(&mut self, note: u8) {
+        self.note = note;
+        self.gate = true;
+        if self.pending_start.is_none() {
+            self.set_pitch(note as f32);
+        }
+    }
+
+    /// Slide into the current note from `from` (a fractional MIDI note)
+    /// instead of starting on pitch.
+    pub fn glide_from(&mut self, from: f32, settings: &GlideSettings) {
+        match self.pending_start.as_mut() {
+            Some(start) => start.glide = Some((from, *settings)),
+            None => self.start_glide(from, settings),
+        }
+    }
+
+    fn start_glide(&mut self, from: f32, settings: &GlideSettings) {
+        self.pitch
+            .start(from, self.note as f32, settings, self.sample_rate);
+        self.set_frequency(midi_pitch_to_freq(from + self.pitch_offset));
+    }
+
+    fn set_pitch(&mut self, pitch: f32) {
+        self.pitch.jump(pitch);
+        self.set_frequency(midi_pitch_to_freq(pitch + self.pitch_offset));
+        self.update_filter_cutoff();
+    }
+
+    /// Tune the oscillators to `hz`, with the sub-oscillator below.
+    fn set_frequency(&mut self, hz: f32) {
+        self.frequency = hz;
+        self.unison.set_frequency(hz);
+        self.sub.set_frequency(hz * self.sub_octave.ratio());
+        if self.engine == Engine::PluckedString {
+            self.string.set_frequency(hz);
+        }
+    }
+
+    /// Apply key tracking and the filter envelope to the base cutoff.
+    fn update_filter_cutoff(&mut self) {
+        let octaves = (self.pitch.pitch() - KEYTRACK_CENTER_NOTE) / 12.0;
+        let sweep = self.filter_env_amount * self.filter_envelope.level();
+        let cutoff = self.cutoff * 2.0f32.powf(self.keytrack * octaves + sweep);
+        match self.filter_type {
+            FilterType::StateVariable => {
+                for filter in self.filters.iter_mut() {
+                    filter.set_cutoff(cutoff);
+                }
+            }
+            FilterType::Ladder => {
+                for ladder in self.ladders.iter_mut() {
+                    ladder.set_cutoff(cutoff);
+                }
+            }
+        }
+    }
+
+    /// Release the voice. The envelope keeps running through its release stage.
+    pub fn note_off(&mut self) {
+        self.gate = false;
+        // A note released before its reset fade ends never starts
+        self.pending_start = None;
+        self.envelope.set_release(self.release_time());
+        self.envelope.note_off();
+        self.filter_envelope.note_off();
+        self.fm_envelope.note_off();
+    }
+
+    /// Velocity the current note was played with.
+    pub fn velocity(&self) -> f32 {
+        self.pending_start
+            .map_or(self.velocity, |start| start.velocity)
+    }
+
+    pub fn note(&self) -> u8 {
+        self.note
+    }
+
+    /// Pitch the voice is sounding right now, which lags `note()` while
+    /// gliding.
+    pub fn pitch(&self) -> f32 {
+        self.pitch.pitch()
+    }
+
+    /// True while the key that started this voice is held down.
+    pub fn is_held(&self) -> bool {
+        self.gate
+    }
+
+    /// True while the voice produces sound (including its release tail).
+    pub fn is_active(&self) -> bool {
+        self.envelope.is_active()
+    }
+
+    pub fn started_at(&self) -> u64 {
+        self.started_at
+    }
+
+    /// Stereo position from the mod matrix, `-1` (left) to `1` (right).
+    pub fn pan(&self) -> f32 {
+        self.modulation.pan.clamp(-1.0, 1.0)
+    }
+
+    /// Current envelope level, used to find the quietest voice.
+    pub fn level(&self) -> f32 {
+        self.envelope.level()
+    }
+
+    /// Produce the next left and right samples for this voice.
+    ///
+    /// `shared` carries the synth-wide modulation sources for this sample
+    /// (the free-running LFO and the MIDI controllers); the voice fills in
+    /// its own before running them through `matrix`.
+    pub fn tick(&mut self, shared: &ModSources, matrix: &ModMatrix) -> (f32, f32) {
+        if !self.envelope.is_active() {
+            return (0.0, 0.0);
+        }
+        if let Some(start) = self.pending_start {
+            if !self.envelope.is_fading() {
+                self.pending_start = None;
+                self.start_note(NoteStart {
+                    from_silence: true,
+                    ..start
+                });
+            }
+        }
+        let mut sources = *shared;
+        if self.lfo_trigger == LfoTrigger::KeySync {
+            sources.lfo = self.lfo.tick();
+        }
+        sources.amp_envelope = self.envelope.level();
+        sources.filter_envelope = self.filter_envelope.level();
+        sources.velocity = self.velocity;
+        sources.keytrack =
+            (self.pitch.pitch() - KEYTRACK_SOURCE_CENTER_NOTE) / KEYTRACK_SOURCE_CENTER_NOTE;
+        self.modulation = matrix.evaluate(&sources);
+        if self.envelope_times_pending {
+            self.envelope_times_pending = false;
+            self.apply_envelope_times();
+        }
+        let lfo = sources.lfo;
+
+        let gliding = self.pitch.is_gliding();
+        if gliding {
+            self.pitch.tick();
+        }
+        let pitch_offset = self.lfo_to_pitch * lfo + self.modulation.pitch_semitones();
+        if gliding || pitch_offset != self.pitch_offset {
+            self.pitch_offset = pitch_offset;
+            let pitch = self.pitch.pitch() + pitch_offset;
+            self.set_frequency(midi_pitch_to_freq(pitch));
+        }
+        let width = self.lfo_to_pulse_width * lfo + self.modulation.pulse_width;
+        self.unison
+            .set_pulse_width(self.pulse_width + LFO_PULSE_WIDTH_RANGE * width);
+        self.unison
+            .set_wavetable_position(self.wavetable_position + self.modulation.wavetable_position);
+        self.filter_envelope.tick();
+        if self.filter_env_amount != 0.0 || (gliding && self.keytrack != 0.0) {
+            self.update_filter_cutoff();
+        }
+        let fm_env = self.fm_envelope.tick();
+        self.unison.set_fm_index(self.fm_index * fm_env);
+        let (left, right) = match self.engine {
+            Engine::Oscillators => self.tick_oscillators(),
+            Engine::PluckedString => {
+                let string = self.string.tick();
+                (string, string)
+            }
+        };
+        let (left, right) = match self.filter_type {
+            FilterType::StateVariable => (
+                self.filters[0].process(left),
+                self.filters[1].process(right),
+            ),
+            FilterType::Ladder => (
+                self.ladders[0].process(left),
+                self.ladders[1].process(right),
+            ),
+        };
+        let env = self.envelope.tick();
+        // Tremolo dips the level from full down to `1 - depth`
+        let tremolo = 1.0 - self.lfo_to_amp * 0.5 * (1.0 - lfo);
+        let gain = self.amplitude * tremolo * self.modulation.gain_scale();
+        let (pan_left, pan_right) = pan_gains(self.modulation.pan);
+        (
+            self.tones[0].process(left * env * gain) * pan_left,
+            self.tones[1].process(right * env * gain) * pan_right,
+        )
+    }
+
+    /// The oscillator engine: the unison stack with the sub and noise.
+    fn tick_oscillators(&mut self) -> (f32, f32) {
+        let (mut left, mut right) = self.unison.tick();
+        // Sub and noise are mono, centred under the stack. Silent ones are
+        // skipped; the sub restarts in phase on the next note anyway
+        let mut centre = 0.0;
+        if self.sub_level > 0.0 {
+            centre += self.sub.tick() * self.sub_level;
+        }
+        if self.noise_level > 0.0 {
+            centre += self.noise.tick() * self.noise_level;
+        }
+        left += centre;
+        right += centre;
+        (left, right)
+    }
+
+    /// Apply velocity and the mod matrix to the envelope times, for a new
+    /// note or when a base time changes.
+    fn apply_envelope_times(&mut self) {
+        let attack_scale = self.attack_scale * ModOffsets::time_scale(self.modulation.attack);
+        self.envelope.set_attack(self.attack * attack_scale);
+        let decay = self.decay * ModOffsets::time_scale(self.modulation.decay);
+        self.envelope.set_decay(decay);
+        self.envelope.set_release(self.release_time());
+    }
+
+    /// Release time with the mod matrix applied.
+    fn release_time(&self) -> f32 {
+        self.release * ModOffsets::time_scale(self.modulation.release)
+    }
+}
+
+/// One-pole lowpass used as a velocity-controlled tone control.
+///
+/// Left fully open it is bypassed, so notes that velocity doesn't darken pass
+/// through bit-for-bit unchanged.
+struct ToneFilter {
+    coeff: f32,
+    state: f32,
+    bypass: bool,
+}
+
+impl ToneFilter {
+    fn new() -> Self {
+        Self {
+            coeff: 1.0,
+            state: 0.0,
+            bypass: true,
+        }
+    }
+
+    fn set_cutoff(&mut self, cutoff_hz: f32, sample_rate: f32) {
+        self.bypass = cutoff_hz >= TONE_OPEN_HZ;
+        // Keep the cutoff below Nyquist so the coefficient stays in (0, 1]
+        let cutoff_hz = cutoff_hz.min(sample_rate * 0.45);
+        self.coeff = 1.0 - (-2.0 * PI * cutoff_hz / sample_rate).exp();
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        if self.bypass {
+            self.state = input;
+            return input;
+        }
+        self.state += self.coeff * (input - self.state);
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::RetriggerMode;
+
+    fn started_voice(note: u8) -> Voice {
+        let mut voice = Voice::new();
+        voice.set_sample_rate(44100.0);
+        voice.note_on(note, 1.0, 1, &VelocityResponse::new());
+        voice
+    }
+
+    /// Tick with only the synth-wide LFO set and an empty mod matrix,
+    /// returning the left channel.
+    fn tick(voice: &mut Voice, lfo: f32) -> f32 {
+        stereo_tick(voice, lfo).0
+    }
+
+    fn stereo_tick(voice: &mut Voice, lfo: f32) -> (f32, f32) {
+        let shared = ModSources {
+            lfo,
+            ..ModSources::default()
+        };
+        voice.tick(&shared, &ModMatrix::new())
+    }
+
+    fn peak(voice: &mut Voice, n: usize) -> f32 {
+        (0..n).map(|_| tick(voice, 0.0).abs()).fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn test_new_voice_is_idle() {
+        let voice = Voice::new();
+        assert!(!voice.is_active());
+        assert!(!voice.is_held());
+    }
+
+    #[test]
+    fn test_idle_voice_is_silent() {
+        let mut voice = Voice::new();
+        assert!((0..100).all(|_| tick(&mut voice, 0.0) == 0.0));
+    }
+
+    #[test]
+    fn test_note_on_holds_note() {
+        let voice = started_voice(60);
+        assert!(voice.is_active());
+        assert!(voice.is_held());
+        assert_eq!(voice.note(), 60);
+        assert_eq!(voice.started_at(), 1);
+    }
+
+    #[test]
+    fn test_note_off_keeps_release_tail() {
+        let mut voice = started_voice(60);
+        for _ in 0..100 {
+            tick(&mut voice, 0.0);
+        }
+        voice.note_off();
+        assert!(!voice.is_held());
+        assert!(voice.is_active(), "voice should ring out during release");
+    }
+
+    #[test]
+    fn test_velocity_scales_amplitude() {
+        let response = VelocityResponse::new();
+        let mut loud = Voice::new();
+        loud.note_on(69, 1.0, 1, &response);
+        let mut soft = Voice::new();
+        soft.note_on(69, 0.25, 1, &response);
+        let ratio = peak(&mut soft, 2000) / peak(&mut loud, 2000);
+        assert!((ratio - 0.25).abs() < 0.01, "ratio {}", ratio);
+    }
+
+    #[test]
+    fn test_velocity_shortens_attack() {
+        let mut response = VelocityResponse::new();
+        response.set_amount(0.0);
+        response.set_to_attack(1.0);
+        let mut voice = Voice::new();
+        voice.set_attack(0.1);
+        voice.note_on(69, 1.0, 1, &response);
+        // Full velocity at full amount: attack is 10% of 0.1s = 441 samples
+        for _ in 0..450 {
+            tick(&mut voice, 0.0);
+        }
+        assert!(voice.level() > 0.95, "attack should be done: {}", voice.level());
+    }
+
+    #[test]
+    fn test_resetting_attack_keeps_velocity_scaling() {
+        let mut response = VelocityResponse::new();
+        response.set_amount(0.0);
+        response.set_to_attack(1.0);
+        let mut voice = Voice::new();
+        voice.set_attack(0.1);
+        voice.note_on(69, 1.0, 1, &response);
+        // The host sends every parameter again at the start of each block
+        for block in 0..8 {
+            voice.set_attack(0.1);
+            for _ in 0..64 {
+                tick(&mut voice, 0.0);
+            }
+            if block == 6 {
+                assert!(voice.level() > 0.95, "attack should be done: {}", voice.level());
+            }
+        }
+    }
+
+    #[test]
+    fn test_velocity_darkens_tone() {
+        let mut response = VelocityResponse::new();
+        response.set_amount(0.0);
+        response.set_to_brightness(1.0);
+        let harmonic_energy = |velocity: f32| {
+            let mut voice = Voice::new();
+            voice.set_oscillator_type(OscillatorType::Square);
+            voice.note_on(69, velocity, 1, &response);
+            let samples: Vec<f32> = (0..4410).map(|_| tick(&mut voice, 0.0)).collect();
+            // Sum of squared first differences tracks high-frequency content
+            samples.windows(2).map(|w| (w[1] - w[0]).powi(2)).sum::<f32>()
+        };
+        assert!(harmonic_energy(0.2) < harmonic_energy(1.0) * 0.5);
+    }
+
+    #[test]
+    fn test_full_velocity_tone_is_bypassed() {
+        let mut response = VelocityResponse::new();
+        response.set_to_brightness(1.0);
+        let mut plain = Voice::new();
+        plain.note_on(69, 1.0, 1, &VelocityResponse::new());
+        let mut toned = Voice::new();
+        toned.note_on(69, 1.0, 1, &response);
+        for _ in 0..1000 {
+            assert_eq!(tick(&mut plain, 0.0), tick(&mut toned, 0.0));
+        }
+    }
+
+    #[test]
+    fn test_glide_from_slides_pitch_to_note() {
+        let mut settings = GlideSettings::new();
+        settings.set_time(0.01);
+        let mut voice = started_voice(72);
+        voice.glide_from(60.0, &settings);
+        assert_eq!(voice.pitch(), 60.0);
+        tick(&mut voice, 0.0);
+        assert!(voice.pitch() > 60.0 && voice.pitch() < 72.0);
+        for _ in 0..441 {
+            tick(&mut voice, 0.0);
+        }
+        assert_eq!(voice.pitch(), 72.0);
+    }
+
+    #[test]
+    fn test_note_on_cancels_glide() {
+        let mut settings = GlideSettings::new();
+        settings.set_time(1.0);
+        let mut voice = started_voice(72);
+        voice.glide_from(60.0, &settings);
+        tick(&mut voice, 0.0);
+        voice.note_on(64, 1.0, 2, &VelocityResponse::new());
+        assert_eq!(voice.pitch(), 64.0);
+    }
+
+    /// Sum of squared first differences: a rough measure of high-frequency
+    /// content.
+    fn brightness(voice: &mut Voice) -> f32 {
+        let samples: Vec<f32> = (0..4410).map(|_| tick(voice, 0.0)).collect();
+        samples.windows(2).map(|w| (w[1] - w[0]).powi(2)).sum()
+    }
+
+    #[test]
+    fn test_filter_cutoff_darkens_voice() {
+        let render = |cutoff: f32| {
+            let mut voice = Voice::new();
+            voice.set_oscillator_type(OscillatorType::Saw);
+            voice.set_filter_cutoff(cutoff);
+            voice.note_on(57, 1.0, 1, &VelocityResponse::new());
+            brightness(&mut voice)
+        };
+        assert!(render(500.0) < render(20000.0) * 0.1);
+    }
+
+    #[test]
+    fn test_filter_keytrack_opens_cutoff_for_high_notes() {
+        let render = |keytrack: f32| {
+            let mut voice = Voice::new();
+            voice.set_oscillator_type(OscillatorType::Saw);
+            voice.set_filter_cutoff(500.0);
+            voice.set_filter_keytrack(keytrack);
+            // Two octaves above the key tracking centre
+            voice.note_on(84, 1.0, 1, &VelocityResponse::new());
+            brightness(&mut voice)
+        };
+        assert!(render(1.0) > render(0.0) * 2.0);
+    }
+
+    #[test]
+    fn test_ladder_filter_darkens_voice() {
+        let render = |filter_type: FilterType| {
+            let mut voice = Voice::new();
+            voice.set_oscillator_type(OscillatorType::Saw);
+            voice.set_filter_cutoff(500.0);
+            voice.set_filter_type(filter_type);
+            voice.note_on(57, 1.0, 1, &VelocityResponse::new());
+            brightness(&mut voice)
+        };
+        // Four poles roll off harder than the two-pole state-variable filter
+        assert!(render(FilterType::Ladder) < render(FilterType::StateVariable));
+    }
+
+    #[test]
+    fn test_filter_envelope_sweeps_cutoff() {
+        let render = |amount: f32| {
+            let mut voice = Voice::new();
+            voice.set_oscillator_type(OscillatorType::Saw);
+            voice.set_filter_cutoff(500.0);
+            voice.set_filter_env_amount(amount);
+            voice.note_on(57, 1.0, 1, &VelocityResponse::new());
+            brightness(&mut voice)
+        };
+        let closed = render(0.0);
+        assert!(render(4.0) > closed * 2.0, "positive amount should open the filter");
+        assert!(render(-2.0) < closed * 0.5, "negative amount should close the filter");
+    }
+
+    #[test]
+    fn test_filter_envelope_follows_its_own_adsr() {
+        let mut voice = Voice::new();
+        voice.set_oscillator_type(OscillatorType::Saw);
+        voice.set_filter_cutoff(500.0);
+        voice.set_filter_env_amount(4.0);
+        voice.set_attack(0.001);
+        let env = voice.filter_envelope_mut();
+        env.set_attack(0.001);
+        env.set_decay(0.02);
+        env.set_sustain(0.0);
+        voice.note_on(57, 1.0, 1, &VelocityResponse::new());
+        let early = brightness(&mut voice);
+        // Once the filter envelope has decayed the cutoff is back at its base
+        // while the amplitude envelope is still sustaining
+        let late = brightness(&mut voice);
+        assert!(late < early * 0.5, "early {} late {}", early, late);
+        assert!(voice.level() > 0.5);
+    }
+
+    #[test]
+    fn test_legato_does_not_retrigger_filter_envelope() {
+        let mut voice = started_voice(60);
+        voice.filter_envelope_mut().set_sustain(0.0);
+        for _ in 0..44100 {
+            tick(&mut voice, 0.0);
+        }
+        voice.change_note(64);
+        tick(&mut voice, 0.0);
+        assert_eq!(voice.filter_envelope.level(), 0.0);
+    }
+
+    #[test]
+    fn test_legato_note_on_keeps_oscillator_phase() {
+        let mut voice = Voice::new();
+        voice.set_sample_rate(44100.0);
+        voice.set_oscillator_type(OscillatorType::Sine);
+        voice.set_sub_level(0.5);
+        voice.envelope_mut().set_retrigger(RetriggerMode::Legato);
+        voice.note_on(60, 1.0, 1, &VelocityResponse::new());
+        let mut last = 0.0;
+        for _ in 0..1000 {
+            last = tick(&mut voice, 0.0);
+        }
+        voice.note_on(62, 1.0, 2, &VelocityResponse::new());
+        let next = tick(&mut voice, 0.0);
+        assert!((next - last).abs() < 0.05, "jump {} -> {}", last, next);
+    }
+
+    #[test]
+    fn test_single_unison_voice_is_centred() {
+        let mut voice = Voice::new();
+        voice.set_oscillator_type(OscillatorType::Saw);
+        voice.set_unison_spread(1.0);
+        voice.note_on(57, 1.0, 1, &VelocityResponse::new());
+        for _ in 0..1000 {
+            let (left, right) = stereo_tick(&mut voice, 0.0);
+            assert_eq!(left, right);
+        }
+    }
+
+    #[test]
+    fn test_unison_spread_widens_stereo_image() {
+        let side = |spread: f32| {
+            let mut voice = Voice::new();
+            voice.set_oscillator_type(OscillatorType::Saw);
+            voice.set_unison_voices(5);
+            voice.set_unison_detune(20.0);
+            voice.set_unison_spread(spread);
+            voice.note_on(57, 1.0, 1, &VelocityResponse::new());
+            (0..4410)
+                .map(|_| {
+                    let (left, right) = stereo_tick(&mut voice, 0.0);
+                    (left - right).abs()
+                })
+                .sum::<f32>()
+        };
+        assert_eq!(side(0.0), 0.0);
+        assert!(side(1.0) > 10.0, "side energy {}", side(1.0));
+    }
+
+    #[test]
+    fn test_unison_detune_beats() {
+        // Two oscillators a few cents apart drift in and out of phase, so
+        // the level rises and falls instead of staying constant
+        let mut voice = Voice::new();
+        voice.set_oscillator_type(OscillatorType::Sine);
+        voice.set_unison_voices(2);
+        voice.set_unison_detune(50.0);
+        voice.envelope_mut().set_sustain(1.0);
+        voice.note_on(69, 1.0, 1, &VelocityResponse::new());
+        let samples: Vec<f32> = (0..44100).map(|_| tick(&mut voice, 0.0)).collect();
+        let window_peaks: Vec<f32> = samples
+            .chunks(100)
+            .skip(10)
+            .map(|c| c.iter().fold(0.0f32, |m, &x| m.max(x.abs())))
+            .collect();
+        let loudest = window_peaks.iter().cloned().fold(0.0, f32::max);
+        let quietest = window_peaks.iter().cloned().fold(f32::MAX, f32::min);
+        assert!(quietest < loudest * 0.2, "{} to {}", quietest, loudest);
+    }
+
+    #[test]
+    fn test_lfo_vibrato_bends_pitch() {
+        let mut voice = Voice::new();
+        voice.set_lfo_to_pitch(12.0);
+        voice.note_on(57, 1.0, 1, &VelocityResponse::new());
+        // LFO held at its peak: a full octave up, 220 Hz becomes 440 Hz
+        let samples: Vec<f32> = (0..44100).map(|_| tick(&mut voice, 1.0)).collect();
+        let crossings = samples
+            .windows(2)
+            .filter(|w| (w[0] >= 0.0) != (w[1] >= 0.0))
+            .count();
+        assert!(crossings.abs_diff(880) <= 4, "{} zero crossings", crossings);
+    }
+
+    #[test]
+    fn test_lfo_tremolo_dips_level() {
+        let mut voice = Voice::new();
+        voice.set_oscillator_type(OscillatorType::Saw);
+        voice.set_lfo_to_amp(1.0);
+        voice.note_on(57, 1.0, 1, &VelocityResponse::new());
+        assert!((0..1000).all(|_| tick(&mut voice, -1.0) == 0.0));
+        assert!((0..1000).any(|_| tick(&mut voice, 1.0) != 0.0));
+    }
+
+    #[test]
+    fn test_lfo_modulates_pulse_width() {
+        let mean = |lfo: f32| {
+            let mut voice = Voice::new();
+            voice.set_oscillator_type(OscillatorType::Square);
+            voice.set_lfo_to_pulse_width(1.0);
+            voice.note_on(57, 1.0, 1, &VelocityResponse::new());
+            (0..4410).map(|_| tick(&mut voice, lfo)).sum::<f32>() / 4410.0
+        };
+        assert!(mean(1.0) > 0.3, "wide pulse should sit above zero: {}", mean(1.0));
+        assert!(mean(-1.0) < -0.3, "narrow pulse should sit below zero: {}", mean(-1.0));
+    }
+
+    #[test]
+    fn test_pulse_width_modulation_moves_from_set_width() {
+        let mean = |lfo: f32| {
+            let mut voice = Voice::new();
+            voice.set_oscillator_type(OscillatorType::Square);
+            voice.set_pulse_width(0.25);
+            voice.set_lfo_to_pulse_width(1.0);
+            voice.note_on(57, 1.0, 1, &VelocityResponse::new());
+            (0..4410).map(|_| tick(&mut voice, lfo)).sum::<f32>() / 4410.0
+        };
+        // 25% sits below zero; a full LFO swing up widens it to 70%
+        assert!(mean(0.0) < -0.3, "25% pulse: {}", mean(0.0));
+        assert!(mean(1.0) > 0.2, "70% pulse: {}", mean(1.0));
+    }
+
+    #[test]
+    fn test_key_sync_restarts_lfo_on_note_on() {
+        let mut voice = Voice::new();
+        voice.set_oscillator_type(OscillatorType::Saw);
+        voice.set_lfo_trigger(LfoTrigger::KeySync);
+        voice.set_lfo_shape(LfoShape::Saw);
+        voice.set_lfo_to_amp(1.0);
+        // A saw LFO starts at -1, which silences the voice at full depth
+        voice.note_on(57, 1.0, 1, &VelocityResponse::new());
+        assert_eq!(tick(&mut voice, 0.0), 0.0);
+        assert!((0..1000).any(|_| tick(&mut voice, 0.0) != 0.0));
+        voice.note_on(57, 1.0, 2, &VelocityResponse::new());
+        assert_eq!(tick(&mut voice, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_fm_index_follows_its_envelope() {
+        let fm_voice = || {
+            let mut voice = Voice::new();
+            voice.set_sample_rate(44100.0);
+            voice.set_oscillator_type(OscillatorType::Sine);
+            voice.set_fm_ratio(1.0);
+            voice.set_fm_index(5.0);
+            let env = voice.fm_envelope_mut();
+            env.set_attack(0.001);
+            env.set_decay(0.05);
+            env.set_sustain(0.0);
+            voice.note_on(57, 1.0, 1, &VelocityResponse::new());
+            voice
+        };
+        let mut plain = fm_voice();
+        let mut fm = fm_voice();
+        fm.set_fm(true);
+        // Bright while the envelope is up...
+        let early = (0..2000)
+            .map(|_| (tick(&mut fm, 0.0) - tick(&mut plain, 0.0)).abs())
+            .fold(0.0, f32::max);
+        assert!(early > 0.1, "early difference {}", early);
+        // ...then back to the plain carrier once it has decayed to zero
+        for _ in 0..20000 {
+            tick(&mut fm, 0.0);
+            tick(&mut plain, 0.0);
+        }
+        let late = (0..2000)
+            .map(|_| (tick(&mut fm, 0.0) - tick(&mut plain, 0.0)).abs())
+            .fold(0.0, f32::max);
+        assert!(late < 1e-4, "late difference {}", late);
+    }
+
+    #[test]
+    fn test_sub_oscillator_sounds_below_the_note() {
+        // Everything up to the amp envelope is linear, so the difference
+        // from a voice without the sub is the sub alone
+        let sub_crossings = |octave: SubOctave, note: u8| {
+            let voice = |level: f32| {
+                let mut voice = Voice::new();
+                voice.set_sample_rate(44100.0);
+                voice.set_sub_shape(SubShape::Sine);
+                voice.set_sub_octave(octave);
+                voice.set_sub_level(level);
+                voice.note_on(81, 1.0, 1, &VelocityResponse::new());
+                voice.change_note(note);
+                voice
+            };
+            let (mut with, mut without) = (voice(1.0), voice(0.0));
+            let sub: Vec<f32> = (0..44100)
+                .map(|_| tick(&mut with, 0.0) - tick(&mut without, 0.0))
+                .collect();
+            sub[1000..].windows(2).filter(|w| (w[0] < 0.0) != (w[1] < 0.0)).count() as f32
+        };
+        // 880 Hz note: 440 Hz sub, 880 crossings a second, less the first
+        // 1000 samples
+        let per_second = 44100.0 / 43100.0;
+        assert!((sub_crossings(SubOctave::OneDown, 81) * per_second - 880.0).abs() < 4.0);
+        assert!((sub_crossings(SubOctave::TwoDown, 81) * per_second - 440.0).abs() < 4.0);
+        // It follows the note as it moves (A4: 220 Hz sub)
+        assert!((sub_crossings(SubOctave::OneDown, 69) * per_second - 440.0).abs() < 4.0);
+    }
+
+    #[test]
+    fn test_noise_and_sub_go_through_the_amp_envelope() {
+        let mut voice = Voice::new();
+        voice.set_sample_rate(44100.0);
+        voice.set_release(0.01);
+        voice.set_sub_level(1.0);
+        voice.set_noise_level(1.0);
+        voice.set_noise_type(NoiseType::Pink);
+        voice.note_on(60, 1.0, 1, &VelocityResponse::new());
+        assert!(peak(&mut voice, 4410) > 0.5);
+        voice.note_off();
+        peak(&mut voice, 4410);
+        assert!(!voice.is_active());
+        assert_eq!(peak(&mut voice, 100), 0.0);
+    }
+
+    #[test]
+    fn test_noise_seed_sets_the_noise() {
+        let render = |seed: u32| {
+            let mut voice = Voice::new();
+            voice.set_sample_rate(44100.0);
+            voice.set_noise_level(1.0);
+            voice.seed_noise(seed);
+            voice.note_on(60, 1.0, 1, &VelocityResponse::new());
+            (0..512).map(|_| tick(&mut voice, 0.0)).collect::<Vec<_>>()
+        };
+        assert_eq!(render(1), render(1));
+        assert_ne!(render(1), render(2));
+    }
+
+    #[test]
+    fn test_plucked_string_engine_rings_and_dies_away() {
+        let mut voice = Voice::new();
+        voice.set_sample_rate(44100.0);
+        voice.set_engine(Engine::PluckedString);
+        voice.set_pluck_decay(0.2);
+        voice.envelope_mut().set_sustain(1.0);
+        voice.note_on(57, 1.0, 1, &VelocityResponse::new());
+        let start = peak(&mut voice, 4410);
+        assert!(start > 0.05, "{}", start);
+        for _ in 0..44100 {
+            tick(&mut voice, 0.0);
+        }
+        // Still held, but the string has stopped ringing
+        assert!(voice.is_active());
+        assert!(peak(&mut voice, 4410) < start * 1e-3);
+    }
+}
@@ -0,0 +1,627 @@
+use crate::params::WavetableBank;
+use core::f64::consts::PI;
+use std::sync::{Arc, OnceLock};
+
+/// Samples in one wavetable frame (one cycle), matching Serum-style tables.
+pub const WAVETABLE_FRAME_SIZE: usize = 2048;
+
+/// Most frames a table may hold.
+pub const MAX_WAVETABLE_FRAMES: usize = 256;
+
+/// Mipmap levels per frame. Level `n` keeps the lowest
+/// `WAVETABLE_FRAME_SIZE / 2 >> n` harmonics, down to a pure fundamental.
+const MIP_LEVELS: usize = 11;
+
+/// Smallest table stored for a mip level. Levels keep at least eight samples
+/// per cycle of their highest harmonic so linear interpolation stays clean.
+const MIN_LEVEL_SIZE: usize = 64;
+
+/// Why a wavetable couldn't be built.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WavetableError {
+    /// Not a RIFF WAVE file, or its chunks run past the end of the data.
+    Malformed,
+    /// A sample format other than 8, 16, 24 or 32-bit PCM or 32 or 64-bit
+    /// float.
+    UnsupportedFormat,
+    /// The sample count (given) is not a whole number of frames between one
+    /// and `MAX_WAVETABLE_FRAMES`.
+    BadLength(usize),
+    /// Mipmap data (its length given) that doesn't hold every level of the
+    /// stated number of frames.
+    BadMipmaps(usize),
+}
+
+impl core::fmt::Display for WavetableError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            WavetableError::Malformed => write!(f, "not a valid WAV file"),
+            WavetableError::UnsupportedFormat => write!(f, "unsupported WAV sample format"),
+            WavetableError::BadLength(len) => write!(
+                f,
+                "{} samples is not a whole number of {}-sample frames (at most {})",
+                len, WAVETABLE_FRAME_SIZE, MAX_WAVETABLE_FRAMES
+            ),
+            WavetableError::BadMipmaps(len) => {
+                write!(f, "{} samples is not a whole set of wavetable mipmaps", len)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WavetableError {}
+
+/// A set of single-cycle frames, each stored as a band-limited mipmap.
+///
+/// Every frame is resynthesised at `MIP_LEVELS` bandwidths, halving the
+/// harmonic count per level, so the oscillator can pick a level whose
+/// highest harmonic stays below Nyquist at the note being played. The DC
+/// component is dropped from every level.
+///
+/// Tables are built once, off the audio thread, and shared between
+/// oscillators behind an `Arc`; reading them never allocates.
+pub struct Wavetable {
+    frames: usize,
+    /// Per level, every frame's table back to back.
+    levels: [Vec<f32>; MIP_LEVELS],
+}
+
+impl Wavetable {
+    /// Build a table from raw samples, `WAVETABLE_FRAME_SIZE` per frame.
+    pub fn from_frames(samples: &[f32]) -> Result<Self, WavetableError> {
+        let len = samples.len();
+        let frames = len / WAVETABLE_FRAME_SIZE;
+        if frames * WAVETABLE_FRAME_SIZE != len || !(1..=MAX_WAVETABLE_FRAMES).contains(&frames) {
+            return Err(WavetableError::BadLength(len));
+        }
+
+        let mut levels: [Vec<f32>; MIP_LEVELS] =
+            core::array::from_fn(|level| Vec::with_capacity(level_size(level) * frames));
+        let mut re = vec![0.0; WAVETABLE_FRAME_SIZE];
+        let mut im = vec![0.0; WAVETABLE_FRAME_SIZE];
+        let mut level_re = vec![0.0; WAVETABLE_FRAME_SIZE];
+        let mut level_im = vec![0.0; WAVETABLE_FRAME_SIZE];
+        for frame in samples.chunks_exact(WAVETABLE_FRAME_SIZE) {
+            for (r, &s) in re.iter_mut().zip(frame) {
+                *r = s as f64;
+            }
+            im.fill(0.0);
+            fft(&mut re, &mut im);
+
+            for (level, table) in levels.iter_mut().enumerate() {
+                // Copy the kept harmonics into a smaller spectrum, mirrored
+                // so the inverse transform comes out real
+                let size = level_size(level);
+                let scale = size as f64 / WAVETABLE_FRAME_SIZE as f64;
+                let (lr, li) = (&mut level_re[..size], &mut level_im[..size]);
+                lr.fill(0.0);
+                li.fill(0.0);
+                for h in 1..=max_harmonic(level) {
+                    lr[h] = re[h] * scale;
+                    li[h] = im[h] * scale;
+                    lr[size - h] = re[h] * scale;
+                    li[size - h] = -im[h] * scale;
+                }
+                inverse_fft(lr, li);
+                table.extend(lr.iter().map(|&x| x as f32));
+            }
+        }
+        Ok(Self { frames, levels })
+    }
+
+    /// Build a table from the bytes of a WAV file holding one or more
+    /// `WAVETABLE_FRAME_SIZE`-sample cycles, as Serum and similar synths
+    /// export them. Only the first channel is used.
+    ///
+    /// Takes the file contents rather than a path so it works the same
+    /// natively and in the browser.
+    pub fn from_wav(bytes: &[u8]) -> Result<Self, WavetableError> {
+        Self::from_frames(&decode_wav(bytes)?)
+    }
+
+    /// Rebuild a table from the samples `mipmaps` returned, without
+    /// resynthesising it. Only copies, so it is cheap enough for a thread
+    /// that mustn't stall while the table built elsewhere is handed over.
+    pub fn from_mipmaps(frames: usize, data: &[f32]) -> Result<Self, WavetableError> {
+        let per_frame: usize = (0..MIP_LEVELS).map(level_size).sum();
+        if !(1..=MAX_WAVETABLE_FRAMES).contains(&frames) || data.len() != per_frame * frames {
+            return Err(WavetableError::BadMipmaps(data.len()));
+        }
+        let mut rest = data;
+        let levels = core::array::from_fn(|level| {
+            let (table, tail) = rest.split_at(level_size(level) * frames);
+            rest = tail;
+            table.to_vec()
+        });
+        Ok(Self { frames, levels })
+    }
+
+    /// Every mip level back to back, as `from_mipmaps` takes them.
+    pub fn mipmaps(&self) -> Vec<f32> {
+        self.levels.concat()
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames
+    }
+
+    /// Read the table at `phase` (a fraction of a cycle in `[0, 1)`) from mip
+    /// `level`, crossfading between the two frames either side of
+    /// `position` (0 = first frame, 1 = last).
+    pub fn sample(&self, level: usize, position: f32, phase: f32) -> f32 {
+        let index = position.clamp(0.0, 1.0) * (self.frames - 1) as f32;
+        let frame = (index as usize).min(self.frames - 1);
+        let frac = index - frame as f32;
+        let a = self.read(level, frame, phase);
+        if frac > 0.0 {
+            let b = self.read(level, frame + 1, phase);
+            a + (b - a) * frac
+        } else {
+            a
+        }
+    }
+
+    fn read(&self, level: usize, frame: usize, phase: f32) -> f32 {
+        let size = level_size(level);
+        let table = &self.levels[level][frame * size..(frame + 1) * size];
+        let x = phase * size as f32;
+        let i = (x as usize).min(size - 1);
+        let frac = x - i as f32;
+        let a = table[i];
+        let b = table[(i + 1) % size];
+        a + (b - a) * frac
+    }
+}
+
+/// The mip level to play at `phase_delta` cycles per sample: the fullest one
+/// whose highest harmonic stays below Nyquist.
+pub fn mip_level(phase_delta: f32) -> usize {
+    (0..MIP_LEVELS)
+        .find(|&level| max_harmonic(level) as f32 * phase_delta < 0.5)
+        .unwrap_or(MIP_LEVELS - 1)
+}
+
+/// One of the tables that ship with the synth, built on first use.
+///
+/// `WavetableBank::Custom` has no built-in table and gets `Basic`.
+pub fn builtin(bank: WavetableBank) -> Arc<Wavetable> {
+    static TABLES: OnceLock<[Arc<Wavetable>; 3]> = OnceLock::new();
+    let tables = TABLES.get_or_init(|| {
+        [basic_frames(), pulse_frames(), harmonic_frames()].map(|samples| {
+            Arc::new(Wavetable::from_frames(&samples).expect("built-in table is whole frames"))
+        })
+    });
+    let index = match bank {
+        WavetableBank::Basic | WavetableBank::Custom => 0,
+        WavetableBank::Pulse => 1,
+        WavetableBank::Harmonics => 2,
+    };
+    tables[index].clone()
+}
+
+fn level_size(level: usize) -> usize {
+    (8 * ((WAVETABLE_FRAME_SIZE / 2) >> level)).clamp(MIN_LEVEL_SIZE, WAVETABLE_FRAME_SIZE)
+}
+
+/// Highest harmonic kept at `level`; below the level's own Nyquist.
+fn max_harmonic(level: usize) -> usize {
+    ((WAVETABLE_FRAME_SIZE / 2) >> level).min(level_size(level) / 2 - 1)
+}
+
+// --- Built-in tables ---
+
+/// Phase of sample `i` within a frame, in `[0, 1)`.
+fn frame_phase(i: usize) -> f32 {
+    i as f32 / WAVETABLE_FRAME_SIZE as f32
+}
+
+fn make_frames(count: usize, shape: impl Fn(usize, f32) -> f32) -> Vec<f32> {
+    (0..count * WAVETABLE_FRAME_SIZE)
+        .map(|i| {
+            shape(
+                i / WAVETABLE_FRAME_SIZE,
+                frame_phase(i % WAVETABLE_FRAME_SIZE),
+            )
+        })
+        .collect()
+}
+
+/// Sine, triangle, saw and square, in that order.
+fn basic_frames() -> Vec<f32> {
+    make_frames(4, |frame, phase| match frame {
+        0 => (2.0 * core::f32::consts::PI * phase).sin(),
+        1 => 1.0 - (4.0 * ((phase + 0.25) % 1.0) - 2.0).abs(),
+        2 => 2.0 * phase - 1.0,
+        _ => {
+            if phase < 0.5 {
+                1.0
+            } else {
+                -1.0
+            }
+        }
+    })
+}
+
+/// Pulse narrowing from square to 5% over eight frames.
+fn pulse_frames() -> Vec<f32> {
+    make_frames(8, |frame, phase| {
+        let width = 0.5 - 0.45 * frame as f32 / 7.0;
+        if phase < width {
+            1.0
+        } else {
+            -1.0
+        }
+    })
+}
+
+/// Sixteen frames adding one saw harmonic at a time, each normalised to a
+/// peak of 1.
+fn harmonic_frames() -> Vec<f32> {
+    let mut samples = make_frames(16, |frame, phase| {
+        (1..=frame + 1)
+            .map(|h| (2.0 * core::f32::consts::PI * h as f32 * phase).sin() / h as f32)
+            .sum()
+    });
+    for frame in samples.chunks_exact_mut(WAVETABLE_FRAME_SIZE) {
+        let peak = frame.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        for s in frame.iter_mut() {
+            *s /= peak;
+        }
+    }
+    samples
+}
+
+// --- FFT ---
+
+/// In-place radix-2 FFT. Both slices must have the same power-of-two length.
+fn fft(re: &mut [f64], im: &mut [f64]) {
+    let n = re.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * PI / len as f64;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (w_im, w_re) = (angle * k as f64).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let t_re = re[b] * w_re - im[b] * w_im;
+                let t_im = re[b] * w_im + im[b] * w_re;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// Inverse of `fft`, including the `1 / n` scaling.
+fn inverse_fft(re: &mut [f64], im: &mut [f64]) {
+    for x in im.iter_mut() {
+        *x = -*x;
+    }
+    fft(re, im);
+    let scale = 1.0 / re.len() as f64;
+    for (r, i) in re.iter_mut().zip(im.iter_mut()) {
+        *r *= scale;
+        *i *= -scale;
+    }
+}
+
+// --- WAV decoding ---
+
+const FORMAT_PCM: u16 = 1;
+const FORMAT_FLOAT: u16 = 3;
+const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// Decode the first channel of a RIFF WAVE file to samples in `[-1, 1]`.
+fn decode_wav(bytes: &[u8]) -> Result<Vec<f32>, WavetableError> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(WavetableError::Malformed);
+    }
+
+    let mut format = None;
+    let mut data = None;
+    let mut pos = 12;
+    while pos + 8 <= bytes.len() {
+        let id = &bytes[pos..pos + 4];
+        let size = u32_at(bytes, pos + 4) as usize;
+        let start = pos + 8;
+        let end = start.checked_add(size).ok_or(WavetableError::Malformed)?;
+        if id == b"data" {
+            // Streaming writers may leave the size unset; take what's there
+            data = Some(&bytes[start..end.min(bytes.len())]);
+        } else if end > bytes.len() {
+            return Err(WavetableError::Malformed);
+        } else if id == b"fmt " {
+            format = Some(&bytes[start..end]);
+        }
+        // Chunks are padded to an even length
+        pos = end + (size & 1);
+    }
+    let (format, data) = match (format, data) {
+        (Some(format), Some(data)) if format.len() >= 16 => (format, data),
+        _ => return Err(WavetableError::Malformed),
+    };
+
+    let mut tag = u16_at(format, 0);
+    let channels = u16_at(format, 2) as usize;
+    let bits = u16_at(format, 14) as usize;
+    if tag == FORMAT_EXTENSIBLE {
+        // The real format is the start of the sub-format GUID
+        if format.len() < 26 {
+            return Err(WavetableError::Malformed);
+        }
+        tag = u16_at(format, 24);
+    }
+    let bytes_per_sample = bits / 8;
+    let decode: fn(&[u8]) -> f32 = match (tag, bits) {
+        (FORMAT_PCM, 8) => |b| (b[0] as f32 - 128.0) / 128.0,
+        (FORMAT_PCM, 16) => |b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0,
+        (FORMAT_PCM, 24) => |b| i32::from_le_bytes([0, b[0], b[1], b[2]]) as f32 / 2_147_483_648.0,
+        (FORMAT_PCM, 32) => {
+            |b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2_147_483_648.0
+        }
+        (FORMAT_FLOAT, 32) => |b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+        (FORMAT_FLOAT, 64) => {
+            |b| f64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]) as f32
+        }
+        _ => return Err(WavetableError::UnsupportedFormat),
+    };
+    if channels == 0 {
+        return Err(WavetableError::Malformed);
+    }
+    Ok(data
+        .chunks_exact(bytes_per_sample * channels)
+        .map(|block| decode(&block[..bytes_per_sample]))
+        .collect())
+}
+
+fn u16_at(bytes: &[u8], pos: usize) -> u16 {
+    u16::from_le_bytes([bytes[pos], bytes[pos + 1]])
+}
+
+fn u32_at(bytes: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes([bytes[pos], bytes[pos + 1], bytes[pos + 2], bytes[pos + 3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A minimal WAV file around `data`.
+    fn wav(tag: u16, channels: u16, bits: u16, data: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(4 + 24 + 8 + data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(b"WAVE");
+        bytes.extend_from_slice(b"fmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&tag.to_le_bytes());
+        bytes.extend_from_slice(&channels.to_le_bytes());
+        bytes.extend_from_slice(&44100u32.to_le_bytes());
+        let block = channels * bits / 8;
+        bytes.extend_from_slice(&(44100 * block as u32).to_le_bytes());
+        bytes.extend_from_slice(&block.to_le_bytes());
+        bytes.extend_from_slice(&bits.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    fn sine_frame() -> Vec<f32> {
+        (0..WAVETABLE_FRAME_SIZE)
+            .map(|i| (2.0 * core::f32::consts::PI * frame_phase(i)).sin())
+            .collect()
+    }
+
+    /// Magnitude of harmonic `h` in one cycle of `table`.
+    fn harmonic(table: &[f32], h: usize) -> f32 {
+        let n = table.len() as f32;
+        let (mut re, mut im) = (0.0, 0.0);
+        for (i, &s) in table.iter().enumerate() {
+            let angle = 2.0 * core::f32::consts::PI * h as f32 * i as f32 / n;
+            re += s * angle.cos();
+            im -= s * angle.sin();
+        }
+        2.0 * (re * re + im * im).sqrt() / n
+    }
+
+    #[test]
+    fn test_fft_round_trip() {
+        let original: Vec<f64> = (0..64).map(|i| ((i * 7) % 13) as f64 - 6.0).collect();
+        let mut re = original.clone();
+        let mut im = vec![0.0; 64];
+        fft(&mut re, &mut im);
+        inverse_fft(&mut re, &mut im);
+        for (a, b) in re.iter().zip(&original) {
+            assert!((a - b).abs() < 1e-9);
+        }
+        assert!(im.iter().all(|x| x.abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_full_level_reproduces_frame() {
+        let frame = sine_frame();
+        let table = Wavetable::from_frames(&frame).unwrap();
+        for (i, &expected) in frame.iter().enumerate().step_by(37) {
+            let s = table.sample(0, 0.0, frame_phase(i));
+            assert!((s - expected).abs() < 1e-5, "{} vs {}", s, expected);
+        }
+    }
+
+    #[test]
+    fn test_levels_drop_harmonics_above_their_limit() {
+        let table = builtin(WavetableBank::Basic);
+        // The saw frame has every harmonic; each level keeps only its share
+        for level in 0..MIP_LEVELS {
+            let size = level_size(level);
+            let saw = &table.levels[level][2 * size..3 * size];
+            let limit = max_harmonic(level);
+            let fundamental = harmonic(saw, 1);
+            assert!((fundamental - 2.0 / core::f32::consts::PI).abs() < 0.01);
+            if limit + 1 < size / 2 {
+                assert!(harmonic(saw, limit + 1) < 1e-4, "level {}", level);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mip_level_stays_below_nyquist() {
+        for i in 1..1000 {
+            let dt = i as f32 * 0.0005;
+            let level = mip_level(dt);
+            if level < MIP_LEVELS - 1 {
+                assert!(max_harmonic(level) as f32 * dt < 0.5, "dt {}", dt);
+            }
+            if level > 0 {
+                // ...while keeping as many harmonics as it can
+                assert!(max_harmonic(level - 1) as f32 * dt >= 0.5, "dt {}", dt);
+            }
+        }
+        assert_eq!(mip_level(0.0), 0);
+    }
+
+    #[test]
+    fn test_position_crossfades_frames() {
+        let mut samples = vec![0.25; WAVETABLE_FRAME_SIZE];
+        samples.extend(sine_frame());
+        let table = Wavetable::from_frames(&samples).unwrap();
+        // The constant first frame is all DC, which is removed
+        assert!(table.sample(0, 0.0, 0.25).abs() < 1e-5);
+        assert!((table.sample(0, 1.0, 0.25) - 1.0).abs() < 1e-5);
+        assert!((table.sample(0, 0.5, 0.25) - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_mipmaps_round_trip() {
+        let mut samples = sine_frame();
+        samples.extend(vec![0.25; WAVETABLE_FRAME_SIZE]);
+        let table = Wavetable::from_frames(&samples).unwrap();
+        let data = table.mipmaps();
+        let copy = Wavetable::from_mipmaps(2, &data).unwrap();
+        assert_eq!(copy.frame_count(), 2);
+        for level in 0..MIP_LEVELS {
+            for position in [0.0, 0.3, 1.0] {
+                assert_eq!(
+                    copy.sample(level, position, 0.1),
+                    table.sample(level, position, 0.1)
+                );
+            }
+        }
+
+        assert_eq!(
+            Wavetable::from_mipmaps(1, &data).err(),
+            Some(WavetableError::BadMipmaps(data.len()))
+        );
+        assert!(Wavetable::from_mipmaps(0, &[]).is_err());
+        assert!(Wavetable::from_mipmaps(2, &data[1..]).is_err());
+    }
+
+    #[test]
+    fn test_builtin_frame_counts() {
+        assert_eq!(builtin(WavetableBank::Basic).frame_count(), 4);
+        assert_eq!(builtin(WavetableBank::Pulse).frame_count(), 8);
+        assert_eq!(builtin(WavetableBank::Harmonics).frame_count(), 16);
+        assert!(Arc::ptr_eq(
+            &builtin(WavetableBank::Custom),
+            &builtin(WavetableBank::Basic)
+        ));
+    }
+
+    #[test]
+    fn test_rejects_partial_frames() {
+        assert_eq!(
+            Wavetable::from_frames(&[0.0; 100]).err(),
+            Some(WavetableError::BadLength(100))
+        );
+        assert!(Wavetable::from_frames(&[]).is_err());
+        let too_many = vec![0.0; (MAX_WAVETABLE_FRAMES + 1) * WAVETABLE_FRAME_SIZE];
+        assert!(Wavetable::from_frames(&too_many).is_err());
+    }
+
+    #[test]
+    fn test_wav_pcm16() {
+        let data: Vec<u8> = sine_frame()
+            .iter()
+            .flat_map(|&s| ((s * 32767.0) as i16).to_le_bytes())
+            .collect();
+        let table = Wavetable::from_wav(&wav(FORMAT_PCM, 1, 16, &data)).unwrap();
+        assert_eq!(table.frame_count(), 1);
+        assert!((table.sample(0, 0.0, 0.25) - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_wav_float_stereo_uses_first_channel() {
+        let mut data = Vec::new();
+        for frame in 0..2 {
+            for &s in sine_frame().iter() {
+                let left = if frame == 0 { s } else { -s };
+                data.extend_from_slice(&left.to_le_bytes());
+                data.extend_from_slice(&0.0f32.to_le_bytes());
+            }
+        }
+        let table = Wavetable::from_wav(&wav(FORMAT_FLOAT, 2, 32, &data)).unwrap();
+        assert_eq!(table.frame_count(), 2);
+        assert!((table.sample(0, 0.0, 0.25) - 1.0).abs() < 1e-5);
+        assert!((table.sample(0, 1.0, 0.25) + 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_wav_24_bit_and_extensible() {
+        let data: Vec<u8> = sine_frame()
+            .iter()
+            .flat_map(|&s| {
+                let b = ((s * 8_388_607.0) as i32).to_le_bytes();
+                [b[0], b[1], b[2]]
+            })
+            .collect();
+        let mut bytes = wav(FORMAT_EXTENSIBLE, 1, 24, &data);
+        // Grow the format chunk to the extensible layout with a PCM sub-format
+        let mut extension = vec![0u8; 24];
+        extension[0] = 22;
+        extension[8..10].copy_from_slice(&FORMAT_PCM.to_le_bytes());
+        bytes.splice(36..36, extension);
+        bytes[16..20].copy_from_slice(&40u32.to_le_bytes());
+        let table = Wavetable::from_wav(&bytes).unwrap();
+        assert!((table.sample(0, 0.0, 0.25) - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_wav_errors() {
+        assert_eq!(
+            Wavetable::from_wav(b"not a wav file").err(),
+            Some(WavetableError::Malformed)
+        );
+        let adpcm = wav(2, 1, 4, &[0; 1024]);
+        assert_eq!(
+            Wavetable::from_wav(&adpcm).err(),
+            Some(WavetableError::UnsupportedFormat)
+        );
+        let short = wav(FORMAT_PCM, 1, 16, &[0; 1000]);
+        assert_eq!(
+            Wavetable::from_wav(&short).err(),
+            Some(WavetableError::BadLength(500))
+        );
+        let mut truncated = wav(FORMAT_PCM, 1, 16, &[0; 4096]);
+        truncated.truncate(30);
+        assert_eq!(
+            Wavetable::from_wav(&truncated).err(),
+            Some(WavetableError::Malformed)
+        );
+    }
+}
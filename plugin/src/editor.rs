@@ -1,4 +1,6 @@
 use crate::{NoteQueue, SimpleSynthParams, VisBuffer};
+use dsp_core::additive;
+use dsp_core::params::AdditivePreset;
 use nih_plug::prelude::*;
 use nih_plug_egui::egui;
 use nih_plug_egui::{create_egui_editor, widgets};
@@ -18,7 +20,7 @@ pub fn create(
         },
         move |egui_ctx, setter, state| {
             egui::CentralPanel::default().show(egui_ctx, |ui| {
-                let samples = vis_buffer.read_front();
+                let (left, right) = vis_buffer.read_front();
 
                 let mut controls = NihPlugControls {
                     params: &params,
@@ -28,17 +30,18 @@ pub fn create(
                     ui,
                     state,
                     &mut controls,
-                    samples.as_slice(),
+                    left.as_slice(),
+                    right.as_slice(),
                     cfg!(feature = "octave-shift"),
                 );
 
                 // Process keyboard events — update UI state AND send to audio thread
                 for event in events {
                     match event {
-                        KeyboardEvent::NoteOn(note) => {
+                        KeyboardEvent::NoteOn(note, velocity) => {
                             if !state.held_notes.contains(&note) {
                                 state.held_notes.push(note);
-                                note_queue.push_note_on(note);
+                                note_queue.push_note_on(note, velocity);
                             }
                         }
                         KeyboardEvent::NoteOff(note) => {
@@ -66,10 +69,406 @@ impl<'a> ControlRenderer for NihPlugControls<'a> {
         ui.add(widgets::ParamSlider::for_param(&self.params.osc_type, self.setter));
     }
 
+    fn render_engine(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.engine, self.setter));
+    }
+
+    fn render_pulse_width(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.pulse_width, self.setter));
+    }
+
+    fn render_wavetable(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.wavetable, self.setter));
+    }
+
+    fn render_wavetable_position(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.wavetable_position, self.setter));
+    }
+
+    fn partial_count(&self) -> usize {
+        self.params.partials.len()
+    }
+
+    fn partial_amplitude(&self, partial: usize) -> f32 {
+        self.params.partials[partial].amplitude.value()
+    }
+
+    fn set_partial_amplitude(&mut self, partial: usize, amplitude: f32) {
+        let param = &self.params.partials[partial].amplitude;
+        if param.value() != amplitude {
+            self.setter.begin_set_parameter(param);
+            self.setter.set_parameter(param, amplitude);
+            self.setter.end_set_parameter(param);
+        }
+    }
+
+    fn render_additive_presets(&mut self, ui: &mut egui::Ui) {
+        for &preset in AdditivePreset::VARIANTS {
+            if ui.button(preset.name()).clicked() {
+                for (partial, &amplitude) in
+                    additive::preset_spectrum(preset).iter().enumerate()
+                {
+                    self.set_partial_amplitude(partial, amplitude);
+                }
+            }
+        }
+    }
+
+    fn render_osc2_type(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.osc2_type, self.setter));
+    }
+
+    fn render_osc2_coarse(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.osc2_coarse, self.setter));
+    }
+
+    fn render_osc2_fine(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.osc2_fine, self.setter));
+    }
+
+    fn render_osc2_level(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.osc2_level, self.setter));
+    }
+
+    fn render_osc2_sync(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.osc2_sync, self.setter));
+    }
+
+    fn render_ring_mix(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.ring_mix, self.setter));
+    }
+
+    fn render_linear_fm(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.linear_fm, self.setter));
+    }
+
+    fn render_sub_level(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.sub_level, self.setter));
+    }
+
+    fn render_sub_octave(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.sub_octave, self.setter));
+    }
+
+    fn render_sub_shape(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.sub_shape, self.setter));
+    }
+
+    fn render_noise_level(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.noise_level, self.setter));
+    }
+
+    fn render_noise_type(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.noise_type, self.setter));
+    }
+
+    fn render_fm_enabled(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.fm_enabled, self.setter));
+    }
+
+    fn render_fm_ratio(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.fm_ratio, self.setter));
+    }
+
+    fn render_fm_fixed(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.fm_fixed, self.setter));
+    }
+
+    fn render_fm_fixed_frequency(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.fm_fixed_frequency, self.setter));
+    }
+
+    fn render_fm_index(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.fm_index, self.setter));
+    }
+
+    fn render_fm_feedback(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.fm_feedback, self.setter));
+    }
+
+    fn render_fm_delay(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.fm_delay, self.setter));
+    }
+
+    fn render_fm_hold(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.fm_hold, self.setter));
+    }
+
+    fn render_fm_envelope_mode(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.fm_envelope_mode, self.setter));
+    }
+
+    fn render_fm_retrigger_mode(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.fm_retrigger_mode, self.setter));
+    }
+
+    fn render_fm_attack_curve(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.fm_attack_curve, self.setter));
+    }
+
+    fn render_fm_decay_curve(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.fm_decay_curve, self.setter));
+    }
+
+    fn render_fm_release_curve(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.fm_release_curve, self.setter));
+    }
+
+    fn render_fm_attack(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.fm_attack, self.setter));
+    }
+
+    fn render_fm_decay(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.fm_decay, self.setter));
+    }
+
+    fn render_fm_sustain(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.fm_sustain, self.setter));
+    }
+
+    fn render_fm_release(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.fm_release, self.setter));
+    }
+
+    fn render_pluck_excitation(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.pluck_excitation, self.setter));
+    }
+
+    fn render_pluck_damping(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.pluck_damping, self.setter));
+    }
+
+    fn render_pluck_brightness(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.pluck_brightness, self.setter));
+    }
+
+    fn render_pluck_decay(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.pluck_decay, self.setter));
+    }
+
+    fn render_unison_voices(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.unison_voices, self.setter));
+    }
+
+    fn render_unison_detune(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.unison_detune, self.setter));
+    }
+
+    fn render_unison_spread(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.unison_spread, self.setter));
+    }
+
+    fn render_unison_random_phase(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.unison_random_phase, self.setter));
+    }
+
+    fn render_voices(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.voices, self.setter));
+    }
+
+    fn render_steal_policy(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.steal_policy, self.setter));
+    }
+
+    fn render_voice_mode(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.voice_mode, self.setter));
+    }
+
+    fn render_note_priority(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.note_priority, self.setter));
+    }
+
+    fn render_legato(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.legato, self.setter));
+    }
+
+    fn render_glide_time(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.glide_time, self.setter));
+    }
+
+    fn render_glide_mode(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.glide_mode, self.setter));
+    }
+
+    fn render_glide_timing(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.glide_timing, self.setter));
+    }
+
+    fn render_velocity_curve(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.velocity_curve, self.setter));
+    }
+
+    fn render_velocity_amount(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.velocity_amount, self.setter));
+    }
+
+    fn render_velocity_to_attack(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.velocity_to_attack, self.setter));
+    }
+
+    fn render_velocity_to_brightness(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.velocity_to_brightness, self.setter));
+    }
+
+    fn render_filter_type(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.filter_type, self.setter));
+    }
+
+    fn render_filter_mode(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.filter_mode, self.setter));
+    }
+
+    fn render_filter_cutoff(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.filter_cutoff, self.setter));
+    }
+
+    fn render_filter_resonance(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.filter_resonance, self.setter));
+    }
+
+    fn render_filter_drive(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.filter_drive, self.setter));
+    }
+
+    fn render_filter_keytrack(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.filter_keytrack, self.setter));
+    }
+
+    fn render_filter_env_amount(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.filter_env_amount, self.setter));
+    }
+
+    fn render_filter_delay(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.filter_delay, self.setter));
+    }
+
+    fn render_filter_hold(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.filter_hold, self.setter));
+    }
+
+    fn render_filter_envelope_mode(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.filter_envelope_mode, self.setter));
+    }
+
+    fn render_filter_retrigger_mode(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.filter_retrigger_mode, self.setter));
+    }
+
+    fn render_filter_attack_curve(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.filter_attack_curve, self.setter));
+    }
+
+    fn render_filter_decay_curve(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.filter_decay_curve, self.setter));
+    }
+
+    fn render_filter_release_curve(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.filter_release_curve, self.setter));
+    }
+
+    fn render_filter_attack(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.filter_attack, self.setter));
+    }
+
+    fn render_filter_decay(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.filter_decay, self.setter));
+    }
+
+    fn render_filter_sustain(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.filter_sustain, self.setter));
+    }
+
+    fn render_filter_release(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.filter_release, self.setter));
+    }
+
+    fn render_lfo_shape(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.lfo_shape, self.setter));
+    }
+
+    fn render_lfo_trigger(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.lfo_trigger, self.setter));
+    }
+
+    fn render_lfo_timing(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.lfo_timing, self.setter));
+    }
+
+    fn render_lfo_rate(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.lfo_rate, self.setter));
+    }
+
+    fn render_lfo_division(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.lfo_division, self.setter));
+    }
+
+    fn render_lfo_to_pitch(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.lfo_to_pitch, self.setter));
+    }
+
+    fn render_lfo_to_amp(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.lfo_to_amp, self.setter));
+    }
+
+    fn render_lfo_to_pulse_width(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.lfo_to_pulse_width, self.setter));
+    }
+
+    fn mod_slot_count(&self) -> usize {
+        self.params.mod_slots.len()
+    }
+
+    fn render_mod_source(&mut self, ui: &mut egui::Ui, slot: usize) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.mod_slots[slot].source, self.setter));
+    }
+
+    fn render_mod_destination(&mut self, ui: &mut egui::Ui, slot: usize) {
+        ui.add(widgets::ParamSlider::for_param(
+            &self.params.mod_slots[slot].destination,
+            self.setter,
+        ));
+    }
+
+    fn render_mod_depth(&mut self, ui: &mut egui::Ui, slot: usize) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.mod_slots[slot].depth, self.setter));
+    }
+
     fn render_gain(&mut self, ui: &mut egui::Ui) {
         ui.add(widgets::ParamSlider::for_param(&self.params.gain, self.setter));
     }
 
+    fn render_pan(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.pan, self.setter));
+    }
+
+    fn render_delay(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.delay, self.setter));
+    }
+
+    fn render_hold(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.hold, self.setter));
+    }
+
+    fn render_envelope_mode(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.envelope_mode, self.setter));
+    }
+
+    fn render_retrigger_mode(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.retrigger_mode, self.setter));
+    }
+
+    fn render_attack_curve(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.attack_curve, self.setter));
+    }
+
+    fn render_decay_curve(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.decay_curve, self.setter));
+    }
+
+    fn render_release_curve(&mut self, ui: &mut egui::Ui) {
+        ui.add(widgets::ParamSlider::for_param(&self.params.release_curve, self.setter));
+    }
+
     fn render_attack(&mut self, ui: &mut egui::Ui) {
         ui.add(widgets::ParamSlider::for_param(&self.params.attack, self.setter));
     }
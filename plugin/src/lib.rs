@@ -1,10 +1,27 @@
 mod editor;
 
-use dsp_core::params::OscillatorType;
-use dsp_core::Synth;
+use dsp_core::additive::{self, MAX_PARTIALS};
+use dsp_core::envelope::MAX_ENVELOPE_CURVE;
+use dsp_core::fm::{MAX_FM_FIXED_HZ, MAX_FM_INDEX, MAX_FM_RATIO, MIN_FM_FIXED_HZ, MIN_FM_RATIO};
+use dsp_core::mod_matrix::MOD_SLOTS;
+use dsp_core::params::{
+    AdditivePreset, Engine, EnvelopeMode, FilterMode, FilterType, GlideMode, GlideTiming, LfoShape,
+    LfoTiming, LfoTrigger, ModDestination, ModSource, NoiseType, NoteDivision, NotePriority,
+    OscillatorType, PluckExcitation, RetriggerMode, StealPolicy, SubOctave, SubShape, VelocityCurve,
+    VoiceMode, WavetableBank,
+};
+use dsp_core::oscillator::{MAX_PULSE_WIDTH, MIN_PULSE_WIDTH};
+use dsp_core::pluck::{MAX_PLUCK_DECAY, MIN_PLUCK_DECAY};
+use dsp_core::unison::{MAX_LINEAR_FM, MAX_UNISON, MAX_UNISON_DETUNE_CENTS};
+use dsp_core::voice::MAX_VOICES;
+use dsp_core::{
+    Synth, MAX_FILTER_ENV_OCTAVES, MAX_LFO_PITCH_SEMITONES, MAX_OSC2_FINE_CENTS,
+    MAX_OSC2_SEMITONES,
+};
+use nih_plug::midi::control_change::MODULATION_MSB;
 use nih_plug::prelude::*;
 use std::num::NonZeroU32;
-use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicU16, AtomicUsize, Ordering};
 use std::sync::Arc;
 
 /// Size of the visualization buffer (power of 2 for efficient wrapping).
@@ -13,17 +30,21 @@ pub const VIS_BUFFER_SIZE: usize = 2048;
 /// Maximum number of UI note events that can be queued per process cycle.
 const NOTE_QUEUE_SIZE: usize = 64;
 
-/// Lock-free double buffer for passing audio data from the audio thread to the UI.
+/// Lock-free double buffer for passing stereo audio data from the audio thread
+/// to the UI.
 ///
 /// Design: two buffers, an atomic index indicating which one is the "front"
 /// (readable by UI). The audio thread always writes to the back buffer.
-/// When the back buffer is full, it atomically swaps front/back.
+/// When the back buffer is full, it atomically swaps front/back. Each buffer
+/// holds both channels, so the UI always sees left and right from the same
+/// stretch of audio.
 ///
 /// Safety: the audio thread is the only writer. It accesses the back buffer
 /// through `push()` via `&self` using `UnsafeCell`. This is sound because
 /// only one thread ever calls `push()`.
 pub struct VisBuffer {
-    buffers: [std::cell::UnsafeCell<[f32; VIS_BUFFER_SIZE]>; 2],
+    /// Two buffers, each a left and a right channel.
+    buffers: [std::cell::UnsafeCell<[[f32; VIS_BUFFER_SIZE]; 2]>; 2],
     write_pos: AtomicUsize,
     /// Which buffer index (0 or 1) the UI should read.
     front: AtomicUsize,
@@ -39,21 +60,21 @@ impl VisBuffer {
     pub fn new() -> Self {
         Self {
             buffers: [
-                std::cell::UnsafeCell::new([0.0; VIS_BUFFER_SIZE]),
-                std::cell::UnsafeCell::new([0.0; VIS_BUFFER_SIZE]),
+                std::cell::UnsafeCell::new([[0.0; VIS_BUFFER_SIZE]; 2]),
+                std::cell::UnsafeCell::new([[0.0; VIS_BUFFER_SIZE]; 2]),
             ],
             write_pos: AtomicUsize::new(0),
             front: AtomicUsize::new(0),
         }
     }
 
-    /// Called from the audio thread only. Writes a sample to the back buffer.
-    /// When the buffer wraps, atomically swaps front/back so the UI sees
-    /// the completed buffer.
+    /// Called from the audio thread only. Writes a stereo frame to the back
+    /// buffer. When the buffer wraps, atomically swaps front/back so the UI
+    /// sees the completed buffer.
     ///
     /// # Safety
     /// Must only be called from one thread (the audio thread).
-    pub fn push(&self, sample: f32) {
+    pub fn push(&self, left: f32, right: f32) {
         let front = self.front.load(Ordering::Relaxed);
         let back = 1 - front;
         let pos = self.write_pos.load(Ordering::Relaxed);
@@ -61,7 +82,9 @@ impl VisBuffer {
         // Safety: only the audio thread writes to the back buffer,
         // and the UI thread only reads the front buffer.
         unsafe {
-            (*self.buffers[back].get())[pos] = sample;
+            let buffer = &mut *self.buffers[back].get();
+            buffer[0][pos] = left;
+            buffer[1][pos] = right;
         }
 
         let next_pos = (pos + 1) % VIS_BUFFER_SIZE;
@@ -73,13 +96,14 @@ impl VisBuffer {
         }
     }
 
-    /// Called from the UI thread. Returns a reference to the most recently
-    /// completed buffer. No allocation, no copy, no lock.
-    pub fn read_front(&self) -> &[f32; VIS_BUFFER_SIZE] {
+    /// Called from the UI thread. Returns the left and right channels of the
+    /// most recently completed buffer. No allocation, no copy, no lock.
+    pub fn read_front(&self) -> (&[f32; VIS_BUFFER_SIZE], &[f32; VIS_BUFFER_SIZE]) {
         let idx = self.front.load(Ordering::Acquire);
         // Safety: the UI only reads the front buffer, the audio thread
         // only writes to the back buffer.
-        unsafe { &*self.buffers[idx].get() }
+        let buffer = unsafe { &*self.buffers[idx].get() };
+        (&buffer[0], &buffer[1])
     }
 }
 
@@ -88,9 +112,10 @@ impl VisBuffer {
 /// The UI thread pushes note on/off events; the audio thread drains them
 /// at the start of each process() call.
 pub struct NoteQueue {
-    /// Each entry: high bit = on/off (0x80 = on), low 7 bits = MIDI note.
-    /// 0xFF = empty slot.
-    slots: [AtomicU8; NOTE_QUEUE_SIZE],
+    /// Each entry: high byte = on/off + velocity (0x80 | 7-bit velocity for
+    /// note-on, 0x00 for note-off), low byte = 7-bit MIDI note.
+    /// 0xFFFF = empty slot.
+    slots: [AtomicU16; NOTE_QUEUE_SIZE],
     /// Next slot the UI thread will write to.
     write_head: AtomicUsize,
     /// Next slot the audio thread will read from.
@@ -100,23 +125,25 @@ pub struct NoteQueue {
 impl NoteQueue {
     pub fn new() -> Self {
         Self {
-            slots: std::array::from_fn(|_| AtomicU8::new(0xFF)),
+            slots: std::array::from_fn(|_| AtomicU16::new(0xFFFF)),
             write_head: AtomicUsize::new(0),
             read_head: AtomicUsize::new(0),
         }
     }
 
-    /// Push a note-on event from the UI thread.
-    pub fn push_note_on(&self, note: u8) -> bool {
-        self.push_raw(0x80 | (note & 0x7F))
+    /// Push a note-on event from the UI thread. `velocity` is in `[0, 1]` and
+    /// is carried with 7-bit (MIDI) resolution.
+    pub fn push_note_on(&self, note: u8, velocity: f32) -> bool {
+        let velocity = (velocity.clamp(0.0, 1.0) * 127.0).round() as u16;
+        self.push_raw(((0x80 | velocity) << 8) | (note & 0x7F) as u16)
     }
 
     /// Push a note-off event from the UI thread.
     pub fn push_note_off(&self, note: u8) -> bool {
-        self.push_raw(note & 0x7F)
+        self.push_raw((note & 0x7F) as u16)
     }
 
-    fn push_raw(&self, value: u8) -> bool {
+    fn push_raw(&self, value: u16) -> bool {
         let head = self.write_head.load(Ordering::Relaxed);
         let next = (head + 1) % NOTE_QUEUE_SIZE;
         if next == self.read_head.load(Ordering::Acquire) {
@@ -128,16 +155,20 @@ impl NoteQueue {
     }
 
     /// Drain all pending events from the audio thread.
-    pub fn drain(&self, mut callback: impl FnMut(bool, u8)) {
+    ///
+    /// The callback receives `(is_on, note, velocity)`; velocity is 0.0 for
+    /// note-off events.
+    pub fn drain(&self, mut callback: impl FnMut(bool, u8, f32)) {
         loop {
             let tail = self.read_head.load(Ordering::Relaxed);
             if tail == self.write_head.load(Ordering::Acquire) {
                 break;
             }
             let raw = self.slots[tail].load(Ordering::Acquire);
-            let is_on = raw & 0x80 != 0;
-            let note = raw & 0x7F;
-            callback(is_on, note);
+            let is_on = raw & 0x8000 != 0;
+            let velocity = ((raw >> 8) & 0x7F) as f32 / 127.0;
+            let note = (raw & 0x7F) as u8;
+            callback(is_on, note, velocity);
             self.read_head
                 .store((tail + 1) % NOTE_QUEUE_SIZE, Ordering::Release);
         }
@@ -159,31 +190,1184 @@ pub struct SimpleSynthParams {
     #[id = "osc-type"]
     pub osc_type: IntParam,
 
-    #[id = "gain"]
-    pub gain: FloatParam,
+    #[id = "engine"]
+    pub engine: IntParam,
+
+    #[id = "pulse-width"]
+    pub pulse_width: FloatParam,
+
+    #[id = "wavetable"]
+    pub wavetable: IntParam,
+
+    #[id = "wt-position"]
+    pub wavetable_position: FloatParam,
+
+    #[id = "osc2-type"]
+    pub osc2_type: IntParam,
+
+    #[id = "osc2-coarse"]
+    pub osc2_coarse: IntParam,
+
+    #[id = "osc2-fine"]
+    pub osc2_fine: FloatParam,
+
+    #[id = "osc2-level"]
+    pub osc2_level: FloatParam,
+
+    #[id = "osc2-sync"]
+    pub osc2_sync: BoolParam,
+
+    #[id = "ring-mix"]
+    pub ring_mix: FloatParam,
+
+    #[id = "linear-fm"]
+    pub linear_fm: FloatParam,
+
+    #[id = "sub-level"]
+    pub sub_level: FloatParam,
+
+    #[id = "sub-octave"]
+    pub sub_octave: IntParam,
+
+    #[id = "sub-shape"]
+    pub sub_shape: IntParam,
+
+    #[id = "noise-level"]
+    pub noise_level: FloatParam,
+
+    #[id = "noise-type"]
+    pub noise_type: IntParam,
+
+    #[id = "fm"]
+    pub fm_enabled: BoolParam,
+
+    #[id = "fm-ratio"]
+    pub fm_ratio: FloatParam,
+
+    #[id = "fm-fixed"]
+    pub fm_fixed: BoolParam,
+
+    #[id = "fm-fixed-freq"]
+    pub fm_fixed_frequency: FloatParam,
+
+    #[id = "fm-index"]
+    pub fm_index: FloatParam,
+
+    #[id = "fm-feedback"]
+    pub fm_feedback: FloatParam,
+
+    #[id = "fm-delay"]
+    pub fm_delay: FloatParam,
+
+    #[id = "fm-hold"]
+    pub fm_hold: FloatParam,
+
+    #[id = "fm-env-mode"]
+    pub fm_envelope_mode: IntParam,
+
+    #[id = "fm-retrigger"]
+    pub fm_retrigger_mode: IntParam,
+
+    #[id = "fm-attack-curve"]
+    pub fm_attack_curve: FloatParam,
+
+    #[id = "fm-decay-curve"]
+    pub fm_decay_curve: FloatParam,
+
+    #[id = "fm-release-curve"]
+    pub fm_release_curve: FloatParam,
+
+    #[id = "fm-attack"]
+    pub fm_attack: FloatParam,
+
+    #[id = "fm-decay"]
+    pub fm_decay: FloatParam,
+
+    #[id = "fm-sustain"]
+    pub fm_sustain: FloatParam,
+
+    #[id = "fm-release"]
+    pub fm_release: FloatParam,
+
+    #[id = "pluck-excitation"]
+    pub pluck_excitation: IntParam,
+
+    #[id = "pluck-damping"]
+    pub pluck_damping: FloatParam,
+
+    #[id = "pluck-brightness"]
+    pub pluck_brightness: FloatParam,
+
+    #[id = "pluck-decay"]
+    pub pluck_decay: FloatParam,
+
+    #[id = "unison"]
+    pub unison_voices: IntParam,
+
+    #[id = "unison-detune"]
+    pub unison_detune: FloatParam,
+
+    #[id = "unison-spread"]
+    pub unison_spread: FloatParam,
+
+    #[id = "unison-phase"]
+    pub unison_random_phase: BoolParam,
+
+    #[id = "voice-mode"]
+    pub voice_mode: IntParam,
+
+    #[id = "voices"]
+    pub voices: IntParam,
+
+    #[id = "steal"]
+    pub steal_policy: IntParam,
+
+    #[id = "priority"]
+    pub note_priority: IntParam,
+
+    #[id = "legato"]
+    pub legato: BoolParam,
+
+    #[id = "glide"]
+    pub glide_time: FloatParam,
+
+    #[id = "glide-mode"]
+    pub glide_mode: IntParam,
+
+    #[id = "glide-timing"]
+    pub glide_timing: IntParam,
+
+    #[id = "vel-curve"]
+    pub velocity_curve: IntParam,
+
+    #[id = "vel-amount"]
+    pub velocity_amount: FloatParam,
+
+    #[id = "vel-attack"]
+    pub velocity_to_attack: FloatParam,
+
+    #[id = "vel-bright"]
+    pub velocity_to_brightness: FloatParam,
+
+    #[id = "filter-type"]
+    pub filter_type: IntParam,
+
+    #[id = "filter-mode"]
+    pub filter_mode: IntParam,
+
+    #[id = "cutoff"]
+    pub filter_cutoff: FloatParam,
+
+    #[id = "resonance"]
+    pub filter_resonance: FloatParam,
+
+    #[id = "drive"]
+    pub filter_drive: FloatParam,
+
+    #[id = "keytrack"]
+    pub filter_keytrack: FloatParam,
+
+    #[id = "fenv-amount"]
+    pub filter_env_amount: FloatParam,
+
+    #[id = "fenv-delay"]
+    pub filter_delay: FloatParam,
+
+    #[id = "fenv-hold"]
+    pub filter_hold: FloatParam,
+
+    #[id = "fenv-mode"]
+    pub filter_envelope_mode: IntParam,
+
+    #[id = "fenv-retrigger"]
+    pub filter_retrigger_mode: IntParam,
+
+    #[id = "fenv-attack-curve"]
+    pub filter_attack_curve: FloatParam,
+
+    #[id = "fenv-decay-curve"]
+    pub filter_decay_curve: FloatParam,
+
+    #[id = "fenv-release-curve"]
+    pub filter_release_curve: FloatParam,
+
+    #[id = "fenv-attack"]
+    pub filter_attack: FloatParam,
+
+    #[id = "fenv-decay"]
+    pub filter_decay: FloatParam,
+
+    #[id = "fenv-sustain"]
+    pub filter_sustain: FloatParam,
+
+    #[id = "fenv-release"]
+    pub filter_release: FloatParam,
+
+    #[id = "lfo-shape"]
+    pub lfo_shape: IntParam,
+
+    #[id = "lfo-trigger"]
+    pub lfo_trigger: IntParam,
+
+    #[id = "lfo-timing"]
+    pub lfo_timing: IntParam,
+
+    #[id = "lfo-rate"]
+    pub lfo_rate: FloatParam,
+
+    #[id = "lfo-division"]
+    pub lfo_division: IntParam,
+
+    #[id = "lfo-pitch"]
+    pub lfo_to_pitch: FloatParam,
+
+    #[id = "lfo-amp"]
+    pub lfo_to_amp: FloatParam,
+
+    #[id = "lfo-pw"]
+    pub lfo_to_pulse_width: FloatParam,
+
+    #[id = "gain"]
+    pub gain: FloatParam,
+
+    #[id = "pan"]
+    pub pan: FloatParam,
+
+    #[id = "delay"]
+    pub delay: FloatParam,
+
+    #[id = "hold"]
+    pub hold: FloatParam,
+
+    #[id = "env-mode"]
+    pub envelope_mode: IntParam,
+
+    #[id = "retrigger"]
+    pub retrigger_mode: IntParam,
+
+    #[id = "attack-curve"]
+    pub attack_curve: FloatParam,
+
+    #[id = "decay-curve"]
+    pub decay_curve: FloatParam,
+
+    #[id = "release-curve"]
+    pub release_curve: FloatParam,
+
+    #[id = "attack"]
+    pub attack: FloatParam,
+
+    #[id = "decay"]
+    pub decay: FloatParam,
+
+    #[id = "sustain"]
+    pub sustain: FloatParam,
+
+    #[id = "release"]
+    pub release: FloatParam,
+
+    /// Mod matrix slots. Parameter IDs get the slot number appended
+    /// (`mod-src_1`, `mod-src_2`, ...), so they are saved with the plugin
+    /// state like any other parameter.
+    #[nested(array, group = "Mod Slot")]
+    pub mod_slots: [ModSlotParams; MOD_SLOTS],
+
+    /// Additive oscillator partials, saved as `partial_1` to `partial_64`.
+    #[nested(array, group = "Partial")]
+    pub partials: [PartialParams; MAX_PARTIALS],
+}
+
+/// Amplitude of one additive partial.
+#[derive(Params)]
+pub struct PartialParams {
+    #[id = "partial"]
+    pub amplitude: FloatParam,
+}
+
+impl PartialParams {
+    fn new(index: usize) -> Self {
+        Self {
+            amplitude: FloatParam::new(
+                format!("Partial {}", index + 1),
+                additive::preset_spectrum(AdditivePreset::Sine)[index],
+                FloatRange::Linear { min: -1.0, max: 1.0 },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+        }
+    }
+}
+
+/// Parameters for one mod matrix slot.
+#[derive(Params)]
+pub struct ModSlotParams {
+    #[id = "mod-src"]
+    pub source: IntParam,
+
+    #[id = "mod-dst"]
+    pub destination: IntParam,
+
+    #[id = "mod-depth"]
+    pub depth: FloatParam,
+}
+
+impl ModSlotParams {
+    fn new(index: usize) -> Self {
+        let slot = index + 1;
+        Self {
+            source: IntParam::new(
+                format!("Mod {} Source", slot),
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: ModSource::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                ModSource::from_index(v as usize).name().to_string()
+            })),
+
+            destination: IntParam::new(
+                format!("Mod {} Destination", slot),
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: ModDestination::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                ModDestination::from_index(v as usize).name().to_string()
+            })),
+
+            depth: FloatParam::new(
+                format!("Mod {} Depth", slot),
+                0.0,
+                FloatRange::Linear {
+                    min: -1.0,
+                    max: 1.0,
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+        }
+    }
+}
+
+impl Default for SimpleSynthParams {
+    fn default() -> Self {
+        Self {
+            editor_state: nih_plug_egui::EguiState::from_size(1200, 800),
+
+            osc_type: IntParam::new(
+                "Oscillator",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: OscillatorType::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                OscillatorType::from_index(v as usize).name().to_string()
+            })),
+
+            engine: IntParam::new(
+                "Engine",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: Engine::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                Engine::from_index(v as usize).name().to_string()
+            })),
+
+            pulse_width: FloatParam::new(
+                "Pulse Width",
+                0.5,
+                FloatRange::Linear {
+                    min: MIN_PULSE_WIDTH,
+                    max: MAX_PULSE_WIDTH,
+                },
+            )
+            .with_unit("%")
+            .with_value_to_string(formatters::v2s_f32_percentage(0))
+            .with_string_to_value(formatters::s2v_f32_percentage()),
+
+            wavetable: IntParam::new(
+                "Wavetable",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: WavetableBank::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                WavetableBank::from_index(v as usize).name().to_string()
+            })),
+
+            wavetable_position: FloatParam::new(
+                "Wavetable Position",
+                0.0,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            osc2_type: IntParam::new(
+                "Osc 2",
+                3,
+                IntRange::Linear {
+                    min: 0,
+                    max: OscillatorType::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                OscillatorType::from_index(v as usize).name().to_string()
+            })),
+
+            osc2_coarse: IntParam::new(
+                "Osc 2 Coarse",
+                0,
+                IntRange::Linear {
+                    min: -MAX_OSC2_SEMITONES,
+                    max: MAX_OSC2_SEMITONES,
+                },
+            )
+            .with_unit(" st"),
+
+            osc2_fine: FloatParam::new(
+                "Osc 2 Fine",
+                0.0,
+                FloatRange::Linear {
+                    min: -MAX_OSC2_FINE_CENTS,
+                    max: MAX_OSC2_FINE_CENTS,
+                },
+            )
+            .with_unit(" ct")
+            .with_value_to_string(formatters::v2s_f32_rounded(1)),
+
+            osc2_level: FloatParam::new(
+                "Osc 2 Level",
+                0.0,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            osc2_sync: BoolParam::new("Osc 2 Sync", false),
+
+            ring_mix: FloatParam::new(
+                "Ring Mix",
+                0.0,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            linear_fm: FloatParam::new(
+                "Linear FM",
+                0.0,
+                FloatRange::Linear {
+                    min: 0.0,
+                    max: MAX_LINEAR_FM,
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            sub_level: FloatParam::new(
+                "Sub Level",
+                0.0,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            sub_octave: IntParam::new(
+                "Sub Octave",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: SubOctave::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                SubOctave::from_index(v as usize).name().to_string()
+            })),
+
+            sub_shape: IntParam::new(
+                "Sub Shape",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: SubShape::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                SubShape::from_index(v as usize).name().to_string()
+            })),
+
+            noise_level: FloatParam::new(
+                "Noise Level",
+                0.0,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            noise_type: IntParam::new(
+                "Noise",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: NoiseType::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                NoiseType::from_index(v as usize).name().to_string()
+            })),
+
+            fm_enabled: BoolParam::new("FM", false),
+
+            fm_ratio: FloatParam::new(
+                "FM Ratio",
+                1.0,
+                FloatRange::Skewed {
+                    min: MIN_FM_RATIO,
+                    max: MAX_FM_RATIO,
+                    factor: FloatRange::skew_factor(-1.0),
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            fm_fixed: BoolParam::new("FM Fixed", false),
+
+            fm_fixed_frequency: FloatParam::new(
+                "FM Fixed Freq",
+                100.0,
+                FloatRange::Skewed {
+                    min: MIN_FM_FIXED_HZ,
+                    max: MAX_FM_FIXED_HZ,
+                    factor: FloatRange::skew_factor(-2.0),
+                },
+            )
+            .with_unit(" Hz")
+            .with_value_to_string(formatters::v2s_f32_rounded(1)),
+
+            fm_index: FloatParam::new(
+                "FM Index",
+                2.0,
+                FloatRange::Linear {
+                    min: 0.0,
+                    max: MAX_FM_INDEX,
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            fm_feedback: FloatParam::new(
+                "FM Feedback",
+                0.0,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            fm_delay: FloatParam::new(
+                "FM Delay",
+                0.0,
+                FloatRange::Skewed {
+                    min: 0.0,
+                    max: 5.0,
+                    factor: FloatRange::skew_factor(-2.0),
+                },
+            )
+            .with_unit(" s")
+            .with_value_to_string(formatters::v2s_f32_rounded(3)),
+
+            fm_hold: FloatParam::new(
+                "FM Hold",
+                0.0,
+                FloatRange::Skewed {
+                    min: 0.0,
+                    max: 5.0,
+                    factor: FloatRange::skew_factor(-2.0),
+                },
+            )
+            .with_unit(" s")
+            .with_value_to_string(formatters::v2s_f32_rounded(3)),
+
+            fm_envelope_mode: IntParam::new(
+                "FM Env Mode",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: EnvelopeMode::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                EnvelopeMode::from_index(v as usize).name().to_string()
+            })),
+
+            fm_retrigger_mode: IntParam::new(
+                "FM Retrigger",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: RetriggerMode::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                RetriggerMode::from_index(v as usize).name().to_string()
+            })),
+
+            fm_attack_curve: FloatParam::new(
+                "FM Attack Curve",
+                0.0,
+                FloatRange::Linear {
+                    min: -MAX_ENVELOPE_CURVE,
+                    max: MAX_ENVELOPE_CURVE,
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            fm_decay_curve: FloatParam::new(
+                "FM Decay Curve",
+                0.0,
+                FloatRange::Linear {
+                    min: -MAX_ENVELOPE_CURVE,
+                    max: MAX_ENVELOPE_CURVE,
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            fm_release_curve: FloatParam::new(
+                "FM Release Curve",
+                0.0,
+                FloatRange::Linear {
+                    min: -MAX_ENVELOPE_CURVE,
+                    max: MAX_ENVELOPE_CURVE,
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            fm_attack: FloatParam::new(
+                "FM Attack",
+                0.01,
+                FloatRange::Skewed {
+                    min: 0.001,
+                    max: 2.0,
+                    factor: FloatRange::skew_factor(-2.0),
+                },
+            )
+            .with_unit(" s")
+            .with_value_to_string(formatters::v2s_f32_rounded(3)),
+
+            fm_decay: FloatParam::new(
+                "FM Decay",
+                0.3,
+                FloatRange::Skewed {
+                    min: 0.001,
+                    max: 2.0,
+                    factor: FloatRange::skew_factor(-2.0),
+                },
+            )
+            .with_unit(" s")
+            .with_value_to_string(formatters::v2s_f32_rounded(3)),
+
+            fm_sustain: FloatParam::new(
+                "FM Sustain",
+                0.3,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            fm_release: FloatParam::new(
+                "FM Release",
+                0.3,
+                FloatRange::Skewed {
+                    min: 0.001,
+                    max: 5.0,
+                    factor: FloatRange::skew_factor(-2.0),
+                },
+            )
+            .with_unit(" s")
+            .with_value_to_string(formatters::v2s_f32_rounded(3)),
+
+            pluck_excitation: IntParam::new(
+                "Pluck Excitation",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: PluckExcitation::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                PluckExcitation::from_index(v as usize).name().to_string()
+            })),
+
+            pluck_damping: FloatParam::new(
+                "String Damping",
+                0.5,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            pluck_brightness: FloatParam::new(
+                "Pluck Brightness",
+                0.5,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            pluck_decay: FloatParam::new(
+                "String Decay",
+                2.0,
+                FloatRange::Skewed {
+                    min: MIN_PLUCK_DECAY,
+                    max: MAX_PLUCK_DECAY,
+                    factor: FloatRange::skew_factor(-2.0),
+                },
+            )
+            .with_unit(" s")
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            unison_voices: IntParam::new(
+                "Unison",
+                1,
+                IntRange::Linear {
+                    min: 1,
+                    max: MAX_UNISON as i32,
+                },
+            ),
+
+            unison_detune: FloatParam::new(
+                "Unison Detune",
+                15.0,
+                FloatRange::Linear {
+                    min: 0.0,
+                    max: MAX_UNISON_DETUNE_CENTS,
+                },
+            )
+            .with_unit(" ct")
+            .with_value_to_string(formatters::v2s_f32_rounded(1)),
+
+            unison_spread: FloatParam::new(
+                "Unison Spread",
+                0.5,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            unison_random_phase: BoolParam::new("Unison Random Phase", false),
+
+            voice_mode: IntParam::new(
+                "Voice Mode",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: VoiceMode::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                VoiceMode::from_index(v as usize).name().to_string()
+            })),
+
+            voices: IntParam::new(
+                "Voices",
+                8,
+                IntRange::Linear {
+                    min: 1,
+                    max: MAX_VOICES as i32,
+                },
+            ),
+
+            steal_policy: IntParam::new(
+                "Voice Steal",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: StealPolicy::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                StealPolicy::from_index(v as usize).name().to_string()
+            })),
+
+            note_priority: IntParam::new(
+                "Note Priority",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: NotePriority::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                NotePriority::from_index(v as usize).name().to_string()
+            })),
+
+            legato: BoolParam::new("Legato", false),
+
+            glide_time: FloatParam::new(
+                "Glide",
+                0.0,
+                FloatRange::Skewed {
+                    min: 0.0,
+                    max: 2.0,
+                    factor: FloatRange::skew_factor(-2.0),
+                },
+            )
+            .with_unit(" s")
+            .with_value_to_string(formatters::v2s_f32_rounded(3)),
+
+            glide_mode: IntParam::new(
+                "Glide Mode",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: GlideMode::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                GlideMode::from_index(v as usize).name().to_string()
+            })),
+
+            glide_timing: IntParam::new(
+                "Glide Timing",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: GlideTiming::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                GlideTiming::from_index(v as usize).name().to_string()
+            })),
+
+            velocity_curve: IntParam::new(
+                "Velocity Curve",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: VelocityCurve::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                VelocityCurve::from_index(v as usize).name().to_string()
+            })),
+
+            velocity_amount: FloatParam::new(
+                "Velocity Amount",
+                1.0,
+                FloatRange::Linear {
+                    min: 0.0,
+                    max: 1.0,
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            velocity_to_attack: FloatParam::new(
+                "Vel > Attack",
+                0.0,
+                FloatRange::Linear {
+                    min: 0.0,
+                    max: 1.0,
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            velocity_to_brightness: FloatParam::new(
+                "Vel > Brightness",
+                0.0,
+                FloatRange::Linear {
+                    min: 0.0,
+                    max: 1.0,
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            filter_type: IntParam::new(
+                "Filter Type",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: FilterType::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                FilterType::from_index(v as usize).name().to_string()
+            })),
+
+            filter_mode: IntParam::new(
+                "Filter Mode",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: FilterMode::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                FilterMode::from_index(v as usize).name().to_string()
+            })),
+
+            filter_cutoff: FloatParam::new(
+                "Cutoff",
+                20000.0,
+                FloatRange::Skewed {
+                    min: 20.0,
+                    max: 20000.0,
+                    factor: FloatRange::skew_factor(-2.0),
+                },
+            )
+            .with_unit(" Hz")
+            .with_value_to_string(formatters::v2s_f32_rounded(0)),
+
+            filter_resonance: FloatParam::new(
+                "Resonance",
+                0.0,
+                FloatRange::Linear {
+                    min: 0.0,
+                    max: 1.0,
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            filter_drive: FloatParam::new(
+                "Drive",
+                0.0,
+                FloatRange::Linear {
+                    min: 0.0,
+                    max: 1.0,
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            filter_keytrack: FloatParam::new(
+                "Key Track",
+                0.0,
+                FloatRange::Linear {
+                    min: 0.0,
+                    max: 1.0,
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            filter_env_amount: FloatParam::new(
+                "Filter Env Amount",
+                0.0,
+                FloatRange::Linear {
+                    min: -MAX_FILTER_ENV_OCTAVES,
+                    max: MAX_FILTER_ENV_OCTAVES,
+                },
+            )
+            .with_unit(" oct")
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            filter_delay: FloatParam::new(
+                "Filter Delay",
+                0.0,
+                FloatRange::Skewed {
+                    min: 0.0,
+                    max: 5.0,
+                    factor: FloatRange::skew_factor(-2.0),
+                },
+            )
+            .with_unit(" s")
+            .with_value_to_string(formatters::v2s_f32_rounded(3)),
+
+            filter_hold: FloatParam::new(
+                "Filter Hold",
+                0.0,
+                FloatRange::Skewed {
+                    min: 0.0,
+                    max: 5.0,
+                    factor: FloatRange::skew_factor(-2.0),
+                },
+            )
+            .with_unit(" s")
+            .with_value_to_string(formatters::v2s_f32_rounded(3)),
+
+            filter_envelope_mode: IntParam::new(
+                "Filter Env Mode",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: EnvelopeMode::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                EnvelopeMode::from_index(v as usize).name().to_string()
+            })),
+
+            filter_retrigger_mode: IntParam::new(
+                "Filter Retrigger",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: RetriggerMode::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                RetriggerMode::from_index(v as usize).name().to_string()
+            })),
+
+            filter_attack_curve: FloatParam::new(
+                "Filter Attack Curve",
+                0.0,
+                FloatRange::Linear {
+                    min: -MAX_ENVELOPE_CURVE,
+                    max: MAX_ENVELOPE_CURVE,
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            filter_decay_curve: FloatParam::new(
+                "Filter Decay Curve",
+                0.0,
+                FloatRange::Linear {
+                    min: -MAX_ENVELOPE_CURVE,
+                    max: MAX_ENVELOPE_CURVE,
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            filter_release_curve: FloatParam::new(
+                "Filter Release Curve",
+                0.0,
+                FloatRange::Linear {
+                    min: -MAX_ENVELOPE_CURVE,
+                    max: MAX_ENVELOPE_CURVE,
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            filter_attack: FloatParam::new(
+                "Filter Attack",
+                0.01,
+                FloatRange::Skewed {
+                    min: 0.001,
+                    max: 2.0,
+                    factor: FloatRange::skew_factor(-2.0),
+                },
+            )
+            .with_unit(" s")
+            .with_value_to_string(formatters::v2s_f32_rounded(3)),
+
+            filter_decay: FloatParam::new(
+                "Filter Decay",
+                0.3,
+                FloatRange::Skewed {
+                    min: 0.001,
+                    max: 2.0,
+                    factor: FloatRange::skew_factor(-2.0),
+                },
+            )
+            .with_unit(" s")
+            .with_value_to_string(formatters::v2s_f32_rounded(3)),
+
+            filter_sustain: FloatParam::new(
+                "Filter Sustain",
+                0.0,
+                FloatRange::Linear {
+                    min: 0.0,
+                    max: 1.0,
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            filter_release: FloatParam::new(
+                "Filter Release",
+                0.3,
+                FloatRange::Skewed {
+                    min: 0.001,
+                    max: 5.0,
+                    factor: FloatRange::skew_factor(-2.0),
+                },
+            )
+            .with_unit(" s")
+            .with_value_to_string(formatters::v2s_f32_rounded(3)),
 
-    #[id = "attack"]
-    pub attack: FloatParam,
+            lfo_shape: IntParam::new(
+                "LFO Shape",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: LfoShape::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                LfoShape::from_index(v as usize).name().to_string()
+            })),
+
+            lfo_trigger: IntParam::new(
+                "LFO Trigger",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: LfoTrigger::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                LfoTrigger::from_index(v as usize).name().to_string()
+            })),
+
+            lfo_timing: IntParam::new(
+                "LFO Timing",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: LfoTiming::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                LfoTiming::from_index(v as usize).name().to_string()
+            })),
 
-    #[id = "decay"]
-    pub decay: FloatParam,
+            lfo_rate: FloatParam::new(
+                "LFO Rate",
+                5.0,
+                FloatRange::Skewed {
+                    min: 0.01,
+                    max: 50.0,
+                    factor: FloatRange::skew_factor(-2.0),
+                },
+            )
+            .with_unit(" Hz")
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
 
-    #[id = "sustain"]
-    pub sustain: FloatParam,
+            lfo_division: IntParam::new(
+                "LFO Division",
+                NoteDivision::Quarter as i32,
+                IntRange::Linear {
+                    min: 0,
+                    max: NoteDivision::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                NoteDivision::from_index(v as usize).name().to_string()
+            })),
 
-    #[id = "release"]
-    pub release: FloatParam,
-}
+            lfo_to_pitch: FloatParam::new(
+                "LFO > Pitch",
+                0.0,
+                FloatRange::Linear {
+                    min: 0.0,
+                    max: MAX_LFO_PITCH_SEMITONES,
+                },
+            )
+            .with_unit(" st")
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
 
-impl Default for SimpleSynthParams {
-    fn default() -> Self {
-        Self {
-            editor_state: nih_plug_egui::EguiState::from_size(1000, 600),
+            lfo_to_amp: FloatParam::new(
+                "LFO > Amp",
+                0.0,
+                FloatRange::Linear {
+                    min: 0.0,
+                    max: 1.0,
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
 
-            osc_type: IntParam::new("Oscillator", 0, IntRange::Linear { min: 0, max: 3 })
-                .with_value_to_string(Arc::new(|v| {
-                    OscillatorType::from_index(v as usize).name().to_string()
-                })),
+            lfo_to_pulse_width: FloatParam::new(
+                "LFO > PW",
+                0.0,
+                FloatRange::Linear {
+                    min: 0.0,
+                    max: 1.0,
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
 
             gain: FloatParam::new(
                 "Gain",
@@ -196,6 +1380,95 @@ impl Default for SimpleSynthParams {
             .with_unit(" ")
             .with_value_to_string(formatters::v2s_f32_rounded(2)),
 
+            pan: FloatParam::new(
+                "Pan",
+                0.0,
+                FloatRange::Linear {
+                    min: -1.0,
+                    max: 1.0,
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_panning())
+            .with_string_to_value(formatters::s2v_f32_panning()),
+
+            delay: FloatParam::new(
+                "Delay",
+                0.0,
+                FloatRange::Skewed {
+                    min: 0.0,
+                    max: 5.0,
+                    factor: FloatRange::skew_factor(-2.0),
+                },
+            )
+            .with_unit(" s")
+            .with_value_to_string(formatters::v2s_f32_rounded(3)),
+
+            hold: FloatParam::new(
+                "Hold",
+                0.0,
+                FloatRange::Skewed {
+                    min: 0.0,
+                    max: 5.0,
+                    factor: FloatRange::skew_factor(-2.0),
+                },
+            )
+            .with_unit(" s")
+            .with_value_to_string(formatters::v2s_f32_rounded(3)),
+
+            envelope_mode: IntParam::new(
+                "Env Mode",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: EnvelopeMode::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                EnvelopeMode::from_index(v as usize).name().to_string()
+            })),
+
+            retrigger_mode: IntParam::new(
+                "Retrigger",
+                0,
+                IntRange::Linear {
+                    min: 0,
+                    max: RetriggerMode::VARIANTS.len() as i32 - 1,
+                },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                RetriggerMode::from_index(v as usize).name().to_string()
+            })),
+
+            attack_curve: FloatParam::new(
+                "Attack Curve",
+                0.0,
+                FloatRange::Linear {
+                    min: -MAX_ENVELOPE_CURVE,
+                    max: MAX_ENVELOPE_CURVE,
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            decay_curve: FloatParam::new(
+                "Decay Curve",
+                0.0,
+                FloatRange::Linear {
+                    min: -MAX_ENVELOPE_CURVE,
+                    max: MAX_ENVELOPE_CURVE,
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            release_curve: FloatParam::new(
+                "Release Curve",
+                0.0,
+                FloatRange::Linear {
+                    min: -MAX_ENVELOPE_CURVE,
+                    max: MAX_ENVELOPE_CURVE,
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
             attack: FloatParam::new(
                 "Attack",
                 0.01,
@@ -241,6 +1514,9 @@ impl Default for SimpleSynthParams {
             )
             .with_unit(" s")
             .with_value_to_string(formatters::v2s_f32_rounded(3)),
+
+            mod_slots: core::array::from_fn(ModSlotParams::new),
+            partials: core::array::from_fn(PartialParams::new),
         }
     }
 }
@@ -256,6 +1532,229 @@ impl Default for SimpleSynth {
     }
 }
 
+impl SimpleSynth {
+    /// Push every parameter's current value into the synth. Unchanged
+    /// values cost nothing, and continuous ones are smoothed in `dsp-core`.
+    fn apply_params(&mut self) {
+        self.synth
+            .set_oscillator_type(OscillatorType::from_index(self.params.osc_type.value() as usize));
+        self.synth
+            .set_engine(Engine::from_index(self.params.engine.value() as usize));
+        self.synth.set_pulse_width(self.params.pulse_width.value());
+        self.synth
+            .set_wavetable(WavetableBank::from_index(self.params.wavetable.value() as usize));
+        self.synth
+            .set_wavetable_position(self.params.wavetable_position.value());
+        self.synth
+            .set_osc2_type(OscillatorType::from_index(self.params.osc2_type.value() as usize));
+        self.synth.set_osc2_coarse(self.params.osc2_coarse.value());
+        self.synth.set_osc2_fine(self.params.osc2_fine.value());
+        self.synth.set_osc2_level(self.params.osc2_level.value());
+        self.synth.set_osc2_sync(self.params.osc2_sync.value());
+        self.synth.set_ring_mix(self.params.ring_mix.value());
+        self.synth.set_linear_fm(self.params.linear_fm.value());
+        self.synth.set_sub_level(self.params.sub_level.value());
+        self.synth
+            .set_sub_octave(SubOctave::from_index(self.params.sub_octave.value() as usize));
+        self.synth
+            .set_sub_shape(SubShape::from_index(self.params.sub_shape.value() as usize));
+        self.synth.set_noise_level(self.params.noise_level.value());
+        self.synth
+            .set_noise_type(NoiseType::from_index(self.params.noise_type.value() as usize));
+        self.synth.set_fm_enabled(self.params.fm_enabled.value());
+        self.synth.set_fm_ratio(self.params.fm_ratio.value());
+        self.synth.set_fm_fixed(self.params.fm_fixed.value());
+        self.synth.set_fm_fixed_frequency(self.params.fm_fixed_frequency.value());
+        self.synth.set_fm_index(self.params.fm_index.value());
+        self.synth.set_fm_feedback(self.params.fm_feedback.value());
+        self.synth.set_fm_delay(self.params.fm_delay.value());
+        self.synth.set_fm_hold(self.params.fm_hold.value());
+        self.synth.set_fm_envelope_mode(EnvelopeMode::from_index(
+            self.params.fm_envelope_mode.value() as usize,
+        ));
+        self.synth.set_fm_retrigger_mode(RetriggerMode::from_index(
+            self.params.fm_retrigger_mode.value() as usize,
+        ));
+        self.synth.set_fm_attack_curve(self.params.fm_attack_curve.value());
+        self.synth.set_fm_decay_curve(self.params.fm_decay_curve.value());
+        self.synth.set_fm_release_curve(self.params.fm_release_curve.value());
+        self.synth.set_fm_attack(self.params.fm_attack.value());
+        self.synth.set_fm_decay(self.params.fm_decay.value());
+        self.synth.set_fm_sustain(self.params.fm_sustain.value());
+        self.synth.set_fm_release(self.params.fm_release.value());
+        self.synth.set_pluck_excitation(PluckExcitation::from_index(
+            self.params.pluck_excitation.value() as usize,
+        ));
+        self.synth.set_pluck_damping(self.params.pluck_damping.value());
+        self.synth.set_pluck_brightness(self.params.pluck_brightness.value());
+        self.synth.set_pluck_decay(self.params.pluck_decay.value());
+        self.synth
+            .set_unison_voices(self.params.unison_voices.value() as usize);
+        self.synth.set_unison_detune(self.params.unison_detune.value());
+        self.synth.set_unison_spread(self.params.unison_spread.value());
+        self.synth
+            .set_unison_random_phase(self.params.unison_random_phase.value());
+        self.synth
+            .set_voice_mode(VoiceMode::from_index(self.params.voice_mode.value() as usize));
+        self.synth.set_voice_count(self.params.voices.value() as usize);
+        self.synth
+            .set_steal_policy(StealPolicy::from_index(self.params.steal_policy.value() as usize));
+        self.synth.set_note_priority(NotePriority::from_index(
+            self.params.note_priority.value() as usize,
+        ));
+        self.synth.set_legato(self.params.legato.value());
+        self.synth.set_glide_time(self.params.glide_time.value());
+        self.synth
+            .set_glide_mode(GlideMode::from_index(self.params.glide_mode.value() as usize));
+        self.synth
+            .set_glide_timing(GlideTiming::from_index(self.params.glide_timing.value() as usize));
+        self.synth.set_velocity_curve(VelocityCurve::from_index(
+            self.params.velocity_curve.value() as usize,
+        ));
+        self.synth.set_velocity_amount(self.params.velocity_amount.value());
+        self.synth
+            .set_velocity_to_attack(self.params.velocity_to_attack.value());
+        self.synth
+            .set_velocity_to_brightness(self.params.velocity_to_brightness.value());
+        self.synth
+            .set_filter_type(FilterType::from_index(self.params.filter_type.value() as usize));
+        self.synth
+            .set_filter_mode(FilterMode::from_index(self.params.filter_mode.value() as usize));
+        self.synth.set_filter_cutoff(self.params.filter_cutoff.value());
+        self.synth
+            .set_filter_resonance(self.params.filter_resonance.value());
+        self.synth.set_filter_drive(self.params.filter_drive.value());
+        self.synth.set_filter_keytrack(self.params.filter_keytrack.value());
+        self.synth
+            .set_filter_env_amount(self.params.filter_env_amount.value());
+        self.synth.set_filter_delay(self.params.filter_delay.value());
+        self.synth.set_filter_hold(self.params.filter_hold.value());
+        self.synth.set_filter_envelope_mode(EnvelopeMode::from_index(
+            self.params.filter_envelope_mode.value() as usize,
+        ));
+        self.synth.set_filter_retrigger_mode(RetriggerMode::from_index(
+            self.params.filter_retrigger_mode.value() as usize,
+        ));
+        self.synth.set_filter_attack_curve(self.params.filter_attack_curve.value());
+        self.synth.set_filter_decay_curve(self.params.filter_decay_curve.value());
+        self.synth.set_filter_release_curve(self.params.filter_release_curve.value());
+        self.synth.set_filter_attack(self.params.filter_attack.value());
+        self.synth.set_filter_decay(self.params.filter_decay.value());
+        self.synth.set_filter_sustain(self.params.filter_sustain.value());
+        self.synth.set_filter_release(self.params.filter_release.value());
+        self.synth
+            .set_lfo_shape(LfoShape::from_index(self.params.lfo_shape.value() as usize));
+        self.synth
+            .set_lfo_trigger(LfoTrigger::from_index(self.params.lfo_trigger.value() as usize));
+        self.synth
+            .set_lfo_timing(LfoTiming::from_index(self.params.lfo_timing.value() as usize));
+        self.synth.set_lfo_rate(self.params.lfo_rate.value());
+        self.synth.set_lfo_division(NoteDivision::from_index(
+            self.params.lfo_division.value() as usize,
+        ));
+        self.synth.set_lfo_to_pitch(self.params.lfo_to_pitch.value());
+        self.synth.set_lfo_to_amp(self.params.lfo_to_amp.value());
+        self.synth
+            .set_lfo_to_pulse_width(self.params.lfo_to_pulse_width.value());
+        self.synth.set_gain(self.params.gain.value());
+        self.synth.set_pan(self.params.pan.value());
+        self.synth.set_delay(self.params.delay.value());
+        self.synth.set_hold(self.params.hold.value());
+        self.synth.set_envelope_mode(EnvelopeMode::from_index(
+            self.params.envelope_mode.value() as usize,
+        ));
+        self.synth.set_retrigger_mode(RetriggerMode::from_index(
+            self.params.retrigger_mode.value() as usize,
+        ));
+        self.synth.set_attack_curve(self.params.attack_curve.value());
+        self.synth.set_decay_curve(self.params.decay_curve.value());
+        self.synth.set_release_curve(self.params.release_curve.value());
+        self.synth.set_attack(self.params.attack.value());
+        self.synth.set_decay(self.params.decay.value());
+        self.synth.set_sustain(self.params.sustain.value());
+        self.synth.set_release(self.params.release.value());
+        for (i, partial) in self.params.partials.iter().enumerate() {
+            self.synth.set_partial(i, partial.amplitude.value());
+        }
+        for (i, slot) in self.params.mod_slots.iter().enumerate() {
+            self.synth.set_mod_slot(
+                i,
+                ModSource::from_index(slot.source.value() as usize),
+                ModDestination::from_index(slot.destination.value() as usize),
+                slot.depth.value(),
+            );
+        }
+    }
+
+    /// Render one process call into `left` and `right` with the parameters
+    /// as they stand, splitting the block at each event from `next_event`
+    /// (timed from the start of `left`) so notes and controllers land on
+    /// their sample.
+    fn render(
+        &mut self,
+        left: &mut [f32],
+        right: &mut [f32],
+        mut next_event: impl FnMut() -> Option<PluginNoteEvent<Self>>,
+    ) {
+        self.apply_params();
+
+        // Drain UI keyboard note events (lock-free)
+        self.note_queue.drain(|is_on, note, velocity| {
+            if is_on {
+                self.synth.note_on(note, velocity);
+            } else {
+                self.synth.note_off(note);
+            }
+        });
+
+        // Process MIDI events with sample-accurate timing
+        let num_samples = left.len();
+        let mut event = next_event();
+        let mut block_start = 0usize;
+
+        while block_start < num_samples {
+            let block_end = match event {
+                Some(ref pending) => {
+                    let timing = pending.timing() as usize;
+                    if timing <= block_start {
+                        match pending {
+                            NoteEvent::NoteOn { note, velocity, .. } => {
+                                self.synth.note_on(*note, *velocity);
+                            }
+                            NoteEvent::NoteOff { note, .. } => {
+                                self.synth.note_off(*note);
+                            }
+                            NoteEvent::MidiCC { cc, value, .. } if *cc == MODULATION_MSB => {
+                                self.synth.set_mod_wheel(*value);
+                            }
+                            NoteEvent::MidiChannelPressure { pressure, .. } => {
+                                self.synth.set_aftertouch(*pressure);
+                            }
+                            _ => {}
+                        }
+                        event = next_event();
+                        continue;
+                    }
+                    timing.min(num_samples)
+                }
+                None => num_samples,
+            };
+
+            // Render audio for this block straight into the output channels
+            let block_left = &mut left[block_start..block_end];
+            let block_right = &mut right[block_start..block_end];
+            self.synth.process_stereo(block_left, block_right);
+
+            // Write to the lock-free visualization buffer
+            for (&l, &r) in block_left.iter().zip(block_right.iter()) {
+                self.vis_buffer.push(l, r);
+            }
+
+            block_start = block_end;
+        }
+    }
+}
+
 impl Plugin for SimpleSynth {
     const NAME: &'static str = "Simple Synth";
     const VENDOR: &'static str = "vst-rust-wasm";
@@ -269,7 +1768,8 @@ impl Plugin for SimpleSynth {
         ..AudioIOLayout::const_default()
     }];
 
-    const MIDI_INPUT: MidiConfig = MidiConfig::Basic;
+    // CCs are needed for the mod wheel and channel pressure
+    const MIDI_INPUT: MidiConfig = MidiConfig::MidiCCs;
     const SAMPLE_ACCURATE_AUTOMATION: bool = true;
 
     type SysExMessage = ();
@@ -303,78 +1803,16 @@ impl Plugin for SimpleSynth {
         _aux: &mut AuxiliaryBuffers,
         context: &mut impl ProcessContext<Self>,
     ) -> ProcessStatus {
-        // Apply parameter changes
-        self.synth
-            .set_oscillator_type(OscillatorType::from_index(self.params.osc_type.value() as usize));
-        self.synth.set_gain(self.params.gain.value());
-        self.synth.set_attack(self.params.attack.value());
-        self.synth.set_decay(self.params.decay.value());
-        self.synth.set_sustain(self.params.sustain.value());
-        self.synth.set_release(self.params.release.value());
-
-        // Drain UI keyboard note events (lock-free)
-        self.note_queue.drain(|is_on, note| {
-            if is_on {
-                self.synth.note_on(note, 0.8);
-            } else {
-                self.synth.note_off(note);
-            }
-        });
-
-        // Process MIDI events with sample-accurate timing
-        let num_samples = buffer.samples();
-        let mut next_event = context.next_event();
-        let mut block_start = 0usize;
-
-        while block_start < num_samples {
-            let block_end = match next_event {
-                Some(ref event) => {
-                    let timing = event.timing() as usize;
-                    if timing <= block_start {
-                        match event {
-                            NoteEvent::NoteOn { note, velocity, .. } => {
-                                self.synth.note_on(*note, *velocity);
-                            }
-                            NoteEvent::NoteOff { note, .. } => {
-                                self.synth.note_off(*note);
-                            }
-                            _ => {}
-                        }
-                        next_event = context.next_event();
-                        continue;
-                    }
-                    timing.min(num_samples)
-                }
-                None => num_samples,
-            };
-
-            // Render audio for this block
-            let block_len = block_end - block_start;
-            let mut mono_buf = [0.0f32; 512];
-            let mut rendered = 0;
-            while rendered < block_len {
-                let chunk = (block_len - rendered).min(512);
-                self.synth.process(&mut mono_buf[..chunk]);
-
-                // Write to lock-free visualization buffer
-                for &s in &mono_buf[..chunk] {
-                    self.vis_buffer.push(s);
-                }
-
-                // Copy mono to stereo output
-                let channel_slices = buffer.as_slice();
-                for i in 0..chunk {
-                    let sample_idx = block_start + rendered + i;
-                    let val = mono_buf[i];
-                    channel_slices[0][sample_idx] = val;
-                    channel_slices[1][sample_idx] = val;
-                }
-
-                rendered += chunk;
-            }
-
-            block_start = block_end;
+        if let Some(tempo) = context.transport().tempo {
+            self.synth.set_tempo(tempo as f32);
         }
+        // Parameter changes land on their sample only because of
+        // SAMPLE_ACCURATE_AUTOMATION: nih-plug splits the buffer at each one
+        // and calls `process` again from that sample, and `render` reads the
+        // parameters once at the start of every call
+        let channel_slices = buffer.as_slice();
+        let (left, right) = channel_slices.split_at_mut(1);
+        self.render(&mut left[0], &mut right[0], || context.next_event());
 
         ProcessStatus::Normal
     }
@@ -383,7 +1821,7 @@ impl Plugin for SimpleSynth {
 impl ClapPlugin for SimpleSynth {
     const CLAP_ID: &'static str = "com.vst-rust-wasm.simple-synth";
     const CLAP_DESCRIPTION: Option<&'static str> =
-        Some("A simple monophonic synthesizer with oscilloscope and spectrum visualizer");
+        Some("A simple polyphonic synthesizer with oscilloscope and spectrum visualizer");
     const CLAP_MANUAL_URL: Option<&'static str> = None;
     const CLAP_SUPPORT_URL: Option<&'static str> = None;
     const CLAP_FEATURES: &'static [ClapFeature] = &[
@@ -406,14 +1844,18 @@ nih_export_vst3!(SimpleSynth);
 
 #[cfg(test)]
 mod tests {
-    use super::{NoteQueue, VisBuffer, VIS_BUFFER_SIZE, NOTE_QUEUE_SIZE};
+    use super::{
+        NoteQueue, SimpleSynth, SimpleSynthParams, VisBuffer, NOTE_QUEUE_SIZE, VIS_BUFFER_SIZE,
+    };
+    use nih_plug::prelude::*;
+    use std::sync::Arc;
 
     // --- VisBuffer tests ---
 
     #[test]
     fn test_vis_buffer_new_reads_zeros() {
         let vb = VisBuffer::new();
-        let front = vb.read_front();
+        let (front, _) = vb.read_front();
         assert!(front.iter().all(|&s| s == 0.0));
     }
 
@@ -421,9 +1863,9 @@ mod tests {
     fn test_vis_buffer_push_full_swaps_to_front() {
         let vb = VisBuffer::new();
         for _ in 0..VIS_BUFFER_SIZE {
-            vb.push(1.0);
+            vb.push(1.0, 1.0);
         }
-        let front = vb.read_front();
+        let (front, _) = vb.read_front();
         assert!(front.iter().all(|&s| s == 1.0));
     }
 
@@ -431,9 +1873,9 @@ mod tests {
     fn test_vis_buffer_partial_push_no_swap() {
         let vb = VisBuffer::new();
         for _ in 0..1000 {
-            vb.push(1.0);
+            vb.push(1.0, 1.0);
         }
-        let front = vb.read_front();
+        let (front, _) = vb.read_front();
         assert!(
             front.iter().all(|&s| s == 0.0),
             "partial push should not swap"
@@ -445,13 +1887,13 @@ mod tests {
         let vb = VisBuffer::new();
         // First cycle: fill with 1.0
         for _ in 0..VIS_BUFFER_SIZE {
-            vb.push(1.0);
+            vb.push(1.0, 1.0);
         }
         // Second cycle: fill with 2.0
         for _ in 0..VIS_BUFFER_SIZE {
-            vb.push(2.0);
+            vb.push(2.0, 2.0);
         }
-        let front = vb.read_front();
+        let (front, _) = vb.read_front();
         assert!(
             front.iter().all(|&s| s == 2.0),
             "second cycle should overwrite"
@@ -462,16 +1904,28 @@ mod tests {
     fn test_vis_buffer_values_are_correct_sequence() {
         let vb = VisBuffer::new();
         for i in 0..VIS_BUFFER_SIZE {
-            vb.push(i as f32);
+            vb.push(i as f32, -(i as f32));
         }
-        let front = vb.read_front();
+        let (front, right) = vb.read_front();
         for i in 0..VIS_BUFFER_SIZE {
             assert_eq!(
                 front[i], i as f32,
                 "sample {} mismatch: expected {}, got {}",
                 i, i, front[i]
             );
+            assert_eq!(right[i], -(i as f32));
+        }
+    }
+
+    #[test]
+    fn test_vis_buffer_keeps_channels_apart() {
+        let vb = VisBuffer::new();
+        for _ in 0..VIS_BUFFER_SIZE {
+            vb.push(1.0, -1.0);
         }
+        let (left, right) = vb.read_front();
+        assert!(left.iter().all(|&s| s == 1.0));
+        assert!(right.iter().all(|&s| s == -1.0));
     }
 
     // --- NoteQueue tests ---
@@ -479,9 +1933,9 @@ mod tests {
     #[test]
     fn test_note_queue_push_on_and_drain() {
         let q = NoteQueue::new();
-        assert!(q.push_note_on(60));
+        assert!(q.push_note_on(60, 0.8));
         let mut events = Vec::new();
-        q.drain(|is_on, note| events.push((is_on, note)));
+        q.drain(|is_on, note, _| events.push((is_on, note)));
         assert_eq!(events, vec![(true, 60)]);
     }
 
@@ -490,19 +1944,19 @@ mod tests {
         let q = NoteQueue::new();
         assert!(q.push_note_off(60));
         let mut events = Vec::new();
-        q.drain(|is_on, note| events.push((is_on, note)));
+        q.drain(|is_on, note, _| events.push((is_on, note)));
         assert_eq!(events, vec![(false, 60)]);
     }
 
     #[test]
     fn test_note_queue_multiple_events_in_order() {
         let q = NoteQueue::new();
-        q.push_note_on(60);
-        q.push_note_on(64);
+        q.push_note_on(60, 0.8);
+        q.push_note_on(64, 0.8);
         q.push_note_off(60);
-        q.push_note_on(67);
+        q.push_note_on(67, 0.8);
         let mut events = Vec::new();
-        q.drain(|is_on, note| events.push((is_on, note)));
+        q.drain(|is_on, note, _| events.push((is_on, note)));
         assert_eq!(
             events,
             vec![(true, 60), (true, 64), (false, 60), (true, 67)]
@@ -515,13 +1969,13 @@ mod tests {
         // Usable capacity is NOTE_QUEUE_SIZE - 1 = 63
         for i in 0..(NOTE_QUEUE_SIZE - 1) {
             assert!(
-                q.push_note_on(i as u8 % 128),
+                q.push_note_on(i as u8 % 128, 0.8),
                 "push {} should succeed",
                 i
             );
         }
         assert!(
-            !q.push_note_on(0),
+            !q.push_note_on(0, 0.8),
             "push should fail when queue is full"
         );
     }
@@ -530,7 +1984,7 @@ mod tests {
     fn test_note_queue_empty_drain_no_callback() {
         let q = NoteQueue::new();
         let mut called = false;
-        q.drain(|_, _| called = true);
+        q.drain(|_, _, _| called = true);
         assert!(!called, "empty drain should not invoke callback");
     }
 
@@ -538,18 +1992,18 @@ mod tests {
     fn test_note_queue_drain_empties_queue() {
         let q = NoteQueue::new();
         for i in 0..5 {
-            q.push_note_on(60 + i);
+            q.push_note_on(60 + i, 0.8);
         }
         let mut count1 = 0;
-        q.drain(|_, _| count1 += 1);
+        q.drain(|_, _, _| count1 += 1);
         assert_eq!(count1, 5);
 
         // Queue should be empty now, push more
         for i in 0..5 {
-            q.push_note_on(70 + i);
+            q.push_note_on(70 + i, 0.8);
         }
         let mut events = Vec::new();
-        q.drain(|is_on, note| events.push((is_on, note)));
+        q.drain(|is_on, note, _| events.push((is_on, note)));
         assert_eq!(events.len(), 5);
         assert_eq!(events[0], (true, 70));
     }
@@ -557,9 +2011,9 @@ mod tests {
     #[test]
     fn test_note_on_encoding_sets_high_bit() {
         let q = NoteQueue::new();
-        q.push_note_on(60);
+        q.push_note_on(60, 0.8);
         let mut events = Vec::new();
-        q.drain(|is_on, note| events.push((is_on, note)));
+        q.drain(|is_on, note, _| events.push((is_on, note)));
         assert_eq!(events[0], (true, 60));
     }
 
@@ -568,7 +2022,7 @@ mod tests {
         let q = NoteQueue::new();
         q.push_note_off(60);
         let mut events = Vec::new();
-        q.drain(|is_on, note| events.push((is_on, note)));
+        q.drain(|is_on, note, _| events.push((is_on, note)));
         assert_eq!(events[0], (false, 60));
     }
 
@@ -577,18 +2031,18 @@ mod tests {
         let q = NoteQueue::new();
         // Push 50, drain all
         for i in 0..50 {
-            q.push_note_on(i as u8);
+            q.push_note_on(i as u8, 0.8);
         }
         let mut count = 0;
-        q.drain(|_, _| count += 1);
+        q.drain(|_, _, _| count += 1);
         assert_eq!(count, 50);
 
         // Push 30 more (these wrap around the 64-slot ring)
         for i in 0..30 {
-            assert!(q.push_note_on(80 + i as u8), "wrap push {} failed", i);
+            assert!(q.push_note_on(80 + i as u8, 0.8), "wrap push {} failed", i);
         }
         let mut events = Vec::new();
-        q.drain(|is_on, note| events.push((is_on, note)));
+        q.drain(|is_on, note, _| events.push((is_on, note)));
         assert_eq!(events.len(), 30);
         for (i, &(is_on, note)) in events.iter().enumerate() {
             assert!(is_on);
@@ -599,22 +2053,157 @@ mod tests {
     #[test]
     fn test_note_queue_high_note_values() {
         let q = NoteQueue::new();
-        // Note 127: push_note_on stores 0x80 | 127 = 0xFF (same as empty sentinel)
-        // but drain relies on head/tail comparison, not slot values
-        q.push_note_on(127);
+        // Note 127 at full velocity: push_note_on stores 0xFFFF (same as the empty
+        // sentinel) but drain relies on head/tail comparison, not slot values
+        q.push_note_on(127, 1.0);
         let mut events = Vec::new();
-        q.drain(|is_on, note| events.push((is_on, note)));
+        q.drain(|is_on, note, _| events.push((is_on, note)));
         assert_eq!(events, vec![(true, 127)]);
 
         q.push_note_off(127);
         let mut events = Vec::new();
-        q.drain(|is_on, note| events.push((is_on, note)));
+        q.drain(|is_on, note, _| events.push((is_on, note)));
         assert_eq!(events, vec![(false, 127)]);
 
         // Note 0
-        q.push_note_on(0);
+        q.push_note_on(0, 0.8);
         let mut events = Vec::new();
-        q.drain(|is_on, note| events.push((is_on, note)));
+        q.drain(|is_on, note, _| events.push((is_on, note)));
         assert_eq!(events, vec![(true, 0)]);
     }
+
+    #[test]
+    fn test_note_queue_carries_velocity() {
+        let q = NoteQueue::new();
+        q.push_note_on(60, 1.0);
+        q.push_note_on(62, 0.5);
+        q.push_note_on(64, 0.0);
+        let mut velocities = Vec::new();
+        q.drain(|_, _, velocity| velocities.push(velocity));
+        assert_eq!(velocities[0], 1.0);
+        assert!((velocities[1] - 0.5).abs() < 1.0 / 127.0);
+        assert_eq!(velocities[2], 0.0);
+    }
+
+    #[test]
+    fn test_note_queue_zero_velocity_is_still_note_on() {
+        let q = NoteQueue::new();
+        q.push_note_on(60, 0.0);
+        let mut events = Vec::new();
+        q.drain(|is_on, note, _| events.push((is_on, note)));
+        assert_eq!(events, vec![(true, 60)]);
+    }
+
+    #[test]
+    fn test_note_queue_clamps_velocity() {
+        let q = NoteQueue::new();
+        q.push_note_on(60, 3.0);
+        q.push_note_on(61, -1.0);
+        let mut velocities = Vec::new();
+        q.drain(|_, _, velocity| velocities.push(velocity));
+        assert_eq!(velocities, vec![1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_note_off_reports_zero_velocity() {
+        let q = NoteQueue::new();
+        q.push_note_off(60);
+        let mut velocities = Vec::new();
+        q.drain(|_, _, velocity| velocities.push(velocity));
+        assert_eq!(velocities, vec![0.0]);
+    }
+
+    // --- Automation tests ---
+    //
+    // With SAMPLE_ACCURATE_AUTOMATION nih-plug splits each host buffer at
+    // every parameter change. That splitting is nih-plug's and isn't
+    // exercised here; these tests check the plugin's side of it, that
+    // `render` picks the parameters up at the start of each call and that
+    // where the calls are split doesn't change the output.
+
+    /// Every parameter at its default except the gain, at `gain`.
+    fn with_gain(gain: f32) -> Arc<SimpleSynthParams> {
+        Arc::new(SimpleSynthParams {
+            gain: FloatParam::new("Gain", gain, FloatRange::Linear { min: 0.0, max: 1.0 }),
+            ..SimpleSynthParams::default()
+        })
+    }
+
+    fn note_on(timing: u32, note: u8) -> PluginNoteEvent<SimpleSynth> {
+        NoteEvent::NoteOn {
+            timing,
+            voice_id: None,
+            channel: 0,
+            note,
+            velocity: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_render_applies_parameters_from_its_first_sample() {
+        let mut plugin = SimpleSynth::default();
+        plugin.synth.prepare(44100.0);
+        plugin.params = with_gain(0.0);
+        let mut left = vec![0.0; 300];
+        let mut right = vec![0.0; 300];
+        let mut events = std::iter::once(note_on(0, 69));
+        plugin.render(&mut left, &mut right, || events.next());
+        assert!(left.iter().all(|&s| s == 0.0));
+
+        plugin.params = with_gain(1.0);
+        plugin.render(&mut left, &mut right, || None);
+        assert_ne!(left[0], 0.0);
+    }
+
+    /// Render `len` samples of the left channel as `render` calls starting
+    /// at each of `splits`, with `params` in place for every call and
+    /// `notes` as note-ons at the given samples.
+    fn render_split(
+        params: &[(usize, Arc<SimpleSynthParams>)],
+        notes: &[(usize, u8)],
+        len: usize,
+        splits: &[usize],
+    ) -> Vec<f32> {
+        let mut plugin = SimpleSynth::default();
+        plugin.synth.prepare(44100.0);
+        let mut left = vec![0.0; len];
+        let mut right = vec![0.0; len];
+        for (i, &start) in splits.iter().enumerate() {
+            let end = splits.get(i + 1).copied().unwrap_or(len);
+            if let Some((_, p)) = params.iter().rev().find(|(sample, _)| *sample <= start) {
+                plugin.params = p.clone();
+            }
+            let mut events = notes
+                .iter()
+                .filter(|(sample, _)| (start..end).contains(sample))
+                .map(|&(sample, note)| note_on((sample - start) as u32, note));
+            plugin.render(&mut left[start..end], &mut right[start..end], || {
+                events.next()
+            });
+        }
+        left
+    }
+
+    #[test]
+    fn test_output_is_independent_of_how_process_is_split() {
+        let params = [
+            (0, with_gain(0.2)),
+            (333, with_gain(1.0)),
+            (777, with_gain(0.5)),
+        ];
+        let notes = [(10, 60), (500, 67)];
+        let reference = render_split(&params, &notes, 2048, &[0, 333, 777]);
+        assert!(reference.iter().any(|&s| s != 0.0));
+        for buffer_size in [1, 64, 100, 512] {
+            let mut splits: Vec<usize> = (0..2048).step_by(buffer_size).chain([333, 777]).collect();
+            splits.sort_unstable();
+            splits.dedup();
+            assert_eq!(
+                render_split(&params, &notes, 2048, &splits),
+                reference,
+                "buffer size {}",
+                buffer_size
+            );
+        }
+    }
 }
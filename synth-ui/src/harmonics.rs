@@ -0,0 +1,148 @@
+use egui;
+
+/// Gap between neighbouring bars, as a fraction of each bar's slot.
+const BAR_GAP: f32 = 0.2;
+
+/// Bar-graph editor for the additive oscillator's partial amplitudes.
+///
+/// One bar per partial, fundamental on the left, growing up from the
+/// centre line for positive amplitudes and down for negative (inverted)
+/// ones. Clicking or dragging over the bars draws the spectrum.
+pub struct HarmonicEditor<'a> {
+    pub rect: egui::Rect,
+    /// Partial amplitudes in `[-1, 1]`.
+    pub amplitudes: &'a [f32],
+}
+
+impl<'a> HarmonicEditor<'a> {
+    /// Draw the bars and return the partial the pointer set this frame,
+    /// with its new amplitude.
+    pub fn paint_and_interact(
+        &self,
+        ui: &egui::Ui,
+        response: &egui::Response,
+    ) -> Option<(usize, f32)> {
+        let painter = ui.painter_at(self.rect);
+        painter.rect_filled(self.rect, 2.0, egui::Color32::from_rgb(20, 20, 30));
+        let centre = self.rect.center().y;
+        painter.hline(
+            self.rect.x_range(),
+            centre,
+            egui::Stroke::new(1.0, egui::Color32::from_rgb(70, 70, 90)),
+        );
+        for (partial, &amplitude) in self.amplitudes.iter().enumerate() {
+            let bar = bar_rect(self.rect, self.amplitudes.len(), partial, amplitude);
+            let fill = if amplitude >= 0.0 {
+                egui::Color32::from_rgb(100, 180, 255)
+            } else {
+                egui::Color32::from_rgb(255, 150, 80)
+            };
+            painter.rect_filled(bar, 0.0, fill);
+        }
+
+        if !(response.clicked() || response.dragged()) {
+            return None;
+        }
+        let pos = response.interact_pointer_pos()?;
+        let partial = partial_at(pos.x, self.rect, self.amplitudes.len())?;
+        Some((partial, amplitude_at(pos.y, self.rect)))
+    }
+}
+
+/// Partial whose slot covers `x`, or `None` outside the editor.
+fn partial_at(x: f32, rect: egui::Rect, count: usize) -> Option<usize> {
+    if count == 0 || rect.width() <= 0.0 || x < rect.left() || x >= rect.right() {
+        return None;
+    }
+    let slot = ((x - rect.left()) / rect.width() * count as f32) as usize;
+    Some(slot.min(count - 1))
+}
+
+/// Amplitude for a pointer at `y`: 1 at the top, 0 on the centre line and
+/// -1 at the bottom.
+fn amplitude_at(y: f32, rect: egui::Rect) -> f32 {
+    let half = rect.height() / 2.0;
+    if half <= 0.0 {
+        return 0.0;
+    }
+    ((rect.center().y - y) / half).clamp(-1.0, 1.0)
+}
+
+/// Bar for `partial` at `amplitude`, between the centre line and its tip.
+fn bar_rect(rect: egui::Rect, count: usize, partial: usize, amplitude: f32) -> egui::Rect {
+    let slot = rect.width() / count.max(1) as f32;
+    let left = rect.left() + slot * partial as f32 + slot * BAR_GAP / 2.0;
+    let tip = rect.center().y - amplitude.clamp(-1.0, 1.0) * rect.height() / 2.0;
+    let centre = rect.center().y;
+    egui::Rect::from_min_max(
+        egui::pos2(left, centre.min(tip)),
+        egui::pos2(left + slot * (1.0 - BAR_GAP), centre.max(tip)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor_rect() -> egui::Rect {
+        egui::Rect::from_min_size(egui::pos2(10.0, 20.0), egui::vec2(640.0, 100.0))
+    }
+
+    #[test]
+    fn partial_at_maps_slots_left_to_right() {
+        let rect = editor_rect();
+        assert_eq!(partial_at(10.0, rect, 64), Some(0));
+        assert_eq!(partial_at(19.9, rect, 64), Some(0));
+        assert_eq!(partial_at(20.0, rect, 64), Some(1));
+        assert_eq!(partial_at(649.9, rect, 64), Some(63));
+    }
+
+    #[test]
+    fn partial_at_outside_editor_is_none() {
+        let rect = editor_rect();
+        assert_eq!(partial_at(9.0, rect, 64), None);
+        assert_eq!(partial_at(650.0, rect, 64), None);
+        assert_eq!(partial_at(100.0, rect, 0), None);
+    }
+
+    #[test]
+    fn amplitude_at_is_bipolar_around_centre() {
+        let rect = editor_rect();
+        assert_eq!(amplitude_at(20.0, rect), 1.0);
+        assert_eq!(amplitude_at(70.0, rect), 0.0);
+        assert_eq!(amplitude_at(120.0, rect), -1.0);
+        assert_eq!(amplitude_at(45.0, rect), 0.5);
+        // Dragging past the edges clamps
+        assert_eq!(amplitude_at(-50.0, rect), 1.0);
+        assert_eq!(amplitude_at(500.0, rect), -1.0);
+    }
+
+    #[test]
+    fn amplitude_at_handles_zero_height_rect() {
+        let rect = egui::Rect::from_min_size(egui::pos2(0.0, 0.0), egui::vec2(100.0, 0.0));
+        assert_eq!(amplitude_at(0.0, rect), 0.0);
+    }
+
+    #[test]
+    fn bars_grow_from_the_centre_line() {
+        let rect = editor_rect();
+        let up = bar_rect(rect, 64, 3, 0.5);
+        assert_eq!(up.bottom(), 70.0);
+        assert_eq!(up.top(), 45.0);
+        let down = bar_rect(rect, 64, 3, -1.0);
+        assert_eq!(down.top(), 70.0);
+        assert_eq!(down.bottom(), 120.0);
+        assert_eq!(bar_rect(rect, 64, 3, 0.0).height(), 0.0);
+    }
+
+    #[test]
+    fn bars_stay_inside_their_slots() {
+        let rect = editor_rect();
+        for partial in 0..64 {
+            let bar = bar_rect(rect, 64, partial, 1.0);
+            assert_eq!(partial_at(bar.left(), rect, 64), Some(partial));
+            assert_eq!(partial_at(bar.right(), rect, 64), Some(partial));
+            assert!(rect.contains_rect(bar));
+        }
+    }
+}
@@ -1,10 +1,31 @@
 use egui;
 
 pub enum KeyboardEvent {
-    NoteOn(u8),
+    /// Note number and velocity in `[0, 1]`.
+    NoteOn(u8, f32),
     NoteOff(u8),
 }
 
+/// Velocity for notes played on the computer keyboard, which has no
+/// velocity sensing of its own.
+pub const COMPUTER_KEY_VELOCITY: f32 = 0.8;
+
+/// Softest velocity a mouse click can produce (clicking the very top of a key).
+const MIN_MOUSE_VELOCITY: f32 = 0.1;
+
+/// Velocity for a mouse click at `y` on a key spanning `top..bottom`.
+///
+/// Like striking a real key further from the hinge, clicking nearer the front
+/// edge of the key plays louder.
+fn velocity_for_click(y: f32, top: f32, bottom: f32) -> f32 {
+    let height = bottom - top;
+    if height <= 0.0 {
+        return 1.0;
+    }
+    let t = ((y - top) / height).clamp(0.0, 1.0);
+    MIN_MOUSE_VELOCITY + (1.0 - MIN_MOUSE_VELOCITY) * t
+}
+
 /// MIDI note numbers for 2 octaves starting at C3 (MIDI 48).
 const FIRST_NOTE: u8 = 48; // C3
 const NUM_WHITE_KEYS: usize = 15; // C3 to D5 (two octaves + 1)
@@ -125,21 +146,24 @@ impl<'a> PianoKeyboard<'a> {
         if response.is_pointer_button_down_on() {
             if let Some(pos) = response.interact_pointer_pos() {
                 // Check black keys first (they overlap white keys visually)
-                let mut clicked_note = None;
+                let mut clicked_key = None;
                 for key in keys.iter().rev() {
                     if key.rect.contains(pos) {
-                        clicked_note = Some(key.note);
+                        clicked_key = Some(key);
                         break;
                     }
                 }
-                if let Some(note) = clicked_note {
+                if let Some(key) = clicked_key {
+                    let note = key.note;
                     if *self.mouse_note != Some(note) {
                         // Release old mouse note if switching keys
                         if let Some(old) = self.mouse_note.take() {
                             events.push(KeyboardEvent::NoteOff(old));
                         }
                         *self.mouse_note = Some(note);
-                        events.push(KeyboardEvent::NoteOn(note));
+                        let velocity =
+                            velocity_for_click(pos.y, key.rect.top(), key.rect.bottom());
+                        events.push(KeyboardEvent::NoteOn(note, velocity));
                     }
                 }
             }
@@ -165,7 +189,10 @@ impl<'a> PianoKeyboard<'a> {
                     for &(map_key, offset) in KEY_MAP {
                         if *key == map_key {
                             if *pressed {
-                                events.push(KeyboardEvent::NoteOn(first_note + offset));
+                                events.push(KeyboardEvent::NoteOn(
+                                    first_note + offset,
+                                    COMPUTER_KEY_VELOCITY,
+                                ));
                             } else {
                                 events.push(KeyboardEvent::NoteOff(first_note + offset));
                             }
@@ -499,4 +526,27 @@ mod tests {
             assert!((key.rect.top() - 50.0).abs() < 0.01);
         }
     }
+
+    // --- Mouse velocity ---
+
+    #[test]
+    fn click_velocity_increases_toward_front_of_key() {
+        let top = velocity_for_click(0.0, 0.0, 120.0);
+        let middle = velocity_for_click(60.0, 0.0, 120.0);
+        let bottom = velocity_for_click(120.0, 0.0, 120.0);
+        assert!((top - MIN_MOUSE_VELOCITY).abs() < 1e-6);
+        assert!(middle > top && middle < bottom);
+        assert!((bottom - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn click_velocity_clamps_outside_key() {
+        assert!((velocity_for_click(-50.0, 0.0, 120.0) - MIN_MOUSE_VELOCITY).abs() < 1e-6);
+        assert!((velocity_for_click(500.0, 0.0, 120.0) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn click_velocity_handles_zero_height_key() {
+        assert_eq!(velocity_for_click(10.0, 10.0, 10.0), 1.0);
+    }
 }
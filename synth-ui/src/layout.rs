@@ -1,12 +1,17 @@
 use egui;
 
+use crate::harmonics::HarmonicEditor;
 use crate::keyboard::PianoKeyboard;
-use crate::visualizer::{FftResources, VisMode, VisualizerWidget};
+use crate::visualizer::{FftResources, VisChannels, VisMode, VisualizerWidget};
 use crate::KeyboardEvent;
 
+/// Height of the additive oscillator's harmonic editor.
+const HARMONIC_EDITOR_HEIGHT: f32 = 120.0;
+
 /// Persistent UI state that lives across frames.
 pub struct UiState {
     pub vis_mode: VisMode,
+    pub vis_channels: VisChannels,
     pub held_notes: Vec<u8>,
     pub fft_resources: FftResources,
     pub octave_offset: i8,
@@ -17,6 +22,7 @@ impl UiState {
     pub fn new() -> Self {
         Self {
             vis_mode: VisMode::Oscilloscope,
+            vis_channels: VisChannels::Mid,
             held_notes: Vec::new(),
             fft_resources: FftResources::new(),
             octave_offset: 0,
@@ -29,7 +35,105 @@ impl UiState {
 /// Each backend (nih-plug plugin, eframe web) provides its own implementation.
 pub trait ControlRenderer {
     fn render_osc_type(&mut self, ui: &mut egui::Ui);
+    fn render_engine(&mut self, ui: &mut egui::Ui);
+    fn render_pulse_width(&mut self, ui: &mut egui::Ui);
+    fn render_wavetable(&mut self, ui: &mut egui::Ui);
+    fn render_wavetable_position(&mut self, ui: &mut egui::Ui);
+    /// Number of additive partials to show in the harmonic editor.
+    fn partial_count(&self) -> usize;
+    fn partial_amplitude(&self, partial: usize) -> f32;
+    fn set_partial_amplitude(&mut self, partial: usize, amplitude: f32);
+    /// Buttons loading the preset spectra into the partials.
+    fn render_additive_presets(&mut self, ui: &mut egui::Ui);
+    fn render_osc2_type(&mut self, ui: &mut egui::Ui);
+    fn render_osc2_coarse(&mut self, ui: &mut egui::Ui);
+    fn render_osc2_fine(&mut self, ui: &mut egui::Ui);
+    fn render_osc2_level(&mut self, ui: &mut egui::Ui);
+    fn render_osc2_sync(&mut self, ui: &mut egui::Ui);
+    fn render_ring_mix(&mut self, ui: &mut egui::Ui);
+    fn render_linear_fm(&mut self, ui: &mut egui::Ui);
+    fn render_sub_level(&mut self, ui: &mut egui::Ui);
+    fn render_sub_octave(&mut self, ui: &mut egui::Ui);
+    fn render_sub_shape(&mut self, ui: &mut egui::Ui);
+    fn render_noise_level(&mut self, ui: &mut egui::Ui);
+    fn render_noise_type(&mut self, ui: &mut egui::Ui);
+    fn render_fm_enabled(&mut self, ui: &mut egui::Ui);
+    fn render_fm_ratio(&mut self, ui: &mut egui::Ui);
+    fn render_fm_fixed(&mut self, ui: &mut egui::Ui);
+    fn render_fm_fixed_frequency(&mut self, ui: &mut egui::Ui);
+    fn render_fm_index(&mut self, ui: &mut egui::Ui);
+    fn render_fm_feedback(&mut self, ui: &mut egui::Ui);
+    fn render_fm_delay(&mut self, ui: &mut egui::Ui);
+    fn render_fm_hold(&mut self, ui: &mut egui::Ui);
+    fn render_fm_envelope_mode(&mut self, ui: &mut egui::Ui);
+    fn render_fm_retrigger_mode(&mut self, ui: &mut egui::Ui);
+    fn render_fm_attack_curve(&mut self, ui: &mut egui::Ui);
+    fn render_fm_decay_curve(&mut self, ui: &mut egui::Ui);
+    fn render_fm_release_curve(&mut self, ui: &mut egui::Ui);
+    fn render_fm_attack(&mut self, ui: &mut egui::Ui);
+    fn render_fm_decay(&mut self, ui: &mut egui::Ui);
+    fn render_fm_sustain(&mut self, ui: &mut egui::Ui);
+    fn render_fm_release(&mut self, ui: &mut egui::Ui);
+    fn render_pluck_excitation(&mut self, ui: &mut egui::Ui);
+    fn render_pluck_damping(&mut self, ui: &mut egui::Ui);
+    fn render_pluck_brightness(&mut self, ui: &mut egui::Ui);
+    fn render_pluck_decay(&mut self, ui: &mut egui::Ui);
+    fn render_unison_voices(&mut self, ui: &mut egui::Ui);
+    fn render_unison_detune(&mut self, ui: &mut egui::Ui);
+    fn render_unison_spread(&mut self, ui: &mut egui::Ui);
+    fn render_unison_random_phase(&mut self, ui: &mut egui::Ui);
+    fn render_voices(&mut self, ui: &mut egui::Ui);
+    fn render_steal_policy(&mut self, ui: &mut egui::Ui);
+    fn render_voice_mode(&mut self, ui: &mut egui::Ui);
+    fn render_note_priority(&mut self, ui: &mut egui::Ui);
+    fn render_legato(&mut self, ui: &mut egui::Ui);
+    fn render_glide_time(&mut self, ui: &mut egui::Ui);
+    fn render_glide_mode(&mut self, ui: &mut egui::Ui);
+    fn render_glide_timing(&mut self, ui: &mut egui::Ui);
+    fn render_velocity_curve(&mut self, ui: &mut egui::Ui);
+    fn render_velocity_amount(&mut self, ui: &mut egui::Ui);
+    fn render_velocity_to_attack(&mut self, ui: &mut egui::Ui);
+    fn render_velocity_to_brightness(&mut self, ui: &mut egui::Ui);
+    fn render_filter_type(&mut self, ui: &mut egui::Ui);
+    fn render_filter_mode(&mut self, ui: &mut egui::Ui);
+    fn render_filter_cutoff(&mut self, ui: &mut egui::Ui);
+    fn render_filter_resonance(&mut self, ui: &mut egui::Ui);
+    fn render_filter_drive(&mut self, ui: &mut egui::Ui);
+    fn render_filter_keytrack(&mut self, ui: &mut egui::Ui);
+    fn render_filter_env_amount(&mut self, ui: &mut egui::Ui);
+    fn render_filter_delay(&mut self, ui: &mut egui::Ui);
+    fn render_filter_hold(&mut self, ui: &mut egui::Ui);
+    fn render_filter_envelope_mode(&mut self, ui: &mut egui::Ui);
+    fn render_filter_retrigger_mode(&mut self, ui: &mut egui::Ui);
+    fn render_filter_attack_curve(&mut self, ui: &mut egui::Ui);
+    fn render_filter_decay_curve(&mut self, ui: &mut egui::Ui);
+    fn render_filter_release_curve(&mut self, ui: &mut egui::Ui);
+    fn render_filter_attack(&mut self, ui: &mut egui::Ui);
+    fn render_filter_decay(&mut self, ui: &mut egui::Ui);
+    fn render_filter_sustain(&mut self, ui: &mut egui::Ui);
+    fn render_filter_release(&mut self, ui: &mut egui::Ui);
+    fn render_lfo_shape(&mut self, ui: &mut egui::Ui);
+    fn render_lfo_trigger(&mut self, ui: &mut egui::Ui);
+    fn render_lfo_timing(&mut self, ui: &mut egui::Ui);
+    fn render_lfo_rate(&mut self, ui: &mut egui::Ui);
+    fn render_lfo_division(&mut self, ui: &mut egui::Ui);
+    fn render_lfo_to_pitch(&mut self, ui: &mut egui::Ui);
+    fn render_lfo_to_amp(&mut self, ui: &mut egui::Ui);
+    fn render_lfo_to_pulse_width(&mut self, ui: &mut egui::Ui);
+    /// Number of mod matrix slots to show.
+    fn mod_slot_count(&self) -> usize;
+    fn render_mod_source(&mut self, ui: &mut egui::Ui, slot: usize);
+    fn render_mod_destination(&mut self, ui: &mut egui::Ui, slot: usize);
+    fn render_mod_depth(&mut self, ui: &mut egui::Ui, slot: usize);
     fn render_gain(&mut self, ui: &mut egui::Ui);
+    fn render_pan(&mut self, ui: &mut egui::Ui);
+    fn render_delay(&mut self, ui: &mut egui::Ui);
+    fn render_hold(&mut self, ui: &mut egui::Ui);
+    fn render_envelope_mode(&mut self, ui: &mut egui::Ui);
+    fn render_retrigger_mode(&mut self, ui: &mut egui::Ui);
+    fn render_attack_curve(&mut self, ui: &mut egui::Ui);
+    fn render_decay_curve(&mut self, ui: &mut egui::Ui);
+    fn render_release_curve(&mut self, ui: &mut egui::Ui);
     fn render_attack(&mut self, ui: &mut egui::Ui);
     fn render_decay(&mut self, ui: &mut egui::Ui);
     fn render_sustain(&mut self, ui: &mut egui::Ui);
@@ -40,17 +144,26 @@ pub trait ControlRenderer {
 ///
 /// This function is shared between the native plugin and the web app. The `controls`
 /// parameter abstracts over nih-plug's ParamSlider (plugin) vs plain egui sliders (web).
+/// `vis_left` and `vis_right` are the latest output samples of each channel.
 pub fn render_synth_ui(
     ui: &mut egui::Ui,
     state: &mut UiState,
     controls: &mut dyn ControlRenderer,
-    vis_samples: &[f32],
+    vis_left: &[f32],
+    vis_right: &[f32],
     enable_octave_shift: bool,
 ) -> Vec<KeyboardEvent> {
     ui.spacing_mut().item_spacing = egui::vec2(8.0, 6.0);
 
-    // --- Top section: oscillator type + ADSR knobs ---
+    // --- Top section: engine, oscillator type + ADSR knobs ---
     ui.horizontal(|ui| {
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Engine");
+                controls.render_engine(ui);
+            });
+        });
+
         ui.group(|ui| {
             ui.vertical(|ui| {
                 ui.label("Oscillator");
@@ -65,10 +178,42 @@ pub fn render_synth_ui(
             });
         });
 
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Pan");
+                controls.render_pan(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Env Mode");
+                controls.render_envelope_mode(ui);
+                ui.label("Retrigger");
+                controls.render_retrigger_mode(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Delay");
+                controls.render_delay(ui);
+            });
+        });
+
         ui.group(|ui| {
             ui.vertical(|ui| {
                 ui.label("Attack");
                 controls.render_attack(ui);
+                ui.label("Curve");
+                controls.render_attack_curve(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Hold");
+                controls.render_hold(ui);
             });
         });
 
@@ -76,6 +221,8 @@ pub fn render_synth_ui(
             ui.vertical(|ui| {
                 ui.label("Decay");
                 controls.render_decay(ui);
+                ui.label("Curve");
+                controls.render_decay_curve(ui);
             });
         });
 
@@ -90,10 +237,595 @@ pub fn render_synth_ui(
             ui.vertical(|ui| {
                 ui.label("Release");
                 controls.render_release(ui);
+                ui.label("Curve");
+                controls.render_release_curve(ui);
+            });
+        });
+    });
+
+    // --- Oscillator shape section: square pulse width, wavetable and scan position ---
+    ui.horizontal(|ui| {
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Pulse Width");
+                controls.render_pulse_width(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Wavetable");
+                controls.render_wavetable(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Position");
+                controls.render_wavetable_position(ui);
+            });
+        });
+    });
+
+    // --- Harmonics section: the additive oscillator's partials as a bar graph ---
+    ui.collapsing("Harmonics", |ui| {
+        ui.horizontal(|ui| {
+            ui.label("Preset");
+            controls.render_additive_presets(ui);
+        });
+        let amplitudes: Vec<f32> = (0..controls.partial_count())
+            .map(|partial| controls.partial_amplitude(partial))
+            .collect();
+        let size = egui::vec2(ui.available_width(), HARMONIC_EDITOR_HEIGHT);
+        let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click_and_drag());
+        let editor = HarmonicEditor {
+            rect,
+            amplitudes: &amplitudes,
+        };
+        if let Some((partial, amplitude)) = editor.paint_and_interact(ui, &response) {
+            controls.set_partial_amplitude(partial, amplitude);
+        }
+    });
+
+    // --- Second oscillator section: tuning, level, sync, ring mod and linear FM against osc 1 ---
+    ui.horizontal(|ui| {
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Osc 2");
+                controls.render_osc2_type(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Coarse");
+                controls.render_osc2_coarse(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Fine");
+                controls.render_osc2_fine(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Level");
+                controls.render_osc2_level(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Sync");
+                controls.render_osc2_sync(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Ring Mix");
+                controls.render_ring_mix(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Linear FM");
+                controls.render_linear_fm(ui);
+            });
+        });
+    });
+
+    // --- Sub-oscillator and noise section: extra sources mixed in under the oscillators ---
+    ui.horizontal(|ui| {
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Sub");
+                controls.render_sub_level(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Sub Octave");
+                controls.render_sub_octave(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Sub Shape");
+                controls.render_sub_shape(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Noise");
+                controls.render_noise_level(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Noise Type");
+                controls.render_noise_type(ui);
+            });
+        });
+    });
+
+    // --- FM section: a sine modulator on osc 1's phase, its depth shaped by its own ADSR ---
+    ui.horizontal(|ui| {
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("FM");
+                controls.render_fm_enabled(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Ratio");
+                controls.render_fm_ratio(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Fixed");
+                controls.render_fm_fixed(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Fixed Freq");
+                controls.render_fm_fixed_frequency(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Index");
+                controls.render_fm_index(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Feedback");
+                controls.render_fm_feedback(ui);
+            });
+        });
+    });
+
+    ui.horizontal(|ui| {
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("FM Env Mode");
+                controls.render_fm_envelope_mode(ui);
+                ui.label("Retrigger");
+                controls.render_fm_retrigger_mode(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("FM Delay");
+                controls.render_fm_delay(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("FM Attack");
+                controls.render_fm_attack(ui);
+                ui.label("Curve");
+                controls.render_fm_attack_curve(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("FM Hold");
+                controls.render_fm_hold(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("FM Decay");
+                controls.render_fm_decay(ui);
+                ui.label("Curve");
+                controls.render_fm_decay_curve(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("FM Sustain");
+                controls.render_fm_sustain(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("FM Release");
+                controls.render_fm_release(ui);
+                ui.label("Curve");
+                controls.render_fm_release_curve(ui);
+            });
+        });
+    });
+
+    // --- String section: the plucked string engine's excitation and tone ---
+    ui.horizontal(|ui| {
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Excitation");
+                controls.render_pluck_excitation(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Damping");
+                controls.render_pluck_damping(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Brightness");
+                controls.render_pluck_brightness(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("String Decay");
+                controls.render_pluck_decay(ui);
+            });
+        });
+    });
+
+    // --- Unison section: stacked, detuned oscillators spread across the stereo field ---
+    ui.horizontal(|ui| {
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Unison");
+                controls.render_unison_voices(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Detune");
+                controls.render_unison_detune(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Spread");
+                controls.render_unison_spread(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Random Phase");
+                controls.render_unison_random_phase(ui);
+            });
+        });
+    });
+
+    // --- Filter section ---
+    ui.horizontal(|ui| {
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Filter");
+                controls.render_filter_type(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Filter Mode");
+                controls.render_filter_mode(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Cutoff");
+                controls.render_filter_cutoff(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Resonance");
+                controls.render_filter_resonance(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Drive");
+                controls.render_filter_drive(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Key Track");
+                controls.render_filter_keytrack(ui);
+            });
+        });
+    });
+
+    // --- Filter envelope section: a second ADSR that sweeps the cutoff ---
+    ui.horizontal(|ui| {
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Env Amount");
+                controls.render_filter_env_amount(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Filter Env Mode");
+                controls.render_filter_envelope_mode(ui);
+                ui.label("Retrigger");
+                controls.render_filter_retrigger_mode(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Filter Delay");
+                controls.render_filter_delay(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Filter Attack");
+                controls.render_filter_attack(ui);
+                ui.label("Curve");
+                controls.render_filter_attack_curve(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Filter Hold");
+                controls.render_filter_hold(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Filter Decay");
+                controls.render_filter_decay(ui);
+                ui.label("Curve");
+                controls.render_filter_decay_curve(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Filter Sustain");
+                controls.render_filter_sustain(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Filter Release");
+                controls.render_filter_release(ui);
+                ui.label("Curve");
+                controls.render_filter_release_curve(ui);
+            });
+        });
+    });
+
+    // --- LFO section: one modulation LFO routed to pitch, level and pulse width ---
+    ui.horizontal(|ui| {
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("LFO");
+                controls.render_lfo_shape(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("LFO Trigger");
+                controls.render_lfo_trigger(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("LFO Timing");
+                controls.render_lfo_timing(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("LFO Rate");
+                controls.render_lfo_rate(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("LFO Division");
+                controls.render_lfo_division(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("LFO > Pitch");
+                controls.render_lfo_to_pitch(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("LFO > Amp");
+                controls.render_lfo_to_amp(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("LFO > PW");
+                controls.render_lfo_to_pulse_width(ui);
+            });
+        });
+    });
+
+    // --- Voice section: polyphony and mono behaviour ---
+    ui.horizontal(|ui| {
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Mode");
+                controls.render_voice_mode(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Voices");
+                controls.render_voices(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Voice Steal");
+                controls.render_steal_policy(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Priority");
+                controls.render_note_priority(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Legato");
+                controls.render_legato(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Glide");
+                controls.render_glide_time(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Glide Mode");
+                controls.render_glide_mode(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Glide Timing");
+                controls.render_glide_timing(ui);
+            });
+        });
+    });
+
+    // --- Velocity section: response curve and routing ---
+    ui.horizontal(|ui| {
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Velocity Curve");
+                controls.render_velocity_curve(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Velocity Amount");
+                controls.render_velocity_amount(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Vel > Attack");
+                controls.render_velocity_to_attack(ui);
+            });
+        });
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Vel > Brightness");
+                controls.render_velocity_to_brightness(ui);
             });
         });
     });
 
+    // --- Mod matrix: one row per slot, source > destination by depth ---
+    ui.collapsing("Mod Matrix", |ui| {
+        egui::Grid::new("mod_matrix")
+            .num_columns(4)
+            .spacing(egui::vec2(12.0, 4.0))
+            .show(ui, |ui| {
+                ui.label("Slot");
+                ui.label("Source");
+                ui.label("Destination");
+                ui.label("Depth");
+                ui.end_row();
+
+                for slot in 0..controls.mod_slot_count() {
+                    ui.label(format!("{}", slot + 1));
+                    controls.render_mod_source(ui, slot);
+                    controls.render_mod_destination(ui, slot);
+                    controls.render_mod_depth(ui, slot);
+                    ui.end_row();
+                }
+            });
+    });
+
     ui.separator();
 
     // --- Middle section: visualizer ---
@@ -111,6 +843,17 @@ pub fn render_synth_ui(
         {
             state.vis_mode = VisMode::Spectrum;
         }
+
+        ui.separator();
+        ui.label("Channels:");
+        for &channels in VisChannels::VARIANTS {
+            if ui
+                .selectable_label(state.vis_channels == channels, channels.name())
+                .clicked()
+            {
+                state.vis_channels = channels;
+            }
+        }
     });
 
     let vis_height = 200.0;
@@ -118,8 +861,10 @@ pub fn render_synth_ui(
     let (vis_rect, _) = ui.allocate_exact_size(vis_size, egui::Sense::hover());
 
     let mut widget = VisualizerWidget {
-        samples: vis_samples,
+        left: vis_left,
+        right: vis_right,
         mode: state.vis_mode,
+        channels: state.vis_channels,
         rect: vis_rect,
         fft: Some(&mut state.fft_resources),
     };
@@ -173,6 +918,12 @@ mod tests {
         assert_eq!(state.vis_mode, VisMode::Oscilloscope);
     }
 
+    #[test]
+    fn ui_state_default_vis_channels_is_mid() {
+        let state = UiState::new();
+        assert_eq!(state.vis_channels, VisChannels::Mid);
+    }
+
     #[test]
     fn ui_state_starts_with_no_held_notes() {
         let state = UiState::new();
@@ -1,7 +1,9 @@
+pub mod harmonics;
 pub mod keyboard;
 pub mod layout;
 pub mod visualizer;
 
+pub use harmonics::HarmonicEditor;
 pub use keyboard::{KeyboardEvent, PianoKeyboard};
 pub use layout::{render_synth_ui, ControlRenderer, UiState};
-pub use visualizer::{FftResources, VisMode, VisualizerWidget};
+pub use visualizer::{FftResources, VisChannels, VisMode, VisualizerWidget};
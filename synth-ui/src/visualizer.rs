@@ -9,6 +9,81 @@ pub enum VisMode {
     Spectrum,
 }
 
+/// Which output channels the visualizer shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VisChannels {
+    /// Left and right averaged into one trace.
+    Mid,
+    Left,
+    Right,
+    /// Left and right as two overlaid traces.
+    Stereo,
+}
+
+impl VisChannels {
+    pub const VARIANTS: &'static [VisChannels] = &[
+        VisChannels::Mid,
+        VisChannels::Left,
+        VisChannels::Right,
+        VisChannels::Stereo,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            VisChannels::Mid => "Mid",
+            VisChannels::Left => "L",
+            VisChannels::Right => "R",
+            VisChannels::Stereo => "L/R",
+        }
+    }
+
+    /// The traces to draw, back to front.
+    fn traces(&self) -> &'static [Trace] {
+        match self {
+            VisChannels::Mid => &[Trace::Mid],
+            VisChannels::Left => &[Trace::Left],
+            VisChannels::Right => &[Trace::Right],
+            VisChannels::Stereo => &[Trace::Left, Trace::Right],
+        }
+    }
+}
+
+/// One line on the display, drawn from one channel or the mid of both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Trace {
+    Mid,
+    Left,
+    Right,
+}
+
+impl Trace {
+    fn sample(self, left: &[f32], right: &[f32], i: usize) -> f32 {
+        match self {
+            Trace::Mid => (left[i] + right[i]) * 0.5,
+            Trace::Left => left[i],
+            Trace::Right => right[i],
+        }
+    }
+
+    /// Line colour; the right channel is orange so it stands apart in
+    /// stereo view.
+    fn color(self, mode: VisMode) -> egui::Color32 {
+        match (self, mode) {
+            (Trace::Right, _) => egui::Color32::from_rgb(240, 150, 70),
+            (_, VisMode::Oscilloscope) => egui::Color32::from_rgb(80, 200, 120),
+            (_, VisMode::Spectrum) => egui::Color32::from_rgb(80, 160, 255),
+        }
+    }
+
+    /// Translucent fill under a spectrum line.
+    fn fill_color(self) -> egui::Color32 {
+        match self {
+            Trace::Right => egui::Color32::from_rgba_premultiplied(120, 70, 30, 60),
+            _ => egui::Color32::from_rgba_premultiplied(40, 100, 200, 60),
+        }
+    }
+}
+
 /// Pre-allocated FFT resources to avoid per-frame heap allocations.
 /// Created once when the editor opens, reused every frame.
 pub struct FftResources {
@@ -16,6 +91,8 @@ pub struct FftResources {
     buffer: Vec<Complex<f32>>,
     magnitudes: Vec<f32>,
     mag_db: Vec<f32>,
+    /// Spectrum of the second trace in stereo view.
+    mag_db_right: Vec<f32>,
 }
 
 const FFT_SIZE: usize = 1024;
@@ -29,13 +106,59 @@ impl FftResources {
             buffer: vec![Complex::new(0.0, 0.0); FFT_SIZE],
             magnitudes: vec![0.0; FFT_SIZE / 2],
             mag_db: vec![0.0; FFT_SIZE / 2],
+            mag_db_right: vec![0.0; FFT_SIZE / 2],
+        }
+    }
+
+    /// Window and transform the last `FFT_SIZE` samples of `trace` into
+    /// `mag_db` (or `mag_db_right` for the second trace). Returns the
+    /// loudest bin in dB.
+    fn analyse(&mut self, trace: Trace, left: &[f32], right: &[f32], second: bool) -> f32 {
+        let len = left.len().min(right.len());
+        let start = len.saturating_sub(FFT_SIZE);
+
+        // Apply Hann window and fill pre-allocated buffer (zero allocation)
+        let sample_count = (len - start).min(FFT_SIZE);
+        for (i, bin) in self.buffer.iter_mut().enumerate() {
+            *bin = if i < sample_count {
+                let window =
+                    0.5 * (1.0 - (2.0 * std::f32::consts::PI * i as f32 / FFT_SIZE as f32).cos());
+                Complex::new(trace.sample(left, right, start + i) * window, 0.0)
+            } else {
+                Complex::new(0.0, 0.0)
+            };
+        }
+
+        // Run FFT using cached plan (no allocation)
+        self.fft.process(&mut self.buffer);
+
+        // Compute magnitudes into pre-allocated vec
+        let half = FFT_SIZE / 2;
+        for i in 0..half {
+            self.magnitudes[i] = (self.buffer[i].norm() / FFT_SIZE as f32).max(1e-10);
+        }
+
+        // Convert to dB
+        let mag_db = if second {
+            &mut self.mag_db_right
+        } else {
+            &mut self.mag_db
+        };
+        let mut max_db = f32::NEG_INFINITY;
+        for (db, &magnitude) in mag_db.iter_mut().zip(self.magnitudes.iter()) {
+            *db = 20.0 * magnitude.log10();
+            max_db = max_db.max(*db);
         }
+        max_db
     }
 }
 
 pub struct VisualizerWidget<'a> {
-    pub samples: &'a [f32],
+    /// Left and right channel samples, oldest first.
+    pub left: &'a [f32],
+    pub right: &'a [f32],
     pub mode: VisMode,
+    pub channels: VisChannels,
     pub rect: egui::Rect,
     pub fft: Option<&'a mut FftResources>,
 }
@@ -61,8 +184,14 @@ impl<'a> VisualizerWidget<'a> {
         );
     }
 
+    /// Number of samples both channels have.
+    fn len(&self) -> usize {
+        self.left.len().min(self.right.len())
+    }
+
     fn paint_oscilloscope(&self, painter: &egui::Painter) {
-        if self.samples.is_empty() {
+        let len = self.len();
+        if len == 0 {
             return;
         }
 
@@ -80,36 +209,38 @@ impl<'a> VisualizerWidget<'a> {
         );
 
         // We display the last ~1024 samples, downsampled to fit the width
-        let display_samples = self.samples.len().min(1024);
-        let start = self.samples.len().saturating_sub(display_samples);
-        let slice = &self.samples[start..];
+        let display_samples = len.min(1024);
+        let start = len - display_samples;
 
-        let num_points = (width as usize).min(slice.len());
+        let num_points = (width as usize).min(display_samples);
         if num_points < 2 {
             return;
         }
 
-        let step = slice.len() as f32 / num_points as f32;
+        let step = display_samples as f32 / num_points as f32;
         let amplitude = height * 0.45;
 
-        let points: Vec<egui::Pos2> = (0..num_points)
-            .map(|i| {
-                let sample_idx = (i as f32 * step) as usize;
-                let sample = slice[sample_idx.min(slice.len() - 1)];
-                let x = self.rect.left() + (i as f32 / num_points as f32) * width;
-                let y = center_y - sample * amplitude;
-                egui::pos2(x, y)
-            })
-            .collect();
+        for &trace in self.channels.traces() {
+            let points: Vec<egui::Pos2> = (0..num_points)
+                .map(|i| {
+                    let sample_idx = (i as f32 * step) as usize;
+                    let idx = start + sample_idx.min(display_samples - 1);
+                    let sample = trace.sample(self.left, self.right, idx);
+                    let x = self.rect.left() + (i as f32 / num_points as f32) * width;
+                    let y = center_y - sample * amplitude;
+                    egui::pos2(x, y)
+                })
+                .collect();
 
-        painter.add(egui::Shape::line(
-            points,
-            egui::Stroke::new(1.5, egui::Color32::from_rgb(80, 200, 120)),
-        ));
+            painter.add(egui::Shape::line(
+                points,
+                egui::Stroke::new(1.5, trace.color(self.mode)),
+            ));
+        }
     }
 
     fn paint_spectrum(&mut self, painter: &egui::Painter) {
-        if self.samples.len() < 64 {
+        if self.len() < 64 {
             return;
         }
 
@@ -118,78 +249,59 @@ impl<'a> VisualizerWidget<'a> {
             None => return,
         };
 
-        let fft_size = FFT_SIZE;
-        let start = self.samples.len().saturating_sub(fft_size);
-        let slice = &self.samples[start..];
-
-        // Apply Hann window and fill pre-allocated buffer (zero allocation)
-        let sample_count = slice.len().min(fft_size);
-        for i in 0..sample_count {
-            let window =
-                0.5 * (1.0 - (2.0 * std::f32::consts::PI * i as f32 / fft_size as f32).cos());
-            fft_res.buffer[i] = Complex::new(slice[i] * window, 0.0);
-        }
-        for i in sample_count..fft_size {
-            fft_res.buffer[i] = Complex::new(0.0, 0.0);
-        }
-
-        // Run FFT using cached plan (no allocation)
-        fft_res.fft.process(&mut fft_res.buffer);
-
-        // Compute magnitudes into pre-allocated vec
-        let half = fft_size / 2;
-        for i in 0..half {
-            fft_res.magnitudes[i] = (fft_res.buffer[i].norm() / fft_size as f32).max(1e-10);
-        }
-
-        // Convert to dB
+        let traces = self.channels.traces();
         let mut max_db = f32::NEG_INFINITY;
-        for i in 0..half {
-            let db = 20.0 * fft_res.magnitudes[i].log10();
-            fft_res.mag_db[i] = db;
-            if db > max_db {
-                max_db = db;
-            }
+        for (n, &trace) in traces.iter().enumerate() {
+            max_db = max_db.max(fft_res.analyse(trace, self.left, self.right, n > 0));
         }
-        let min_db = max_db - 80.0; // 80 dB dynamic range
+        // 80 dB dynamic range, shared so stereo traces compare directly
+        let min_db = max_db - 80.0;
 
+        let half = FFT_SIZE / 2;
         let width = self.rect.width();
         let height = self.rect.height();
         let num_bars = (width as usize).min(half);
 
-        // Use logarithmic frequency mapping for more musical display
-        let points: Vec<egui::Pos2> = (0..num_bars)
-            .map(|i| {
-                let t = i as f32 / num_bars as f32;
-                let freq_idx =
-                    ((half as f32).powf(t) - 1.0).round() as usize;
-                let freq_idx = freq_idx.min(half - 1);
-                let db = fft_res.mag_db[freq_idx];
-                let normalized = ((db - min_db) / (max_db - min_db)).clamp(0.0, 1.0);
-
-                let x = self.rect.left() + (i as f32 / num_bars as f32) * width;
-                let y = self.rect.bottom() - normalized * height;
-                egui::pos2(x, y)
-            })
-            .collect();
-
-        if points.len() >= 2 {
-            // Fill under the curve
-            let mut fill_points = vec![egui::pos2(self.rect.left(), self.rect.bottom())];
-            fill_points.extend_from_slice(&points);
-            fill_points.push(egui::pos2(self.rect.right(), self.rect.bottom()));
-
-            painter.add(egui::Shape::convex_polygon(
-                fill_points,
-                egui::Color32::from_rgba_premultiplied(40, 100, 200, 60),
-                egui::Stroke::NONE,
-            ));
-
-            // Line on top
-            painter.add(egui::Shape::line(
-                points,
-                egui::Stroke::new(1.5, egui::Color32::from_rgb(80, 160, 255)),
-            ));
+        for (n, &trace) in traces.iter().enumerate() {
+            let mag_db = if n > 0 {
+                &fft_res.mag_db_right
+            } else {
+                &fft_res.mag_db
+            };
+
+            // Use logarithmic frequency mapping for more musical display
+            let points: Vec<egui::Pos2> = (0..num_bars)
+                .map(|i| {
+                    let t = i as f32 / num_bars as f32;
+                    let freq_idx =
+                        ((half as f32).powf(t) - 1.0).round() as usize;
+                    let freq_idx = freq_idx.min(half - 1);
+                    let db = mag_db[freq_idx];
+                    let normalized = ((db - min_db) / (max_db - min_db)).clamp(0.0, 1.0);
+
+                    let x = self.rect.left() + (i as f32 / num_bars as f32) * width;
+                    let y = self.rect.bottom() - normalized * height;
+                    egui::pos2(x, y)
+                })
+                .collect();
+
+            if points.len() >= 2 {
+                let color = trace.color(self.mode);
+
+                // Fill under the curve
+                let mut fill_points = vec![egui::pos2(self.rect.left(), self.rect.bottom())];
+                fill_points.extend_from_slice(&points);
+                fill_points.push(egui::pos2(self.rect.right(), self.rect.bottom()));
+
+                painter.add(egui::Shape::convex_polygon(
+                    fill_points,
+                    trace.fill_color(),
+                    egui::Stroke::NONE,
+                ));
+
+                // Line on top
+                painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, color)));
+            }
         }
     }
 }
@@ -220,8 +332,43 @@ mod tests {
         assert_eq!(format!("{:?}", VisMode::Spectrum), "Spectrum");
     }
 
+    // --- VisChannels ---
+
+    #[test]
+    fn vis_channels_stereo_draws_both_channels() {
+        assert_eq!(VisChannels::Stereo.traces(), &[Trace::Left, Trace::Right]);
+        for &channels in &VisChannels::VARIANTS[..3] {
+            assert_eq!(channels.traces().len(), 1);
+        }
+    }
+
+    #[test]
+    fn trace_samples_pick_channel() {
+        let left = [1.0, 0.5];
+        let right = [0.0, -0.5];
+        assert_eq!(Trace::Left.sample(&left, &right, 0), 1.0);
+        assert_eq!(Trace::Right.sample(&left, &right, 1), -0.5);
+        assert_eq!(Trace::Mid.sample(&left, &right, 0), 0.5);
+        assert_eq!(Trace::Mid.sample(&left, &right, 1), 0.0);
+    }
+
     // --- FftResources ---
 
+    #[test]
+    fn fft_resources_analyse_keeps_channels_apart() {
+        let mut res = FftResources::new();
+        let left: Vec<f32> = (0..FFT_SIZE)
+            .map(|i| (2.0 * std::f32::consts::PI * 32.0 * i as f32 / FFT_SIZE as f32).sin())
+            .collect();
+        let right = vec![0.0; FFT_SIZE];
+        let loud = res.analyse(Trace::Left, &left, &right, false);
+        let quiet = res.analyse(Trace::Right, &left, &right, true);
+        assert!(loud > -20.0, "left peak {} dB", loud);
+        assert!(quiet < -150.0, "silent right peak {} dB", quiet);
+        // Each trace has its own spectrum buffer
+        assert!(res.mag_db[32] > res.mag_db_right[32] + 100.0);
+    }
+
     #[test]
     fn fft_resources_new_creates_valid_state() {
         let res = FftResources::new();
@@ -1,5 +1,12 @@
-use dsp_core::params::OscillatorType;
+use dsp_core::params::{
+    Engine, EnvelopeMode, FilterMode, FilterType, GlideMode, GlideTiming, LfoShape, LfoTiming,
+    LfoTrigger, ModDestination, ModSource, NoiseType, NoteDivision, NotePriority, OscillatorType,
+    PluckExcitation, RetriggerMode, StealPolicy, SubOctave, SubShape, VelocityCurve, VoiceMode,
+    WavetableBank,
+};
+use dsp_core::wavetable::Wavetable;
 use dsp_core::Synth;
+use std::sync::Arc;
 use wasm_bindgen::prelude::*;
 
 const VIS_BUFFER_SIZE: usize = 2048;
@@ -11,7 +18,13 @@ pub struct WasmSynth {
     synth: Synth,
     /// Internal audio output buffer (128 samples = 1 render quantum).
     audio_buf: Vec<f32>,
+    /// Planar stereo render buffer: one render quantum of left samples,
+    /// then one of right.
+    stereo_buf: Vec<f32>,
     vis_buffer: Vec<f32>,
+    /// Planar stereo visualization buffer: `VIS_BUFFER_SIZE` left samples,
+    /// then as many right, written alongside `vis_buffer`.
+    vis_stereo: Vec<f32>,
     vis_write_pos: usize,
     vis_ready: bool,
 }
@@ -23,7 +36,9 @@ impl WasmSynth {
         Self {
             synth: Synth::new(),
             audio_buf: vec![0.0; RENDER_QUANTUM],
+            stereo_buf: vec![0.0; 2 * RENDER_QUANTUM],
             vis_buffer: vec![0.0; VIS_BUFFER_SIZE],
+            vis_stereo: vec![0.0; 2 * VIS_BUFFER_SIZE],
             vis_write_pos: 0,
             vis_ready: false,
         }
@@ -33,8 +48,8 @@ impl WasmSynth {
         self.synth.prepare(sample_rate);
     }
 
-    pub fn note_on(&mut self, note: u8) {
-        self.synth.note_on(note, 0.8);
+    pub fn note_on(&mut self, note: u8, velocity: f32) {
+        self.synth.note_on(note, velocity);
     }
 
     pub fn note_off(&mut self, note: u8) {
@@ -46,10 +61,410 @@ impl WasmSynth {
             .set_oscillator_type(OscillatorType::from_index(index as usize));
     }
 
+    pub fn set_engine(&mut self, index: u32) {
+        self.synth.set_engine(Engine::from_index(index as usize));
+    }
+
+    pub fn set_pulse_width(&mut self, v: f32) {
+        self.synth.set_pulse_width(v);
+    }
+
+    pub fn set_wavetable(&mut self, index: u32) {
+        self.synth
+            .set_wavetable(WavetableBank::from_index(index as usize));
+    }
+
+    pub fn set_wavetable_position(&mut self, v: f32) {
+        self.synth.set_wavetable_position(v);
+    }
+
+    /// Load a table built on the main thread, given its frame count and
+    /// `Wavetable::mipmaps`, as the custom table. Resynthesising the
+    /// mipmaps from a WAV file takes far longer than a render quantum, so
+    /// the worklet only copies the finished samples. Returns false, leaving
+    /// the current table in place, if the data doesn't fit `frames`.
+    pub fn load_wavetable(&mut self, frames: u32, mipmaps: &[f32]) -> bool {
+        match Wavetable::from_mipmaps(frames as usize, mipmaps) {
+            Ok(table) => {
+                self.synth.load_wavetable(Arc::new(table));
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    pub fn set_osc2_type(&mut self, index: u32) {
+        self.synth
+            .set_osc2_type(OscillatorType::from_index(index as usize));
+    }
+
+    pub fn set_osc2_coarse(&mut self, semitones: i32) {
+        self.synth.set_osc2_coarse(semitones);
+    }
+
+    pub fn set_osc2_fine(&mut self, v: f32) {
+        self.synth.set_osc2_fine(v);
+    }
+
+    pub fn set_osc2_level(&mut self, v: f32) {
+        self.synth.set_osc2_level(v);
+    }
+
+    pub fn set_osc2_sync(&mut self, enabled: bool) {
+        self.synth.set_osc2_sync(enabled);
+    }
+
+    pub fn set_ring_mix(&mut self, v: f32) {
+        self.synth.set_ring_mix(v);
+    }
+
+    pub fn set_linear_fm(&mut self, v: f32) {
+        self.synth.set_linear_fm(v);
+    }
+
+    pub fn set_sub_level(&mut self, v: f32) {
+        self.synth.set_sub_level(v);
+    }
+
+    pub fn set_sub_octave(&mut self, index: u32) {
+        self.synth
+            .set_sub_octave(SubOctave::from_index(index as usize));
+    }
+
+    pub fn set_sub_shape(&mut self, index: u32) {
+        self.synth
+            .set_sub_shape(SubShape::from_index(index as usize));
+    }
+
+    pub fn set_noise_level(&mut self, v: f32) {
+        self.synth.set_noise_level(v);
+    }
+
+    pub fn set_noise_type(&mut self, index: u32) {
+        self.synth
+            .set_noise_type(NoiseType::from_index(index as usize));
+    }
+
+    pub fn set_fm_enabled(&mut self, enabled: bool) {
+        self.synth.set_fm_enabled(enabled);
+    }
+
+    pub fn set_fm_ratio(&mut self, v: f32) {
+        self.synth.set_fm_ratio(v);
+    }
+
+    pub fn set_fm_fixed(&mut self, enabled: bool) {
+        self.synth.set_fm_fixed(enabled);
+    }
+
+    pub fn set_fm_fixed_frequency(&mut self, v: f32) {
+        self.synth.set_fm_fixed_frequency(v);
+    }
+
+    pub fn set_fm_index(&mut self, v: f32) {
+        self.synth.set_fm_index(v);
+    }
+
+    pub fn set_fm_feedback(&mut self, v: f32) {
+        self.synth.set_fm_feedback(v);
+    }
+
+    pub fn set_fm_delay(&mut self, v: f32) {
+        self.synth.set_fm_delay(v);
+    }
+
+    pub fn set_fm_hold(&mut self, v: f32) {
+        self.synth.set_fm_hold(v);
+    }
+
+    pub fn set_fm_envelope_mode(&mut self, index: u32) {
+        self.synth
+            .set_fm_envelope_mode(EnvelopeMode::from_index(index as usize));
+    }
+
+    pub fn set_fm_retrigger_mode(&mut self, index: u32) {
+        self.synth
+            .set_fm_retrigger_mode(RetriggerMode::from_index(index as usize));
+    }
+
+    pub fn set_fm_attack_curve(&mut self, v: f32) {
+        self.synth.set_fm_attack_curve(v);
+    }
+
+    pub fn set_fm_decay_curve(&mut self, v: f32) {
+        self.synth.set_fm_decay_curve(v);
+    }
+
+    pub fn set_fm_release_curve(&mut self, v: f32) {
+        self.synth.set_fm_release_curve(v);
+    }
+
+    pub fn set_fm_attack(&mut self, v: f32) {
+        self.synth.set_fm_attack(v);
+    }
+
+    pub fn set_fm_decay(&mut self, v: f32) {
+        self.synth.set_fm_decay(v);
+    }
+
+    pub fn set_fm_sustain(&mut self, v: f32) {
+        self.synth.set_fm_sustain(v);
+    }
+
+    pub fn set_fm_release(&mut self, v: f32) {
+        self.synth.set_fm_release(v);
+    }
+
+    pub fn set_pluck_excitation(&mut self, index: u32) {
+        self.synth.set_pluck_excitation(PluckExcitation::from_index(index as usize));
+    }
+
+    pub fn set_pluck_damping(&mut self, v: f32) {
+        self.synth.set_pluck_damping(v);
+    }
+
+    pub fn set_pluck_brightness(&mut self, v: f32) {
+        self.synth.set_pluck_brightness(v);
+    }
+
+    pub fn set_pluck_decay(&mut self, v: f32) {
+        self.synth.set_pluck_decay(v);
+    }
+
+    pub fn set_unison_voices(&mut self, count: u32) {
+        self.synth.set_unison_voices(count as usize);
+    }
+
+    pub fn set_unison_detune(&mut self, cents: f32) {
+        self.synth.set_unison_detune(cents);
+    }
+
+    pub fn set_unison_spread(&mut self, v: f32) {
+        self.synth.set_unison_spread(v);
+    }
+
+    pub fn set_unison_random_phase(&mut self, enabled: bool) {
+        self.synth.set_unison_random_phase(enabled);
+    }
+
+    pub fn set_voice_count(&mut self, count: u32) {
+        self.synth.set_voice_count(count as usize);
+    }
+
+    pub fn set_steal_policy(&mut self, index: u32) {
+        self.synth
+            .set_steal_policy(StealPolicy::from_index(index as usize));
+    }
+
+    pub fn set_voice_mode(&mut self, index: u32) {
+        self.synth
+            .set_voice_mode(VoiceMode::from_index(index as usize));
+    }
+
+    pub fn set_note_priority(&mut self, index: u32) {
+        self.synth
+            .set_note_priority(NotePriority::from_index(index as usize));
+    }
+
+    pub fn set_legato(&mut self, enabled: bool) {
+        self.synth.set_legato(enabled);
+    }
+
+    pub fn set_glide_time(&mut self, seconds: f32) {
+        self.synth.set_glide_time(seconds);
+    }
+
+    pub fn set_glide_mode(&mut self, index: u32) {
+        self.synth.set_glide_mode(GlideMode::from_index(index as usize));
+    }
+
+    pub fn set_glide_timing(&mut self, index: u32) {
+        self.synth
+            .set_glide_timing(GlideTiming::from_index(index as usize));
+    }
+
+    pub fn set_velocity_curve(&mut self, index: u32) {
+        self.synth
+            .set_velocity_curve(VelocityCurve::from_index(index as usize));
+    }
+
+    pub fn set_velocity_amount(&mut self, v: f32) {
+        self.synth.set_velocity_amount(v);
+    }
+
+    pub fn set_velocity_to_attack(&mut self, v: f32) {
+        self.synth.set_velocity_to_attack(v);
+    }
+
+    pub fn set_velocity_to_brightness(&mut self, v: f32) {
+        self.synth.set_velocity_to_brightness(v);
+    }
+
+    pub fn set_filter_type(&mut self, index: u32) {
+        self.synth
+            .set_filter_type(FilterType::from_index(index as usize));
+    }
+
+    pub fn set_filter_mode(&mut self, index: u32) {
+        self.synth
+            .set_filter_mode(FilterMode::from_index(index as usize));
+    }
+
+    pub fn set_filter_cutoff(&mut self, hz: f32) {
+        self.synth.set_filter_cutoff(hz);
+    }
+
+    pub fn set_filter_resonance(&mut self, v: f32) {
+        self.synth.set_filter_resonance(v);
+    }
+
+    pub fn set_filter_drive(&mut self, v: f32) {
+        self.synth.set_filter_drive(v);
+    }
+
+    pub fn set_filter_keytrack(&mut self, v: f32) {
+        self.synth.set_filter_keytrack(v);
+    }
+
+    pub fn set_filter_env_amount(&mut self, v: f32) {
+        self.synth.set_filter_env_amount(v);
+    }
+
+    pub fn set_filter_delay(&mut self, v: f32) {
+        self.synth.set_filter_delay(v);
+    }
+
+    pub fn set_filter_hold(&mut self, v: f32) {
+        self.synth.set_filter_hold(v);
+    }
+
+    pub fn set_filter_envelope_mode(&mut self, index: u32) {
+        self.synth
+            .set_filter_envelope_mode(EnvelopeMode::from_index(index as usize));
+    }
+
+    pub fn set_filter_retrigger_mode(&mut self, index: u32) {
+        self.synth
+            .set_filter_retrigger_mode(RetriggerMode::from_index(index as usize));
+    }
+
+    pub fn set_filter_attack_curve(&mut self, v: f32) {
+        self.synth.set_filter_attack_curve(v);
+    }
+
+    pub fn set_filter_decay_curve(&mut self, v: f32) {
+        self.synth.set_filter_decay_curve(v);
+    }
+
+    pub fn set_filter_release_curve(&mut self, v: f32) {
+        self.synth.set_filter_release_curve(v);
+    }
+
+    pub fn set_filter_attack(&mut self, v: f32) {
+        self.synth.set_filter_attack(v);
+    }
+
+    pub fn set_filter_decay(&mut self, v: f32) {
+        self.synth.set_filter_decay(v);
+    }
+
+    pub fn set_filter_sustain(&mut self, v: f32) {
+        self.synth.set_filter_sustain(v);
+    }
+
+    pub fn set_filter_release(&mut self, v: f32) {
+        self.synth.set_filter_release(v);
+    }
+
+    pub fn set_lfo_shape(&mut self, index: u32) {
+        self.synth.set_lfo_shape(LfoShape::from_index(index as usize));
+    }
+
+    pub fn set_lfo_trigger(&mut self, index: u32) {
+        self.synth
+            .set_lfo_trigger(LfoTrigger::from_index(index as usize));
+    }
+
+    pub fn set_lfo_timing(&mut self, index: u32) {
+        self.synth
+            .set_lfo_timing(LfoTiming::from_index(index as usize));
+    }
+
+    pub fn set_lfo_rate(&mut self, hz: f32) {
+        self.synth.set_lfo_rate(hz);
+    }
+
+    pub fn set_lfo_division(&mut self, index: u32) {
+        self.synth
+            .set_lfo_division(NoteDivision::from_index(index as usize));
+    }
+
+    pub fn set_lfo_to_pitch(&mut self, semitones: f32) {
+        self.synth.set_lfo_to_pitch(semitones);
+    }
+
+    pub fn set_lfo_to_amp(&mut self, v: f32) {
+        self.synth.set_lfo_to_amp(v);
+    }
+
+    pub fn set_lfo_to_pulse_width(&mut self, v: f32) {
+        self.synth.set_lfo_to_pulse_width(v);
+    }
+
+    /// Set one mod matrix slot. Out-of-range slots are ignored.
+    pub fn set_mod_slot(&mut self, slot: u32, source: u32, destination: u32, depth: f32) {
+        self.synth.set_mod_slot(
+            slot as usize,
+            ModSource::from_index(source as usize),
+            ModDestination::from_index(destination as usize),
+            depth,
+        );
+    }
+
+    /// Set one additive partial's amplitude. Out-of-range partials are
+    /// ignored.
+    pub fn set_partial(&mut self, partial: u32, amplitude: f32) {
+        self.synth.set_partial(partial as usize, amplitude);
+    }
+
     pub fn set_gain(&mut self, v: f32) {
         self.synth.set_gain(v);
     }
 
+    pub fn set_pan(&mut self, v: f32) {
+        self.synth.set_pan(v);
+    }
+
+    pub fn set_delay(&mut self, v: f32) {
+        self.synth.set_delay(v);
+    }
+
+    pub fn set_hold(&mut self, v: f32) {
+        self.synth.set_hold(v);
+    }
+
+    pub fn set_envelope_mode(&mut self, index: u32) {
+        self.synth
+            .set_envelope_mode(EnvelopeMode::from_index(index as usize));
+    }
+
+    pub fn set_retrigger_mode(&mut self, index: u32) {
+        self.synth
+            .set_retrigger_mode(RetriggerMode::from_index(index as usize));
+    }
+
+    pub fn set_attack_curve(&mut self, v: f32) {
+        self.synth.set_attack_curve(v);
+    }
+
+    pub fn set_decay_curve(&mut self, v: f32) {
+        self.synth.set_decay_curve(v);
+    }
+
+    pub fn set_release_curve(&mut self, v: f32) {
+        self.synth.set_release_curve(v);
+    }
+
     pub fn set_attack(&mut self, v: f32) {
         self.synth.set_attack(v);
     }
@@ -68,22 +483,24 @@ impl WasmSynth {
 
     /// Process 128 samples of audio and return them as a Float32Array.
     /// wasm-bindgen converts Vec<f32> to a JS Float32Array automatically.
+    ///
+    /// The samples are the stereo mix folded down to mono.
     pub fn process_audio(&mut self) -> Vec<f32> {
-        self.synth.process(&mut self.audio_buf);
-
-        // Accumulate samples into the visualization buffer
-        for &sample in self.audio_buf.iter() {
-            self.vis_buffer[self.vis_write_pos] = sample;
-            self.vis_write_pos += 1;
-            if self.vis_write_pos >= VIS_BUFFER_SIZE {
-                self.vis_write_pos = 0;
-                self.vis_ready = true;
-            }
+        self.render();
+        let (left, right) = self.stereo_buf.split_at(RENDER_QUANTUM);
+        for ((out, &l), &r) in self.audio_buf.iter_mut().zip(left).zip(right) {
+            *out = (l + r) * 0.5;
         }
-
         self.audio_buf.clone()
     }
 
+    /// Process 128 stereo frames and return them planar: 128 left samples
+    /// followed by 128 right samples.
+    pub fn process_audio_stereo(&mut self) -> Vec<f32> {
+        self.render();
+        self.stereo_buf.clone()
+    }
+
     /// Returns true if a full visualization buffer is ready, then clears the flag.
     pub fn vis_ready(&mut self) -> bool {
         let ready = self.vis_ready;
@@ -95,6 +512,32 @@ impl WasmSynth {
     pub fn get_vis_data(&self) -> Vec<f32> {
         self.vis_buffer.clone()
     }
+
+    /// Returns a copy of the stereo visualization buffer: the left channel
+    /// followed by the right.
+    pub fn get_vis_data_stereo(&self) -> Vec<f32> {
+        self.vis_stereo.clone()
+    }
+}
+
+impl WasmSynth {
+    /// Render one quantum into `stereo_buf` and feed the visualization buffer.
+    fn render(&mut self) {
+        let (left, right) = self.stereo_buf.split_at_mut(RENDER_QUANTUM);
+        self.synth.process_stereo(left, right);
+
+        // Accumulate into the visualization buffers
+        for (&l, &r) in left.iter().zip(right.iter()) {
+            self.vis_buffer[self.vis_write_pos] = (l + r) * 0.5;
+            self.vis_stereo[self.vis_write_pos] = l;
+            self.vis_stereo[VIS_BUFFER_SIZE + self.vis_write_pos] = r;
+            self.vis_write_pos += 1;
+            if self.vis_write_pos >= VIS_BUFFER_SIZE {
+                self.vis_write_pos = 0;
+                self.vis_ready = true;
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -150,6 +593,129 @@ mod tests {
         assert_eq!(output.len(), RENDER_QUANTUM);
     }
 
+    #[test]
+    fn process_audio_stereo_returns_two_planar_channels() {
+        let mut s = WasmSynth::new();
+        s.prepare(44100.0);
+        s.note_on(60, 0.8);
+        let output = s.process_audio_stereo();
+        assert_eq!(output.len(), 2 * RENDER_QUANTUM);
+        // Without unison spread both channels carry the same signal
+        let (left, right) = output.split_at(RENDER_QUANTUM);
+        assert_eq!(left, right);
+        assert!(left.iter().any(|&x| x != 0.0));
+    }
+
+    #[test]
+    fn unison_spread_separates_channels() {
+        let mut s = WasmSynth::new();
+        s.prepare(44100.0);
+        s.set_osc_type(3); // Saw
+        s.set_unison_voices(5);
+        s.set_unison_detune(30.0);
+        s.set_unison_spread(1.0);
+        s.set_unison_random_phase(true);
+        s.note_on(60, 0.8);
+        let output = s.process_audio_stereo();
+        let (left, right) = output.split_at(RENDER_QUANTUM);
+        assert_ne!(left, right);
+    }
+
+    #[test]
+    fn fm_matches_plugin_synth_bit_for_bit() {
+        // The plugin drives a dsp_core::Synth directly; the worklet must
+        // render the same bits from the same settings and notes
+        let mut s = WasmSynth::new();
+        let mut synth = Synth::new();
+        s.prepare(48000.0);
+        synth.prepare(48000.0);
+        s.set_osc_type(0); // Sine
+        synth.set_oscillator_type(OscillatorType::Sine);
+        s.set_fm_enabled(true);
+        synth.set_fm_enabled(true);
+        s.set_fm_ratio(1.41);
+        synth.set_fm_ratio(1.41);
+        s.set_fm_index(6.0);
+        synth.set_fm_index(6.0);
+        s.set_fm_feedback(0.5);
+        synth.set_fm_feedback(0.5);
+        s.note_on(69, 0.9);
+        synth.note_on(69, 0.9);
+        let mut left = [0.0; RENDER_QUANTUM];
+        let mut right = [0.0; RENDER_QUANTUM];
+        for _ in 0..8 {
+            let output = s.process_audio_stereo();
+            synth.process_stereo(&mut left, &mut right);
+            assert_eq!(&output[..RENDER_QUANTUM], &left[..]);
+            assert_eq!(&output[RENDER_QUANTUM..], &right[..]);
+        }
+    }
+
+    #[test]
+    fn load_wavetable_accepts_mipmaps_and_rejects_garbage() {
+        let mut s = WasmSynth::new();
+        s.prepare(44100.0);
+        assert!(!s.load_wavetable(1, &[0.0; 100]));
+
+        // One silent frame, built as the page builds it
+        let table = Wavetable::from_frames(&[0.0; 2048]).unwrap();
+        let mipmaps = table.mipmaps();
+        assert!(!s.load_wavetable(2, &mipmaps));
+        assert!(s.load_wavetable(1, &mipmaps));
+
+        s.set_osc_type(4); // Wavetable
+        s.set_wavetable(3); // Custom
+        s.note_on(60, 0.8);
+        assert!(s.process_audio().iter().all(|&x| x == 0.0));
+        s.set_wavetable(0); // Basic
+        assert!(s.process_audio().iter().any(|&x| x != 0.0));
+    }
+
+    #[test]
+    fn set_unison_out_of_range_does_not_panic() {
+        let mut s = WasmSynth::new();
+        s.prepare(44100.0);
+        s.set_unison_voices(0);
+        s.set_unison_voices(1000);
+        s.set_unison_detune(-5.0);
+        s.set_unison_detune(1e6);
+        s.set_unison_spread(7.0);
+        s.note_on(60, 0.8);
+        let output = s.process_audio_stereo();
+        assert!(output.iter().all(|x| x.is_finite()));
+    }
+
+    #[test]
+    fn sub_and_noise_out_of_range_do_not_panic() {
+        let mut s = WasmSynth::new();
+        s.prepare(44100.0);
+        s.set_sub_level(5.0);
+        s.set_sub_octave(99);
+        s.set_sub_shape(99);
+        s.set_noise_level(-1.0);
+        s.set_noise_level(5.0);
+        s.set_noise_type(99);
+        s.note_on(60, 0.8);
+        let output = s.process_audio_stereo();
+        assert!(output.iter().all(|x| x.is_finite()));
+    }
+
+    #[test]
+    fn plucked_string_out_of_range_does_not_panic() {
+        let mut s = WasmSynth::new();
+        s.prepare(44100.0);
+        s.set_engine(99);
+        s.set_pluck_excitation(99);
+        s.set_pluck_damping(-1.0);
+        s.set_pluck_brightness(5.0);
+        s.set_pluck_decay(1000.0);
+        s.note_on(127, 0.8);
+        s.note_on(0, 0.8);
+        let output = s.process_audio_stereo();
+        assert!(output.iter().all(|x| x.is_finite()));
+        assert!(output.iter().any(|&x| x != 0.0));
+    }
+
     #[test]
     fn process_audio_silent_when_no_note() {
         let mut s = WasmSynth::new();
@@ -165,7 +731,7 @@ mod tests {
     fn process_audio_produces_sound_after_note_on() {
         let mut s = WasmSynth::new();
         s.prepare(44100.0);
-        s.note_on(60); // C4
+        s.note_on(60, 0.8); // C4
         let output = s.process_audio();
         let max = output.iter().fold(0.0f32, |a, &b| a.max(b.abs()));
         assert!(max > 0.0, "expected non-silent output after note_on");
@@ -177,7 +743,7 @@ mod tests {
         s.prepare(44100.0);
         s.set_attack(0.0);
         s.set_release(0.001); // Very short release
-        s.note_on(60);
+        s.note_on(60, 0.8);
         s.process_audio(); // Play a few quanta
         s.note_off(60);
         // Process enough quanta for the release to finish
@@ -193,6 +759,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn envelope_delay_silences_first_quantum() {
+        let mut s = WasmSynth::new();
+        s.prepare(44100.0);
+        s.set_delay(0.01); // 441 samples, more than one quantum
+        s.set_filter_delay(0.01);
+        s.set_fm_delay(0.01);
+        s.note_on(60, 0.8);
+        assert!(s.process_audio().iter().all(|&x| x == 0.0));
+        s.process_audio();
+        s.process_audio();
+        assert!(s.process_audio().iter().any(|&x| x != 0.0));
+    }
+
+    #[test]
+    fn one_shot_envelope_keeps_sounding_after_note_off() {
+        let mut s = WasmSynth::new();
+        s.prepare(44100.0);
+        s.set_envelope_mode(2); // One Shot
+        s.set_filter_envelope_mode(1);
+        s.set_fm_envelope_mode(99);
+        s.set_release(0.001);
+        s.set_hold(0.1);
+        s.set_filter_hold(-1.0);
+        s.set_fm_hold(1e6);
+        s.note_on(60, 0.8);
+        s.process_audio();
+        s.note_off(60);
+        // Still holding at full level long after the short release
+        for _ in 0..10 {
+            s.process_audio();
+        }
+        let max = s.process_audio().iter().fold(0.0f32, |a, &b| a.max(b.abs()));
+        assert!(max > 0.01, "expected the hold to keep sounding, got max={}", max);
+    }
+
+    #[test]
+    fn retrigger_mode_changes_repeated_note() {
+        let render = |mode: u32| {
+            let mut s = WasmSynth::new();
+            s.prepare(44100.0);
+            s.set_retrigger_mode(mode);
+            s.set_filter_retrigger_mode(mode);
+            s.set_fm_retrigger_mode(mode);
+            s.note_on(60, 0.8);
+            for _ in 0..100 {
+                s.process_audio();
+            }
+            s.note_on(60, 0.8);
+            s.process_audio()
+        };
+        let resume = render(0);
+        assert!(resume.iter().all(|x| x.is_finite()));
+        assert_ne!(render(1), resume);
+        assert_ne!(render(2), resume);
+        assert_eq!(render(99), render(2));
+    }
+
     // --- Vis buffer ring buffer ---
 
     #[test]
@@ -274,11 +898,27 @@ mod tests {
         assert_eq!(data.len(), VIS_BUFFER_SIZE);
     }
 
+    #[test]
+    fn get_vis_data_stereo_holds_both_channels() {
+        let mut s = WasmSynth::new();
+        s.prepare(44100.0);
+        s.set_pan(-1.0);
+        s.note_on(60, 0.8);
+        for _ in 0..16 {
+            s.process_audio();
+        }
+        let data = s.get_vis_data_stereo();
+        assert_eq!(data.len(), 2 * VIS_BUFFER_SIZE);
+        let (left, right) = data.split_at(VIS_BUFFER_SIZE);
+        assert!(left.iter().any(|&x| x != 0.0));
+        assert!(right.iter().all(|&x| x == 0.0), "hard left pan leaves right silent");
+    }
+
     #[test]
     fn vis_data_contains_audio_after_processing() {
         let mut s = WasmSynth::new();
         s.prepare(44100.0);
-        s.note_on(60);
+        s.note_on(60, 0.8);
         for _ in 0..16 {
             s.process_audio();
         }
@@ -298,6 +938,87 @@ mod tests {
         s.set_osc_type(3); // Saw
     }
 
+    #[test]
+    fn set_voice_count_does_not_panic() {
+        let mut s = WasmSynth::new();
+        s.set_voice_count(0);
+        s.set_voice_count(8);
+        s.set_voice_count(1000);
+    }
+
+    #[test]
+    fn chord_sounds_louder_with_multiple_voices() {
+        let peak = |voices: u32| {
+            let mut s = WasmSynth::new();
+            s.prepare(44100.0);
+            s.set_voice_count(voices);
+            s.note_on(60, 0.8);
+            s.note_on(64, 0.8);
+            s.note_on(67, 0.8);
+            let mut max = 0.0f32;
+            for _ in 0..16 {
+                let out = s.process_audio();
+                max = out.iter().fold(max, |a, &b| a.max(b.abs()));
+            }
+            max
+        };
+        assert!(
+            peak(8) > peak(1),
+            "three voices should peak higher than one"
+        );
+    }
+
+    #[test]
+    fn set_steal_policy_does_not_panic() {
+        let mut s = WasmSynth::new();
+        for index in 0..8 {
+            s.set_steal_policy(index);
+        }
+    }
+
+    #[test]
+    fn refuse_policy_keeps_first_notes() {
+        let mut s = WasmSynth::new();
+        s.prepare(44100.0);
+        s.set_voice_count(1);
+        s.set_steal_policy(4); // Refuse
+        s.note_on(60, 0.8);
+        s.note_on(72, 0.8);
+        // The refused note's note_off must not release the held one
+        s.note_off(72);
+        for _ in 0..16 {
+            s.process_audio();
+        }
+        let out = s.process_audio();
+        let max = out.iter().fold(0.0f32, |a, &b| a.max(b.abs()));
+        assert!(max > 0.01, "first note should still be held, max={}", max);
+    }
+
+    #[test]
+    fn mono_mode_falls_back_to_held_note() {
+        let mut s = WasmSynth::new();
+        s.prepare(44100.0);
+        s.set_voice_mode(1); // Mono
+        s.set_legato(true);
+        s.note_on(60, 0.8);
+        s.note_on(62, 0.8);
+        s.note_off(62);
+        for _ in 0..16 {
+            s.process_audio();
+        }
+        let out = s.process_audio();
+        let max = out.iter().fold(0.0f32, |a, &b| a.max(b.abs()));
+        assert!(max > 0.01, "held note should keep sounding, max={}", max);
+    }
+
+    #[test]
+    fn set_note_priority_does_not_panic() {
+        let mut s = WasmSynth::new();
+        for index in 0..5 {
+            s.set_note_priority(index);
+        }
+    }
+
     #[test]
     fn set_gain_does_not_panic() {
         let mut s = WasmSynth::new();
@@ -322,7 +1043,7 @@ mod tests {
             let mut s = WasmSynth::new();
             s.prepare(44100.0);
             s.set_osc_type(osc);
-            s.note_on(69); // A4
+            s.note_on(69, 0.8); // A4
             // Process a few quanta to get past the attack transient
             for _ in 0..4 {
                 s.process_audio();
@@ -347,7 +1068,7 @@ mod tests {
         let mut s = WasmSynth::new();
         s.prepare(44100.0);
         s.set_gain(0.0);
-        s.note_on(60);
+        s.note_on(60, 0.8);
         let output = s.process_audio();
         assert!(
             output.iter().all(|&x| x == 0.0),
@@ -362,9 +1083,241 @@ mod tests {
         let mut s = WasmSynth::new();
         s.prepare(44100.0);
         for note in 0..=127u8 {
-            s.note_on(note);
+            s.note_on(note, 0.8);
             s.process_audio();
             s.note_off(note);
         }
     }
+
+    #[test]
+    fn note_on_velocity_scales_output() {
+        let peak = |velocity: f32| {
+            let mut s = WasmSynth::new();
+            s.prepare(44100.0);
+            s.note_on(69, velocity);
+            let mut max = 0.0f32;
+            for _ in 0..8 {
+                max = s.process_audio().iter().fold(max, |a, &b| a.max(b.abs()));
+            }
+            max
+        };
+        assert!(peak(0.3) < peak(1.0) * 0.5);
+    }
+
+    #[test]
+    fn fixed_velocity_curve_ignores_velocity() {
+        let render = |velocity: f32| {
+            let mut s = WasmSynth::new();
+            s.prepare(44100.0);
+            s.set_velocity_curve(2); // Fixed
+            s.note_on(69, velocity);
+            s.process_audio()
+        };
+        assert_eq!(render(0.2), render(1.0));
+    }
+
+    #[test]
+    fn glide_changes_pitch_of_second_note() {
+        let render = |glide: f32| {
+            let mut s = WasmSynth::new();
+            s.prepare(44100.0);
+            s.set_glide_time(glide);
+            s.note_on(48, 0.8);
+            s.note_off(48);
+            s.note_on(72, 0.8);
+            s.process_audio()
+        };
+        assert_ne!(render(0.0), render(0.5));
+    }
+
+    #[test]
+    fn set_glide_options_do_not_panic() {
+        let mut s = WasmSynth::new();
+        s.set_glide_mode(1);
+        s.set_glide_timing(1);
+        s.set_glide_mode(42);
+        s.set_glide_timing(42);
+    }
+
+    #[test]
+    fn filter_cutoff_changes_output() {
+        let render = |cutoff: f32| {
+            let mut s = WasmSynth::new();
+            s.prepare(44100.0);
+            s.set_osc_type(3); // Saw
+            s.set_filter_cutoff(cutoff);
+            s.note_on(57, 0.8);
+            s.process_audio()
+        };
+        assert_ne!(render(300.0), render(20000.0));
+    }
+
+    #[test]
+    fn set_filter_params_do_not_panic() {
+        let mut s = WasmSynth::new();
+        s.prepare(44100.0);
+        s.set_filter_type(99);
+        s.set_filter_mode(99);
+        s.set_filter_drive(1.0);
+        s.set_filter_resonance(1.0);
+        s.set_filter_keytrack(1.0);
+        s.set_filter_cutoff(1e9);
+        s.note_on(60, 0.8);
+        let output = s.process_audio();
+        assert!(output.iter().all(|x| x.is_finite()));
+    }
+
+    #[test]
+    fn filter_envelope_changes_output() {
+        let render = |amount: f32| {
+            let mut s = WasmSynth::new();
+            s.prepare(44100.0);
+            s.set_osc_type(3); // Saw
+            s.set_filter_cutoff(300.0);
+            s.set_filter_attack(0.001);
+            s.set_filter_decay(0.5);
+            s.set_filter_env_amount(amount);
+            s.note_on(57, 0.8);
+            s.process_audio()
+        };
+        assert_ne!(render(0.0), render(4.0));
+    }
+
+    #[test]
+    fn lfo_tremolo_changes_output() {
+        let render = |depth: f32| {
+            let mut s = WasmSynth::new();
+            s.prepare(44100.0);
+            s.set_osc_type(3); // Saw
+            s.set_lfo_shape(3); // Square
+            s.set_lfo_rate(50.0);
+            s.set_lfo_to_amp(depth);
+            s.note_on(57, 0.8);
+            let mut out = Vec::new();
+            for _ in 0..8 {
+                out.extend(s.process_audio());
+            }
+            out
+        };
+        assert_ne!(render(0.0), render(1.0));
+    }
+
+    #[test]
+    fn mod_slot_changes_output() {
+        let render = |depth: f32| {
+            let mut s = WasmSynth::new();
+            s.prepare(44100.0);
+            s.set_osc_type(3); // Saw
+            s.set_mod_slot(0, 5, 2, depth); // Key Track > Gain
+            s.note_on(96, 0.8);
+            let mut out = Vec::new();
+            for _ in 0..8 {
+                out.extend(s.process_audio());
+            }
+            out
+        };
+        assert_ne!(render(0.0), render(1.0));
+    }
+
+    #[test]
+    fn set_mod_slot_out_of_range_does_not_panic() {
+        let mut s = WasmSynth::new();
+        s.prepare(44100.0);
+        s.set_mod_slot(99, 1, 1, 1.0);
+        s.set_mod_slot(0, 99, 99, 1e6);
+        s.note_on(60, 0.8);
+        let output = s.process_audio();
+        assert!(output.iter().all(|x| x.is_finite()));
+    }
+
+    #[test]
+    fn additive_partials_change_output() {
+        let render = |amplitude: f32| {
+            let mut s = WasmSynth::new();
+            s.prepare(44100.0);
+            s.set_osc_type(5); // Additive
+            s.set_partial(2, amplitude);
+            s.set_partial(99, 1.0);
+            s.note_on(60, 0.8);
+            s.process_audio()
+        };
+        let with_third = render(0.5);
+        assert!(with_third.iter().all(|x| x.is_finite()));
+        assert_ne!(render(0.0), with_third);
+    }
+
+    #[test]
+    fn envelope_curves_change_output() {
+        let render = |curve: f32| {
+            let mut s = WasmSynth::new();
+            s.prepare(44100.0);
+            s.set_attack(0.01);
+            s.set_attack_curve(curve);
+            s.set_decay_curve(curve);
+            s.set_release_curve(curve);
+            s.set_filter_attack_curve(curve);
+            s.set_filter_decay_curve(curve);
+            s.set_filter_release_curve(curve);
+            s.set_fm_attack_curve(curve);
+            s.set_fm_decay_curve(curve);
+            s.set_fm_release_curve(curve);
+            s.note_on(60, 0.8);
+            s.process_audio()
+        };
+        let curved = render(1.0);
+        assert!(curved.iter().all(|x| x.is_finite()));
+        assert_ne!(render(0.0), curved);
+        // Out-of-range curves are clamped
+        assert_eq!(render(1e6), curved);
+    }
+
+    #[test]
+    fn gain_change_between_quanta_is_smoothed() {
+        let max_step = |samples: &[f32]| {
+            samples.windows(2).map(|w| (w[1] - w[0]).abs()).fold(0.0, f32::max)
+        };
+        let mut s = WasmSynth::new();
+        s.prepare(44100.0);
+        s.set_osc_type(0);
+        s.set_gain(0.1);
+        s.note_on(45, 1.0);
+        let mut before = Vec::new();
+        for _ in 0..40 {
+            before = s.process_audio();
+        }
+        s.set_gain(1.0);
+        let mut output = before;
+        for _ in 0..4 {
+            output.extend(s.process_audio());
+        }
+        let settled = max_step(&s.process_audio());
+        let step = max_step(&output);
+        assert!(step < settled * 1.2 + 0.005, "step {} against {}", step, settled);
+    }
+
+    #[test]
+    fn set_lfo_params_do_not_panic() {
+        let mut s = WasmSynth::new();
+        s.prepare(44100.0);
+        s.set_lfo_shape(99);
+        s.set_lfo_trigger(99);
+        s.set_lfo_timing(99);
+        s.set_lfo_division(99);
+        s.set_lfo_rate(1e6);
+        s.set_lfo_to_pitch(1e6);
+        s.set_lfo_to_amp(-1.0);
+        s.set_lfo_to_pulse_width(2.0);
+        s.note_on(60, 0.8);
+        let output = s.process_audio();
+        assert!(output.iter().all(|x| x.is_finite()));
+    }
+
+    #[test]
+    fn set_velocity_routing_does_not_panic() {
+        let mut s = WasmSynth::new();
+        s.set_velocity_amount(0.5);
+        s.set_velocity_to_attack(1.0);
+        s.set_velocity_to_brightness(1.0);
+        s.set_velocity_curve(99);
+    }
 }
@@ -29,7 +29,7 @@ class SynthProcessor extends AudioWorkletProcessor {
 
     switch (msg.type) {
       case "noteOn":
-        this.wasm.wasmsynth_note_on(this.synthPtr, msg.note);
+        this.wasm.wasmsynth_note_on(this.synthPtr, msg.note, msg.velocity);
         break;
       case "noteOff":
         this.wasm.wasmsynth_note_off(this.synthPtr, msg.note);
@@ -37,6 +37,16 @@ class SynthProcessor extends AudioWorkletProcessor {
       case "param":
         this.setParam(msg.name, msg.value);
         break;
+      case "modSlot":
+        this.wasm.wasmsynth_set_mod_slot(
+          this.synthPtr, msg.slot, msg.source, msg.destination, msg.depth);
+        break;
+      case "partial":
+        this.wasm.wasmsynth_set_partial(this.synthPtr, msg.partial, msg.amplitude);
+        break;
+      case "loadWavetable":
+        this.loadWavetable(msg.frames, msg.mipmaps);
+        break;
     }
   }
 
@@ -89,15 +99,288 @@ class SynthProcessor extends AudioWorkletProcessor {
     return copy;
   }
 
+  // Copy a wavetable's mipmaps, built on the main thread, into WASM memory
+  // and load them as the custom table. The Rust side takes ownership of the
+  // copy and frees it.
+  loadWavetable(frames, mipmaps) {
+    const ptr = this.wasm.__wbindgen_malloc(mipmaps.length * 4, 4) >>> 0;
+    new Float32Array(this.wasm.memory.buffer, ptr, mipmaps.length).set(mipmaps);
+    const ok = this.wasm.wasmsynth_load_wavetable(this.synthPtr, frames, ptr, mipmaps.length);
+    if (!ok) {
+      this.port.postMessage({ type: "error", message: "Invalid wavetable data" });
+    }
+  }
+
   setParam(name, value) {
     if (!this.wasm) return;
     switch (name) {
       case "osc_type":
         this.wasm.wasmsynth_set_osc_type(this.synthPtr, value);
         break;
+      case "engine":
+        this.wasm.wasmsynth_set_engine(this.synthPtr, value);
+        break;
+      case "pulse_width":
+        this.wasm.wasmsynth_set_pulse_width(this.synthPtr, value);
+        break;
+      case "wavetable":
+        this.wasm.wasmsynth_set_wavetable(this.synthPtr, value);
+        break;
+      case "wavetable_position":
+        this.wasm.wasmsynth_set_wavetable_position(this.synthPtr, value);
+        break;
+      case "osc2_type":
+        this.wasm.wasmsynth_set_osc2_type(this.synthPtr, value);
+        break;
+      case "osc2_coarse":
+        this.wasm.wasmsynth_set_osc2_coarse(this.synthPtr, value);
+        break;
+      case "osc2_fine":
+        this.wasm.wasmsynth_set_osc2_fine(this.synthPtr, value);
+        break;
+      case "osc2_level":
+        this.wasm.wasmsynth_set_osc2_level(this.synthPtr, value);
+        break;
+      case "osc2_sync":
+        this.wasm.wasmsynth_set_osc2_sync(this.synthPtr, value ? 1 : 0);
+        break;
+      case "ring_mix":
+        this.wasm.wasmsynth_set_ring_mix(this.synthPtr, value);
+        break;
+      case "linear_fm":
+        this.wasm.wasmsynth_set_linear_fm(this.synthPtr, value);
+        break;
+      case "sub_level":
+        this.wasm.wasmsynth_set_sub_level(this.synthPtr, value);
+        break;
+      case "sub_octave":
+        this.wasm.wasmsynth_set_sub_octave(this.synthPtr, value);
+        break;
+      case "sub_shape":
+        this.wasm.wasmsynth_set_sub_shape(this.synthPtr, value);
+        break;
+      case "noise_level":
+        this.wasm.wasmsynth_set_noise_level(this.synthPtr, value);
+        break;
+      case "noise_type":
+        this.wasm.wasmsynth_set_noise_type(this.synthPtr, value);
+        break;
+      case "fm_enabled":
+        this.wasm.wasmsynth_set_fm_enabled(this.synthPtr, value ? 1 : 0);
+        break;
+      case "fm_ratio":
+        this.wasm.wasmsynth_set_fm_ratio(this.synthPtr, value);
+        break;
+      case "fm_fixed":
+        this.wasm.wasmsynth_set_fm_fixed(this.synthPtr, value ? 1 : 0);
+        break;
+      case "fm_fixed_frequency":
+        this.wasm.wasmsynth_set_fm_fixed_frequency(this.synthPtr, value);
+        break;
+      case "fm_index":
+        this.wasm.wasmsynth_set_fm_index(this.synthPtr, value);
+        break;
+      case "fm_feedback":
+        this.wasm.wasmsynth_set_fm_feedback(this.synthPtr, value);
+        break;
+      case "fm_delay":
+        this.wasm.wasmsynth_set_fm_delay(this.synthPtr, value);
+        break;
+      case "fm_hold":
+        this.wasm.wasmsynth_set_fm_hold(this.synthPtr, value);
+        break;
+      case "fm_envelope_mode":
+        this.wasm.wasmsynth_set_fm_envelope_mode(this.synthPtr, value);
+        break;
+      case "fm_retrigger_mode":
+        this.wasm.wasmsynth_set_fm_retrigger_mode(this.synthPtr, value);
+        break;
+      case "fm_attack_curve":
+        this.wasm.wasmsynth_set_fm_attack_curve(this.synthPtr, value);
+        break;
+      case "fm_decay_curve":
+        this.wasm.wasmsynth_set_fm_decay_curve(this.synthPtr, value);
+        break;
+      case "fm_release_curve":
+        this.wasm.wasmsynth_set_fm_release_curve(this.synthPtr, value);
+        break;
+      case "fm_attack":
+        this.wasm.wasmsynth_set_fm_attack(this.synthPtr, value);
+        break;
+      case "fm_decay":
+        this.wasm.wasmsynth_set_fm_decay(this.synthPtr, value);
+        break;
+      case "fm_sustain":
+        this.wasm.wasmsynth_set_fm_sustain(this.synthPtr, value);
+        break;
+      case "fm_release":
+        this.wasm.wasmsynth_set_fm_release(this.synthPtr, value);
+        break;
+      case "pluck_excitation":
+        this.wasm.wasmsynth_set_pluck_excitation(this.synthPtr, value);
+        break;
+      case "pluck_damping":
+        this.wasm.wasmsynth_set_pluck_damping(this.synthPtr, value);
+        break;
+      case "pluck_brightness":
+        this.wasm.wasmsynth_set_pluck_brightness(this.synthPtr, value);
+        break;
+      case "pluck_decay":
+        this.wasm.wasmsynth_set_pluck_decay(this.synthPtr, value);
+        break;
+      case "unison_voices":
+        this.wasm.wasmsynth_set_unison_voices(this.synthPtr, value);
+        break;
+      case "unison_detune":
+        this.wasm.wasmsynth_set_unison_detune(this.synthPtr, value);
+        break;
+      case "unison_spread":
+        this.wasm.wasmsynth_set_unison_spread(this.synthPtr, value);
+        break;
+      case "unison_random_phase":
+        this.wasm.wasmsynth_set_unison_random_phase(this.synthPtr, value ? 1 : 0);
+        break;
+      case "voices":
+        this.wasm.wasmsynth_set_voice_count(this.synthPtr, value);
+        break;
+      case "steal_policy":
+        this.wasm.wasmsynth_set_steal_policy(this.synthPtr, value);
+        break;
+      case "voice_mode":
+        this.wasm.wasmsynth_set_voice_mode(this.synthPtr, value);
+        break;
+      case "note_priority":
+        this.wasm.wasmsynth_set_note_priority(this.synthPtr, value);
+        break;
+      case "legato":
+        this.wasm.wasmsynth_set_legato(this.synthPtr, value ? 1 : 0);
+        break;
+      case "glide_time":
+        this.wasm.wasmsynth_set_glide_time(this.synthPtr, value);
+        break;
+      case "glide_mode":
+        this.wasm.wasmsynth_set_glide_mode(this.synthPtr, value);
+        break;
+      case "glide_timing":
+        this.wasm.wasmsynth_set_glide_timing(this.synthPtr, value);
+        break;
+      case "velocity_curve":
+        this.wasm.wasmsynth_set_velocity_curve(this.synthPtr, value);
+        break;
+      case "velocity_amount":
+        this.wasm.wasmsynth_set_velocity_amount(this.synthPtr, value);
+        break;
+      case "velocity_to_attack":
+        this.wasm.wasmsynth_set_velocity_to_attack(this.synthPtr, value);
+        break;
+      case "velocity_to_brightness":
+        this.wasm.wasmsynth_set_velocity_to_brightness(this.synthPtr, value);
+        break;
+      case "filter_type":
+        this.wasm.wasmsynth_set_filter_type(this.synthPtr, value);
+        break;
+      case "filter_mode":
+        this.wasm.wasmsynth_set_filter_mode(this.synthPtr, value);
+        break;
+      case "filter_cutoff":
+        this.wasm.wasmsynth_set_filter_cutoff(this.synthPtr, value);
+        break;
+      case "filter_resonance":
+        this.wasm.wasmsynth_set_filter_resonance(this.synthPtr, value);
+        break;
+      case "filter_drive":
+        this.wasm.wasmsynth_set_filter_drive(this.synthPtr, value);
+        break;
+      case "filter_keytrack":
+        this.wasm.wasmsynth_set_filter_keytrack(this.synthPtr, value);
+        break;
+      case "filter_env_amount":
+        this.wasm.wasmsynth_set_filter_env_amount(this.synthPtr, value);
+        break;
+      case "filter_delay":
+        this.wasm.wasmsynth_set_filter_delay(this.synthPtr, value);
+        break;
+      case "filter_hold":
+        this.wasm.wasmsynth_set_filter_hold(this.synthPtr, value);
+        break;
+      case "filter_envelope_mode":
+        this.wasm.wasmsynth_set_filter_envelope_mode(this.synthPtr, value);
+        break;
+      case "filter_retrigger_mode":
+        this.wasm.wasmsynth_set_filter_retrigger_mode(this.synthPtr, value);
+        break;
+      case "filter_attack_curve":
+        this.wasm.wasmsynth_set_filter_attack_curve(this.synthPtr, value);
+        break;
+      case "filter_decay_curve":
+        this.wasm.wasmsynth_set_filter_decay_curve(this.synthPtr, value);
+        break;
+      case "filter_release_curve":
+        this.wasm.wasmsynth_set_filter_release_curve(this.synthPtr, value);
+        break;
+      case "filter_attack":
+        this.wasm.wasmsynth_set_filter_attack(this.synthPtr, value);
+        break;
+      case "filter_decay":
+        this.wasm.wasmsynth_set_filter_decay(this.synthPtr, value);
+        break;
+      case "filter_sustain":
+        this.wasm.wasmsynth_set_filter_sustain(this.synthPtr, value);
+        break;
+      case "filter_release":
+        this.wasm.wasmsynth_set_filter_release(this.synthPtr, value);
+        break;
+      case "lfo_shape":
+        this.wasm.wasmsynth_set_lfo_shape(this.synthPtr, value);
+        break;
+      case "lfo_trigger":
+        this.wasm.wasmsynth_set_lfo_trigger(this.synthPtr, value);
+        break;
+      case "lfo_timing":
+        this.wasm.wasmsynth_set_lfo_timing(this.synthPtr, value);
+        break;
+      case "lfo_rate":
+        this.wasm.wasmsynth_set_lfo_rate(this.synthPtr, value);
+        break;
+      case "lfo_division":
+        this.wasm.wasmsynth_set_lfo_division(this.synthPtr, value);
+        break;
+      case "lfo_to_pitch":
+        this.wasm.wasmsynth_set_lfo_to_pitch(this.synthPtr, value);
+        break;
+      case "lfo_to_amp":
+        this.wasm.wasmsynth_set_lfo_to_amp(this.synthPtr, value);
+        break;
+      case "lfo_to_pulse_width":
+        this.wasm.wasmsynth_set_lfo_to_pulse_width(this.synthPtr, value);
+        break;
       case "gain":
         this.wasm.wasmsynth_set_gain(this.synthPtr, value);
         break;
+      case "pan":
+        this.wasm.wasmsynth_set_pan(this.synthPtr, value);
+        break;
+      case "delay":
+        this.wasm.wasmsynth_set_delay(this.synthPtr, value);
+        break;
+      case "hold":
+        this.wasm.wasmsynth_set_hold(this.synthPtr, value);
+        break;
+      case "envelope_mode":
+        this.wasm.wasmsynth_set_envelope_mode(this.synthPtr, value);
+        break;
+      case "retrigger_mode":
+        this.wasm.wasmsynth_set_retrigger_mode(this.synthPtr, value);
+        break;
+      case "attack_curve":
+        this.wasm.wasmsynth_set_attack_curve(this.synthPtr, value);
+        break;
+      case "decay_curve":
+        this.wasm.wasmsynth_set_decay_curve(this.synthPtr, value);
+        break;
+      case "release_curve":
+        this.wasm.wasmsynth_set_release_curve(this.synthPtr, value);
+        break;
       case "attack":
         this.wasm.wasmsynth_set_attack(this.synthPtr, value);
         break;
@@ -119,19 +402,29 @@ class SynthProcessor extends AudioWorkletProcessor {
     const output = outputs[0];
     if (!output || output.length === 0) return true;
 
-    const channel0 = output[0];
-
-    const ret = this.wasm.wasmsynth_process_audio(this.synthPtr);
+    // Planar stereo: one render quantum of left samples, then right
+    const ret = this.wasm.wasmsynth_process_audio_stereo(this.synthPtr);
     const samples = this.readF32Array(ret);
-    channel0.set(samples);
+    const frames = samples.length / 2;
+    const left = samples.subarray(0, frames);
+    const right = samples.subarray(frames);
 
-    for (let ch = 1; ch < output.length; ch++) {
-      output[ch].set(channel0);
+    if (output.length === 1) {
+      // Mono output: fold the channels down
+      for (let i = 0; i < frames; i++) {
+        output[0][i] = (left[i] + right[i]) * 0.5;
+      }
+    } else {
+      output[0].set(left);
+      output[1].set(right);
+      for (let ch = 2; ch < output.length; ch++) {
+        output[ch].fill(0);
+      }
     }
 
     const visReady = this.wasm.wasmsynth_vis_ready(this.synthPtr);
     if (visReady !== 0) {
-      const visRet = this.wasm.wasmsynth_get_vis_data(this.synthPtr);
+      const visRet = this.wasm.wasmsynth_get_vis_data_stereo(this.synthPtr);
       const visData = this.readF32Array(visRet);
       this.port.postMessage(visData, [visData.buffer]);
     }
@@ -1,18 +1,23 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
+use dsp_core::additive::MAX_PARTIALS;
+use dsp_core::params::WavetableBank;
+use dsp_core::wavetable::Wavetable;
 use eframe::egui;
 use synth_ui::{render_synth_ui, KeyboardEvent, UiState};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 
 use crate::audio_bridge::AudioBridge;
-use crate::web_controls::{WebControls, WebParams};
+use crate::web_controls::{DirtyFlags, WebControls, WebParams};
 
 pub struct SynthWebApp {
     state: UiState,
     params: WebParams,
     audio: Option<Rc<RefCell<AudioBridge>>>,
+    /// Latest visualization data from the worklet, planar stereo: the left
+    /// channel followed by the right.
     vis_samples: Vec<f32>,
     audio_started: bool,
     /// Shared buffer for receiving vis data from the worklet callback
@@ -26,7 +31,7 @@ impl SynthWebApp {
             state: UiState::new(),
             params: WebParams::default(),
             audio: None,
-            vis_samples: vec![0.0; 2048],
+            vis_samples: vec![0.0; 2 * 2048],
             audio_started: false,
             shared_vis: Rc::new(RefCell::new(None)),
         }
@@ -96,6 +101,8 @@ impl SynthWebApp {
             BRIDGE.with(|b| {
                 if let Some(bridge) = b.borrow_mut().take() {
                     self.audio = Some(bridge);
+                    // The worklet knows nothing of the UI yet
+                    self.params.dirty = DirtyFlags::all();
                 }
             });
         }
@@ -113,9 +120,281 @@ impl SynthWebApp {
         if d.osc_type {
             let _ = b.send_param("osc_type", p.osc_type as f64);
         }
+        if d.engine {
+            let _ = b.send_param("engine", p.engine as f64);
+        }
+        if d.pulse_width {
+            let _ = b.send_param("pulse_width", p.pulse_width as f64);
+        }
+        if d.wavetable {
+            let _ = b.send_param("wavetable", p.wavetable as f64);
+        }
+        if d.wavetable_position {
+            let _ = b.send_param("wavetable_position", p.wavetable_position as f64);
+        }
+        for partial in (0..MAX_PARTIALS).filter(|&i| d.partials & (1 << i) != 0) {
+            let _ = b.send_partial(partial, p.partials[partial]);
+        }
+        if d.osc2_type {
+            let _ = b.send_param("osc2_type", p.osc2_type as f64);
+        }
+        if d.osc2_coarse {
+            let _ = b.send_param("osc2_coarse", p.osc2_coarse as f64);
+        }
+        if d.osc2_fine {
+            let _ = b.send_param("osc2_fine", p.osc2_fine as f64);
+        }
+        if d.osc2_level {
+            let _ = b.send_param("osc2_level", p.osc2_level as f64);
+        }
+        if d.osc2_sync {
+            let _ = b.send_param("osc2_sync", if p.osc2_sync { 1.0 } else { 0.0 });
+        }
+        if d.ring_mix {
+            let _ = b.send_param("ring_mix", p.ring_mix as f64);
+        }
+        if d.linear_fm {
+            let _ = b.send_param("linear_fm", p.linear_fm as f64);
+        }
+        if d.sub_level {
+            let _ = b.send_param("sub_level", p.sub_level as f64);
+        }
+        if d.sub_octave {
+            let _ = b.send_param("sub_octave", p.sub_octave as f64);
+        }
+        if d.sub_shape {
+            let _ = b.send_param("sub_shape", p.sub_shape as f64);
+        }
+        if d.noise_level {
+            let _ = b.send_param("noise_level", p.noise_level as f64);
+        }
+        if d.noise_type {
+            let _ = b.send_param("noise_type", p.noise_type as f64);
+        }
+        if d.fm_enabled {
+            let _ = b.send_param("fm_enabled", if p.fm_enabled { 1.0 } else { 0.0 });
+        }
+        if d.fm_ratio {
+            let _ = b.send_param("fm_ratio", p.fm_ratio as f64);
+        }
+        if d.fm_fixed {
+            let _ = b.send_param("fm_fixed", if p.fm_fixed { 1.0 } else { 0.0 });
+        }
+        if d.fm_fixed_frequency {
+            let _ = b.send_param("fm_fixed_frequency", p.fm_fixed_frequency as f64);
+        }
+        if d.fm_index {
+            let _ = b.send_param("fm_index", p.fm_index as f64);
+        }
+        if d.fm_feedback {
+            let _ = b.send_param("fm_feedback", p.fm_feedback as f64);
+        }
+        if d.fm_delay {
+            let _ = b.send_param("fm_delay", p.fm_delay as f64);
+        }
+        if d.fm_hold {
+            let _ = b.send_param("fm_hold", p.fm_hold as f64);
+        }
+        if d.fm_envelope_mode {
+            let _ = b.send_param("fm_envelope_mode", p.fm_envelope_mode as f64);
+        }
+        if d.fm_retrigger_mode {
+            let _ = b.send_param("fm_retrigger_mode", p.fm_retrigger_mode as f64);
+        }
+        if d.fm_attack_curve {
+            let _ = b.send_param("fm_attack_curve", p.fm_attack_curve as f64);
+        }
+        if d.fm_decay_curve {
+            let _ = b.send_param("fm_decay_curve", p.fm_decay_curve as f64);
+        }
+        if d.fm_release_curve {
+            let _ = b.send_param("fm_release_curve", p.fm_release_curve as f64);
+        }
+        if d.fm_attack {
+            let _ = b.send_param("fm_attack", p.fm_attack as f64);
+        }
+        if d.fm_decay {
+            let _ = b.send_param("fm_decay", p.fm_decay as f64);
+        }
+        if d.fm_sustain {
+            let _ = b.send_param("fm_sustain", p.fm_sustain as f64);
+        }
+        if d.fm_release {
+            let _ = b.send_param("fm_release", p.fm_release as f64);
+        }
+        if d.pluck_excitation {
+            let _ = b.send_param("pluck_excitation", p.pluck_excitation as f64);
+        }
+        if d.pluck_damping {
+            let _ = b.send_param("pluck_damping", p.pluck_damping as f64);
+        }
+        if d.pluck_brightness {
+            let _ = b.send_param("pluck_brightness", p.pluck_brightness as f64);
+        }
+        if d.pluck_decay {
+            let _ = b.send_param("pluck_decay", p.pluck_decay as f64);
+        }
+        if d.unison_voices {
+            let _ = b.send_param("unison_voices", p.unison_voices as f64);
+        }
+        if d.unison_detune {
+            let _ = b.send_param("unison_detune", p.unison_detune as f64);
+        }
+        if d.unison_spread {
+            let _ = b.send_param("unison_spread", p.unison_spread as f64);
+        }
+        if d.unison_random_phase {
+            let _ = b.send_param("unison_random_phase", if p.unison_random_phase { 1.0 } else { 0.0 });
+        }
+        if d.voice_mode {
+            let _ = b.send_param("voice_mode", p.voice_mode as f64);
+        }
+        if d.voices {
+            let _ = b.send_param("voices", p.voices as f64);
+        }
+        if d.steal_policy {
+            let _ = b.send_param("steal_policy", p.steal_policy as f64);
+        }
+        if d.note_priority {
+            let _ = b.send_param("note_priority", p.note_priority as f64);
+        }
+        if d.legato {
+            let _ = b.send_param("legato", if p.legato { 1.0 } else { 0.0 });
+        }
+        if d.glide_time {
+            let _ = b.send_param("glide_time", p.glide_time as f64);
+        }
+        if d.glide_mode {
+            let _ = b.send_param("glide_mode", p.glide_mode as f64);
+        }
+        if d.glide_timing {
+            let _ = b.send_param("glide_timing", p.glide_timing as f64);
+        }
+        if d.velocity_curve {
+            let _ = b.send_param("velocity_curve", p.velocity_curve as f64);
+        }
+        if d.velocity_amount {
+            let _ = b.send_param("velocity_amount", p.velocity_amount as f64);
+        }
+        if d.velocity_to_attack {
+            let _ = b.send_param("velocity_to_attack", p.velocity_to_attack as f64);
+        }
+        if d.velocity_to_brightness {
+            let _ = b.send_param("velocity_to_brightness", p.velocity_to_brightness as f64);
+        }
+        if d.filter_type {
+            let _ = b.send_param("filter_type", p.filter_type as f64);
+        }
+        if d.filter_mode {
+            let _ = b.send_param("filter_mode", p.filter_mode as f64);
+        }
+        if d.filter_cutoff {
+            let _ = b.send_param("filter_cutoff", p.filter_cutoff as f64);
+        }
+        if d.filter_resonance {
+            let _ = b.send_param("filter_resonance", p.filter_resonance as f64);
+        }
+        if d.filter_drive {
+            let _ = b.send_param("filter_drive", p.filter_drive as f64);
+        }
+        if d.filter_keytrack {
+            let _ = b.send_param("filter_keytrack", p.filter_keytrack as f64);
+        }
+        if d.filter_env_amount {
+            let _ = b.send_param("filter_env_amount", p.filter_env_amount as f64);
+        }
+        if d.filter_delay {
+            let _ = b.send_param("filter_delay", p.filter_delay as f64);
+        }
+        if d.filter_hold {
+            let _ = b.send_param("filter_hold", p.filter_hold as f64);
+        }
+        if d.filter_envelope_mode {
+            let _ = b.send_param("filter_envelope_mode", p.filter_envelope_mode as f64);
+        }
+        if d.filter_retrigger_mode {
+            let _ = b.send_param("filter_retrigger_mode", p.filter_retrigger_mode as f64);
+        }
+        if d.filter_attack_curve {
+            let _ = b.send_param("filter_attack_curve", p.filter_attack_curve as f64);
+        }
+        if d.filter_decay_curve {
+            let _ = b.send_param("filter_decay_curve", p.filter_decay_curve as f64);
+        }
+        if d.filter_release_curve {
+            let _ = b.send_param("filter_release_curve", p.filter_release_curve as f64);
+        }
+        if d.filter_attack {
+            let _ = b.send_param("filter_attack", p.filter_attack as f64);
+        }
+        if d.filter_decay {
+            let _ = b.send_param("filter_decay", p.filter_decay as f64);
+        }
+        if d.filter_sustain {
+            let _ = b.send_param("filter_sustain", p.filter_sustain as f64);
+        }
+        if d.filter_release {
+            let _ = b.send_param("filter_release", p.filter_release as f64);
+        }
+        if d.lfo_shape {
+            let _ = b.send_param("lfo_shape", p.lfo_shape as f64);
+        }
+        if d.lfo_trigger {
+            let _ = b.send_param("lfo_trigger", p.lfo_trigger as f64);
+        }
+        if d.lfo_timing {
+            let _ = b.send_param("lfo_timing", p.lfo_timing as f64);
+        }
+        if d.lfo_rate {
+            let _ = b.send_param("lfo_rate", p.lfo_rate as f64);
+        }
+        if d.lfo_division {
+            let _ = b.send_param("lfo_division", p.lfo_division as f64);
+        }
+        if d.lfo_to_pitch {
+            let _ = b.send_param("lfo_to_pitch", p.lfo_to_pitch as f64);
+        }
+        if d.lfo_to_amp {
+            let _ = b.send_param("lfo_to_amp", p.lfo_to_amp as f64);
+        }
+        if d.lfo_to_pulse_width {
+            let _ = b.send_param("lfo_to_pulse_width", p.lfo_to_pulse_width as f64);
+        }
+        for (slot, _) in d.mod_slots.iter().enumerate().filter(|(_, &dirty)| dirty) {
+            let _ = b.send_mod_slot(
+                slot,
+                p.mod_source[slot],
+                p.mod_destination[slot],
+                p.mod_depth[slot],
+            );
+        }
         if d.gain {
             let _ = b.send_param("gain", p.gain as f64);
         }
+        if d.pan {
+            let _ = b.send_param("pan", p.pan as f64);
+        }
+        if d.delay {
+            let _ = b.send_param("delay", p.delay as f64);
+        }
+        if d.hold {
+            let _ = b.send_param("hold", p.hold as f64);
+        }
+        if d.envelope_mode {
+            let _ = b.send_param("envelope_mode", p.envelope_mode as f64);
+        }
+        if d.retrigger_mode {
+            let _ = b.send_param("retrigger_mode", p.retrigger_mode as f64);
+        }
+        if d.attack_curve {
+            let _ = b.send_param("attack_curve", p.attack_curve as f64);
+        }
+        if d.decay_curve {
+            let _ = b.send_param("decay_curve", p.decay_curve as f64);
+        }
+        if d.release_curve {
+            let _ = b.send_param("release_curve", p.release_curve as f64);
+        }
         if d.attack {
             let _ = b.send_param("attack", p.attack as f64);
         }
@@ -132,14 +411,39 @@ impl SynthWebApp {
         self.params.dirty.clear();
     }
 
+    /// Load WAV files dropped onto the page as the custom wavetable and
+    /// switch to it.
+    fn load_dropped_wavetables(&mut self, ctx: &egui::Context) {
+        let dropped = ctx.input(|i| i.raw.dropped_files.clone());
+        let bridge = match self.audio.as_ref() {
+            Some(b) => b,
+            None => return,
+        };
+        for bytes in dropped.into_iter().filter_map(|file| file.bytes) {
+            // Built here rather than in the worklet: resynthesising the
+            // mipmaps would stall the audio thread for many render quanta
+            let table = match Wavetable::from_wav(&bytes) {
+                Ok(table) => table,
+                Err(e) => {
+                    web_sys::console::error_1(&format!("Wavetable not loaded: {}", e).into());
+                    continue;
+                }
+            };
+            if bridge.borrow().send_wavetable(&table).is_ok() {
+                self.params.wavetable = WavetableBank::Custom as i32;
+                self.params.dirty.wavetable = true;
+            }
+        }
+    }
+
     fn process_keyboard_events(&mut self, events: Vec<KeyboardEvent>) {
         for event in events {
             match event {
-                KeyboardEvent::NoteOn(note) => {
+                KeyboardEvent::NoteOn(note, velocity) => {
                     if !self.state.held_notes.contains(&note) {
                         self.state.held_notes.push(note);
                         if let Some(bridge) = &self.audio {
-                            let _ = bridge.borrow().send_note_on(note);
+                            let _ = bridge.borrow().send_note_on(note, velocity);
                         }
                     }
                 }
@@ -187,10 +491,20 @@ impl eframe::App for SynthWebApp {
             let mut controls = WebControls {
                 params: &mut self.params,
             };
-            let events = render_synth_ui(ui, &mut self.state, &mut controls, &self.vis_samples, true);
+            let (vis_left, vis_right) = self.vis_samples.split_at(self.vis_samples.len() / 2);
+            let events = render_synth_ui(
+                ui,
+                &mut self.state,
+                &mut controls,
+                vis_left,
+                vis_right,
+                true,
+            );
             self.process_keyboard_events(events);
         });
 
+        self.load_dropped_wavetables(ctx);
+
         // Send any dirty params to the worklet
         self.send_dirty_params();
 
@@ -1,3 +1,4 @@
+use dsp_core::wavetable::Wavetable;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use web_sys::{AudioContext, AudioWorkletNode, MessagePort};
@@ -59,10 +60,11 @@ impl AudioBridge {
         })
     }
 
-    pub fn send_note_on(&self, note: u8) -> Result<(), JsValue> {
+    pub fn send_note_on(&self, note: u8, velocity: f32) -> Result<(), JsValue> {
         let msg = js_sys::Object::new();
         js_sys::Reflect::set(&msg, &"type".into(), &"noteOn".into())?;
         js_sys::Reflect::set(&msg, &"note".into(), &(note as f64).into())?;
+        js_sys::Reflect::set(&msg, &"velocity".into(), &(velocity as f64).into())?;
         self.port.post_message(&msg)
     }
 
@@ -81,6 +83,46 @@ impl AudioBridge {
         self.port.post_message(&msg)
     }
 
+    /// Send one mod matrix slot. The three fields travel together so the
+    /// worklet never sees a half-updated routing.
+    pub fn send_mod_slot(
+        &self,
+        slot: usize,
+        source: i32,
+        destination: i32,
+        depth: f32,
+    ) -> Result<(), JsValue> {
+        let msg = js_sys::Object::new();
+        js_sys::Reflect::set(&msg, &"type".into(), &"modSlot".into())?;
+        js_sys::Reflect::set(&msg, &"slot".into(), &(slot as f64).into())?;
+        js_sys::Reflect::set(&msg, &"source".into(), &(source as f64).into())?;
+        js_sys::Reflect::set(&msg, &"destination".into(), &(destination as f64).into())?;
+        js_sys::Reflect::set(&msg, &"depth".into(), &(depth as f64).into())?;
+        self.port.post_message(&msg)
+    }
+
+    /// Send one additive partial's amplitude.
+    pub fn send_partial(&self, partial: usize, amplitude: f32) -> Result<(), JsValue> {
+        let msg = js_sys::Object::new();
+        js_sys::Reflect::set(&msg, &"type".into(), &"partial".into())?;
+        js_sys::Reflect::set(&msg, &"partial".into(), &(partial as f64).into())?;
+        js_sys::Reflect::set(&msg, &"amplitude".into(), &(amplitude as f64).into())?;
+        self.port.post_message(&msg)
+    }
+
+    /// Send a wavetable to the worklet, which loads it as the custom table.
+    /// The table is built here so the audio thread only has to copy it.
+    pub fn send_wavetable(&self, table: &Wavetable) -> Result<(), JsValue> {
+        let data = js_sys::Float32Array::from(&table.mipmaps()[..]);
+        let msg = js_sys::Object::new();
+        js_sys::Reflect::set(&msg, &"type".into(), &"loadWavetable".into())?;
+        js_sys::Reflect::set(&msg, &"frames".into(), &(table.frame_count() as f64).into())?;
+        js_sys::Reflect::set(&msg, &"mipmaps".into(), &data)?;
+        let transfer = js_sys::Array::new();
+        transfer.push(&data.buffer());
+        self.port.post_message_with_transferable(&msg, &transfer)
+    }
+
     /// Set the callback that receives visualization data from the worklet.
     pub fn set_vis_callback(&self, callback: Closure<dyn FnMut(web_sys::MessageEvent)>) {
         self.port
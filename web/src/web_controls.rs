@@ -1,4 +1,21 @@
-use dsp_core::params::OscillatorType;
+use dsp_core::additive::{self, MAX_PARTIALS};
+use dsp_core::envelope::MAX_ENVELOPE_CURVE;
+use dsp_core::fm::{MAX_FM_FIXED_HZ, MAX_FM_INDEX, MAX_FM_RATIO, MIN_FM_FIXED_HZ, MIN_FM_RATIO};
+use dsp_core::mod_matrix::MOD_SLOTS;
+use dsp_core::oscillator::{MAX_PULSE_WIDTH, MIN_PULSE_WIDTH};
+use dsp_core::params::{
+    AdditivePreset, Engine, EnvelopeMode, FilterMode, FilterType, GlideMode, GlideTiming, LfoShape,
+    LfoTiming, LfoTrigger, ModDestination, ModSource, NoiseType, NoteDivision, NotePriority,
+    OscillatorType, PluckExcitation, RetriggerMode, StealPolicy, SubOctave, SubShape, VelocityCurve,
+    VoiceMode, WavetableBank,
+};
+use dsp_core::pluck::{MAX_PLUCK_DECAY, MIN_PLUCK_DECAY};
+use dsp_core::unison::{MAX_LINEAR_FM, MAX_UNISON, MAX_UNISON_DETUNE_CENTS};
+use dsp_core::voice::MAX_VOICES;
+use dsp_core::{
+    MAX_FILTER_ENV_OCTAVES, MAX_LFO_PITCH_SEMITONES, MAX_OSC2_FINE_CENTS,
+    MAX_OSC2_SEMITONES,
+};
 use eframe::egui;
 use synth_ui::ControlRenderer;
 
@@ -6,7 +23,99 @@ use synth_ui::ControlRenderer;
 /// Each frame, changed values are sent to the AudioWorklet.
 pub struct WebParams {
     pub osc_type: i32,
+    pub engine: i32,
+    pub pulse_width: f32,
+    pub wavetable: i32,
+    pub wavetable_position: f32,
+    /// Additive partial amplitudes, fundamental first.
+    pub partials: [f32; MAX_PARTIALS],
+    pub osc2_type: i32,
+    pub osc2_coarse: i32,
+    pub osc2_fine: f32,
+    pub osc2_level: f32,
+    pub osc2_sync: bool,
+    pub ring_mix: f32,
+    pub linear_fm: f32,
+    pub sub_level: f32,
+    pub sub_octave: i32,
+    pub sub_shape: i32,
+    pub noise_level: f32,
+    pub noise_type: i32,
+    pub fm_enabled: bool,
+    pub fm_ratio: f32,
+    pub fm_fixed: bool,
+    pub fm_fixed_frequency: f32,
+    pub fm_index: f32,
+    pub fm_feedback: f32,
+    pub fm_delay: f32,
+    pub fm_hold: f32,
+    pub fm_envelope_mode: i32,
+    pub fm_retrigger_mode: i32,
+    pub fm_attack_curve: f32,
+    pub fm_decay_curve: f32,
+    pub fm_release_curve: f32,
+    pub fm_attack: f32,
+    pub fm_decay: f32,
+    pub fm_sustain: f32,
+    pub fm_release: f32,
+    pub pluck_excitation: i32,
+    pub pluck_damping: f32,
+    pub pluck_brightness: f32,
+    pub pluck_decay: f32,
+    pub unison_voices: i32,
+    pub unison_detune: f32,
+    pub unison_spread: f32,
+    pub unison_random_phase: bool,
+    pub voice_mode: i32,
+    pub voices: i32,
+    pub steal_policy: i32,
+    pub note_priority: i32,
+    pub legato: bool,
+    pub glide_time: f32,
+    pub glide_mode: i32,
+    pub glide_timing: i32,
+    pub velocity_curve: i32,
+    pub velocity_amount: f32,
+    pub velocity_to_attack: f32,
+    pub velocity_to_brightness: f32,
+    pub filter_type: i32,
+    pub filter_mode: i32,
+    pub filter_cutoff: f32,
+    pub filter_resonance: f32,
+    pub filter_drive: f32,
+    pub filter_keytrack: f32,
+    pub filter_env_amount: f32,
+    pub filter_delay: f32,
+    pub filter_hold: f32,
+    pub filter_envelope_mode: i32,
+    pub filter_retrigger_mode: i32,
+    pub filter_attack_curve: f32,
+    pub filter_decay_curve: f32,
+    pub filter_release_curve: f32,
+    pub filter_attack: f32,
+    pub filter_decay: f32,
+    pub filter_sustain: f32,
+    pub filter_release: f32,
+    pub lfo_shape: i32,
+    pub lfo_trigger: i32,
+    pub lfo_timing: i32,
+    pub lfo_rate: f32,
+    pub lfo_division: i32,
+    pub lfo_to_pitch: f32,
+    pub lfo_to_amp: f32,
+    pub lfo_to_pulse_width: f32,
+    pub mod_source: [i32; MOD_SLOTS],
+    pub mod_destination: [i32; MOD_SLOTS],
+    pub mod_depth: [f32; MOD_SLOTS],
     pub gain: f32,
+    pub pan: f32,
+    pub delay: f32,
+    pub hold: f32,
+    pub envelope_mode: i32,
+    pub retrigger_mode: i32,
+    pub attack_curve: f32,
+    pub decay_curve: f32,
+    pub release_curve: f32,
     pub attack: f32,
     pub decay: f32,
     pub sustain: f32,
@@ -18,7 +127,98 @@ pub struct WebParams {
 #[derive(Default)]
 pub struct DirtyFlags {
     pub osc_type: bool,
+    pub engine: bool,
+    pub pulse_width: bool,
+    pub wavetable: bool,
+    pub wavetable_position: bool,
+    /// One bit per additive partial, lowest bit the fundamental.
+    pub partials: u64,
+    pub osc2_type: bool,
+    pub osc2_coarse: bool,
+    pub osc2_fine: bool,
+    pub osc2_level: bool,
+    pub osc2_sync: bool,
+    pub ring_mix: bool,
+    pub linear_fm: bool,
+    pub sub_level: bool,
+    pub sub_octave: bool,
+    pub sub_shape: bool,
+    pub noise_level: bool,
+    pub noise_type: bool,
+    pub fm_enabled: bool,
+    pub fm_ratio: bool,
+    pub fm_fixed: bool,
+    pub fm_fixed_frequency: bool,
+    pub fm_index: bool,
+    pub fm_feedback: bool,
+    pub fm_delay: bool,
+    pub fm_hold: bool,
+    pub fm_envelope_mode: bool,
+    pub fm_retrigger_mode: bool,
+    pub fm_attack_curve: bool,
+    pub fm_decay_curve: bool,
+    pub fm_release_curve: bool,
+    pub fm_attack: bool,
+    pub fm_decay: bool,
+    pub fm_sustain: bool,
+    pub fm_release: bool,
+    pub pluck_excitation: bool,
+    pub pluck_damping: bool,
+    pub pluck_brightness: bool,
+    pub pluck_decay: bool,
+    pub unison_voices: bool,
+    pub unison_detune: bool,
+    pub unison_spread: bool,
+    pub unison_random_phase: bool,
+    pub voice_mode: bool,
+    pub voices: bool,
+    pub steal_policy: bool,
+    pub note_priority: bool,
+    pub legato: bool,
+    pub glide_time: bool,
+    pub glide_mode: bool,
+    pub glide_timing: bool,
+    pub velocity_curve: bool,
+    pub velocity_amount: bool,
+    pub velocity_to_attack: bool,
+    pub velocity_to_brightness: bool,
+    pub filter_type: bool,
+    pub filter_mode: bool,
+    pub filter_cutoff: bool,
+    pub filter_resonance: bool,
+    pub filter_drive: bool,
+    pub filter_keytrack: bool,
+    pub filter_env_amount: bool,
+    pub filter_delay: bool,
+    pub filter_hold: bool,
+    pub filter_envelope_mode: bool,
+    pub filter_retrigger_mode: bool,
+    pub filter_attack_curve: bool,
+    pub filter_decay_curve: bool,
+    pub filter_release_curve: bool,
+    pub filter_attack: bool,
+    pub filter_decay: bool,
+    pub filter_sustain: bool,
+    pub filter_release: bool,
+    pub lfo_shape: bool,
+    pub lfo_trigger: bool,
+    pub lfo_timing: bool,
+    pub lfo_rate: bool,
+    pub lfo_division: bool,
+    pub lfo_to_pitch: bool,
+    pub lfo_to_amp: bool,
+    pub lfo_to_pulse_width: bool,
+    /// One flag per mod matrix slot; a slot is always sent whole.
+    pub mod_slots: [bool; MOD_SLOTS],
     pub gain: bool,
+    pub pan: bool,
+    pub delay: bool,
+    pub hold: bool,
+    pub envelope_mode: bool,
+    pub retrigger_mode: bool,
+    pub attack_curve: bool,
+    pub decay_curve: bool,
+    pub release_curve: bool,
     pub attack: bool,
     pub decay: bool,
     pub sustain: bool,
@@ -26,47 +226,1292 @@ pub struct DirtyFlags {
 }
 
 impl DirtyFlags {
+    /// Every parameter marked, so the next send pushes the whole UI state.
+    /// The worklet starts from the engine's own defaults, which aren't all
+    /// the UI's (the UI starts at 8 voices, `Synth::new` at 1).
+    pub fn all() -> Self {
+        Self {
+            osc_type: true,
+            engine: true,
+            pulse_width: true,
+            wavetable: true,
+            wavetable_position: true,
+            partials: u64::MAX,
+            osc2_type: true,
+            osc2_coarse: true,
+            osc2_fine: true,
+            osc2_level: true,
+            osc2_sync: true,
+            ring_mix: true,
+            linear_fm: true,
+            sub_level: true,
+            sub_octave: true,
+            sub_shape: true,
+            noise_level: true,
+            noise_type: true,
+            fm_enabled: true,
+            fm_ratio: true,
+            fm_fixed: true,
+            fm_fixed_frequency: true,
+            fm_index: true,
+            fm_feedback: true,
+            fm_delay: true,
+            fm_hold: true,
+            fm_envelope_mode: true,
+            fm_retrigger_mode: true,
+            fm_attack_curve: true,
+            fm_decay_curve: true,
+            fm_release_curve: true,
+            fm_attack: true,
+            fm_decay: true,
+            fm_sustain: true,
+            fm_release: true,
+            pluck_excitation: true,
+            pluck_damping: true,
+            pluck_brightness: true,
+            pluck_decay: true,
+            unison_voices: true,
+            unison_detune: true,
+            unison_spread: true,
+            unison_random_phase: true,
+            voice_mode: true,
+            voices: true,
+            steal_policy: true,
+            note_priority: true,
+            legato: true,
+            glide_time: true,
+            glide_mode: true,
+            glide_timing: true,
+            velocity_curve: true,
+            velocity_amount: true,
+            velocity_to_attack: true,
+            velocity_to_brightness: true,
+            filter_type: true,
+            filter_mode: true,
+            filter_cutoff: true,
+            filter_resonance: true,
+            filter_drive: true,
+            filter_keytrack: true,
+            filter_env_amount: true,
+            filter_delay: true,
+            filter_hold: true,
+            filter_envelope_mode: true,
+            filter_retrigger_mode: true,
+            filter_attack_curve: true,
+            filter_decay_curve: true,
+            filter_release_curve: true,
+            filter_attack: true,
+            filter_decay: true,
+            filter_sustain: true,
+            filter_release: true,
+            lfo_shape: true,
+            lfo_trigger: true,
+            lfo_timing: true,
+            lfo_rate: true,
+            lfo_division: true,
+            lfo_to_pitch: true,
+            lfo_to_amp: true,
+            lfo_to_pulse_width: true,
+            mod_slots: [true; MOD_SLOTS],
+            gain: true,
+            pan: true,
+            delay: true,
+            hold: true,
+            envelope_mode: true,
+            retrigger_mode: true,
+            attack_curve: true,
+            decay_curve: true,
+            release_curve: true,
+            attack: true,
+            decay: true,
+            sustain: true,
+            release: true,
+        }
+    }
+
     pub fn any(&self) -> bool {
-        self.osc_type || self.gain || self.attack || self.decay || self.sustain || self.release
+        self.osc_type
+            || self.engine
+            || self.pulse_width
+            || self.wavetable
+            || self.wavetable_position
+            || self.partials != 0
+            || self.osc2_type
+            || self.osc2_coarse
+            || self.osc2_fine
+            || self.osc2_level
+            || self.osc2_sync
+            || self.ring_mix
+            || self.linear_fm
+            || self.sub_level
+            || self.sub_octave
+            || self.sub_shape
+            || self.noise_level
+            || self.noise_type
+            || self.fm_enabled
+            || self.fm_ratio
+            || self.fm_fixed
+            || self.fm_fixed_frequency
+            || self.fm_index
+            || self.fm_feedback
+            || self.fm_delay
+            || self.fm_hold
+            || self.fm_envelope_mode
+            || self.fm_retrigger_mode
+            || self.fm_attack_curve
+            || self.fm_decay_curve
+            || self.fm_release_curve
+            || self.fm_attack
+            || self.fm_decay
+            || self.fm_sustain
+            || self.fm_release
+            || self.pluck_excitation
+            || self.pluck_damping
+            || self.pluck_brightness
+            || self.pluck_decay
+            || self.unison_voices
+            || self.unison_detune
+            || self.unison_spread
+            || self.unison_random_phase
+            || self.voice_mode
+            || self.voices
+            || self.steal_policy
+            || self.note_priority
+            || self.legato
+            || self.glide_time
+            || self.glide_mode
+            || self.glide_timing
+            || self.velocity_curve
+            || self.velocity_amount
+            || self.velocity_to_attack
+            || self.velocity_to_brightness
+            || self.filter_type
+            || self.filter_mode
+            || self.filter_cutoff
+            || self.filter_resonance
+            || self.filter_drive
+            || self.filter_keytrack
+            || self.filter_env_amount
+            || self.filter_delay
+            || self.filter_hold
+            || self.filter_envelope_mode
+            || self.filter_retrigger_mode
+            || self.filter_attack_curve
+            || self.filter_decay_curve
+            || self.filter_release_curve
+            || self.filter_attack
+            || self.filter_decay
+            || self.filter_sustain
+            || self.filter_release
+            || self.lfo_shape
+            || self.lfo_trigger
+            || self.lfo_timing
+            || self.lfo_rate
+            || self.lfo_division
+            || self.lfo_to_pitch
+            || self.lfo_to_amp
+            || self.lfo_to_pulse_width
+            || self.mod_slots.iter().any(|&d| d)
+            || self.gain
+            || self.pan
+            || self.delay
+            || self.hold
+            || self.envelope_mode
+            || self.retrigger_mode
+            || self.attack_curve
+            || self.decay_curve
+            || self.release_curve
+            || self.attack
+            || self.decay
+            || self.sustain
+            || self.release
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl Default for WebParams {
+    fn default() -> Self {
+        Self {
+            osc_type: 0,
+            engine: 0,
+            pulse_width: 0.5,
+            wavetable: 0,
+            wavetable_position: 0.0,
+            partials: additive::preset_spectrum(AdditivePreset::Sine),
+            osc2_type: 3,
+            osc2_coarse: 0,
+            osc2_fine: 0.0,
+            osc2_level: 0.0,
+            osc2_sync: false,
+            ring_mix: 0.0,
+            linear_fm: 0.0,
+            sub_level: 0.0,
+            sub_octave: 0,
+            sub_shape: 0,
+            noise_level: 0.0,
+            noise_type: 0,
+            fm_enabled: false,
+            fm_ratio: 1.0,
+            fm_fixed: false,
+            fm_fixed_frequency: 100.0,
+            fm_index: 2.0,
+            fm_feedback: 0.0,
+            fm_delay: 0.0,
+            fm_hold: 0.0,
+            fm_envelope_mode: 0,
+            fm_retrigger_mode: 0,
+            fm_attack_curve: 0.0,
+            fm_decay_curve: 0.0,
+            fm_release_curve: 0.0,
+            fm_attack: 0.01,
+            fm_decay: 0.3,
+            fm_sustain: 0.3,
+            fm_release: 0.3,
+            pluck_excitation: 0,
+            pluck_damping: 0.5,
+            pluck_brightness: 0.5,
+            pluck_decay: 2.0,
+            unison_voices: 1,
+            unison_detune: 15.0,
+            unison_spread: 0.5,
+            unison_random_phase: false,
+            voice_mode: 0,
+            voices: 8,
+            steal_policy: 0,
+            note_priority: 0,
+            legato: false,
+            glide_time: 0.0,
+            glide_mode: 0,
+            glide_timing: 0,
+            velocity_curve: 0,
+            velocity_amount: 1.0,
+            velocity_to_attack: 0.0,
+            velocity_to_brightness: 0.0,
+            filter_type: 0,
+            filter_mode: 0,
+            filter_cutoff: 20000.0,
+            filter_resonance: 0.0,
+            filter_drive: 0.0,
+            filter_keytrack: 0.0,
+            filter_env_amount: 0.0,
+            filter_delay: 0.0,
+            filter_hold: 0.0,
+            filter_envelope_mode: 0,
+            filter_retrigger_mode: 0,
+            filter_attack_curve: 0.0,
+            filter_decay_curve: 0.0,
+            filter_release_curve: 0.0,
+            filter_attack: 0.01,
+            filter_decay: 0.3,
+            filter_sustain: 0.0,
+            filter_release: 0.3,
+            lfo_shape: 0,
+            lfo_trigger: 0,
+            lfo_timing: 0,
+            lfo_rate: 5.0,
+            lfo_division: NoteDivision::Quarter as i32,
+            lfo_to_pitch: 0.0,
+            lfo_to_amp: 0.0,
+            lfo_to_pulse_width: 0.0,
+            mod_source: [0; MOD_SLOTS],
+            mod_destination: [0; MOD_SLOTS],
+            mod_depth: [0.0; MOD_SLOTS],
+            gain: 0.8,
+            pan: 0.0,
+            delay: 0.0,
+            hold: 0.0,
+            envelope_mode: 0,
+            retrigger_mode: 0,
+            attack_curve: 0.0,
+            decay_curve: 0.0,
+            release_curve: 0.0,
+            attack: 0.01,
+            decay: 0.1,
+            sustain: 0.7,
+            release: 0.3,
+            dirty: DirtyFlags::default(),
+        }
+    }
+}
+
+/// Wraps WebParams to implement ControlRenderer using plain egui sliders.
+pub struct WebControls<'a> {
+    pub params: &'a mut WebParams,
+}
+
+impl<'a> ControlRenderer for WebControls<'a> {
+    fn render_osc_type(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.osc_type;
+        let name = OscillatorType::from_index(self.params.osc_type as usize).name();
+        egui::ComboBox::from_id_salt("osc_type")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in OscillatorType::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.osc_type, i as i32, variant.name());
+                }
+            });
+        if self.params.osc_type != prev {
+            self.params.dirty.osc_type = true;
+        }
+    }
+
+    fn render_engine(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.engine;
+        let name = Engine::from_index(self.params.engine as usize).name();
+        egui::ComboBox::from_id_salt("engine")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in Engine::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.engine, i as i32, variant.name());
+                }
+            });
+        if self.params.engine != prev {
+            self.params.dirty.engine = true;
+        }
+    }
+
+    fn render_pulse_width(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.pulse_width;
+        ui.add(egui::Slider::new(&mut self.params.pulse_width, MIN_PULSE_WIDTH..=MAX_PULSE_WIDTH).text(""));
+        if (self.params.pulse_width - prev).abs() > f32::EPSILON {
+            self.params.dirty.pulse_width = true;
+        }
+    }
+
+    fn render_wavetable(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.wavetable;
+        let name = WavetableBank::from_index(self.params.wavetable as usize).name();
+        egui::ComboBox::from_id_salt("wavetable")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in WavetableBank::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.wavetable, i as i32, variant.name());
+                }
+            });
+        if self.params.wavetable != prev {
+            self.params.dirty.wavetable = true;
+        }
+    }
+
+    fn render_wavetable_position(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.wavetable_position;
+        ui.add(egui::Slider::new(&mut self.params.wavetable_position, 0.0..=1.0).text(""));
+        if (self.params.wavetable_position - prev).abs() > f32::EPSILON {
+            self.params.dirty.wavetable_position = true;
+        }
+    }
+
+    fn partial_count(&self) -> usize {
+        MAX_PARTIALS
+    }
+
+    fn partial_amplitude(&self, partial: usize) -> f32 {
+        self.params.partials[partial]
+    }
+
+    fn set_partial_amplitude(&mut self, partial: usize, amplitude: f32) {
+        if self.params.partials[partial] != amplitude {
+            self.params.partials[partial] = amplitude;
+            self.params.dirty.partials |= 1 << partial;
+        }
+    }
+
+    fn render_additive_presets(&mut self, ui: &mut egui::Ui) {
+        for &preset in AdditivePreset::VARIANTS {
+            if ui.button(preset.name()).clicked() {
+                for (partial, &amplitude) in
+                    additive::preset_spectrum(preset).iter().enumerate()
+                {
+                    self.set_partial_amplitude(partial, amplitude);
+                }
+            }
+        }
+    }
+
+    fn render_osc2_type(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.osc2_type;
+        let name = OscillatorType::from_index(self.params.osc2_type as usize).name();
+        egui::ComboBox::from_id_salt("osc2_type")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in OscillatorType::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.osc2_type, i as i32, variant.name());
+                }
+            });
+        if self.params.osc2_type != prev {
+            self.params.dirty.osc2_type = true;
+        }
+    }
+
+    fn render_osc2_coarse(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.osc2_coarse;
+        ui.add(
+            egui::Slider::new(&mut self.params.osc2_coarse, -MAX_OSC2_SEMITONES..=MAX_OSC2_SEMITONES)
+                .suffix(" st")
+                .text(""),
+        );
+        if self.params.osc2_coarse != prev {
+            self.params.dirty.osc2_coarse = true;
+        }
+    }
+
+    fn render_osc2_fine(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.osc2_fine;
+        ui.add(
+            egui::Slider::new(&mut self.params.osc2_fine, -MAX_OSC2_FINE_CENTS..=MAX_OSC2_FINE_CENTS)
+                .suffix(" ct")
+                .text(""),
+        );
+        if (self.params.osc2_fine - prev).abs() > f32::EPSILON {
+            self.params.dirty.osc2_fine = true;
+        }
+    }
+
+    fn render_osc2_level(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.osc2_level;
+        ui.add(egui::Slider::new(&mut self.params.osc2_level, 0.0..=1.0).text(""));
+        if (self.params.osc2_level - prev).abs() > f32::EPSILON {
+            self.params.dirty.osc2_level = true;
+        }
+    }
+
+    fn render_osc2_sync(&mut self, ui: &mut egui::Ui) {
+        if ui.checkbox(&mut self.params.osc2_sync, "").changed() {
+            self.params.dirty.osc2_sync = true;
+        }
+    }
+
+    fn render_ring_mix(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.ring_mix;
+        ui.add(egui::Slider::new(&mut self.params.ring_mix, 0.0..=1.0).text(""));
+        if (self.params.ring_mix - prev).abs() > f32::EPSILON {
+            self.params.dirty.ring_mix = true;
+        }
+    }
+
+    fn render_linear_fm(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.linear_fm;
+        ui.add(egui::Slider::new(&mut self.params.linear_fm, 0.0..=MAX_LINEAR_FM).text(""));
+        if (self.params.linear_fm - prev).abs() > f32::EPSILON {
+            self.params.dirty.linear_fm = true;
+        }
+    }
+
+    fn render_sub_level(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.sub_level;
+        ui.add(egui::Slider::new(&mut self.params.sub_level, 0.0..=1.0).text(""));
+        if (self.params.sub_level - prev).abs() > f32::EPSILON {
+            self.params.dirty.sub_level = true;
+        }
+    }
+
+    fn render_sub_octave(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.sub_octave;
+        let name = SubOctave::from_index(self.params.sub_octave as usize).name();
+        egui::ComboBox::from_id_salt("sub_octave")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in SubOctave::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.sub_octave, i as i32, variant.name());
+                }
+            });
+        if self.params.sub_octave != prev {
+            self.params.dirty.sub_octave = true;
+        }
+    }
+
+    fn render_sub_shape(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.sub_shape;
+        let name = SubShape::from_index(self.params.sub_shape as usize).name();
+        egui::ComboBox::from_id_salt("sub_shape")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in SubShape::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.sub_shape, i as i32, variant.name());
+                }
+            });
+        if self.params.sub_shape != prev {
+            self.params.dirty.sub_shape = true;
+        }
+    }
+
+    fn render_noise_level(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.noise_level;
+        ui.add(egui::Slider::new(&mut self.params.noise_level, 0.0..=1.0).text(""));
+        if (self.params.noise_level - prev).abs() > f32::EPSILON {
+            self.params.dirty.noise_level = true;
+        }
+    }
+
+    fn render_noise_type(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.noise_type;
+        let name = NoiseType::from_index(self.params.noise_type as usize).name();
+        egui::ComboBox::from_id_salt("noise_type")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in NoiseType::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.noise_type, i as i32, variant.name());
+                }
+            });
+        if self.params.noise_type != prev {
+            self.params.dirty.noise_type = true;
+        }
+    }
+
+    fn render_fm_enabled(&mut self, ui: &mut egui::Ui) {
+        if ui.checkbox(&mut self.params.fm_enabled, "").changed() {
+            self.params.dirty.fm_enabled = true;
+        }
+    }
+
+    fn render_fm_ratio(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.fm_ratio;
+        ui.add(
+            egui::Slider::new(&mut self.params.fm_ratio, MIN_FM_RATIO..=MAX_FM_RATIO)
+                .logarithmic(true)
+                .text(""),
+        );
+        if (self.params.fm_ratio - prev).abs() > f32::EPSILON {
+            self.params.dirty.fm_ratio = true;
+        }
+    }
+
+    fn render_fm_fixed(&mut self, ui: &mut egui::Ui) {
+        if ui.checkbox(&mut self.params.fm_fixed, "").changed() {
+            self.params.dirty.fm_fixed = true;
+        }
+    }
+
+    fn render_fm_fixed_frequency(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.fm_fixed_frequency;
+        ui.add(
+            egui::Slider::new(&mut self.params.fm_fixed_frequency, MIN_FM_FIXED_HZ..=MAX_FM_FIXED_HZ)
+                .logarithmic(true)
+                .suffix(" Hz")
+                .text(""),
+        );
+        if (self.params.fm_fixed_frequency - prev).abs() > f32::EPSILON {
+            self.params.dirty.fm_fixed_frequency = true;
+        }
+    }
+
+    fn render_fm_index(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.fm_index;
+        ui.add(egui::Slider::new(&mut self.params.fm_index, 0.0..=MAX_FM_INDEX).text(""));
+        if (self.params.fm_index - prev).abs() > f32::EPSILON {
+            self.params.dirty.fm_index = true;
+        }
+    }
+
+    fn render_fm_feedback(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.fm_feedback;
+        ui.add(egui::Slider::new(&mut self.params.fm_feedback, 0.0..=1.0).text(""));
+        if (self.params.fm_feedback - prev).abs() > f32::EPSILON {
+            self.params.dirty.fm_feedback = true;
+        }
+    }
+
+    fn render_fm_delay(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.fm_delay;
+        ui.add(
+            egui::Slider::new(&mut self.params.fm_delay, 0.0..=5.0)
+                .logarithmic(true)
+                .suffix(" s")
+                .text(""),
+        );
+        if (self.params.fm_delay - prev).abs() > f32::EPSILON {
+            self.params.dirty.fm_delay = true;
+        }
+    }
+
+    fn render_fm_hold(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.fm_hold;
+        ui.add(
+            egui::Slider::new(&mut self.params.fm_hold, 0.0..=5.0)
+                .logarithmic(true)
+                .suffix(" s")
+                .text(""),
+        );
+        if (self.params.fm_hold - prev).abs() > f32::EPSILON {
+            self.params.dirty.fm_hold = true;
+        }
+    }
+
+    fn render_fm_envelope_mode(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.fm_envelope_mode;
+        let name = EnvelopeMode::from_index(self.params.fm_envelope_mode as usize).name();
+        egui::ComboBox::from_id_salt("fm_envelope_mode")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in EnvelopeMode::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.fm_envelope_mode, i as i32, variant.name());
+                }
+            });
+        if self.params.fm_envelope_mode != prev {
+            self.params.dirty.fm_envelope_mode = true;
+        }
+    }
+
+    fn render_fm_retrigger_mode(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.fm_retrigger_mode;
+        let name = RetriggerMode::from_index(self.params.fm_retrigger_mode as usize).name();
+        egui::ComboBox::from_id_salt("fm_retrigger_mode")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in RetriggerMode::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.fm_retrigger_mode, i as i32, variant.name());
+                }
+            });
+        if self.params.fm_retrigger_mode != prev {
+            self.params.dirty.fm_retrigger_mode = true;
+        }
+    }
+
+    fn render_fm_attack_curve(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.fm_attack_curve;
+        let range = -MAX_ENVELOPE_CURVE..=MAX_ENVELOPE_CURVE;
+        ui.add(egui::Slider::new(&mut self.params.fm_attack_curve, range).text(""));
+        if (self.params.fm_attack_curve - prev).abs() > f32::EPSILON {
+            self.params.dirty.fm_attack_curve = true;
+        }
+    }
+
+    fn render_fm_decay_curve(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.fm_decay_curve;
+        let range = -MAX_ENVELOPE_CURVE..=MAX_ENVELOPE_CURVE;
+        ui.add(egui::Slider::new(&mut self.params.fm_decay_curve, range).text(""));
+        if (self.params.fm_decay_curve - prev).abs() > f32::EPSILON {
+            self.params.dirty.fm_decay_curve = true;
+        }
+    }
+
+    fn render_fm_release_curve(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.fm_release_curve;
+        let range = -MAX_ENVELOPE_CURVE..=MAX_ENVELOPE_CURVE;
+        ui.add(egui::Slider::new(&mut self.params.fm_release_curve, range).text(""));
+        if (self.params.fm_release_curve - prev).abs() > f32::EPSILON {
+            self.params.dirty.fm_release_curve = true;
+        }
+    }
+
+    fn render_fm_attack(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.fm_attack;
+        ui.add(
+            egui::Slider::new(&mut self.params.fm_attack, 0.001..=2.0)
+                .logarithmic(true)
+                .suffix(" s")
+                .text(""),
+        );
+        if (self.params.fm_attack - prev).abs() > f32::EPSILON {
+            self.params.dirty.fm_attack = true;
+        }
+    }
+
+    fn render_fm_decay(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.fm_decay;
+        ui.add(
+            egui::Slider::new(&mut self.params.fm_decay, 0.001..=2.0)
+                .logarithmic(true)
+                .suffix(" s")
+                .text(""),
+        );
+        if (self.params.fm_decay - prev).abs() > f32::EPSILON {
+            self.params.dirty.fm_decay = true;
+        }
+    }
+
+    fn render_fm_sustain(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.fm_sustain;
+        ui.add(egui::Slider::new(&mut self.params.fm_sustain, 0.0..=1.0).text(""));
+        if (self.params.fm_sustain - prev).abs() > f32::EPSILON {
+            self.params.dirty.fm_sustain = true;
+        }
+    }
+
+    fn render_fm_release(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.fm_release;
+        ui.add(
+            egui::Slider::new(&mut self.params.fm_release, 0.001..=5.0)
+                .logarithmic(true)
+                .suffix(" s")
+                .text(""),
+        );
+        if (self.params.fm_release - prev).abs() > f32::EPSILON {
+            self.params.dirty.fm_release = true;
+        }
+    }
+
+    fn render_pluck_excitation(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.pluck_excitation;
+        let name = PluckExcitation::from_index(self.params.pluck_excitation as usize).name();
+        egui::ComboBox::from_id_salt("pluck_excitation")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in PluckExcitation::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.pluck_excitation, i as i32, variant.name());
+                }
+            });
+        if self.params.pluck_excitation != prev {
+            self.params.dirty.pluck_excitation = true;
+        }
+    }
+
+    fn render_pluck_damping(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.pluck_damping;
+        ui.add(egui::Slider::new(&mut self.params.pluck_damping, 0.0..=1.0).text(""));
+        if (self.params.pluck_damping - prev).abs() > f32::EPSILON {
+            self.params.dirty.pluck_damping = true;
+        }
+    }
+
+    fn render_pluck_brightness(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.pluck_brightness;
+        ui.add(egui::Slider::new(&mut self.params.pluck_brightness, 0.0..=1.0).text(""));
+        if (self.params.pluck_brightness - prev).abs() > f32::EPSILON {
+            self.params.dirty.pluck_brightness = true;
+        }
+    }
+
+    fn render_pluck_decay(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.pluck_decay;
+        ui.add(
+            egui::Slider::new(&mut self.params.pluck_decay, MIN_PLUCK_DECAY..=MAX_PLUCK_DECAY)
+                .logarithmic(true)
+                .suffix(" s")
+                .text(""),
+        );
+        if (self.params.pluck_decay - prev).abs() > f32::EPSILON {
+            self.params.dirty.pluck_decay = true;
+        }
+    }
+
+    fn render_unison_voices(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.unison_voices;
+        ui.add(egui::Slider::new(&mut self.params.unison_voices, 1..=MAX_UNISON as i32).text(""));
+        if self.params.unison_voices != prev {
+            self.params.dirty.unison_voices = true;
+        }
+    }
+
+    fn render_unison_detune(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.unison_detune;
+        ui.add(
+            egui::Slider::new(&mut self.params.unison_detune, 0.0..=MAX_UNISON_DETUNE_CENTS)
+                .suffix(" ct")
+                .text(""),
+        );
+        if (self.params.unison_detune - prev).abs() > f32::EPSILON {
+            self.params.dirty.unison_detune = true;
+        }
+    }
+
+    fn render_unison_spread(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.unison_spread;
+        ui.add(egui::Slider::new(&mut self.params.unison_spread, 0.0..=1.0).text(""));
+        if (self.params.unison_spread - prev).abs() > f32::EPSILON {
+            self.params.dirty.unison_spread = true;
+        }
+    }
+
+    fn render_unison_random_phase(&mut self, ui: &mut egui::Ui) {
+        if ui.checkbox(&mut self.params.unison_random_phase, "").changed() {
+            self.params.dirty.unison_random_phase = true;
+        }
+    }
+
+    fn render_voices(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.voices;
+        ui.add(egui::Slider::new(&mut self.params.voices, 1..=MAX_VOICES as i32).text(""));
+        if self.params.voices != prev {
+            self.params.dirty.voices = true;
+        }
+    }
+
+    fn render_steal_policy(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.steal_policy;
+        let name = StealPolicy::from_index(self.params.steal_policy as usize).name();
+        egui::ComboBox::from_id_salt("steal_policy")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in StealPolicy::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.steal_policy, i as i32, variant.name());
+                }
+            });
+        if self.params.steal_policy != prev {
+            self.params.dirty.steal_policy = true;
+        }
+    }
+
+    fn render_voice_mode(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.voice_mode;
+        let name = VoiceMode::from_index(self.params.voice_mode as usize).name();
+        egui::ComboBox::from_id_salt("voice_mode")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in VoiceMode::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.voice_mode, i as i32, variant.name());
+                }
+            });
+        if self.params.voice_mode != prev {
+            self.params.dirty.voice_mode = true;
+        }
+    }
+
+    fn render_note_priority(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.note_priority;
+        let name = NotePriority::from_index(self.params.note_priority as usize).name();
+        egui::ComboBox::from_id_salt("note_priority")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in NotePriority::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.note_priority, i as i32, variant.name());
+                }
+            });
+        if self.params.note_priority != prev {
+            self.params.dirty.note_priority = true;
+        }
+    }
+
+    fn render_legato(&mut self, ui: &mut egui::Ui) {
+        if ui.checkbox(&mut self.params.legato, "").changed() {
+            self.params.dirty.legato = true;
+        }
+    }
+
+    fn render_glide_time(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.glide_time;
+        ui.add(
+            egui::Slider::new(&mut self.params.glide_time, 0.0..=2.0)
+                .logarithmic(true)
+                .suffix(" s")
+                .text(""),
+        );
+        if (self.params.glide_time - prev).abs() > f32::EPSILON {
+            self.params.dirty.glide_time = true;
+        }
+    }
+
+    fn render_glide_mode(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.glide_mode;
+        let name = GlideMode::from_index(self.params.glide_mode as usize).name();
+        egui::ComboBox::from_id_salt("glide_mode")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in GlideMode::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.glide_mode, i as i32, variant.name());
+                }
+            });
+        if self.params.glide_mode != prev {
+            self.params.dirty.glide_mode = true;
+        }
+    }
+
+    fn render_glide_timing(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.glide_timing;
+        let name = GlideTiming::from_index(self.params.glide_timing as usize).name();
+        egui::ComboBox::from_id_salt("glide_timing")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in GlideTiming::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.glide_timing, i as i32, variant.name());
+                }
+            });
+        if self.params.glide_timing != prev {
+            self.params.dirty.glide_timing = true;
+        }
+    }
+
+    fn render_velocity_curve(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.velocity_curve;
+        let name = VelocityCurve::from_index(self.params.velocity_curve as usize).name();
+        egui::ComboBox::from_id_salt("velocity_curve")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in VelocityCurve::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.velocity_curve, i as i32, variant.name());
+                }
+            });
+        if self.params.velocity_curve != prev {
+            self.params.dirty.velocity_curve = true;
+        }
+    }
+
+    fn render_velocity_amount(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.velocity_amount;
+        ui.add(egui::Slider::new(&mut self.params.velocity_amount, 0.0..=1.0).text(""));
+        if (self.params.velocity_amount - prev).abs() > f32::EPSILON {
+            self.params.dirty.velocity_amount = true;
+        }
+    }
+
+    fn render_velocity_to_attack(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.velocity_to_attack;
+        ui.add(egui::Slider::new(&mut self.params.velocity_to_attack, 0.0..=1.0).text(""));
+        if (self.params.velocity_to_attack - prev).abs() > f32::EPSILON {
+            self.params.dirty.velocity_to_attack = true;
+        }
+    }
+
+    fn render_velocity_to_brightness(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.velocity_to_brightness;
+        ui.add(egui::Slider::new(&mut self.params.velocity_to_brightness, 0.0..=1.0).text(""));
+        if (self.params.velocity_to_brightness - prev).abs() > f32::EPSILON {
+            self.params.dirty.velocity_to_brightness = true;
+        }
+    }
+
+    fn render_filter_type(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.filter_type;
+        let name = FilterType::from_index(self.params.filter_type as usize).name();
+        egui::ComboBox::from_id_salt("filter_type")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in FilterType::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.filter_type, i as i32, variant.name());
+                }
+            });
+        if self.params.filter_type != prev {
+            self.params.dirty.filter_type = true;
+        }
+    }
+
+    fn render_filter_mode(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.filter_mode;
+        let name = FilterMode::from_index(self.params.filter_mode as usize).name();
+        egui::ComboBox::from_id_salt("filter_mode")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in FilterMode::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.filter_mode, i as i32, variant.name());
+                }
+            });
+        if self.params.filter_mode != prev {
+            self.params.dirty.filter_mode = true;
+        }
+    }
+
+    fn render_filter_cutoff(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.filter_cutoff;
+        ui.add(
+            egui::Slider::new(&mut self.params.filter_cutoff, 20.0..=20000.0)
+                .logarithmic(true)
+                .suffix(" Hz")
+                .text(""),
+        );
+        if (self.params.filter_cutoff - prev).abs() > f32::EPSILON {
+            self.params.dirty.filter_cutoff = true;
+        }
+    }
+
+    fn render_filter_resonance(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.filter_resonance;
+        ui.add(egui::Slider::new(&mut self.params.filter_resonance, 0.0..=1.0).text(""));
+        if (self.params.filter_resonance - prev).abs() > f32::EPSILON {
+            self.params.dirty.filter_resonance = true;
+        }
+    }
+
+    fn render_filter_drive(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.filter_drive;
+        ui.add(egui::Slider::new(&mut self.params.filter_drive, 0.0..=1.0).text(""));
+        if (self.params.filter_drive - prev).abs() > f32::EPSILON {
+            self.params.dirty.filter_drive = true;
+        }
+    }
+
+    fn render_filter_keytrack(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.filter_keytrack;
+        ui.add(egui::Slider::new(&mut self.params.filter_keytrack, 0.0..=1.0).text(""));
+        if (self.params.filter_keytrack - prev).abs() > f32::EPSILON {
+            self.params.dirty.filter_keytrack = true;
+        }
+    }
+
+    fn render_filter_env_amount(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.filter_env_amount;
+        ui.add(
+            egui::Slider::new(&mut self.params.filter_env_amount, -MAX_FILTER_ENV_OCTAVES..=MAX_FILTER_ENV_OCTAVES)
+                .suffix(" oct")
+                .text(""),
+        );
+        if (self.params.filter_env_amount - prev).abs() > f32::EPSILON {
+            self.params.dirty.filter_env_amount = true;
+        }
+    }
+
+    fn render_filter_delay(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.filter_delay;
+        ui.add(
+            egui::Slider::new(&mut self.params.filter_delay, 0.0..=5.0)
+                .logarithmic(true)
+                .suffix(" s")
+                .text(""),
+        );
+        if (self.params.filter_delay - prev).abs() > f32::EPSILON {
+            self.params.dirty.filter_delay = true;
+        }
+    }
+
+    fn render_filter_hold(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.filter_hold;
+        ui.add(
+            egui::Slider::new(&mut self.params.filter_hold, 0.0..=5.0)
+                .logarithmic(true)
+                .suffix(" s")
+                .text(""),
+        );
+        if (self.params.filter_hold - prev).abs() > f32::EPSILON {
+            self.params.dirty.filter_hold = true;
+        }
+    }
+
+    fn render_filter_envelope_mode(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.filter_envelope_mode;
+        let name = EnvelopeMode::from_index(self.params.filter_envelope_mode as usize).name();
+        egui::ComboBox::from_id_salt("filter_envelope_mode")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in EnvelopeMode::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.filter_envelope_mode, i as i32, variant.name());
+                }
+            });
+        if self.params.filter_envelope_mode != prev {
+            self.params.dirty.filter_envelope_mode = true;
+        }
+    }
+
+    fn render_filter_retrigger_mode(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.filter_retrigger_mode;
+        let name = RetriggerMode::from_index(self.params.filter_retrigger_mode as usize).name();
+        egui::ComboBox::from_id_salt("filter_retrigger_mode")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in RetriggerMode::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.filter_retrigger_mode, i as i32, variant.name());
+                }
+            });
+        if self.params.filter_retrigger_mode != prev {
+            self.params.dirty.filter_retrigger_mode = true;
+        }
+    }
+
+    fn render_filter_attack_curve(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.filter_attack_curve;
+        let range = -MAX_ENVELOPE_CURVE..=MAX_ENVELOPE_CURVE;
+        ui.add(egui::Slider::new(&mut self.params.filter_attack_curve, range).text(""));
+        if (self.params.filter_attack_curve - prev).abs() > f32::EPSILON {
+            self.params.dirty.filter_attack_curve = true;
+        }
+    }
+
+    fn render_filter_decay_curve(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.filter_decay_curve;
+        let range = -MAX_ENVELOPE_CURVE..=MAX_ENVELOPE_CURVE;
+        ui.add(egui::Slider::new(&mut self.params.filter_decay_curve, range).text(""));
+        if (self.params.filter_decay_curve - prev).abs() > f32::EPSILON {
+            self.params.dirty.filter_decay_curve = true;
+        }
+    }
+
+    fn render_filter_release_curve(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.filter_release_curve;
+        let range = -MAX_ENVELOPE_CURVE..=MAX_ENVELOPE_CURVE;
+        ui.add(egui::Slider::new(&mut self.params.filter_release_curve, range).text(""));
+        if (self.params.filter_release_curve - prev).abs() > f32::EPSILON {
+            self.params.dirty.filter_release_curve = true;
+        }
+    }
+
+    fn render_filter_attack(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.filter_attack;
+        ui.add(
+            egui::Slider::new(&mut self.params.filter_attack, 0.001..=2.0)
+                .logarithmic(true)
+                .suffix(" s")
+                .text(""),
+        );
+        if (self.params.filter_attack - prev).abs() > f32::EPSILON {
+            self.params.dirty.filter_attack = true;
+        }
+    }
+
+    fn render_filter_decay(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.filter_decay;
+        ui.add(
+            egui::Slider::new(&mut self.params.filter_decay, 0.001..=2.0)
+                .logarithmic(true)
+                .suffix(" s")
+                .text(""),
+        );
+        if (self.params.filter_decay - prev).abs() > f32::EPSILON {
+            self.params.dirty.filter_decay = true;
+        }
+    }
+
+    fn render_filter_sustain(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.filter_sustain;
+        ui.add(egui::Slider::new(&mut self.params.filter_sustain, 0.0..=1.0).text(""));
+        if (self.params.filter_sustain - prev).abs() > f32::EPSILON {
+            self.params.dirty.filter_sustain = true;
+        }
+    }
+
+    fn render_filter_release(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.filter_release;
+        ui.add(
+            egui::Slider::new(&mut self.params.filter_release, 0.001..=5.0)
+                .logarithmic(true)
+                .suffix(" s")
+                .text(""),
+        );
+        if (self.params.filter_release - prev).abs() > f32::EPSILON {
+            self.params.dirty.filter_release = true;
+        }
+    }
+
+    fn render_lfo_shape(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.lfo_shape;
+        let name = LfoShape::from_index(self.params.lfo_shape as usize).name();
+        egui::ComboBox::from_id_salt("lfo_shape")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in LfoShape::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.lfo_shape, i as i32, variant.name());
+                }
+            });
+        if self.params.lfo_shape != prev {
+            self.params.dirty.lfo_shape = true;
+        }
+    }
+
+    fn render_lfo_trigger(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.lfo_trigger;
+        let name = LfoTrigger::from_index(self.params.lfo_trigger as usize).name();
+        egui::ComboBox::from_id_salt("lfo_trigger")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in LfoTrigger::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.lfo_trigger, i as i32, variant.name());
+                }
+            });
+        if self.params.lfo_trigger != prev {
+            self.params.dirty.lfo_trigger = true;
+        }
+    }
+
+    fn render_lfo_timing(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.lfo_timing;
+        let name = LfoTiming::from_index(self.params.lfo_timing as usize).name();
+        egui::ComboBox::from_id_salt("lfo_timing")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in LfoTiming::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.lfo_timing, i as i32, variant.name());
+                }
+            });
+        if self.params.lfo_timing != prev {
+            self.params.dirty.lfo_timing = true;
+        }
+    }
+
+    fn render_lfo_rate(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.lfo_rate;
+        ui.add(
+            egui::Slider::new(&mut self.params.lfo_rate, 0.01..=50.0)
+                .logarithmic(true)
+                .suffix(" Hz")
+                .text(""),
+        );
+        if (self.params.lfo_rate - prev).abs() > f32::EPSILON {
+            self.params.dirty.lfo_rate = true;
+        }
     }
 
-    pub fn clear(&mut self) {
-        *self = Self::default();
+    fn render_lfo_division(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.lfo_division;
+        let name = NoteDivision::from_index(self.params.lfo_division as usize).name();
+        egui::ComboBox::from_id_salt("lfo_division")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in NoteDivision::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.lfo_division, i as i32, variant.name());
+                }
+            });
+        if self.params.lfo_division != prev {
+            self.params.dirty.lfo_division = true;
+        }
     }
-}
 
-impl Default for WebParams {
-    fn default() -> Self {
-        Self {
-            osc_type: 0,
-            gain: 0.8,
-            attack: 0.01,
-            decay: 0.1,
-            sustain: 0.7,
-            release: 0.3,
-            dirty: DirtyFlags::default(),
+    fn render_lfo_to_pitch(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.lfo_to_pitch;
+        ui.add(
+            egui::Slider::new(&mut self.params.lfo_to_pitch, 0.0..=MAX_LFO_PITCH_SEMITONES)
+                .suffix(" st")
+                .text(""),
+        );
+        if (self.params.lfo_to_pitch - prev).abs() > f32::EPSILON {
+            self.params.dirty.lfo_to_pitch = true;
         }
     }
-}
 
-/// Wraps WebParams to implement ControlRenderer using plain egui sliders.
-pub struct WebControls<'a> {
-    pub params: &'a mut WebParams,
-}
+    fn render_lfo_to_amp(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.lfo_to_amp;
+        ui.add(egui::Slider::new(&mut self.params.lfo_to_amp, 0.0..=1.0).text(""));
+        if (self.params.lfo_to_amp - prev).abs() > f32::EPSILON {
+            self.params.dirty.lfo_to_amp = true;
+        }
+    }
 
-impl<'a> ControlRenderer for WebControls<'a> {
-    fn render_osc_type(&mut self, ui: &mut egui::Ui) {
-        let prev = self.params.osc_type;
-        let name = OscillatorType::from_index(self.params.osc_type as usize).name();
-        egui::ComboBox::from_id_salt("osc_type")
-            .selected_text(name)
+    fn render_lfo_to_pulse_width(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.lfo_to_pulse_width;
+        ui.add(egui::Slider::new(&mut self.params.lfo_to_pulse_width, 0.0..=1.0).text(""));
+        if (self.params.lfo_to_pulse_width - prev).abs() > f32::EPSILON {
+            self.params.dirty.lfo_to_pulse_width = true;
+        }
+    }
+
+    fn mod_slot_count(&self) -> usize {
+        MOD_SLOTS
+    }
+
+    fn render_mod_source(&mut self, ui: &mut egui::Ui, slot: usize) {
+        let value = &mut self.params.mod_source[slot];
+        let prev = *value;
+        egui::ComboBox::from_id_salt(("mod_source", slot))
+            .selected_text(ModSource::from_index(*value as usize).name())
             .show_ui(ui, |ui: &mut egui::Ui| {
-                for (i, variant) in OscillatorType::VARIANTS.iter().enumerate() {
-                    ui.selectable_value(&mut self.params.osc_type, i as i32, variant.name());
+                for (i, variant) in ModSource::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(value, i as i32, variant.name());
                 }
             });
-        if self.params.osc_type != prev {
-            self.params.dirty.osc_type = true;
+        if *value != prev {
+            self.params.dirty.mod_slots[slot] = true;
+        }
+    }
+
+    fn render_mod_destination(&mut self, ui: &mut egui::Ui, slot: usize) {
+        let value = &mut self.params.mod_destination[slot];
+        let prev = *value;
+        egui::ComboBox::from_id_salt(("mod_destination", slot))
+            .selected_text(ModDestination::from_index(*value as usize).name())
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in ModDestination::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(value, i as i32, variant.name());
+                }
+            });
+        if *value != prev {
+            self.params.dirty.mod_slots[slot] = true;
+        }
+    }
+
+    fn render_mod_depth(&mut self, ui: &mut egui::Ui, slot: usize) {
+        let prev = self.params.mod_depth[slot];
+        ui.add(egui::Slider::new(&mut self.params.mod_depth[slot], -1.0..=1.0).text(""));
+        if (self.params.mod_depth[slot] - prev).abs() > f32::EPSILON {
+            self.params.dirty.mod_slots[slot] = true;
         }
     }
 
@@ -78,6 +1523,97 @@ impl<'a> ControlRenderer for WebControls<'a> {
         }
     }
 
+    fn render_pan(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.pan;
+        ui.add(egui::Slider::new(&mut self.params.pan, -1.0..=1.0).text(""));
+        if (self.params.pan - prev).abs() > f32::EPSILON {
+            self.params.dirty.pan = true;
+        }
+    }
+
+    fn render_delay(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.delay;
+        ui.add(
+            egui::Slider::new(&mut self.params.delay, 0.0..=5.0)
+                .logarithmic(true)
+                .suffix(" s")
+                .text(""),
+        );
+        if (self.params.delay - prev).abs() > f32::EPSILON {
+            self.params.dirty.delay = true;
+        }
+    }
+
+    fn render_hold(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.hold;
+        ui.add(
+            egui::Slider::new(&mut self.params.hold, 0.0..=5.0)
+                .logarithmic(true)
+                .suffix(" s")
+                .text(""),
+        );
+        if (self.params.hold - prev).abs() > f32::EPSILON {
+            self.params.dirty.hold = true;
+        }
+    }
+
+    fn render_envelope_mode(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.envelope_mode;
+        let name = EnvelopeMode::from_index(self.params.envelope_mode as usize).name();
+        egui::ComboBox::from_id_salt("envelope_mode")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in EnvelopeMode::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.envelope_mode, i as i32, variant.name());
+                }
+            });
+        if self.params.envelope_mode != prev {
+            self.params.dirty.envelope_mode = true;
+        }
+    }
+
+    fn render_retrigger_mode(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.retrigger_mode;
+        let name = RetriggerMode::from_index(self.params.retrigger_mode as usize).name();
+        egui::ComboBox::from_id_salt("retrigger_mode")
+            .selected_text(name)
+            .show_ui(ui, |ui: &mut egui::Ui| {
+                for (i, variant) in RetriggerMode::VARIANTS.iter().enumerate() {
+                    ui.selectable_value(&mut self.params.retrigger_mode, i as i32, variant.name());
+                }
+            });
+        if self.params.retrigger_mode != prev {
+            self.params.dirty.retrigger_mode = true;
+        }
+    }
+
+    fn render_attack_curve(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.attack_curve;
+        let range = -MAX_ENVELOPE_CURVE..=MAX_ENVELOPE_CURVE;
+        ui.add(egui::Slider::new(&mut self.params.attack_curve, range).text(""));
+        if (self.params.attack_curve - prev).abs() > f32::EPSILON {
+            self.params.dirty.attack_curve = true;
+        }
+    }
+
+    fn render_decay_curve(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.decay_curve;
+        let range = -MAX_ENVELOPE_CURVE..=MAX_ENVELOPE_CURVE;
+        ui.add(egui::Slider::new(&mut self.params.decay_curve, range).text(""));
+        if (self.params.decay_curve - prev).abs() > f32::EPSILON {
+            self.params.dirty.decay_curve = true;
+        }
+    }
+
+    fn render_release_curve(&mut self, ui: &mut egui::Ui) {
+        let prev = self.params.release_curve;
+        let range = -MAX_ENVELOPE_CURVE..=MAX_ENVELOPE_CURVE;
+        ui.add(egui::Slider::new(&mut self.params.release_curve, range).text(""));
+        if (self.params.release_curve - prev).abs() > f32::EPSILON {
+            self.params.dirty.release_curve = true;
+        }
+    }
+
     fn render_attack(&mut self, ui: &mut egui::Ui) {
         let prev = self.params.attack;
         ui.add(
@@ -136,6 +1672,11 @@ mod tests {
     fn dirty_flags_default_all_false() {
         let flags = DirtyFlags::default();
         assert!(!flags.osc_type);
+        assert!(!flags.voices);
+        assert!(!flags.voice_mode);
+        assert!(!flags.steal_policy);
+        assert!(!flags.note_priority);
+        assert!(!flags.legato);
         assert!(!flags.gain);
         assert!(!flags.attack);
         assert!(!flags.decay);
@@ -156,6 +1697,13 @@ mod tests {
         assert!(flags.any());
     }
 
+    #[test]
+    fn dirty_flags_any_true_when_voices_set() {
+        let mut flags = DirtyFlags::default();
+        flags.voices = true;
+        assert!(flags.any());
+    }
+
     #[test]
     fn dirty_flags_any_true_when_gain_set() {
         let mut flags = DirtyFlags::default();
@@ -195,7 +1743,96 @@ mod tests {
     fn dirty_flags_any_true_when_all_set() {
         let flags = DirtyFlags {
             osc_type: true,
+            engine: true,
+            pulse_width: true,
+            wavetable: true,
+            wavetable_position: true,
+            partials: u64::MAX,
+            osc2_type: true,
+            osc2_coarse: true,
+            osc2_fine: true,
+            osc2_level: true,
+            osc2_sync: true,
+            ring_mix: true,
+            linear_fm: true,
+            sub_level: true,
+            sub_octave: true,
+            sub_shape: true,
+            noise_level: true,
+            noise_type: true,
+            fm_enabled: true,
+            fm_ratio: true,
+            fm_fixed: true,
+            fm_fixed_frequency: true,
+            fm_index: true,
+            fm_feedback: true,
+            fm_delay: true,
+            fm_hold: true,
+            fm_envelope_mode: true,
+            fm_retrigger_mode: true,
+            fm_attack_curve: true,
+            fm_decay_curve: true,
+            fm_release_curve: true,
+            fm_attack: true,
+            fm_decay: true,
+            fm_sustain: true,
+            fm_release: true,
+            pluck_excitation: true,
+            pluck_damping: true,
+            pluck_brightness: true,
+            pluck_decay: true,
+            unison_voices: true,
+            unison_detune: true,
+            unison_spread: true,
+            unison_random_phase: true,
+            voice_mode: true,
+            voices: true,
+            steal_policy: true,
+            note_priority: true,
+            legato: true,
+            glide_time: true,
+            glide_mode: true,
+            glide_timing: true,
+            velocity_curve: true,
+            velocity_amount: true,
+            velocity_to_attack: true,
+            velocity_to_brightness: true,
+            filter_type: true,
+            filter_mode: true,
+            filter_cutoff: true,
+            filter_resonance: true,
+            filter_drive: true,
+            filter_keytrack: true,
+            filter_env_amount: true,
+            filter_delay: true,
+            filter_hold: true,
+            filter_envelope_mode: true,
+            filter_retrigger_mode: true,
+            filter_attack_curve: true,
+            filter_decay_curve: true,
+            filter_release_curve: true,
+            filter_attack: true,
+            filter_decay: true,
+            filter_sustain: true,
+            filter_release: true,
+            lfo_shape: true,
+            lfo_trigger: true,
+            lfo_timing: true,
+            lfo_rate: true,
+            lfo_division: true,
+            lfo_to_pitch: true,
+            lfo_to_amp: true,
+            lfo_to_pulse_width: true,
+            mod_slots: [true; MOD_SLOTS],
             gain: true,
+            pan: true,
+            delay: true,
+            hold: true,
+            envelope_mode: true,
+            retrigger_mode: true,
+            attack_curve: true,
+            decay_curve: true,
+            release_curve: true,
             attack: true,
             decay: true,
             sustain: true,
@@ -208,7 +1845,96 @@ mod tests {
     fn dirty_flags_clear_resets_all() {
         let mut flags = DirtyFlags {
             osc_type: true,
+            engine: true,
+            pulse_width: true,
+            wavetable: true,
+            wavetable_position: true,
+            partials: u64::MAX,
+            osc2_type: true,
+            osc2_coarse: true,
+            osc2_fine: true,
+            osc2_level: true,
+            osc2_sync: true,
+            ring_mix: true,
+            linear_fm: true,
+            sub_level: true,
+            sub_octave: true,
+            sub_shape: true,
+            noise_level: true,
+            noise_type: true,
+            fm_enabled: true,
+            fm_ratio: true,
+            fm_fixed: true,
+            fm_fixed_frequency: true,
+            fm_index: true,
+            fm_feedback: true,
+            fm_delay: true,
+            fm_hold: true,
+            fm_envelope_mode: true,
+            fm_retrigger_mode: true,
+            fm_attack_curve: true,
+            fm_decay_curve: true,
+            fm_release_curve: true,
+            fm_attack: true,
+            fm_decay: true,
+            fm_sustain: true,
+            fm_release: true,
+            pluck_excitation: true,
+            pluck_damping: true,
+            pluck_brightness: true,
+            pluck_decay: true,
+            unison_voices: true,
+            unison_detune: true,
+            unison_spread: true,
+            unison_random_phase: true,
+            voice_mode: true,
+            voices: true,
+            steal_policy: true,
+            note_priority: true,
+            legato: true,
+            glide_time: true,
+            glide_mode: true,
+            glide_timing: true,
+            velocity_curve: true,
+            velocity_amount: true,
+            velocity_to_attack: true,
+            velocity_to_brightness: true,
+            filter_type: true,
+            filter_mode: true,
+            filter_cutoff: true,
+            filter_resonance: true,
+            filter_drive: true,
+            filter_keytrack: true,
+            filter_env_amount: true,
+            filter_delay: true,
+            filter_hold: true,
+            filter_envelope_mode: true,
+            filter_retrigger_mode: true,
+            filter_attack_curve: true,
+            filter_decay_curve: true,
+            filter_release_curve: true,
+            filter_attack: true,
+            filter_decay: true,
+            filter_sustain: true,
+            filter_release: true,
+            lfo_shape: true,
+            lfo_trigger: true,
+            lfo_timing: true,
+            lfo_rate: true,
+            lfo_division: true,
+            lfo_to_pitch: true,
+            lfo_to_amp: true,
+            lfo_to_pulse_width: true,
+            mod_slots: [true; MOD_SLOTS],
             gain: true,
+            pan: true,
+            delay: true,
+            hold: true,
+            envelope_mode: true,
+            retrigger_mode: true,
+            attack_curve: true,
+            decay_curve: true,
+            release_curve: true,
             attack: true,
             decay: true,
             sustain: true,
@@ -216,6 +1942,7 @@ mod tests {
         };
         flags.clear();
         assert!(!flags.osc_type);
+        assert!(!flags.voices);
         assert!(!flags.gain);
         assert!(!flags.attack);
         assert!(!flags.decay);
@@ -234,6 +1961,16 @@ mod tests {
         assert!(!flags.any());
     }
 
+    #[test]
+    fn dirty_flags_all_marks_the_whole_state() {
+        // Sent when the worklet connects; its engine starts monophonic
+        let flags = DirtyFlags::all();
+        assert!(flags.voices && flags.voice_mode && flags.steal_policy);
+        assert_eq!(flags.partials, u64::MAX);
+        assert!(flags.mod_slots.iter().all(|&d| d));
+        assert!(flags.any());
+    }
+
     // --- WebParams ---
 
     #[test]
@@ -242,6 +1979,232 @@ mod tests {
         assert_eq!(p.osc_type, 0, "default osc type should be Sine (0)");
     }
 
+    #[test]
+    fn web_params_default_voices() {
+        let p = WebParams::default();
+        assert_eq!(p.voices, 8);
+        assert!(p.voices as usize <= MAX_VOICES);
+    }
+
+    #[test]
+    fn web_params_default_steal_policy_is_oldest() {
+        let p = WebParams::default();
+        assert_eq!(
+            StealPolicy::from_index(p.steal_policy as usize),
+            StealPolicy::Oldest
+        );
+    }
+
+    #[test]
+    fn web_params_default_voice_mode_is_poly_without_legato() {
+        let p = WebParams::default();
+        assert_eq!(VoiceMode::from_index(p.voice_mode as usize), VoiceMode::Poly);
+        assert_eq!(
+            NotePriority::from_index(p.note_priority as usize),
+            NotePriority::Last
+        );
+        assert!(!p.legato);
+    }
+
+    #[test]
+    fn dirty_flags_any_true_when_legato_set() {
+        let mut flags = DirtyFlags::default();
+        flags.legato = true;
+        assert!(flags.any());
+    }
+
+    #[test]
+    fn web_params_default_glide_is_off() {
+        let p = WebParams::default();
+        assert_eq!(p.glide_time, 0.0);
+        assert_eq!(GlideMode::from_index(p.glide_mode as usize), GlideMode::Always);
+        assert_eq!(
+            GlideTiming::from_index(p.glide_timing as usize),
+            GlideTiming::ConstantTime
+        );
+    }
+
+    #[test]
+    fn dirty_flags_any_true_when_glide_time_set() {
+        let mut flags = DirtyFlags::default();
+        flags.glide_time = true;
+        assert!(flags.any());
+    }
+
+    #[test]
+    fn web_params_default_velocity_is_linear_full_amount() {
+        let p = WebParams::default();
+        assert_eq!(
+            VelocityCurve::from_index(p.velocity_curve as usize),
+            VelocityCurve::Linear
+        );
+        assert!((p.velocity_amount - 1.0).abs() < f32::EPSILON);
+        assert_eq!(p.velocity_to_attack, 0.0);
+        assert_eq!(p.velocity_to_brightness, 0.0);
+    }
+
+    #[test]
+    fn dirty_flags_any_true_when_velocity_amount_set() {
+        let mut flags = DirtyFlags::default();
+        flags.velocity_amount = true;
+        assert!(flags.any());
+    }
+
+    #[test]
+    fn web_params_default_filter_is_open() {
+        let p = WebParams::default();
+        assert_eq!(
+            FilterType::from_index(p.filter_type as usize),
+            FilterType::StateVariable
+        );
+        assert_eq!(FilterMode::from_index(p.filter_mode as usize), FilterMode::Lowpass);
+        assert!((p.filter_cutoff - 20000.0).abs() < f32::EPSILON);
+        assert_eq!(p.filter_resonance, 0.0);
+        assert_eq!(p.filter_drive, 0.0);
+        assert_eq!(p.filter_keytrack, 0.0);
+    }
+
+    #[test]
+    fn web_params_default_filter_envelope_is_off() {
+        let p = WebParams::default();
+        assert_eq!(p.filter_env_amount, 0.0);
+        assert!((p.filter_attack - 0.01).abs() < f32::EPSILON);
+        assert!((p.filter_decay - 0.3).abs() < f32::EPSILON);
+        assert_eq!(p.filter_sustain, 0.0);
+        assert!((p.filter_release - 0.3).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn dirty_flags_all_sends_the_filter_envelope() {
+        // `Envelope::new` decays over 0.1 s to 0.7, not the UI's 0.3 s to 0
+        let flags = DirtyFlags::all();
+        assert!(flags.filter_decay && flags.filter_sustain);
+    }
+
+    #[test]
+    fn web_params_default_lfo_is_unrouted() {
+        let p = WebParams::default();
+        assert_eq!(LfoShape::from_index(p.lfo_shape as usize), LfoShape::Sine);
+        assert_eq!(LfoTrigger::from_index(p.lfo_trigger as usize), LfoTrigger::Free);
+        assert_eq!(LfoTiming::from_index(p.lfo_timing as usize), LfoTiming::Hz);
+        assert_eq!(
+            NoteDivision::from_index(p.lfo_division as usize),
+            NoteDivision::Quarter
+        );
+        assert!((p.lfo_rate - 5.0).abs() < f32::EPSILON);
+        assert_eq!(p.lfo_to_pitch, 0.0);
+        assert_eq!(p.lfo_to_amp, 0.0);
+        assert_eq!(p.lfo_to_pulse_width, 0.0);
+    }
+
+    #[test]
+    fn web_params_default_unison_is_one_oscillator() {
+        let p = WebParams::default();
+        assert_eq!(p.unison_voices, 1);
+        assert!(!p.unison_random_phase);
+        assert!(p.unison_detune <= MAX_UNISON_DETUNE_CENTS);
+    }
+
+    #[test]
+    fn dirty_flags_all_sends_unison_detune_and_spread() {
+        // The engine's unison starts with no detune or spread
+        let flags = DirtyFlags::all();
+        assert!(flags.unison_detune && flags.unison_spread);
+    }
+
+    #[test]
+    fn dirty_flags_any_true_when_unison_voices_set() {
+        let mut flags = DirtyFlags::default();
+        flags.unison_voices = true;
+        assert!(flags.any());
+    }
+
+    #[test]
+    fn web_params_default_mod_matrix_is_empty() {
+        let p = WebParams::default();
+        for slot in 0..MOD_SLOTS {
+            assert_eq!(ModSource::from_index(p.mod_source[slot] as usize), ModSource::Off);
+            assert_eq!(
+                ModDestination::from_index(p.mod_destination[slot] as usize),
+                ModDestination::Off
+            );
+            assert_eq!(p.mod_depth[slot], 0.0);
+        }
+    }
+
+    #[test]
+    fn dirty_flags_any_true_when_one_mod_slot_set() {
+        let mut flags = DirtyFlags::default();
+        flags.mod_slots[MOD_SLOTS - 1] = true;
+        assert!(flags.any());
+    }
+
+    #[test]
+    fn dirty_flags_any_true_when_one_partial_set() {
+        let mut flags = DirtyFlags::default();
+        flags.partials = 1 << (MAX_PARTIALS - 1);
+        assert!(flags.any());
+    }
+
+    #[test]
+    fn web_params_default_envelopes_are_plain_adsr() {
+        let p = WebParams::default();
+        for seconds in [p.delay, p.hold, p.filter_delay, p.filter_hold, p.fm_delay, p.fm_hold] {
+            assert_eq!(seconds, 0.0);
+        }
+        for mode in [p.envelope_mode, p.filter_envelope_mode, p.fm_envelope_mode] {
+            assert_eq!(EnvelopeMode::from_index(mode as usize), EnvelopeMode::Normal);
+        }
+        for mode in [p.retrigger_mode, p.filter_retrigger_mode, p.fm_retrigger_mode] {
+            assert_eq!(RetriggerMode::from_index(mode as usize), RetriggerMode::Resume);
+        }
+    }
+
+    #[test]
+    fn web_params_default_envelope_curves_are_linear() {
+        let p = WebParams::default();
+        for curve in [
+            p.attack_curve,
+            p.decay_curve,
+            p.release_curve,
+            p.filter_attack_curve,
+            p.filter_decay_curve,
+            p.filter_release_curve,
+            p.fm_attack_curve,
+            p.fm_decay_curve,
+            p.fm_release_curve,
+        ] {
+            assert_eq!(curve, 0.0);
+        }
+    }
+
+    #[test]
+    fn web_params_default_partials_are_the_sine_preset() {
+        let p = WebParams::default();
+        assert_eq!(p.partials, additive::preset_spectrum(AdditivePreset::Sine));
+    }
+
+    #[test]
+    fn dirty_flags_any_true_when_lfo_shape_set() {
+        let mut flags = DirtyFlags::default();
+        flags.lfo_shape = true;
+        assert!(flags.any());
+    }
+
+    #[test]
+    fn dirty_flags_any_true_when_filter_env_amount_set() {
+        let mut flags = DirtyFlags::default();
+        flags.filter_env_amount = true;
+        assert!(flags.any());
+    }
+
+    #[test]
+    fn dirty_flags_any_true_when_filter_cutoff_set() {
+        let mut flags = DirtyFlags::default();
+        flags.filter_cutoff = true;
+        assert!(flags.any());
+    }
+
     #[test]
     fn web_params_default_gain() {
         let p = WebParams::default();
@@ -287,11 +2250,92 @@ mod tests {
 
     #[test]
     fn web_params_all_osc_indices_valid() {
-        for i in 0..4 {
+        for i in 0..5 {
             let mut p = WebParams::default();
             p.osc_type = i;
             let osc = OscillatorType::from_index(p.osc_type as usize);
             assert_eq!(osc, OscillatorType::VARIANTS[i as usize]);
         }
     }
+
+    #[test]
+    fn web_params_default_pulse_width_is_square() {
+        let p = WebParams::default();
+        assert_eq!(p.pulse_width, 0.5);
+    }
+
+    #[test]
+    fn web_params_default_wavetable_is_basic_at_first_frame() {
+        let p = WebParams::default();
+        assert_eq!(
+            WavetableBank::from_index(p.wavetable as usize),
+            WavetableBank::Basic
+        );
+        assert_eq!(p.wavetable_position, 0.0);
+    }
+
+    #[test]
+    fn web_params_default_osc2_is_silent_and_unsynced() {
+        let p = WebParams::default();
+        assert_eq!(
+            OscillatorType::from_index(p.osc2_type as usize),
+            OscillatorType::Saw
+        );
+        assert_eq!(p.osc2_coarse, 0);
+        assert_eq!(p.osc2_fine, 0.0);
+        assert_eq!(p.osc2_level, 0.0);
+        assert!(!p.osc2_sync);
+    }
+
+    #[test]
+    fn dirty_flags_all_sends_the_osc2_type() {
+        // Osc 2 starts as a sine in the engine and a saw in the UI
+        let flags = DirtyFlags::all();
+        assert!(flags.osc2_type && flags.osc2_coarse && flags.osc2_fine);
+    }
+
+    #[test]
+    fn web_params_default_fm_is_off_at_unison_ratio() {
+        let p = WebParams::default();
+        assert!(!p.fm_enabled);
+        assert_eq!(p.fm_ratio, 1.0);
+        assert!(!p.fm_fixed);
+        assert_eq!(p.fm_feedback, 0.0);
+    }
+
+    #[test]
+    fn dirty_flags_all_sends_the_fm_envelope() {
+        // The engine's FM envelope starts at index 0, decay 0.1 and sustain
+        // 0.7; the UI's values only play once they are sent
+        let flags = DirtyFlags::all();
+        assert!(flags.fm_index && flags.fm_decay && flags.fm_sustain);
+    }
+
+    #[test]
+    fn web_params_default_sub_and_noise_are_silent() {
+        let p = WebParams::default();
+        assert_eq!(p.sub_level, 0.0);
+        assert_eq!(SubOctave::from_index(p.sub_octave as usize), SubOctave::OneDown);
+        assert_eq!(SubShape::from_index(p.sub_shape as usize), SubShape::Square);
+        assert_eq!(p.noise_level, 0.0);
+        assert_eq!(NoiseType::from_index(p.noise_type as usize), NoiseType::White);
+    }
+
+    #[test]
+    fn web_params_default_ring_and_linear_fm_are_off() {
+        let p = WebParams::default();
+        assert_eq!(p.ring_mix, 0.0);
+        assert_eq!(p.linear_fm, 0.0);
+    }
+
+    #[test]
+    fn web_params_default_engine_is_oscillators() {
+        let p = WebParams::default();
+        assert_eq!(Engine::from_index(p.engine as usize), Engine::Oscillators);
+        assert_eq!(
+            PluckExcitation::from_index(p.pluck_excitation as usize),
+            PluckExcitation::Noise
+        );
+        assert_eq!(p.pluck_decay, 2.0);
+    }
 }